@@ -10,14 +10,17 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_lock::{Mutex, RwLock};
 use futures::Future;
-use futures::future::{BoxFuture, LocalBoxFuture, join_all};
+use futures::future::{BoxFuture, Either, LocalBoxFuture, join_all, select};
+use futures_timer::Delay;
+use indexmap::IndexMap;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -29,12 +32,16 @@ use crate::proto::{
     HostedTable, MakeTableReq, RemoveHostedTablesUpdateReq, Request, Response, ServerError,
     ServerSystemInfoReq,
 };
-use crate::table::{Table, TableInitOptions, TableOptions};
-use crate::table_data::{TableData, UpdateData};
+use crate::subscription::{self, Subscription, SubscriptionOptions};
+use crate::table::{Table, TableInitOptions, TableOptions, UpdateOptions};
+use crate::table_data::{TableData, UpdateData, rows_schema};
 use crate::utils::*;
 use crate::view::{OnUpdateData, ViewWindow};
 use crate::{OnUpdateMode, OnUpdateOptions, asyncfn, clone};
 
+#[cfg(test)]
+mod tests;
+
 /// Metadata about the engine runtime (such as total heap utilization).
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct SystemInfo<T = u64> {
@@ -189,6 +196,59 @@ impl ReconnectCallback {
     }
 }
 
+/// Options for a single request to the server, e.g.
+/// [`View::to_arrow_with`](crate::View::to_arrow_with).
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    /// How long to wait for the response before failing with
+    /// [`ClientError::Timeout`], overriding the [`Client`]'s
+    /// [`Client::set_request_timeout`].
+    pub timeout: Option<Duration>,
+}
+
+/// The requests which re-create this [`Client`]'s `View`s and subscriptions
+/// on a server, in the order they were issued, keyed by `msg_id`.
+///
+/// `Table`s created by this [`Client`] are not replayed, as their data may
+/// have changed since they were created. Re-create them in the
+/// [`ReconnectCallback`] to restore their `View`s.
+#[derive(Default)]
+struct ReplayState {
+    requests: IndexMap<u32, Request>,
+
+    /// Whether the transport errored since [`ReplayState::requests`] were
+    /// last replayed successfully.
+    pending: bool,
+
+    /// The [`ReplayState::requests`] which were replayed since the transport
+    /// last errored, and are skipped if the replay is retried.
+    restored: HashSet<u32>,
+
+    /// The names of the `Table`s created by this [`Client`].
+    tables: HashSet<String>,
+}
+
+/// A request awaiting its response in [`Client::oneshot_with`], which is
+/// removed from [`Client::subscriptions_once`] if dropped before it responds.
+struct PendingRequest<'a> {
+    client: &'a Client,
+    msg_id: Option<u32>,
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        let Some(msg_id) = self.msg_id else {
+            return;
+        };
+
+        if let Some(mut subscriptions_once) = self.client.subscriptions_once.try_write() {
+            subscriptions_once.remove(&msg_id);
+        } else if let Ok(mut cancelled) = self.client.cancelled.lock() {
+            cancelled.push(msg_id);
+        }
+    }
+}
+
 /// An instance of a [`Client`] is a connection to a single
 /// `perspective_server::Server`, whether locally in-memory or remote over some
 /// transport like a WebSocket.
@@ -214,6 +274,21 @@ pub struct Client {
     subscriptions_errors: Subscriptions<OnErrorCallback>,
     subscriptions_once: Subscriptions<OnceCallback>,
     subscriptions: Subscriptions<BoxFn<Response, BoxFuture<'static, Result<(), ClientError>>>>,
+    replay: Arc<Mutex<ReplayState>>,
+
+    /// The `msg_id`s of [`ReplayState::requests`], so responses to other
+    /// requests needn't lock [`Client::replay`].
+    replay_ids: Arc<std::sync::Mutex<HashSet<u32>>>,
+    request_timeout: Arc<std::sync::Mutex<Option<Duration>>>,
+
+    /// `msg_id`s of requests and subscriptions which were dropped while
+    /// [`Client::subscriptions_once`] or [`Client::subscriptions`] was locked,
+    /// to remove when they are next written.
+    cancelled: Arc<std::sync::Mutex<Vec<u32>>>,
+
+    /// Subscriptions whose [`Subscription`] was dropped, and the requests
+    /// which remove them from the server.
+    dropped: Arc<std::sync::Mutex<Vec<(u32, Request)>>>,
 }
 
 impl PartialEq for Client {
@@ -253,6 +328,11 @@ impl Client {
             subscriptions: Subscriptions::default(),
             subscriptions_errors: Arc::default(),
             subscriptions_once: Arc::default(),
+            replay: Arc::default(),
+            replay_ids: Arc::default(),
+            request_timeout: Arc::default(),
+            cancelled: Arc::default(),
+            dropped: Arc::default(),
         })
     }
 
@@ -273,6 +353,15 @@ impl Client {
         self.name.as_str()
     }
 
+    /// Set how long to wait for the response to each request before failing
+    /// with [`ClientError::Timeout`], for this [`Client`] and its clones.
+    /// `None`, the default, waits indefinitely. Can be overridden per request
+    /// with [`RequestOptions`].
+    pub fn set_request_timeout(&self, timeout: Option<Duration>) -> ClientResult<()> {
+        *self.request_timeout.lock()? = timeout;
+        Ok(())
+    }
+
     /// Handle a message from the external message queue.
     /// [`Client::handle_response`] is part of the low-level message-handling
     /// API necessary to implement new transports for a [`Client`]
@@ -285,6 +374,7 @@ impl Client {
         let mut wr = self.subscriptions_once.write().await;
         if let Some(handler) = (*wr).remove(&msg.msg_id) {
             drop(wr);
+            self.forget_request(msg.msg_id).await;
             handler(msg)?;
             return Ok(true);
         } else if let Some(handler) = self.subscriptions.try_read().unwrap().get(&msg.msg_id) {
@@ -307,6 +397,11 @@ impl Client {
     }
 
     /// Handle an exception from the underlying transport.
+    ///
+    /// Pending requests fail with `message`. If `reconnect` is provided, this
+    /// [`Client`]'s `View`s and subscriptions are kept, and re-created on the
+    /// server once the [`ReconnectCallback`] passed to the [`Client::on_error`]
+    /// callbacks succeeds. Otherwise, they are closed.
     pub async fn handle_error<T, U>(
         &self,
         message: ClientError,
@@ -316,28 +411,46 @@ impl Client {
         T: Fn() -> U + Clone + Send + Sync + 'static,
         U: Future<Output = ClientResult<()>>,
     {
+        let restore = reconnect.is_some();
+        if restore {
+            let mut replay = self.replay.lock().await;
+            replay.pending = true;
+            replay.restored.clear();
+        }
+
         let subs = self.subscriptions_errors.read().await;
         let tasks = join_all(subs.values().map(|callback| {
             callback(
                 message.clone(),
                 reconnect.clone().map(move |f| {
+                    let client = self.clone();
                     ReconnectCallback(Arc::new(move || {
-                        clone!(f);
-                        Box::pin(async move { Ok(f().await?) }) as LocalBoxFuture<'static, _>
+                        clone!(f, client);
+                        Box::pin(async move {
+                            f().await?;
+                            Ok(client.replay_requests().await?)
+                        }) as LocalBoxFuture<'static, _>
                     }))
                 }),
             )
         }));
 
         tasks.await.into_iter().collect::<Result<(), _>>()?;
-        self.close_and_error_subscriptions(&message).await
+        self.close_and_error_subscriptions(&message, restore).await
     }
 
     /// TODO Synthesize an error to provide to the caller, since the
     /// server did not respond and the other option is to just drop the call
     /// which results in a non-descript error message. It would be nice to
     /// have client-side failures be a native part of the Client API.
-    async fn close_and_error_subscriptions(&self, message: &ClientError) -> ClientResult<()> {
+    ///
+    /// If `restore`, subscriptions which will be replayed on reconnect are
+    /// kept.
+    async fn close_and_error_subscriptions(
+        &self,
+        message: &ClientError,
+        restore: bool,
+    ) -> ClientResult<()> {
         let synthetic_error = |msg_id| Response {
             msg_id,
             entity_id: "".to_string(),
@@ -347,19 +460,151 @@ impl Client {
             })),
         };
 
-        self.subscriptions.write().await.clear();
-        let callbacks_once = self
-            .subscriptions_once
+        let mut replay = self.replay.lock().await;
+        if !restore {
+            replay.requests.clear();
+            replay.pending = false;
+            replay.restored.clear();
+            if let Ok(mut replay_ids) = self.replay_ids.lock() {
+                replay_ids.clear();
+            }
+        }
+
+        self.subscriptions
             .write()
             .await
-            .drain()
-            .collect::<Vec<_>>();
+            .retain(|msg_id, _| replay.requests.contains_key(msg_id));
+
+        let callbacks_once = {
+            let mut subscriptions_once = self.subscriptions_once.write().await;
+            let msg_ids = subscriptions_once
+                .keys()
+                .copied()
+                .filter(|msg_id| !replay.requests.contains_key(msg_id))
+                .collect::<Vec<_>>();
+
+            msg_ids
+                .into_iter()
+                .filter_map(|msg_id| subscriptions_once.remove_entry(&msg_id))
+                .collect::<Vec<_>>()
+        };
 
+        drop(replay);
         callbacks_once
             .into_iter()
             .try_for_each(|(msg_id, f)| f(synthetic_error(msg_id)))
     }
 
+    /// Re-sends the requests which re-create this [`Client`]'s `View`s and
+    /// subscriptions, once per transport error. Each `View` is awaited before
+    /// the requests which follow it, as these may subscribe to it. If any
+    /// request fails, the next call retries those which did not succeed.
+    async fn replay_requests(&self) -> ClientResult<()> {
+        let (requests, tables) = {
+            let replay = self.replay.lock().await;
+            if !replay.pending {
+                return Ok(());
+            }
+
+            let requests = replay
+                .requests
+                .iter()
+                .filter(|(msg_id, _)| !replay.restored.contains(msg_id))
+                .map(|(msg_id, req)| (*msg_id, req.clone()))
+                .collect::<Vec<_>>();
+
+            (requests, replay.tables.clone())
+        };
+
+        let mut result = Ok(());
+        for (msg_id, req) in requests {
+            let replayed = if let Some(ClientReq::TableMakeViewReq(_)) = &req.client_req {
+                let msg = Request {
+                    msg_id: self.gen_id(),
+                    ..req.clone()
+                };
+
+                match self.oneshot(&msg).await {
+                    Ok(ClientResp::TableMakeViewResp(_)) => Ok(()),
+                    Ok(_) | Err(_) if tables.contains(&req.entity_id) => {
+                        Err(ClientError::Unknown(format!(
+                            "Table \"{}\" was created by this Client and is not restored on \
+                             reconnect; re-create it in the reconnect callback",
+                            req.entity_id
+                        )))
+                    },
+                    Ok(resp) => Err(resp.into()),
+                    Err(e) => Err(e),
+                }
+            } else {
+                tracing::debug!("SEND {}", req);
+                (self.send)(&req)
+                    .await
+                    .map_err(|e| ClientError::Unknown(e.to_string()))
+            };
+
+            match replayed {
+                Ok(()) => {
+                    self.replay.lock().await.restored.insert(msg_id);
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to restore {}: {}", req, e);
+                    result = result.and(Err(e));
+                },
+            }
+        }
+
+        if result.is_ok() {
+            let mut replay = self.replay.lock().await;
+            replay.pending = false;
+            replay.restored.clear();
+        }
+
+        result
+    }
+
+    /// Remember `msg`, a request which creates a `View` or a subscription, to
+    /// replay after a reconnect.
+    pub(crate) async fn replay_on_reconnect(&self, msg: &Request) {
+        let mut replay = self.replay.lock().await;
+        replay.requests.insert(msg.msg_id, msg.clone());
+        if let Ok(mut replay_ids) = self.replay_ids.lock() {
+            replay_ids.insert(msg.msg_id);
+        }
+    }
+
+    /// Forget the request `msg_id` remembered by
+    /// [`Client::replay_on_reconnect`].
+    pub(crate) async fn forget_request(&self, msg_id: u32) {
+        if matches!(self.replay_ids.lock(), Ok(replay_ids) if !replay_ids.contains(&msg_id)) {
+            return;
+        }
+
+        let mut replay = self.replay.lock().await;
+        replay.requests.shift_remove(&msg_id);
+        if let Ok(mut replay_ids) = self.replay_ids.lock() {
+            replay_ids.remove(&msg_id);
+        }
+    }
+
+    /// Forget the requests remembered by [`Client::replay_on_reconnect`] which
+    /// create or subscribe to the `Table` or `View` `entity_id`.
+    pub(crate) async fn forget_entity(&self, entity_id: &str) {
+        let mut replay = self.replay.lock().await;
+        replay.tables.remove(entity_id);
+        replay.requests.retain(|_, req| {
+            req.entity_id != entity_id
+                && !matches!(
+                    &req.client_req,
+                    Some(ClientReq::TableMakeViewReq(req)) if req.view_id == entity_id
+                )
+        });
+
+        if let Ok(mut replay_ids) = self.replay_ids.lock() {
+            replay_ids.retain(|msg_id| replay.requests.contains_key(msg_id));
+        }
+    }
+
     pub async fn on_error<T, U, V>(&self, on_error: T) -> ClientResult<u32>
     where
         T: Fn(ClientError, Option<ReconnectCallback>) -> U + Clone + Send + Sync + 'static,
@@ -390,6 +635,7 @@ impl Client {
             .ok_or(ClientError::Unknown("remove_update".to_string()))?;
 
         drop(callback);
+        self.forget_request(update_id).await;
         Ok(())
     }
 
@@ -399,10 +645,23 @@ impl Client {
         msg: &Request,
         on_update: Box<dyn FnOnce(Response) -> ClientResult<()> + Send + Sync + 'static>,
     ) -> ClientResult<()> {
-        self.subscriptions_once
-            .write()
-            .await
-            .insert(msg.msg_id, on_update);
+        self.flush_dropped_subscriptions().await?;
+        let mut subscriptions_once = self.subscriptions_once.write().await;
+        let mut subscriptions = self.subscriptions.try_write();
+        self.cancelled.lock()?.retain(|msg_id| {
+            subscriptions_once.remove(msg_id);
+            match &mut subscriptions {
+                Some(subscriptions) => {
+                    subscriptions.remove(msg_id);
+                    false
+                },
+                None => true,
+            }
+        });
+
+        drop(subscriptions);
+        subscriptions_once.insert(msg.msg_id, on_update);
+        drop(subscriptions_once);
 
         tracing::debug!("SEND {}", msg);
         if let Err(e) = (self.send)(msg).await {
@@ -413,6 +672,56 @@ impl Client {
         }
     }
 
+    /// Unsubscribe `update_id` with the `remove` request, when its
+    /// [`Subscription`] is dropped. As this can't `await`, it is sent by
+    /// [`Client::flush_dropped_subscriptions`].
+    pub(crate) fn drop_subscription(&self, update_id: u32, remove: Request) {
+        if let Ok(mut dropped) = self.dropped.lock() {
+            dropped.push((update_id, remove));
+        }
+    }
+
+    /// Removes the subscriptions whose [`Subscription`] was dropped, called
+    /// with each request and by a [`Subscription`]'s callback when it finds
+    /// it was dropped. Their callbacks may be running, so they are removed
+    /// when [`Client::subscriptions`] is next written.
+    pub(crate) async fn flush_dropped_subscriptions(&self) -> ClientResult<()> {
+        let dropped = std::mem::take(&mut *self.dropped.lock()?);
+        for (update_id, remove) in dropped {
+            self.forget_request(update_id).await;
+            self.cancelled.lock()?.push(update_id);
+            self.subscriptions_once
+                .write()
+                .await
+                .insert(remove.msg_id, Box::new(|_| Ok(())));
+
+            tracing::debug!("SEND {}", remove);
+            (self.send)(&remove)
+                .await
+                .map_err(|e| ClientError::Unknown(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Client::subscribe_once`], for a subscription which is replayed
+    /// after a reconnect until it responds, e.g. `on_delete`.
+    pub(crate) async fn subscribe_once_replayed(
+        &self,
+        msg: &Request,
+        on_update: Box<dyn FnOnce(Response) -> ClientResult<()> + Send + Sync + 'static>,
+    ) -> ClientResult<()> {
+        self.replay_on_reconnect(msg).await;
+        let result = self.subscribe_once(msg, on_update).await;
+        if result.is_err() {
+            self.forget_request(msg.msg_id).await;
+        }
+
+        result
+    }
+
+    /// Register a callback which may respond many times, and which is
+    /// replayed after a reconnect until unsubscribed.
     pub(crate) async fn subscribe<T, U>(&self, msg: &Request, on_update: T) -> ClientResult<()>
     where
         T: Fn(Response) -> U + Send + Sync + 'static,
@@ -423,9 +732,11 @@ impl Client {
             .await
             .insert(msg.msg_id, Box::new(move |x| Box::pin(on_update(x))));
 
+        self.replay_on_reconnect(msg).await;
         tracing::debug!("SEND {}", msg);
         if let Err(e) = (self.send)(msg).await {
             self.subscriptions.write().await.remove(&msg.msg_id);
+            self.forget_request(msg.msg_id).await;
             Err(ClientError::Unknown(e.to_string()))
         } else {
             Ok(())
@@ -435,15 +746,45 @@ impl Client {
     /// Send a `ClientReq` and await both the successful completion of the
     /// `send`, _and_ the `ClientResp` which is returned.
     pub(crate) async fn oneshot(&self, req: &Request) -> ClientResult<ClientResp> {
+        self.oneshot_with(req, &RequestOptions::default()).await
+    }
+
+    /// Like [`Client::oneshot`], but fails with [`ClientError::Timeout`] if
+    /// the response takes longer than `options.timeout`. The request is
+    /// forgotten if it times out, or if the returned future is dropped.
+    pub(crate) async fn oneshot_with(
+        &self,
+        req: &Request,
+        options: &RequestOptions,
+    ) -> ClientResult<ClientResp> {
+        let timeout = match options.timeout {
+            Some(timeout) => Some(timeout),
+            None => *self.request_timeout.lock()?,
+        };
+
         let (sender, receiver) = futures::channel::oneshot::channel::<ClientResp>();
         let on_update = Box::new(move |res: Response| {
-            sender.send(res.client_resp.unwrap()).map_err(|x| x.into())
+            // The receiver is only dropped if the request was cancelled.
+            let _ = sender.send(res.client_resp.unwrap());
+            Ok(())
         });
 
+        let mut pending = PendingRequest {
+            client: self,
+            msg_id: Some(req.msg_id),
+        };
+
         self.subscribe_once(req, on_update).await?;
-        receiver
-            .await
-            .map_err(|_| ClientError::Unknown(format!("Internal error for req {req}")))
+        let resp = match timeout {
+            None => receiver.await,
+            Some(timeout) => match select(receiver, Delay::new(timeout)).await {
+                Either::Left((resp, _)) => resp,
+                Either::Right(_) => return Err(ClientError::Timeout(timeout)),
+            },
+        };
+
+        pending.msg_id = None;
+        resp.map_err(|_| ClientError::Unknown(format!("Internal error for req {req}")))
     }
 
     pub(crate) async fn get_features(&self) -> ClientResult<Features> {
@@ -554,6 +895,45 @@ impl Client {
         }
     }
 
+    /// Creates a new [`Table`] from `rows` of a type which serializes as a
+    /// map, such as a struct, with the schema derived by
+    /// [`TableData::schema_from_rows`], so that e.g. a float field which
+    /// happens to hold only integers is still a `"float"` column.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     x: i32,
+    ///     y: Option<String>,
+    /// }
+    ///
+    /// let rows = vec![Row { x: 1, y: None }, Row {
+    ///     x: 2,
+    ///     y: Some("a".into()),
+    /// }];
+    /// let table = client
+    ///     .table_from_rows(&rows, TableInitOptions::default())
+    ///     .await?;
+    /// ```
+    pub async fn table_from_rows<T: Serialize>(
+        &self,
+        rows: &[T],
+        options: TableInitOptions,
+    ) -> ClientResult<Table> {
+        let json = serde_json::to_string(rows)?;
+        let schema = rows_schema(&json)?;
+        let table = self.table(TableData::Schema(schema), options).await?;
+        table
+            .update(UpdateData::JsonRows(json), UpdateOptions::default())
+            .await?;
+
+        Ok(table)
+    }
+
     async fn crate_table_inner(
         &self,
         input: TableData,
@@ -571,7 +951,10 @@ impl Client {
 
         let client = self.clone();
         match self.oneshot(&msg).await? {
-            ClientResp::MakeTableResp(_) => Ok(Table::new(entity_id, client, options)),
+            ClientResp::MakeTableResp(_) => {
+                self.replay.lock().await.tables.insert(entity_id.clone());
+                Ok(Table::new(entity_id, client, options))
+            },
             resp => Err(resp.into()),
         }
     }
@@ -683,6 +1066,48 @@ impl Client {
         Ok(msg.msg_id)
     }
 
+    /// A [`Subscription`] to the events of [`Client::on_hosted_tables_update`],
+    /// which unsubscribes when dropped.
+    pub async fn hosted_tables_updates(&self) -> ClientResult<Subscription<()>> {
+        let (sender, receiver) = subscription::channel(SubscriptionOptions::default());
+        let client = self.clone();
+        let callback = move |resp: Response| {
+            let sender = sender.clone();
+            let client = client.clone();
+            async move {
+                match resp.client_resp {
+                    Some(ClientResp::GetHostedTablesResp(_)) | None => {
+                        if !sender.send(()) {
+                            client.flush_dropped_subscriptions().await?;
+                        }
+
+                        Ok(())
+                    },
+                    resp => Err(resp.into()),
+                }
+            }
+        };
+
+        let msg = Request {
+            msg_id: self.gen_id(),
+            entity_id: "".to_owned(),
+            client_req: Some(ClientReq::GetHostedTablesReq(GetHostedTablesReq {
+                subscribe: true,
+            })),
+        };
+
+        self.subscribe(&msg, callback).await?;
+        let remove = Request {
+            msg_id: self.gen_id(),
+            entity_id: "".to_owned(),
+            client_req: Some(ClientReq::RemoveHostedTablesUpdateReq(
+                RemoveHostedTablesUpdateReq { id: msg.msg_id },
+            )),
+        };
+
+        Ok(receiver.unsubscribe_on_drop(self.clone(), msg.msg_id, remove))
+    }
+
     /// Remove a callback previously registered via
     /// `Client::on_hosted_tables_update`.
     pub async fn remove_hosted_tables_update(&self, update_id: u32) -> ClientResult<()> {
@@ -0,0 +1,529 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::executor::block_on;
+use futures::{FutureExt, StreamExt};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::proto::request::ClientReq;
+use crate::proto::response::ClientResp;
+use crate::proto::*;
+use crate::{
+    Client, ClientError, OnUpdateOptions, OverflowPolicy, ReconnectCallback, RequestOptions,
+    SubscriptionOptions, TableInitOptions, ViewWindow,
+};
+
+type Requests = Arc<Mutex<Vec<Request>>>;
+
+/// The response a server would send to `req`, for requests a [`Client`]
+/// awaits.
+fn respond(req: &Request) -> Option<ClientResp> {
+    match req.client_req.as_ref()? {
+        ClientReq::GetHostedTablesReq(GetHostedTablesReq { subscribe: false }) => {
+            Some(ClientResp::GetHostedTablesResp(GetHostedTablesResp {
+                table_infos: vec!["table".into()],
+            }))
+        },
+        ClientReq::TableMakeViewReq(TableMakeViewReq { view_id, .. }) => {
+            Some(ClientResp::TableMakeViewResp(TableMakeViewResp {
+                view_id: view_id.clone(),
+            }))
+        },
+        ClientReq::MakeTableReq(_) => Some(ClientResp::MakeTableResp(MakeTableResp {})),
+        ClientReq::TableUpdateReq(_) => Some(ClientResp::TableUpdateResp(TableUpdateResp {})),
+        ClientReq::ViewToRowsStringReq(_) => {
+            Some(ClientResp::ViewToRowsStringResp(ViewToRowsStringResp {
+                json_string: r#"[{"x":1,"y":null},{"x":2,"y":"a"}]"#.to_string(),
+            }))
+        },
+        ClientReq::ViewDeleteReq(_) => Some(ClientResp::ViewDeleteResp(ViewDeleteResp {})),
+        ClientReq::ViewRemoveOnUpdateReq(_) => Some(ClientResp::ViewRemoveOnUpdateResp(
+            ViewRemoveOnUpdateResp {},
+        )),
+        ClientReq::RemoveHostedTablesUpdateReq(_) => Some(
+            ClientResp::RemoveHostedTablesUpdateResp(RemoveHostedTablesUpdateResp {}),
+        ),
+        _ => None,
+    }
+}
+
+fn encode(msg: Response) -> Vec<u8> {
+    let mut bytes = vec![];
+    msg.encode(&mut bytes).unwrap();
+    bytes
+}
+
+/// A [`Client`] connected to a server which records its requests.
+fn test_client() -> (Client, Requests) {
+    test_client_with(respond)
+}
+
+/// Like [`test_client`], for a server which answers requests with `respond`.
+fn test_client_with(
+    respond: impl Fn(&Request) -> Option<ClientResp> + Send + Sync + 'static,
+) -> (Client, Requests) {
+    let requests = Requests::default();
+    let server_client = Arc::new(Mutex::new(None::<Client>));
+    let client = Client::new_with_callback(None, {
+        let requests = requests.clone();
+        let server_client = server_client.clone();
+        move |bytes: Vec<u8>| {
+            let req = Request::decode(bytes.as_slice()).unwrap();
+            requests.lock().unwrap().push(req.clone());
+            let client = server_client.lock().unwrap().clone().unwrap();
+            let resp = respond(&req);
+            async move {
+                if let Some(resp) = resp {
+                    let resp = Response {
+                        msg_id: req.msg_id,
+                        entity_id: req.entity_id,
+                        client_resp: Some(resp),
+                    };
+
+                    client.handle_response(&encode(resp)).await?;
+                }
+
+                Ok(())
+            }
+        }
+    })
+    .unwrap();
+
+    *server_client.lock().unwrap() = Some(client.clone());
+    (client, requests)
+}
+
+/// Errors `client`'s transport, returning the [`ReconnectCallback`] passed to
+/// its `on_error` callbacks.
+async fn transport_error(client: &Client) -> ReconnectCallback {
+    let reconnects = Arc::new(Mutex::new(vec![]));
+    let callback_id = client
+        .on_error({
+            let reconnects = reconnects.clone();
+            move |_, reconnect| {
+                reconnects.lock().unwrap().extend(reconnect);
+                async { Ok::<(), ClientError>(()) }
+            }
+        })
+        .await
+        .unwrap();
+
+    client
+        .handle_error(
+            ClientError::TransportError("closed".to_string()),
+            Some(|| async { Ok(()) }),
+        )
+        .await
+        .unwrap();
+
+    client
+        .subscriptions_errors
+        .write()
+        .await
+        .remove(&callback_id);
+
+    reconnects.lock().unwrap().pop().unwrap()
+}
+
+#[test]
+fn test_views_and_subscriptions_are_replayed_after_reconnect() {
+    block_on(async {
+        let (client, requests) = test_client();
+        let table = client.open_table("table".to_string()).await.unwrap();
+        let view = table.view(None).await.unwrap();
+        let updates = Arc::new(AtomicU32::default());
+        let update_id = view
+            .on_update(
+                {
+                    let updates = updates.clone();
+                    move |_| {
+                        updates.fetch_add(1, Ordering::SeqCst);
+                        async {}
+                    }
+                },
+                OnUpdateOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let hosted_id = client.on_hosted_tables_update(|| async {}).await.unwrap();
+        requests.lock().unwrap().clear();
+        let reconnect = transport_error(&client).await;
+        assert!(requests.lock().unwrap().is_empty());
+        reconnect().await.unwrap();
+
+        let replayed = std::mem::take(&mut *requests.lock().unwrap());
+        assert_eq!(replayed.len(), 3);
+        assert!(matches!(
+            &replayed[0].client_req,
+            Some(ClientReq::TableMakeViewReq(req)) if req.view_id == view.name
+        ));
+
+        assert_eq!(replayed[0].entity_id, "table");
+        assert_eq!(replayed[1].msg_id, update_id);
+        assert!(matches!(
+            replayed[1].client_req,
+            Some(ClientReq::ViewOnUpdateReq(_))
+        ));
+
+        assert_eq!(replayed[2].msg_id, hosted_id);
+
+        // The `on_update` callback survives the reconnect.
+        let update = Response {
+            msg_id: update_id,
+            entity_id: view.name.clone(),
+            client_resp: Some(ClientResp::ViewOnUpdateResp(ViewOnUpdateResp::default())),
+        };
+
+        assert!(client.handle_response(&encode(update)).await.unwrap());
+        assert_eq!(updates.load(Ordering::SeqCst), 1);
+
+        // Requests are replayed once per transport error.
+        reconnect().await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+
+        // Deleted views and removed subscriptions are not replayed.
+        view.delete().await.unwrap();
+        client.remove_hosted_tables_update(hosted_id).await.unwrap();
+        requests.lock().unwrap().clear();
+        let reconnect = transport_error(&client).await;
+        reconnect().await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+    })
+}
+
+#[test]
+fn test_failed_replay_is_retried() {
+    block_on(async {
+        let restarted = Arc::new(AtomicBool::new(false));
+        let (client, requests) = test_client_with({
+            let restarted = restarted.clone();
+            move |req| match &req.client_req {
+                // After a restart, the server has lost the `Table`s which
+                // this `Client` created until they are re-created.
+                Some(ClientReq::TableMakeViewReq(_)) if restarted.load(Ordering::SeqCst) => {
+                    Some(ClientResp::ServerError(ServerError {
+                        message: "Table not found".to_string(),
+                        status_code: 1,
+                    }))
+                },
+                Some(ClientReq::MakeTableReq(_)) => {
+                    restarted.store(false, Ordering::SeqCst);
+                    respond(req)
+                },
+                _ => respond(req),
+            }
+        });
+
+        let options = TableInitOptions {
+            name: Some("local".to_string()),
+            ..TableInitOptions::default()
+        };
+
+        let table = client
+            .table_from_rows(
+                &[Row {
+                    x: 1.0,
+                    y: Some("a".to_string()),
+                }],
+                options,
+            )
+            .await
+            .unwrap();
+
+        let view = table.view(None).await.unwrap();
+        let update_id = view
+            .on_update(|_| async {}, OnUpdateOptions::default())
+            .await
+            .unwrap();
+
+        let reconnect = transport_error(&client).await;
+        restarted.store(true, Ordering::SeqCst);
+        requests.lock().unwrap().clear();
+        let err = reconnect().await.unwrap_err();
+        assert!(err.to_string().contains("created by this Client"), "{err}");
+
+        // The `on_update` subscription was replayed, and is not sent again.
+        let replayed = std::mem::take(&mut *requests.lock().unwrap());
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[1].msg_id, update_id);
+
+        // The `View` is restored once its `Table` is re-created.
+        let options = TableInitOptions {
+            name: Some("local".to_string()),
+            ..TableInitOptions::default()
+        };
+
+        client
+            .table_from_rows(
+                &[Row {
+                    x: 1.0,
+                    y: Some("a".to_string()),
+                }],
+                options,
+            )
+            .await
+            .unwrap();
+
+        requests.lock().unwrap().clear();
+        reconnect().await.unwrap();
+        let replayed = std::mem::take(&mut *requests.lock().unwrap());
+        assert_eq!(replayed.len(), 1);
+        assert!(matches!(
+            &replayed[0].client_req,
+            Some(ClientReq::TableMakeViewReq(req)) if req.view_id == view.name
+        ));
+
+        reconnect().await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+    })
+}
+
+#[test]
+fn test_subscriptions_are_closed_without_reconnect() {
+    block_on(async {
+        let (client, requests) = test_client();
+        let table = client.open_table("table".to_string()).await.unwrap();
+        let view = table.view(None).await.unwrap();
+        let update_id = view
+            .on_update(|_| async {}, OnUpdateOptions::default())
+            .await
+            .unwrap();
+
+        client
+            .handle_error(
+                ClientError::TransportError("closed".to_string()),
+                None::<fn() -> futures::future::Ready<Result<(), ClientError>>>,
+            )
+            .await
+            .unwrap();
+
+        let update = Response {
+            msg_id: update_id,
+            entity_id: view.name.clone(),
+            client_resp: Some(ClientResp::ViewOnUpdateResp(ViewOnUpdateResp::default())),
+        };
+
+        assert!(!client.handle_response(&encode(update)).await.unwrap());
+        assert!(client.replay.lock().await.requests.is_empty());
+        drop(requests);
+    })
+}
+
+#[test]
+fn test_request_timeout() {
+    block_on(async {
+        let (client, _requests) = test_client();
+        let view = client
+            .open_table("table".to_string())
+            .await
+            .unwrap()
+            .view(None)
+            .await
+            .unwrap();
+
+        let options = RequestOptions {
+            timeout: Some(Duration::from_millis(10)),
+        };
+
+        let err = view
+            .to_arrow_with(ViewWindow::default(), options)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ClientError::Timeout(_)), "{err:?}");
+        assert!(client.subscriptions_once.read().await.is_empty());
+
+        client
+            .set_request_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+
+        let err = view.to_csv(ViewWindow::default()).await.unwrap_err();
+        assert!(matches!(err, ClientError::Timeout(_)), "{err:?}");
+        assert!(client.subscriptions_once.read().await.is_empty());
+    })
+}
+
+#[test]
+fn test_dropped_request_is_forgotten() {
+    block_on(async {
+        let (client, _requests) = test_client();
+        let view = client
+            .open_table("table".to_string())
+            .await
+            .unwrap()
+            .view(None)
+            .await
+            .unwrap();
+
+        assert!(
+            view.to_arrow(ViewWindow::default())
+                .now_or_never()
+                .is_none()
+        );
+        assert!(client.subscriptions_once.read().await.is_empty());
+    })
+}
+
+#[test]
+fn test_request_dropped_while_sending_is_forgotten() {
+    block_on(async {
+        let client = Client::new_with_callback(None, |_| futures::future::pending()).unwrap();
+        let req = Request {
+            msg_id: client.gen_id(),
+            entity_id: "view".to_string(),
+            client_req: Some(ClientReq::ViewToArrowReq(ViewToArrowReq::default())),
+        };
+
+        assert!(client.oneshot(&req).now_or_never().is_none());
+        assert!(client.subscriptions_once.read().await.is_empty());
+    })
+}
+
+/// A [`ViewOnUpdateResp`] for the subscription `update_id` of `view_id`.
+fn view_update(update_id: u32, view_id: &str, port_id: u32) -> Vec<u8> {
+    encode(Response {
+        msg_id: update_id,
+        entity_id: view_id.to_owned(),
+        client_resp: Some(ClientResp::ViewOnUpdateResp(ViewOnUpdateResp {
+            port_id,
+            delta: None,
+        })),
+    })
+}
+
+#[test]
+fn test_view_updates_stream_overflow() {
+    block_on(async {
+        let (client, requests) = test_client();
+        let table = client.open_table("table".to_string()).await.unwrap();
+        let view = table.view(None).await.unwrap();
+        let options = SubscriptionOptions {
+            capacity: 2,
+            overflow: OverflowPolicy::DropOldest,
+        };
+
+        let mut updates = view
+            .updates_with(OnUpdateOptions::default(), options)
+            .await
+            .unwrap();
+
+        let update_id = requests.lock().unwrap().last().unwrap().msg_id;
+        for port_id in 0..3 {
+            let update = view_update(update_id, &view.name, port_id);
+            assert!(client.handle_response(&update).await.unwrap());
+        }
+
+        assert_eq!(updates.lagged(), 1);
+        assert_eq!(updates.next().await.unwrap().port_id, 1);
+        assert_eq!(updates.next().await.unwrap().port_id, 2);
+        assert!(updates.next().now_or_never().is_none());
+    })
+}
+
+#[test]
+fn test_dropped_subscription_unsubscribes() {
+    block_on(async {
+        let (client, requests) = test_client();
+        let table = client.open_table("table".to_string()).await.unwrap();
+        let view = table.view(None).await.unwrap();
+        let updates = view.updates(OnUpdateOptions::default()).await.unwrap();
+        let update_id = requests.lock().unwrap().last().unwrap().msg_id;
+        drop(updates);
+
+        // The next event is discarded, and unsubscribes.
+        requests.lock().unwrap().clear();
+        let update = view_update(update_id, &view.name, 0);
+        assert!(client.handle_response(&update).await.unwrap());
+        assert!(matches!(
+            &requests.lock().unwrap()[0].client_req,
+            Some(ClientReq::ViewRemoveOnUpdateReq(req)) if req.id == update_id
+        ));
+
+        // The callback is removed with the next request.
+        client.get_hosted_table_names().await.unwrap();
+
+        assert!(!client.subscriptions.read().await.contains_key(&update_id));
+        assert!(!client.replay.lock().await.requests.contains_key(&update_id));
+        assert!(!client.handle_response(&update).await.unwrap());
+    })
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Row {
+    x: f64,
+    y: Option<String>,
+}
+
+#[test]
+fn test_table_from_rows() {
+    block_on(async {
+        let (client, requests) = test_client();
+        let rows = [Row { x: 1.0, y: None }, Row {
+            x: 2.0,
+            y: Some("a".to_string()),
+        }];
+
+        let table = client
+            .table_from_rows(&rows, TableInitOptions::default())
+            .await
+            .unwrap();
+
+        let requests = std::mem::take(&mut *requests.lock().unwrap());
+        let Some(ClientReq::MakeTableReq(MakeTableReq { data, .. })) = &requests[0].client_req
+        else {
+            panic!("{:?}", requests[0]);
+        };
+
+        assert!(matches!(
+            &data.as_ref().unwrap().data,
+            Some(make_table_data::Data::FromSchema(Schema { schema })) if schema == &[
+                schema::KeyTypePair { name: "x".to_string(), r#type: ColumnType::Float as i32 },
+                schema::KeyTypePair { name: "y".to_string(), r#type: ColumnType::String as i32 },
+            ]
+        ));
+
+        assert_eq!(requests[1].entity_id, table.get_name());
+        assert!(matches!(
+            &requests[1].client_req,
+            Some(ClientReq::TableUpdateReq(TableUpdateReq { data: Some(MakeTableData {
+                data: Some(make_table_data::Data::FromRows(json))
+            }), .. })) if json == &serde_json::to_string(&rows).unwrap()
+        ));
+    })
+}
+
+#[test]
+fn test_view_to_rows() {
+    block_on(async {
+        let (client, _requests) = test_client();
+        let table = client.open_table("table".to_string()).await.unwrap();
+        let view = table.view(None).await.unwrap();
+        let rows = view.to_rows::<Row>(ViewWindow::default()).await.unwrap();
+        assert_eq!(rows, [Row { x: 1.0, y: None }, Row {
+            x: 2.0,
+            y: Some("a".to_string())
+        }]);
+
+        let err = view
+            .to_rows::<(f64, String)>(ViewWindow::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ClientError::SerdeJsonError(_)), "{err:?}");
+    })
+}
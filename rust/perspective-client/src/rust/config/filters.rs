@@ -19,21 +19,114 @@ use ts_rs::TS;
 use crate::proto;
 use crate::proto::scalar;
 
+#[cfg(test)]
+mod tests;
+
 /// This type represents the ViewConfig serializable type, which must be JSON
 /// safe.
+///
+/// `Date` and `Datetime` serialize as `{"date": "2024-01-31"}` and
+/// `{"datetime": 1706659200000}` respectively, so they can be told apart
+/// from plain strings and numbers.
 #[derive(Clone, Default, Deserialize, Debug, PartialEq, Serialize, TS)]
 #[serde(untagged)]
 pub enum Scalar {
     Float(f64),
     String(String),
     Bool(bool),
-    // DateTime(i64),
-    // Date(String),
+
+    /// An ISO 8601 `YYYY-MM-DD` date.
+    Date(
+        #[serde(with = "date_scalar")]
+        #[ts(type = "{ date: string }")]
+        String,
+    ),
+
+    /// Milliseconds since the epoch, UTC.
+    Datetime(
+        #[serde(with = "datetime_scalar")]
+        #[ts(type = "{ datetime: number }")]
+        i64,
+    ),
+
     // Int(i32),
     #[default]
     Null,
 }
 
+mod date_scalar {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(deny_unknown_fields)]
+    struct Date<T> {
+        date: T,
+    }
+
+    pub fn serialize<S: Serializer>(date: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        Date { date }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        Ok(Date::deserialize(deserializer)?.date)
+    }
+}
+
+mod datetime_scalar {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(deny_unknown_fields)]
+    struct Datetime {
+        datetime: i64,
+    }
+
+    pub fn serialize<S: Serializer>(datetime: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        Datetime {
+            datetime: *datetime,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        Ok(Datetime::deserialize(deserializer)?.datetime)
+    }
+}
+
+impl Scalar {
+    /// Formats a `Datetime` as an ISO 8601 `YYYY-MM-DD HH:MM:SS.sss` string in
+    /// UTC, or `None` for any other variant.
+    pub fn to_datetime_string(&self) -> Option<String> {
+        let Self::Datetime(ms) = self else {
+            return None;
+        };
+
+        let days = ms.div_euclid(86_400_000);
+        let ms = ms.rem_euclid(86_400_000);
+
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Some(format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+            year,
+            month,
+            day,
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000
+        ))
+    }
+}
+
 impl From<&str> for Scalar {
     fn from(value: &str) -> Self {
         Self::String(value.into())
@@ -46,6 +139,8 @@ impl Display for Scalar {
             Self::Float(x) => write!(fmt, "{x}"),
             Self::String(x) => write!(fmt, "{x}"),
             Self::Bool(x) => write!(fmt, "{x}"),
+            Self::Date(x) => write!(fmt, "{x}"),
+            Self::Datetime(_) => write!(fmt, "{}", self.to_datetime_string().unwrap_or_default()),
             Self::Null => write!(fmt, ""),
         }
     }
@@ -155,6 +250,12 @@ impl From<Scalar> for proto::Scalar {
             Scalar::Bool(x) => proto::Scalar {
                 scalar: Some(scalar::Scalar::Bool(x)),
             },
+            Scalar::Date(x) => proto::Scalar {
+                scalar: Some(scalar::Scalar::Date(x)),
+            },
+            Scalar::Datetime(x) => proto::Scalar {
+                scalar: Some(scalar::Scalar::Datetime(x)),
+            },
             Scalar::Null => proto::Scalar {
                 scalar: Some(scalar::Scalar::Null(0)),
             },
@@ -168,6 +269,8 @@ impl From<proto::Scalar> for Scalar {
             Some(scalar::Scalar::Bool(x)) => Scalar::Bool(x),
             Some(scalar::Scalar::String(x)) => Scalar::String(x),
             Some(scalar::Scalar::Float(x)) => Scalar::Float(x),
+            Some(scalar::Scalar::Date(x)) => Scalar::Date(x),
+            Some(scalar::Scalar::Datetime(x)) => Scalar::Datetime(x),
             Some(scalar::Scalar::Null(_)) => Scalar::Null,
             None => Scalar::Null,
         }
@@ -0,0 +1,94 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn test_scalar_json_round_trip() {
+    let cases = [
+        (Scalar::Float(1.5), json!(1.5)),
+        (
+            Scalar::String("2024-01-31".to_string()),
+            json!("2024-01-31"),
+        ),
+        (Scalar::Bool(true), json!(true)),
+        (
+            Scalar::Date("2024-01-31".to_string()),
+            json!({"date": "2024-01-31"}),
+        ),
+        (
+            Scalar::Datetime(1706704496789),
+            json!({"datetime": 1706704496789_i64}),
+        ),
+        (Scalar::Null, json!(null)),
+    ];
+
+    for (scalar, value) in cases {
+        assert_eq!(serde_json::to_value(&scalar).unwrap(), value);
+        assert_eq!(serde_json::from_value::<Scalar>(value).unwrap(), scalar);
+    }
+
+    assert!(serde_json::from_value::<Scalar>(json!({"date": 1, "datetime": 1})).is_err());
+}
+
+#[test]
+fn test_scalar_proto_round_trip() {
+    for scalar in [
+        Scalar::Float(1.5),
+        Scalar::String("x".to_string()),
+        Scalar::Bool(false),
+        Scalar::Date("2024-01-31".to_string()),
+        Scalar::Datetime(-1),
+        Scalar::Null,
+    ] {
+        assert_eq!(Scalar::from(proto::Scalar::from(scalar.clone())), scalar);
+    }
+}
+
+#[test]
+fn test_filter_proto_round_trip() {
+    let filter = Filter::new(
+        "t",
+        "in",
+        FilterTerm::Array(vec![Scalar::Datetime(0), Scalar::Datetime(1706704496789)]),
+    );
+
+    let json = serde_json::to_string(&filter).unwrap();
+    assert_eq!(
+        json,
+        r#"["t","in",[{"datetime":0},{"datetime":1706704496789}]]"#
+    );
+
+    assert_eq!(serde_json::from_str::<Filter>(&json).unwrap(), filter);
+    assert_eq!(
+        Filter::from(proto::view_config::Filter::from(filter.clone())),
+        filter
+    );
+}
+
+#[test]
+fn test_scalar_datetime_string() {
+    let cases = [
+        (0, "1970-01-01 00:00:00.000"),
+        (-1, "1969-12-31 23:59:59.999"),
+        (951782400000, "2000-02-29 00:00:00.000"),
+        (1706704496789, "2024-01-31 12:34:56.789"),
+    ];
+
+    for (ms, expected) in cases {
+        assert_eq!(Scalar::Datetime(ms).to_string(), expected);
+    }
+
+    assert_eq!(Scalar::Float(0.0).to_datetime_string(), None);
+}
@@ -10,18 +10,22 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
+//! A tokenizer for the Perspective/ExprTK expression language, shared by the
+//! `<perspective-viewer>` syntax highlighter and the
+//! [`crate::virtual_server::GenericSQLVirtualServerModel`] SQL translator.
+
 mod comment;
 mod number;
 mod string;
 mod symbol;
 
+use itertools::Itertools;
 use nom::IResult;
 use nom::branch::alt;
 use nom::bytes::complete::{is_a, is_not};
 use nom::character::complete::{line_ending, space1};
 use nom::combinator::map;
 use nom::multi::many0;
-use yew::prelude::*;
 
 use self::comment::*;
 use self::number::*;
@@ -62,20 +66,9 @@ pub enum Token<'a> {
 
 use Token::*;
 
-impl Token<'_> {
-    pub fn to_html(&self) -> Html {
-        html! {
-            if matches!(self, Break(_)) {
-                <br />
-            } else {
-                <span class={self.class_name()}>{ self.content() }</span>
-            }
-        }
-    }
-}
-
 impl<'a> Token<'a> {
-    const fn class_name(&self) -> &'static str {
+    /// The CSS class name used when syntax-highlighting this token.
+    pub const fn class_name(&self) -> &'static str {
         match self {
             Comment(_) => "comment",
             Whitespace(_) => "whitespace",
@@ -111,10 +104,7 @@ fn parse_multiline_string<'a>(
     lit: impl Fn(&'a str) -> Token<'a>,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<Token<'a>>> {
     map(parse_string_literal(sep), move |x| {
-        x.into_iter()
-            .map(|x| lit(x))
-            .intersperse(Token::Break("\n"))
-            .collect()
+        Itertools::intersperse(x.into_iter().map(|x| lit(x)), Token::Break("\n")).collect()
     })
 }
 
@@ -153,55 +143,4 @@ pub fn tokenize(input: &str) -> Vec<Token<'_>> {
 }
 
 #[cfg(test)]
-mod tests {
-    use wasm_bindgen_test::*;
-
-    use super::*;
-
-    #[wasm_bindgen_test]
-    fn test_simple() {
-        let s = "123 abc 'hello' \"Sales\"";
-        assert_eq!(tokenize(s), vec![
-            Literal("123"),
-            Whitespace(" "),
-            Symbol("abc"),
-            Whitespace(" "),
-            Column("'hello'"),
-            Whitespace(" "),
-            Literal("\"Sales\"")
-        ]);
-    }
-
-    #[wasm_bindgen_test]
-    fn test_complex_string() {
-        let s = "'this is 'a \"test of\" strings";
-        assert_eq!(tokenize(s), vec![
-            Column("'this is '"),
-            Symbol("a"),
-            Whitespace(" "),
-            Literal("\"test of\""),
-            Whitespace(" "),
-            Symbol("strings"),
-        ]);
-    }
-
-    #[wasm_bindgen_test]
-    fn test_comment_newline() {
-        let s = "// Title\n1 + 2";
-        assert_eq!(tokenize(s), vec![
-            Comment("// Title"),
-            Break("\n"),
-            Literal("1"),
-            Whitespace(" "),
-            Operator("+"),
-            Whitespace(" "),
-            Literal("2"),
-        ]);
-    }
-
-    #[wasm_bindgen_test]
-    fn test_escape_strings() {
-        let s = "'test\\/'";
-        assert_eq!(tokenize(s), vec![Literal("'test\\/'"),]);
-    }
-}
+mod tests;
@@ -0,0 +1,66 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use super::*;
+
+#[test]
+fn test_simple() {
+    let s = "123 abc 'hello' \"Sales\"";
+    assert_eq!(tokenize(s), vec![
+        Literal("123"),
+        Whitespace(" "),
+        Symbol("abc"),
+        Whitespace(" "),
+        Literal("'hello'"),
+        Whitespace(" "),
+        Column("\"Sales\"")
+    ]);
+}
+
+#[test]
+fn test_complex_string() {
+    let s = "'this is 'a \"test of\" strings";
+    assert_eq!(tokenize(s), vec![
+        Literal("'this is '"),
+        Symbol("a"),
+        Whitespace(" "),
+        Column("\"test of\""),
+        Whitespace(" "),
+        Symbol("strings"),
+    ]);
+}
+
+#[test]
+fn test_comment_newline() {
+    let s = "// Title\n1 + 2";
+    assert_eq!(tokenize(s), vec![
+        Comment("// Title"),
+        Break("\n"),
+        Literal("1"),
+        Whitespace(" "),
+        Operator("+"),
+        Whitespace(" "),
+        Literal("2"),
+    ]);
+}
+
+#[test]
+fn test_escape_strings() {
+    let s = "'test\\/'";
+    assert_eq!(tokenize(s), vec![Literal("'test\\/'"),]);
+}
+
+#[test]
+fn test_multiline_string() {
+    let s = "'a\nb'";
+    assert_eq!(tokenize(s), vec![Literal("'a"), Break("\n"), Literal("b'")]);
+}
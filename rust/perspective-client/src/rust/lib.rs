@@ -36,12 +36,14 @@
 
 mod client;
 mod session;
+mod subscription;
 mod table;
 mod table_data;
 mod view;
 pub mod virtual_server;
 
 pub mod config;
+pub mod exprtk;
 
 #[rustfmt::skip]
 #[allow(clippy::all)]
@@ -49,9 +51,12 @@ pub mod proto;
 
 pub mod utils;
 
-pub use crate::client::{Client, ClientHandler, Features, ReconnectCallback, SystemInfo};
+pub use crate::client::{
+    Client, ClientHandler, Features, ReconnectCallback, RequestOptions, SystemInfo,
+};
 use crate::proto::HostedTable;
 pub use crate::session::{ProxySession, Session};
+pub use crate::subscription::{OverflowPolicy, Subscription, SubscriptionOptions};
 pub use crate::table::{
     DeleteOptions, ExprValidationResult, Table, TableInitOptions, TableReadFormat, UpdateOptions,
 };
@@ -123,6 +128,7 @@ macro_rules! assert_view_api {
             fn [< _assert_table_api_ $x:lower >]() {
                 let _ = (
                     &$x::column_paths,
+                    &$x::column_paths_structured,
                     &$x::delete,
                     &$x::dimensions,
                     &$x::expression_schema,
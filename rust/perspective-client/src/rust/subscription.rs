@@ -0,0 +1,207 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures::Stream;
+
+use crate::client::Client;
+use crate::proto::Request;
+
+/// What a [`Subscription`] does with a new event when its buffer is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered event to make room for the new one.
+    #[default]
+    DropOldest,
+
+    /// Discard the new event.
+    DropNewest,
+}
+
+/// Options for the buffer of a [`Subscription`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionOptions {
+    /// The number of events buffered before `overflow` applies.
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for SubscriptionOptions {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+struct Buffer<T> {
+    items: VecDeque<T>,
+    options: SubscriptionOptions,
+    lagged: u64,
+    waker: Option<Waker>,
+
+    /// Whether the [`SubscriptionSender`] was dropped, e.g. because the
+    /// subscription responds only once.
+    closed: bool,
+
+    /// Whether the [`Subscription`] was dropped.
+    dropped: bool,
+}
+
+/// A [`Stream`] of the events of a subscription, e.g. [`View::updates`].
+///
+/// When the stream's buffer is full, new events are handled according to
+/// its [`SubscriptionOptions::overflow`] policy, and counted by
+/// [`Subscription::lagged`].
+///
+/// Dropping a [`Subscription`] unsubscribes it: later events are discarded,
+/// and the server is notified along with the [`Client`]'s next request or
+/// event.
+///
+/// [`View::updates`]: crate::View::updates
+pub struct Subscription<T> {
+    buffer: Arc<Mutex<Buffer<T>>>,
+    unsubscribe: Option<(Client, u32, Request)>,
+}
+
+/// The sending half of a [`Subscription`], held by its callback.
+pub(crate) struct SubscriptionSender<T>(Arc<SenderGuard<T>>);
+
+struct SenderGuard<T>(Arc<Mutex<Buffer<T>>>);
+
+impl<T> Clone for SubscriptionSender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Drop for SenderGuard<T> {
+    fn drop(&mut self) {
+        if let Ok(mut buffer) = self.0.lock() {
+            buffer.closed = true;
+            if let Some(waker) = buffer.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> SubscriptionSender<T> {
+    /// Buffers `item`, returning `false` if the [`Subscription`] was dropped.
+    pub(crate) fn send(&self, item: T) -> bool {
+        let Ok(mut buffer) = self.0.0.lock() else {
+            return false;
+        };
+
+        if buffer.dropped {
+            return false;
+        }
+
+        if buffer.items.len() >= buffer.options.capacity.max(1) {
+            buffer.lagged += 1;
+            match buffer.options.overflow {
+                OverflowPolicy::DropOldest => {
+                    buffer.items.pop_front();
+                },
+                OverflowPolicy::DropNewest => return true,
+            }
+        }
+
+        buffer.items.push_back(item);
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+
+        true
+    }
+}
+
+/// Creates a [`Subscription`] and the [`SubscriptionSender`] for its
+/// callback.
+pub(crate) fn channel<T>(options: SubscriptionOptions) -> (SubscriptionSender<T>, Subscription<T>) {
+    let buffer = Arc::new(Mutex::new(Buffer {
+        items: VecDeque::new(),
+        options,
+        lagged: 0,
+        waker: None,
+        closed: false,
+        dropped: false,
+    }));
+
+    let sender = SubscriptionSender(Arc::new(SenderGuard(buffer.clone())));
+    let subscription = Subscription {
+        buffer,
+        unsubscribe: None,
+    };
+
+    (sender, subscription)
+}
+
+impl<T> Subscription<T> {
+    /// Unsubscribe `update_id` from `client` with the `remove` request when
+    /// this [`Subscription`] is dropped.
+    pub(crate) fn unsubscribe_on_drop(
+        mut self,
+        client: Client,
+        update_id: u32,
+        remove: Request,
+    ) -> Self {
+        self.unsubscribe = Some((client, update_id, remove));
+        self
+    }
+
+    /// The number of events discarded because the buffer was full.
+    pub fn lagged(&self) -> u64 {
+        self.buffer.lock().map_or(0, |buffer| buffer.lagged)
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let Ok(mut buffer) = self.buffer.lock() else {
+            return Poll::Ready(None);
+        };
+
+        if let Some(item) = buffer.items.pop_front() {
+            Poll::Ready(Some(item))
+        } else if buffer.closed {
+            Poll::Ready(None)
+        } else {
+            buffer.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        let closed = match self.buffer.lock() {
+            Ok(mut buffer) => {
+                buffer.dropped = true;
+                buffer.items.clear();
+                buffer.closed
+            },
+            Err(_) => true,
+        };
+
+        if let (false, Some((client, update_id, remove))) = (closed, self.unsubscribe.take()) {
+            client.drop_subscription(update_id, remove);
+        }
+    }
+}
@@ -24,6 +24,7 @@ use crate::proto::make_table_req::make_table_options::MakeTableType;
 use crate::proto::request::ClientReq;
 use crate::proto::response::ClientResp;
 use crate::proto::*;
+use crate::subscription::{self, Subscription, SubscriptionOptions};
 use crate::table_data::UpdateData;
 use crate::utils::*;
 use crate::view::View;
@@ -293,7 +294,10 @@ impl Table {
         }));
 
         match self.client.oneshot(&msg).await? {
-            ClientResp::TableDeleteResp(_) => Ok(()),
+            ClientResp::TableDeleteResp(_) => {
+                self.client.forget_entity(&self.name).await;
+                Ok(())
+            },
             resp => Err(resp.into()),
         }
     }
@@ -387,10 +391,36 @@ impl Table {
         };
 
         let msg = self.client_message(ClientReq::TableOnDeleteReq(TableOnDeleteReq {}));
-        self.client.subscribe_once(&msg, Box::new(callback)).await?;
+        self.client
+            .subscribe_once_replayed(&msg, Box::new(callback))
+            .await?;
         Ok(msg.msg_id)
     }
 
+    /// A [`Subscription`] which yields once when this [`Table`] is deleted,
+    /// like [`Table::on_delete`], and unsubscribes when dropped.
+    pub async fn deletes(&self) -> ClientResult<Subscription<()>> {
+        let (sender, receiver) = subscription::channel(SubscriptionOptions::default());
+        let callback = move |resp: Response| match resp.client_resp {
+            Some(ClientResp::TableOnDeleteResp(_)) => {
+                sender.send(());
+                Ok(())
+            },
+            resp => Err(resp.into()),
+        };
+
+        let msg = self.client_message(ClientReq::TableOnDeleteReq(TableOnDeleteReq {}));
+        self.client
+            .subscribe_once_replayed(&msg, Box::new(callback))
+            .await?;
+
+        let remove = self.client_message(ClientReq::TableRemoveDeleteReq(TableRemoveDeleteReq {
+            id: msg.msg_id,
+        }));
+
+        Ok(receiver.unsubscribe_on_drop(self.client.clone(), msg.msg_id, remove))
+    }
+
     /// Removes a listener with a given ID, as returned by a previous call to
     /// [`Table::on_delete`].
     pub async fn remove_delete(&self, callback_id: u32) -> ClientResult<()> {
@@ -398,6 +428,7 @@ impl Table {
             id: callback_id,
         }));
 
+        self.client.forget_request(callback_id).await;
         match self.client.oneshot(&msg).await? {
             ClientResp::TableRemoveDeleteResp(_) => Ok(()),
             resp => Err(resp.into()),
@@ -492,6 +523,16 @@ impl Table {
         }
     }
 
+    /// Updates this [`Table`] with `rows` of a type which serializes as a
+    /// map, such as a struct, as [`UpdateData::from_rows`].
+    pub async fn update_rows<T: Serialize>(
+        &self,
+        rows: &[T],
+        options: UpdateOptions,
+    ) -> ClientResult<()> {
+        self.update(UpdateData::from_rows(rows)?, options).await
+    }
+
     /// Validates the given expressions.
     pub async fn validate_expressions(
         &self,
@@ -553,6 +594,7 @@ impl Table {
             ClientResp::TableMakeViewResp(TableMakeViewResp { view_id })
                 if view_id == view_name =>
             {
+                self.client.replay_on_reconnect(&msg).await;
                 Ok(View::new(view_name, self.client.clone()))
             },
             resp => Err(resp.into()),
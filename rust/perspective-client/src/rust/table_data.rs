@@ -10,10 +10,14 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
+use indexmap::IndexMap;
 use prost::bytes::Bytes;
+use serde::Serialize;
+use serde_json::Value;
 
 use crate::proto;
 use crate::proto::*;
+use crate::utils::{ClientError, ClientResult};
 use crate::view::View;
 #[cfg(doc)]
 use crate::{Client, Table};
@@ -77,3 +81,78 @@ impl From<UpdateData> for proto::MakeTableData {
         MakeTableData { data: Some(data) }
     }
 }
+
+impl UpdateData {
+    /// Serializes `rows` of a type which serializes as a map, such as a
+    /// struct, as [`UpdateData::JsonRows`].
+    pub fn from_rows<T: Serialize>(rows: &[T]) -> ClientResult<Self> {
+        Ok(UpdateData::JsonRows(serde_json::to_string(rows)?))
+    }
+}
+
+impl TableData {
+    /// A [`TableData::Schema`] for `rows` of a type which serializes as a
+    /// map, such as a struct, with a column for each of its fields in order.
+    ///
+    /// A field's type is derived from how it serializes: `bool` is
+    /// [`ColumnType::Boolean`], integers are [`ColumnType::Integer`], floats
+    /// are [`ColumnType::Float`], and strings (including unit enum variants
+    /// and most date types) are [`ColumnType::String`]. `None` fields take
+    /// their type from the other `rows`.
+    pub fn schema_from_rows<T: Serialize>(rows: &[T]) -> ClientResult<Self> {
+        let json = serde_json::to_string(rows)?;
+        Ok(TableData::Schema(rows_schema(&json)?))
+    }
+}
+
+/// The schema of `json`, a JSON array of row objects as serialized by
+/// [`UpdateData::from_rows`].
+pub(crate) fn rows_schema(json: &str) -> ClientResult<Vec<(String, ColumnType)>> {
+    let rows: Vec<IndexMap<String, Value>> = serde_json::from_str(json)?;
+    let mut schema = IndexMap::<String, Option<ColumnType>>::new();
+    for (name, value) in rows.into_iter().flatten() {
+        let dtype = match value {
+            Value::Null => None,
+            Value::Bool(_) => Some(ColumnType::Boolean),
+            Value::Number(x) if x.is_f64() => Some(ColumnType::Float),
+            Value::Number(_) => Some(ColumnType::Integer),
+            Value::String(_) => Some(ColumnType::String),
+            Value::Array(_) | Value::Object(_) => {
+                return Err(ClientError::SchemaError(format!(
+                    "Column \"{name}\" is not a scalar"
+                )));
+            },
+        };
+
+        let column = schema.entry(name.clone()).or_default();
+        *column = match (*column, dtype) {
+            (Some(ColumnType::Integer), Some(ColumnType::Float))
+            | (Some(ColumnType::Float), Some(ColumnType::Integer)) => Some(ColumnType::Float),
+            (Some(x), Some(y)) if x != y => {
+                return Err(ClientError::SchemaError(format!(
+                    "Column \"{name}\" is both {} and {}",
+                    x.as_str_name(),
+                    y.as_str_name()
+                )));
+            },
+            (x, y) => x.or(y),
+        };
+    }
+
+    if schema.is_empty() {
+        return Err(ClientError::SchemaError("No columns".to_string()));
+    }
+
+    schema
+        .into_iter()
+        .map(|(name, dtype)| match dtype {
+            Some(dtype) => Ok((name, dtype)),
+            None => Err(ClientError::SchemaError(format!(
+                "Column \"{name}\" has no non-null values"
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
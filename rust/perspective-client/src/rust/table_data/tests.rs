@@ -0,0 +1,97 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use serde::Serialize;
+
+use crate::proto::ColumnType;
+use crate::{ClientError, TableData, UpdateData};
+
+#[derive(Serialize)]
+enum Side {
+    Buy,
+}
+
+#[derive(Serialize)]
+struct Trade {
+    price: f64,
+    size: u32,
+    side: Side,
+    filled: bool,
+    note: Option<String>,
+}
+
+fn schema_of<T: Serialize>(rows: &[T]) -> Result<Vec<(String, ColumnType)>, ClientError> {
+    TableData::schema_from_rows(rows).map(|data| match data {
+        TableData::Schema(schema) => schema,
+        _ => vec![],
+    })
+}
+
+#[test]
+fn test_schema_from_rows() {
+    let rows = [
+        Trade {
+            price: 1.0,
+            size: 1,
+            side: Side::Buy,
+            filled: false,
+            note: None,
+        },
+        Trade {
+            price: 2.5,
+            size: 2,
+            side: Side::Buy,
+            filled: true,
+            note: Some("a".to_string()),
+        },
+    ];
+
+    assert_eq!(schema_of(&rows).unwrap(), [
+        ("price".to_string(), ColumnType::Float),
+        ("size".to_string(), ColumnType::Integer),
+        ("side".to_string(), ColumnType::String),
+        ("filled".to_string(), ColumnType::Boolean),
+        ("note".to_string(), ColumnType::String),
+    ]);
+
+    assert!(matches!(
+        UpdateData::from_rows(&rows).unwrap(),
+        UpdateData::JsonRows(json) if json.starts_with(r#"[{"price":1.0,"size":1,"#)
+    ));
+}
+
+#[test]
+fn test_schema_from_rows_errors() {
+    let rows = [Trade {
+        price: 1.0,
+        size: 1,
+        side: Side::Buy,
+        filled: false,
+        note: None,
+    }];
+
+    assert!(matches!(schema_of(&rows), Err(ClientError::SchemaError(_))));
+    assert!(matches!(
+        schema_of::<Trade>(&[]),
+        Err(ClientError::SchemaError(_))
+    ));
+
+    assert!(matches!(
+        schema_of(&[vec![1]]),
+        Err(ClientError::SerdeJsonError(_))
+    ));
+
+    assert!(matches!(
+        schema_of(&[serde_json::json!({"x": 1}), serde_json::json!({"x": "a"})]),
+        Err(ClientError::SchemaError(_))
+    ));
+}
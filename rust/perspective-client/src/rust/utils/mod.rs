@@ -45,6 +45,18 @@ pub enum ClientError {
     #[error("Transport error: {0}")]
     TransportError(String),
 
+    #[error("{0}")]
+    UnsupportedOperation(String),
+
+    #[error("{0}")]
+    InvalidExpression(String),
+
+    #[error("{0}")]
+    InvalidConfig(String),
+
+    #[error("{0}")]
+    BackendUnavailable(String),
+
     #[error("Client not yet initialized")]
     NotInitialized,
 
@@ -63,6 +75,9 @@ pub enum ClientError {
     #[error("Response aborted")]
     ResponseAborted,
 
+    #[error("Request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
     #[error("Unexpected response {0:?}")]
     ResponseFailed(Box<proto::response::ClientResp>),
 
@@ -81,6 +96,12 @@ pub enum ClientError {
     #[error("Duplicate name {0}")]
     DuplicateNameError(String),
 
+    #[error("Invalid JSON: {0}")]
+    SerdeJsonError(Arc<serde_json::Error>),
+
+    #[error("Can't derive a schema: {0}")]
+    SchemaError(String),
+
     #[error("{0}")]
     TimeError(#[from] SystemTimeError),
 }
@@ -93,20 +114,38 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for ClientError {
     }
 }
 
+impl From<serde_json::Error> for ClientError {
+    fn from(value: serde_json::Error) -> Self {
+        ClientError::SerdeJsonError(Arc::new(value))
+    }
+}
+
 impl<'a, A> From<std::sync::PoisonError<std::sync::MutexGuard<'a, A>>> for ClientError {
     fn from(_: std::sync::PoisonError<std::sync::MutexGuard<'a, A>>) -> Self {
         ClientError::Internal("Lock Error".to_owned())
     }
 }
 
+impl From<proto::ServerError> for ClientError {
+    fn from(value: proto::ServerError) -> Self {
+        match value.status_code() {
+            proto::StatusCode::ServerError => ClientError::Internal(value.message),
+            proto::StatusCode::ViewNotFound => ClientError::ViewNotFound,
+            proto::StatusCode::TransportError => ClientError::TransportError(value.message),
+            proto::StatusCode::UnsupportedOperation => {
+                ClientError::UnsupportedOperation(value.message)
+            },
+            proto::StatusCode::InvalidExpression => ClientError::InvalidExpression(value.message),
+            proto::StatusCode::InvalidConfig => ClientError::InvalidConfig(value.message),
+            proto::StatusCode::BackendUnavailable => ClientError::BackendUnavailable(value.message),
+        }
+    }
+}
+
 impl From<Option<proto::response::ClientResp>> for ClientError {
     fn from(value: Option<proto::response::ClientResp>) -> Self {
         match value {
-            Some(proto::response::ClientResp::ServerError(x)) => match x.status_code() {
-                proto::StatusCode::ServerError => ClientError::Internal(x.message),
-                proto::StatusCode::ViewNotFound => ClientError::ViewNotFound,
-                proto::StatusCode::TransportError => ClientError::TransportError(x.message),
-            },
+            Some(proto::response::ClientResp::ServerError(x)) => x.into(),
             Some(x) => ClientError::ResponseFailed(Box::new(x)),
             None => ClientError::ResponseAborted,
         }
@@ -116,11 +155,7 @@ impl From<Option<proto::response::ClientResp>> for ClientError {
 impl From<proto::response::ClientResp> for ClientError {
     fn from(value: proto::response::ClientResp) -> Self {
         match value {
-            proto::response::ClientResp::ServerError(x) => match x.status_code() {
-                proto::StatusCode::ServerError => ClientError::Internal(x.message),
-                proto::StatusCode::ViewNotFound => ClientError::ViewNotFound,
-                proto::StatusCode::TransportError => ClientError::TransportError(x.message),
-            },
+            proto::response::ClientResp::ServerError(x) => x.into(),
             x => ClientError::ResponseFailed(Box::new(x)),
         }
     }
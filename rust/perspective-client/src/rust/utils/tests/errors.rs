@@ -0,0 +1,55 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use crate::proto::response::ClientResp;
+use crate::proto::{ServerError, StatusCode};
+use crate::*;
+
+fn server_error(status_code: StatusCode) -> ClientError {
+    ClientResp::ServerError(ServerError {
+        message: "message".to_string(),
+        status_code: status_code as i32,
+    })
+    .into()
+}
+
+#[test]
+fn test_server_error_status_codes() {
+    assert!(matches!(
+        server_error(StatusCode::ServerError),
+        ClientError::Internal(_)
+    ));
+
+    assert!(matches!(
+        server_error(StatusCode::ViewNotFound),
+        ClientError::ViewNotFound
+    ));
+
+    assert!(matches!(
+        server_error(StatusCode::UnsupportedOperation),
+        ClientError::UnsupportedOperation(_)
+    ));
+
+    assert!(matches!(
+        server_error(StatusCode::InvalidExpression),
+        ClientError::InvalidExpression(_)
+    ));
+
+    assert!(matches!(
+        server_error(StatusCode::InvalidConfig),
+        ClientError::InvalidConfig(_)
+    ));
+
+    let err = server_error(StatusCode::BackendUnavailable);
+    assert!(matches!(err, ClientError::BackendUnavailable(_)));
+    assert_eq!(err.to_string(), "message");
+}
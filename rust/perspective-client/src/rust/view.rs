@@ -17,15 +17,17 @@ use std::sync::Arc;
 
 use futures::Future;
 use prost::bytes::Bytes;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use self::view_on_update_req::Mode;
 use crate::assert_view_api;
-use crate::client::Client;
+use crate::client::{Client, RequestOptions};
 use crate::proto::request::ClientReq;
 use crate::proto::response::ClientResp;
 use crate::proto::*;
+use crate::subscription::{self, Subscription, SubscriptionOptions};
 #[cfg(doc)]
 use crate::table::Table;
 pub use crate::utils::*;
@@ -268,9 +270,42 @@ impl View {
         }));
 
         match self.client.oneshot(&msg).await? {
-            ClientResp::ViewColumnPathsResp(ViewColumnPathsResp { paths }) => {
-                // Ok(paths.into_iter().map(|x| x.path).collect())
-                Ok(paths)
+            ClientResp::ViewColumnPathsResp(ViewColumnPathsResp { paths, .. }) => Ok(paths),
+            resp => Err(resp.into()),
+        }
+    }
+
+    /// Returns the column paths of the [`View`] as in
+    /// [`View::column_paths`], but with each path as a list of its elements,
+    /// one per `split_by` level followed by the source column name.
+    ///
+    /// Unlike [`View::column_paths`], which joins these elements with `|`,
+    /// this form is unambiguous for column names and `split_by` values which
+    /// themselves contain `|`.
+    pub async fn column_paths_structured(
+        &self,
+        window: ColumnWindow,
+    ) -> ClientResult<Vec<Vec<String>>> {
+        let msg = self.client_message(ClientReq::ViewColumnPathsReq(ViewColumnPathsReq {
+            start_col: window.start_col.map(|x| x as u32),
+            end_col: window.end_col.map(|x| x as u32),
+        }));
+
+        match self.client.oneshot(&msg).await? {
+            ClientResp::ViewColumnPathsResp(ViewColumnPathsResp {
+                paths,
+                structured_paths,
+            }) => {
+                // Servers which predate `structured_paths` only send the
+                // `|`-joined form.
+                if structured_paths.is_empty() && !paths.is_empty() {
+                    Ok(paths
+                        .iter()
+                        .map(|x| x.split('|').map(|x| x.to_owned()).collect())
+                        .collect())
+                } else {
+                    Ok(structured_paths.into_iter().map(|x| x.path).collect())
+                }
             },
             resp => Err(resp.into()),
         }
@@ -364,12 +399,21 @@ impl View {
 
     /// Serializes a [`View`] to the Apache Arrow data format.
     pub async fn to_arrow(&self, window: ViewWindow) -> ClientResult<Bytes> {
+        self.to_arrow_with(window, RequestOptions::default()).await
+    }
+
+    /// Like [`View::to_arrow`], with [`RequestOptions`] such as a timeout.
+    pub async fn to_arrow_with(
+        &self,
+        window: ViewWindow,
+        options: RequestOptions,
+    ) -> ClientResult<Bytes> {
         let msg = self.client_message(ClientReq::ViewToArrowReq(ViewToArrowReq {
             viewport: Some(window.clone().into()),
             compression: window.compression,
         }));
 
-        match self.client.oneshot(&msg).await? {
+        match self.client.oneshot_with(&msg, &options).await? {
             ClientResp::ViewToArrowResp(ViewToArrowResp { arrow }) => Ok(arrow.into()),
             resp => Err(resp.into()),
         }
@@ -378,6 +422,17 @@ impl View {
     /// Serializes this [`View`] to a string of JSON data. Useful if you want to
     /// save additional round trip serialize/deserialize cycles.    
     pub async fn to_columns_string(&self, window: ViewWindow) -> ClientResult<String> {
+        self.to_columns_string_with(window, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`View::to_columns_string`], with [`RequestOptions`] such as a
+    /// timeout.
+    pub async fn to_columns_string_with(
+        &self,
+        window: ViewWindow,
+        options: RequestOptions,
+    ) -> ClientResult<String> {
         let msg = self.client_message(ClientReq::ViewToColumnsStringReq(ViewToColumnsStringReq {
             viewport: Some(window.clone().into()),
             id: window.id,
@@ -386,7 +441,7 @@ impl View {
             leaves_only: window.leaves_only,
         }));
 
-        match self.client.oneshot(&msg).await? {
+        match self.client.oneshot_with(&msg, &options).await? {
             ClientResp::ViewToColumnsStringResp(ViewToColumnsStringResp { json_string }) => {
                 Ok(json_string)
             },
@@ -396,6 +451,17 @@ impl View {
 
     /// Render this `View` as a JSON string.
     pub async fn to_json_string(&self, window: ViewWindow) -> ClientResult<String> {
+        self.to_json_string_with(window, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`View::to_json_string`], with [`RequestOptions`] such as a
+    /// timeout.
+    pub async fn to_json_string_with(
+        &self,
+        window: ViewWindow,
+        options: RequestOptions,
+    ) -> ClientResult<String> {
         let viewport = ViewPort::from(window.clone());
         let msg = self.client_message(ClientReq::ViewToRowsStringReq(ViewToRowsStringReq {
             viewport: Some(viewport),
@@ -405,7 +471,7 @@ impl View {
             leaves_only: window.leaves_only,
         }));
 
-        match self.client.oneshot(&msg).await? {
+        match self.client.oneshot_with(&msg, &options).await? {
             ClientResp::ViewToRowsStringResp(ViewToRowsStringResp { json_string }) => {
                 Ok(json_string)
             },
@@ -413,9 +479,47 @@ impl View {
         }
     }
 
+    /// Deserializes the rows of this [`View`] as [`View::to_json_string`]
+    /// renders them, e.g. into structs with a field for each column.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Row {
+    ///     x: i32,
+    ///     y: Option<String>,
+    /// }
+    ///
+    /// let rows = view.to_rows::<Row>(ViewWindow::default()).await?;
+    /// ```
+    pub async fn to_rows<T: DeserializeOwned>(&self, window: ViewWindow) -> ClientResult<Vec<T>> {
+        let json = self.to_json_string(window).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Deserializes the columns of this [`View`] as
+    /// [`View::to_columns_string`] renders them, e.g. into a struct with a
+    /// `Vec` field for each column.
+    pub async fn to_columns<T: DeserializeOwned>(&self, window: ViewWindow) -> ClientResult<T> {
+        let json = self.to_columns_string(window).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
     /// Renders this [`View`] as an [NDJSON](https://github.com/ndjson/ndjson-spec)
     /// formatted [`String`].
     pub async fn to_ndjson(&self, window: ViewWindow) -> ClientResult<String> {
+        self.to_ndjson_with(window, RequestOptions::default()).await
+    }
+
+    /// Like [`View::to_ndjson`], with [`RequestOptions`] such as a timeout.
+    pub async fn to_ndjson_with(
+        &self,
+        window: ViewWindow,
+        options: RequestOptions,
+    ) -> ClientResult<String> {
         let viewport = ViewPort::from(window.clone());
         let msg = self.client_message(ClientReq::ViewToNdjsonStringReq(ViewToNdjsonStringReq {
             viewport: Some(viewport),
@@ -425,7 +529,7 @@ impl View {
             leaves_only: window.leaves_only,
         }));
 
-        match self.client.oneshot(&msg).await? {
+        match self.client.oneshot_with(&msg, &options).await? {
             ClientResp::ViewToNdjsonStringResp(ViewToNdjsonStringResp { ndjson_string }) => {
                 Ok(ndjson_string)
             },
@@ -435,11 +539,20 @@ impl View {
 
     /// Serializes this [`View`] to CSV data in a standard format.
     pub async fn to_csv(&self, window: ViewWindow) -> ClientResult<String> {
+        self.to_csv_with(window, RequestOptions::default()).await
+    }
+
+    /// Like [`View::to_csv`], with [`RequestOptions`] such as a timeout.
+    pub async fn to_csv_with(
+        &self,
+        window: ViewWindow,
+        options: RequestOptions,
+    ) -> ClientResult<String> {
         let msg = self.client_message(ClientReq::ViewToCsvReq(ViewToCsvReq {
             viewport: Some(window.into()),
         }));
 
-        match self.client.oneshot(&msg).await? {
+        match self.client.oneshot_with(&msg, &options).await? {
             ClientResp::ViewToCsvResp(ViewToCsvResp { csv }) => Ok(csv),
             resp => Err(resp.into()),
         }
@@ -452,7 +565,10 @@ impl View {
     pub async fn delete(&self) -> ClientResult<()> {
         let msg = self.client_message(ClientReq::ViewDeleteReq(ViewDeleteReq {}));
         match self.client.oneshot(&msg).await? {
-            ClientResp::ViewDeleteResp(_) => Ok(()),
+            ClientResp::ViewDeleteResp(_) => {
+                self.client.forget_entity(&self.name).await;
+                Ok(())
+            },
             resp => Err(resp.into()),
         }
     }
@@ -515,6 +631,64 @@ impl View {
         Ok(msg.msg_id)
     }
 
+    /// A [`Subscription`] to the updates of this [`View`], as delivered to
+    /// [`View::on_update`], which unsubscribes when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use futures::StreamExt;
+    ///
+    /// let mut updates = view.updates(OnUpdateOptions::default()).await?;
+    /// while let Some(update) = updates.next().await {
+    ///     println!("Updated port {}", update.port_id);
+    /// }
+    /// ```
+    pub async fn updates(
+        &self,
+        options: OnUpdateOptions,
+    ) -> ClientResult<Subscription<OnUpdateData>> {
+        self.updates_with(options, SubscriptionOptions::default())
+            .await
+    }
+
+    /// Like [`View::updates`], with [`SubscriptionOptions`] for its buffer.
+    pub async fn updates_with(
+        &self,
+        options: OnUpdateOptions,
+        subscription: SubscriptionOptions,
+    ) -> ClientResult<Subscription<OnUpdateData>> {
+        let (sender, receiver) = subscription::channel(subscription);
+        let client = self.client.clone();
+        let callback = move |resp: Response| {
+            let sender = sender.clone();
+            let client = client.clone();
+            async move {
+                match resp.client_resp {
+                    Some(ClientResp::ViewOnUpdateResp(resp)) => {
+                        if !sender.send(OnUpdateData(resp)) {
+                            client.flush_dropped_subscriptions().await?;
+                        }
+
+                        Ok(())
+                    },
+                    resp => Err(resp.into()),
+                }
+            }
+        };
+
+        let msg = self.client_message(ClientReq::ViewOnUpdateReq(ViewOnUpdateReq {
+            mode: options.mode.map(|OnUpdateMode::Row| Mode::Row as i32),
+        }));
+
+        self.client.subscribe(&msg, callback).await?;
+        let remove = self.client_message(ClientReq::ViewRemoveOnUpdateReq(ViewRemoveOnUpdateReq {
+            id: msg.msg_id,
+        }));
+
+        Ok(receiver.unsubscribe_on_drop(self.client.clone(), msg.msg_id, remove))
+    }
+
     /// Unregister a previously registered update callback with this [`View`].
     ///
     /// # Arguments
@@ -556,7 +730,9 @@ impl View {
         };
 
         let msg = self.client_message(ClientReq::ViewOnDeleteReq(ViewOnDeleteReq {}));
-        self.client.subscribe_once(&msg, Box::new(callback)).await?;
+        self.client
+            .subscribe_once_replayed(&msg, Box::new(callback))
+            .await?;
         Ok(msg.msg_id)
     }
 
@@ -566,6 +742,7 @@ impl View {
             id: callback_id,
         }));
 
+        self.client.forget_request(callback_id).await;
         match self.client.oneshot(&msg).await? {
             ClientResp::ViewRemoveDeleteResp(ViewRemoveDeleteResp {}) => Ok(()),
             resp => Err(resp.into()),
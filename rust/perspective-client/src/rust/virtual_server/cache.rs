@@ -0,0 +1,719 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::collections::HashMap;
+use std::future::ready;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use indexmap::IndexMap;
+
+use super::data::VirtualDataSlice;
+use super::diagnostic::ExpressionDiagnostic;
+use super::features::Features;
+use super::handler::{VirtualServerFuture, VirtualServerHandler};
+use super::notifier::VirtualServerNotifier;
+use crate::config::{Scalar, ViewConfig, ViewConfigUpdate};
+use crate::proto::{
+    ColumnType, HostedTable, MakeTableData, StatusCode, TableMakePortReq, ViewPort,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Options for a [`CachingVirtualServerHandler`].
+#[derive(Clone, Debug)]
+pub struct CacheOptions {
+    /// The number of rows of a view fetched and cached together. Defaults to
+    /// `1000`.
+    pub block_size: u32,
+
+    /// The number of blocks before and after a requested viewport which are
+    /// fetched along with it, so that scrolling can be served from the
+    /// cache. Defaults to `1`.
+    pub prefetch_blocks: u32,
+
+    /// The maximum number of values (rows times columns) of view data to
+    /// cache, across all views. The least recently used blocks are evicted
+    /// beyond this limit. Defaults to `1_000_000`.
+    pub max_cells: usize,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        CacheOptions {
+            block_size: 1000,
+            prefetch_blocks: 1,
+            max_cells: 1_000_000,
+        }
+    }
+}
+
+/// The cached results of a table or view. `generation` changes whenever they
+/// are invalidated, so that a result fetched across an invalidation is not
+/// cached.
+#[derive(Default)]
+struct TableCache {
+    generation: u64,
+    size: Option<u32>,
+    column_size: Option<u32>,
+    schema: Option<IndexMap<String, ColumnType>>,
+}
+
+#[derive(Default)]
+struct ViewCache {
+    table_id: Option<String>,
+    generation: u64,
+    size: Option<u32>,
+    column_size: Option<u32>,
+    schema: Option<IndexMap<String, ColumnType>>,
+}
+
+/// A block of `CacheOptions::block_size` rows of a view, for a column range.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct BlockKey {
+    view_id: String,
+    start_col: Option<u32>,
+    end_col: Option<u32>,
+    block: usize,
+}
+
+#[derive(Default)]
+struct CacheState {
+    tables: HashMap<String, TableCache>,
+    views: HashMap<String, ViewCache>,
+
+    /// Cached blocks, from least to most recently used.
+    blocks: IndexMap<BlockKey, VirtualDataSlice>,
+    cells: usize,
+
+    /// The last generation assigned to a table or view.
+    generation: u64,
+}
+
+impl CacheState {
+    fn next_generation(&mut self) -> u64 {
+        self.generation += 1;
+        self.generation
+    }
+
+    fn table_generation(&self, table_id: &str) -> Option<u64> {
+        self.tables.get(table_id).map(|table| table.generation)
+    }
+
+    fn view_generation(&self, view_id: &str) -> Option<u64> {
+        self.views.get(view_id).map(|view| view.generation)
+    }
+
+    /// The cache of `table_id`, unless it has been invalidated since
+    /// `generation`.
+    fn table_since(&mut self, table_id: &str, generation: Option<u64>) -> Option<&mut TableCache> {
+        (self.table_generation(table_id) == generation)
+            .then(|| self.tables.entry(table_id.to_owned()).or_default())
+    }
+
+    /// The cache of `view_id`, unless it has been invalidated since
+    /// `generation`.
+    fn view_since(&mut self, view_id: &str, generation: Option<u64>) -> Option<&mut ViewCache> {
+        (self.view_generation(view_id) == generation)
+            .then(|| self.views.entry(view_id.to_owned()).or_default())
+    }
+
+    fn touch_block(&mut self, key: &BlockKey) -> Option<&VirtualDataSlice> {
+        let idx = self.blocks.get_index_of(key)?;
+        let last = self.blocks.len() - 1;
+        self.blocks.move_index(idx, last);
+        self.blocks.get_index(last).map(|(_, block)| block)
+    }
+
+    fn insert_block(&mut self, key: BlockKey, block: VirtualDataSlice, max_cells: usize) {
+        let cells = block.cell_count();
+        if cells > max_cells {
+            return;
+        }
+
+        while self.cells + cells > max_cells {
+            match self.blocks.shift_remove_index(0) {
+                Some((_, evicted)) => self.cells -= evicted.cell_count(),
+                None => break,
+            }
+        }
+
+        self.cells += cells;
+        if let Some(replaced) = self.blocks.insert(key, block) {
+            self.cells -= replaced.cell_count();
+        }
+    }
+
+    fn remove_blocks(&mut self, view_id: &str) {
+        let mut cells = 0;
+        self.blocks.retain(|key, block| {
+            let keep = key.view_id != view_id;
+            if !keep {
+                cells += block.cell_count();
+            }
+
+            keep
+        });
+
+        self.cells -= cells;
+    }
+
+    /// Forgets the rows of `view_id`, e.g. after a group is expanded.
+    fn invalidate_view_rows(&mut self, view_id: &str) {
+        let generation = self.next_generation();
+        if let Some(view) = self.views.get_mut(view_id) {
+            view.generation = generation;
+            view.size = None;
+        }
+
+        self.remove_blocks(view_id);
+    }
+
+    /// Forgets everything about `table_id` and its views except which table
+    /// each view belongs to, e.g. after the table is updated.
+    fn invalidate_table(&mut self, table_id: &str) {
+        let generation = self.next_generation();
+        self.tables.insert(table_id.to_owned(), TableCache {
+            generation,
+            ..TableCache::default()
+        });
+
+        let view_ids = self
+            .views
+            .iter_mut()
+            .filter(|(_, view)| view.table_id.as_deref() == Some(table_id))
+            .map(|(view_id, view)| {
+                *view = ViewCache {
+                    table_id: view.table_id.take(),
+                    generation,
+                    ..ViewCache::default()
+                };
+
+                view_id.clone()
+            })
+            .collect::<Vec<_>>();
+
+        for view_id in view_ids {
+            self.remove_blocks(&view_id);
+        }
+    }
+}
+
+/// A [`VirtualServerHandler`] which wraps another handler to cache the
+/// results of its most frequent queries, for backends where each query is
+/// slow, e.g. a remote data warehouse.
+///
+/// - Table and view dimensions and schemas are memoized, so e.g. a
+///   `ViewDimensionsReq` only queries the wrapped handler once per view.
+/// - View data is fetched in blocks of [`CacheOptions::block_size`] rows,
+///   including [`CacheOptions::prefetch_blocks`] blocks around each requested
+///   viewport, which are cached up to [`CacheOptions::max_cells`].
+///
+/// Cached results are invalidated when a view is deleted, expanded,
+/// collapsed or has its depth set, and when its table is updated, removed
+/// from or replaced through this handler or the wrapped handler signals a
+/// table update via its [`VirtualServerNotifier`]. Results fetched while
+/// their table or view is invalidated are returned but not cached.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let handler = CachingVirtualServerHandler::new(handler, CacheOptions {
+///     block_size: 500,
+///     ..CacheOptions::default()
+/// });
+///
+/// let server = VirtualServer::new(handler);
+/// ```
+pub struct CachingVirtualServerHandler<T> {
+    handler: T,
+    options: CacheOptions,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl<T: VirtualServerHandler + Sync> CachingVirtualServerHandler<T> {
+    /// Wraps `handler` in a cache configured by `options`.
+    pub fn new(handler: T, options: CacheOptions) -> Self {
+        CachingVirtualServerHandler {
+            handler,
+            options,
+            state: Arc::default(),
+        }
+    }
+
+    /// The wrapped handler.
+    pub fn inner(&self) -> &T {
+        &self.handler
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Assembles the rows `start..end` of a view from cached blocks, or
+    /// `None` if a block is not cached.
+    fn cached_rows(
+        &self,
+        key: &BlockKey,
+        config: &ViewConfig,
+        start: usize,
+        end: usize,
+    ) -> Option<VirtualDataSlice> {
+        let block_size = self.options.block_size.max(1) as usize;
+        let mut state = self.state();
+        let mut data = VirtualDataSlice::new(config.clone());
+        for block in start / block_size..=(end - 1) / block_size {
+            let offset = block * block_size;
+            let key = BlockKey {
+                block,
+                ..key.clone()
+            };
+
+            let rows = state.touch_block(&key)?.slice_rows(
+                start.max(offset) - offset,
+                end.min(offset + block_size) - offset,
+            );
+
+            data.append_rows(rows);
+        }
+
+        Some(data)
+    }
+
+    async fn get_data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> Result<VirtualDataSlice, T::Error> {
+        let num_rows = self.view_size(view_id, config).await? as usize;
+        let generation = self.state().view_generation(view_id);
+        let start = viewport.start_row.unwrap_or(0) as usize;
+        let end = viewport
+            .end_row
+            .map_or(num_rows, |x| (x as usize).min(num_rows));
+
+        if start >= end {
+            return self
+                .handler
+                .view_get_data(view_id, config, schema, viewport)
+                .await;
+        }
+
+        let key = BlockKey {
+            view_id: view_id.to_owned(),
+            start_col: viewport.start_col,
+            end_col: viewport.end_col,
+            block: 0,
+        };
+
+        let block_size = self.options.block_size.max(1) as usize;
+        let prefetch = self.options.prefetch_blocks as usize;
+        let first = (start / block_size).saturating_sub(prefetch);
+        let last = ((end - 1) / block_size + prefetch).min((num_rows - 1) / block_size);
+        let missing = {
+            let state = self.state();
+            (first..=last)
+                .filter(|block| {
+                    !state.blocks.contains_key(&BlockKey {
+                        block: *block,
+                        ..key.clone()
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Fetch every missing block in one query, including any cached
+        // blocks between them.
+        if let (Some(&lo), Some(&hi)) = (missing.first(), missing.last()) {
+            let fetch_viewport = ViewPort {
+                start_row: Some((lo * block_size) as u32),
+                end_row: Some(((hi + 1) * block_size).min(num_rows) as u32),
+                ..viewport.clone()
+            };
+
+            let data = self
+                .handler
+                .view_get_data(view_id, config, schema, &fetch_viewport)
+                .await?;
+
+            // The view was invalidated during the query, so `data` may be
+            // stale and `num_rows` out of date.
+            if self.state().view_generation(view_id) != generation {
+                return self
+                    .handler
+                    .view_get_data(view_id, config, schema, viewport)
+                    .await;
+            }
+
+            let mut state = self.state();
+            for block in lo..=hi {
+                let offset = (block - lo) * block_size;
+                let key = BlockKey {
+                    block,
+                    ..key.clone()
+                };
+
+                let rows = data.slice_rows(offset, offset + block_size);
+                state.insert_block(key, rows, self.options.max_cells);
+            }
+        }
+
+        match self.cached_rows(&key, config, start, end) {
+            Some(data) => Ok(data),
+
+            // The blocks exceed `max_cells`, so query the viewport directly.
+            None => {
+                self.handler
+                    .view_get_data(view_id, config, schema, viewport)
+                    .await
+            },
+        }
+    }
+}
+
+impl<T: VirtualServerHandler + Sync> VirtualServerHandler for CachingVirtualServerHandler<T> {
+    type Error = T::Error;
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        self.handler.get_hosted_tables()
+    }
+
+    fn table_schema(
+        &self,
+        table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        if let Some(schema) = self
+            .state()
+            .tables
+            .get(table_id)
+            .and_then(|x| x.schema.clone())
+        {
+            return Box::pin(ready(Ok(schema)));
+        }
+
+        let table_id = table_id.to_owned();
+        Box::pin(async move {
+            let generation = self.state().table_generation(&table_id);
+            let schema = self.handler.table_schema(&table_id).await?;
+            if let Some(table) = self.state().table_since(&table_id, generation) {
+                table.schema = Some(schema.clone());
+            }
+
+            Ok(schema)
+        })
+    }
+
+    fn table_size(&self, table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        if let Some(size) = self.state().tables.get(table_id).and_then(|x| x.size) {
+            return Box::pin(ready(Ok(size)));
+        }
+
+        let table_id = table_id.to_owned();
+        Box::pin(async move {
+            let generation = self.state().table_generation(&table_id);
+            let size = self.handler.table_size(&table_id).await?;
+            if let Some(table) = self.state().table_since(&table_id, generation) {
+                table.size = Some(size);
+            }
+
+            Ok(size)
+        })
+    }
+
+    fn table_column_size(
+        &self,
+        table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        if let Some(size) = self
+            .state()
+            .tables
+            .get(table_id)
+            .and_then(|x| x.column_size)
+        {
+            return Box::pin(ready(Ok(size)));
+        }
+
+        let table_id = table_id.to_owned();
+        Box::pin(async move {
+            let generation = self.state().table_generation(&table_id);
+            let size = self.handler.table_column_size(&table_id).await?;
+            if let Some(table) = self.state().table_since(&table_id, generation) {
+                table.column_size = Some(size);
+            }
+
+            Ok(size)
+        })
+    }
+
+    fn table_make_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        let mut state = self.state();
+        let generation = state.next_generation();
+        state.remove_blocks(view_id);
+        state.views.insert(view_id.to_owned(), ViewCache {
+            table_id: Some(table_id.to_owned()),
+            generation,
+            ..ViewCache::default()
+        });
+
+        drop(state);
+        self.handler.table_make_view(table_id, view_id, config)
+    }
+
+    fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let mut state = self.state();
+        state.views.remove(view_id);
+        state.remove_blocks(view_id);
+        drop(state);
+        self.handler.view_delete(view_id)
+    }
+
+    fn view_get_data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let (view_id, config) = (view_id.to_owned(), config.clone());
+        let (schema, viewport) = (schema.clone(), viewport.clone());
+        Box::pin(async move { self.get_data(&view_id, &config, &schema, &viewport).await })
+    }
+
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        if let Some(size) = self.state().views.get(view_id).and_then(|x| x.size) {
+            return Box::pin(ready(Ok(size)));
+        }
+
+        let (view_id, config) = (view_id.to_owned(), config.clone());
+        Box::pin(async move {
+            let generation = self.state().view_generation(&view_id);
+            let size = self.handler.view_size(&view_id, &config).await?;
+            if let Some(view) = self.state().view_since(&view_id, generation) {
+                view.size = Some(size);
+            }
+
+            Ok(size)
+        })
+    }
+
+    fn view_column_size(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        if let Some(size) = self.state().views.get(view_id).and_then(|x| x.column_size) {
+            return Box::pin(ready(Ok(size)));
+        }
+
+        let (view_id, config) = (view_id.to_owned(), config.clone());
+        Box::pin(async move {
+            let generation = self.state().view_generation(&view_id);
+            let size = self.handler.view_column_size(&view_id, &config).await?;
+            if let Some(view) = self.state().view_since(&view_id, generation) {
+                view.column_size = Some(size);
+            }
+
+            Ok(size)
+        })
+    }
+
+    fn view_schema(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        if let Some(schema) = self
+            .state()
+            .views
+            .get(view_id)
+            .and_then(|x| x.schema.clone())
+        {
+            return Box::pin(ready(Ok(schema)));
+        }
+
+        let (view_id, config) = (view_id.to_owned(), config.clone());
+        Box::pin(async move {
+            let generation = self.state().view_generation(&view_id);
+            let schema = self.handler.view_schema(&view_id, &config).await?;
+            if let Some(view) = self.state().view_since(&view_id, generation) {
+                view.schema = Some(schema.clone());
+            }
+
+            Ok(schema)
+        })
+    }
+
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        self.handler.view_get_min_max(view_id, column_name, config)
+    }
+
+    fn view_expand(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let view_id = view_id.to_owned();
+        let fut = self.handler.view_expand(&view_id, config, row_index);
+        Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_view_rows(&view_id);
+            result
+        })
+    }
+
+    fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let view_id = view_id.to_owned();
+        let fut = self.handler.view_collapse(&view_id, config, row_index);
+        Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_view_rows(&view_id);
+            result
+        })
+    }
+
+    fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        depth: u32,
+    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let view_id = view_id.to_owned();
+        let fut = self.handler.view_set_depth(&view_id, config, depth);
+        Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_view_rows(&view_id);
+            result
+        })
+    }
+
+    fn table_validate_expression(
+        &self,
+        table_id: &str,
+        expression: &str,
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        self.handler.table_validate_expression(table_id, expression)
+    }
+
+    fn error_status_code(error: &Self::Error) -> StatusCode {
+        T::error_status_code(error)
+    }
+
+    fn get_features(&self) -> VirtualServerFuture<'_, Result<Features<'_>, Self::Error>> {
+        self.handler.get_features()
+    }
+
+    fn table_make_port(
+        &self,
+        req: &TableMakePortReq,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        self.handler.table_make_port(req)
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        self.handler.supports_table_updates()
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let table_id = table_id.to_owned();
+        let fut = self.handler.table_update(&table_id, index, data)?;
+        Some(Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_table(&table_id);
+            result
+        }))
+    }
+
+    fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let table_id = table_id.to_owned();
+        let fut = self.handler.table_remove(&table_id, index, data)?;
+        Some(Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_table(&table_id);
+            result
+        }))
+    }
+
+    fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let table_id = table_id.to_owned();
+        let fut = self.handler.table_replace(&table_id, data)?;
+        Some(Box::pin(async move {
+            let result = fut.await;
+            self.state().invalidate_table(&table_id);
+            result
+        }))
+    }
+
+    fn table_delete(
+        &self,
+        table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let fut = self.handler.table_delete(table_id)?;
+        self.state().invalidate_table(table_id);
+        Some(fut)
+    }
+
+    fn set_update_notifier(&mut self, notifier: VirtualServerNotifier) {
+        let state = self.state.clone();
+        self.handler
+            .set_update_notifier(notifier.intercept(move |table_id| {
+                state
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .invalidate_table(table_id);
+            }));
+    }
+
+    fn make_table(
+        &mut self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        self.handler.make_table(table_id, data)
+    }
+}
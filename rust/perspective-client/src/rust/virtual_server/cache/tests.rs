@@ -0,0 +1,370 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+use std::sync::{Arc, Mutex};
+
+use super::*;
+
+#[derive(Debug, thiserror::Error)]
+#[error("test error")]
+struct TestError;
+
+/// A handler for views of a `x` column of `0.0..num_rows`, which records the
+/// queries it receives.
+#[derive(Clone)]
+struct TestHandler {
+    num_rows: Arc<Mutex<u32>>,
+    queries: Arc<Mutex<Vec<String>>>,
+    notifier: Arc<Mutex<VirtualServerNotifier>>,
+
+    /// Whether the next `view_get_data` query updates the table before it
+    /// returns, as another request could while it is awaited.
+    update_during_query: Arc<Mutex<bool>>,
+}
+
+impl TestHandler {
+    fn new(num_rows: u32) -> Self {
+        TestHandler {
+            num_rows: Arc::new(Mutex::new(num_rows)),
+            queries: Arc::default(),
+            notifier: Arc::default(),
+            update_during_query: Arc::default(),
+        }
+    }
+
+    /// Appends a row to the table and signals the update via the notifier.
+    fn update(&self) {
+        *self.num_rows.lock().unwrap() += 1;
+        self.notifier.lock().unwrap().notify_table_update("table");
+    }
+
+    fn record(&self, query: String) {
+        self.queries.lock().unwrap().push(query);
+    }
+
+    fn take_queries(&self) -> Vec<String> {
+        std::mem::take(&mut *self.queries.lock().unwrap())
+    }
+}
+
+impl VirtualServerHandler for TestHandler {
+    type Error = TestError;
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        Box::pin(ready(Ok(vec![])))
+    }
+
+    fn table_schema(
+        &self,
+        _table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        self.record("table_schema".to_string());
+        Box::pin(ready(Ok(IndexMap::from_iter([(
+            "x".to_string(),
+            ColumnType::Float,
+        )]))))
+    }
+
+    fn table_size(&self, _table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        self.record("table_size".to_string());
+        Box::pin(ready(Ok(*self.num_rows.lock().unwrap())))
+    }
+
+    fn view_size(
+        &self,
+        _view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        self.record("view_size".to_string());
+        Box::pin(ready(Ok(*self.num_rows.lock().unwrap())))
+    }
+
+    fn table_make_view(
+        &self,
+        _table_id: &str,
+        view_id: &str,
+        _config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        Box::pin(ready(Ok(view_id.to_string())))
+    }
+
+    fn view_delete(&self, _view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        Box::pin(ready(Ok(())))
+    }
+
+    fn view_get_data(
+        &self,
+        _view_id: &str,
+        config: &ViewConfig,
+        _schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let start = viewport.start_row.unwrap_or(0);
+        let end = viewport
+            .end_row
+            .unwrap_or(u32::MAX)
+            .min(*self.num_rows.lock().unwrap());
+
+        self.record(format!("view_get_data {}..{}", start, end));
+        if std::mem::take(&mut *self.update_during_query.lock().unwrap()) {
+            self.update();
+        }
+
+        let mut data = VirtualDataSlice::new(config.clone());
+        for (idx, row) in (start..end).enumerate() {
+            data.set_col("x", None, idx, Some(row as f64)).unwrap();
+        }
+
+        Box::pin(ready(Ok(data)))
+    }
+
+    fn view_expand(
+        &self,
+        _view_id: &str,
+        _config: &ViewConfig,
+        _row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        Box::pin(ready(Ok(0)))
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        true
+    }
+
+    fn table_update(
+        &self,
+        _table_id: &str,
+        _index: Option<&str>,
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        *self.num_rows.lock().unwrap() += 1;
+        Some(Box::pin(ready(Ok(()))))
+    }
+
+    fn set_update_notifier(&mut self, notifier: VirtualServerNotifier) {
+        *self.notifier.lock().unwrap() = notifier;
+    }
+}
+
+fn cached_view(
+    num_rows: u32,
+    options: CacheOptions,
+) -> (CachingVirtualServerHandler<TestHandler>, TestHandler) {
+    let handler = TestHandler::new(num_rows);
+    let mut cache = CachingVirtualServerHandler::new(handler.clone(), options);
+    cache.set_update_notifier(VirtualServerNotifier::default());
+    futures::executor::block_on(cache.table_make_view(
+        "table",
+        "view",
+        &mut ViewConfigUpdate::default(),
+    ))
+    .unwrap();
+
+    (cache, handler)
+}
+
+fn options(block_size: u32, prefetch_blocks: u32) -> CacheOptions {
+    CacheOptions {
+        block_size,
+        prefetch_blocks,
+        ..CacheOptions::default()
+    }
+}
+
+fn viewport(start_row: u32, end_row: u32) -> ViewPort {
+    ViewPort {
+        start_row: Some(start_row),
+        end_row: Some(end_row),
+        ..ViewPort::default()
+    }
+}
+
+/// The `x` values of the rows `start_row..end_row` of `view`.
+fn get_rows(
+    cache: &CachingVirtualServerHandler<TestHandler>,
+    start_row: u32,
+    end_row: u32,
+) -> Vec<Option<f64>> {
+    let data = futures::executor::block_on(cache.view_get_data(
+        "view",
+        &ViewConfig::default(),
+        &IndexMap::default(),
+        &viewport(start_row, end_row),
+    ))
+    .unwrap();
+
+    match data.get("x") {
+        Some(crate::virtual_server::VirtualDataColumn::Float(x)) => x.clone(),
+        _ => vec![],
+    }
+}
+
+fn expected_rows(start_row: u32, end_row: u32) -> Vec<Option<f64>> {
+    (start_row..end_row).map(|x| Some(x as f64)).collect()
+}
+
+#[test]
+fn test_memoizes_dimensions_and_schemas() {
+    let (cache, handler) = cached_view(100, CacheOptions::default());
+    let config = ViewConfig::default();
+    futures::executor::block_on(async {
+        for _ in 0..2 {
+            assert_eq!(cache.table_size("table").await.unwrap(), 100);
+            assert_eq!(cache.table_column_size("table").await.unwrap(), 1);
+            assert_eq!(cache.view_size("view", &config).await.unwrap(), 100);
+        }
+    });
+
+    assert_eq!(handler.take_queries(), vec![
+        "table_size",
+        "table_schema",
+        "view_size"
+    ]);
+}
+
+#[test]
+fn test_fetches_and_prefetches_blocks() {
+    let (cache, handler) = cached_view(100, options(10, 1));
+    assert_eq!(get_rows(&cache, 15, 25), expected_rows(15, 25));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..40"
+    ]);
+
+    // Rows within the prefetched blocks are served from the cache.
+    assert_eq!(get_rows(&cache, 20, 30), expected_rows(20, 30));
+    assert!(handler.take_queries().is_empty());
+
+    // Only the missing blocks are fetched.
+    assert_eq!(get_rows(&cache, 45, 50), expected_rows(45, 50));
+    assert_eq!(handler.take_queries(), vec!["view_get_data 40..60"]);
+
+    // The last block is clamped to the view's size.
+    assert_eq!(get_rows(&cache, 95, 200), expected_rows(95, 100));
+    assert_eq!(handler.take_queries(), vec!["view_get_data 80..100"]);
+}
+
+#[test]
+fn test_invalidates_on_expand_update_and_delete() {
+    let (cache, handler) = cached_view(100, options(10, 0));
+    let config = ViewConfig::default();
+    get_rows(&cache, 0, 10);
+    handler.take_queries();
+
+    futures::executor::block_on(cache.view_expand("view", &config, 0)).unwrap();
+    get_rows(&cache, 0, 10);
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..10"
+    ]);
+
+    let update = cache
+        .table_update("table", None, &MakeTableData::default())
+        .unwrap();
+
+    futures::executor::block_on(update).unwrap();
+    assert_eq!(get_rows(&cache, 95, 101), expected_rows(95, 101));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 90..101"
+    ]);
+
+    futures::executor::block_on(cache.view_delete("view")).unwrap();
+    get_rows(&cache, 0, 10);
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..10"
+    ]);
+}
+
+#[test]
+fn test_invalidates_on_notified_update() {
+    let (cache, handler) = cached_view(100, options(10, 0));
+    get_rows(&cache, 90, 100);
+    handler.take_queries();
+
+    handler.update();
+    assert_eq!(get_rows(&cache, 90, 101), expected_rows(90, 101));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 90..101"
+    ]);
+}
+
+#[test]
+fn test_make_view_keeps_other_views_cached() {
+    let (cache, handler) = cached_view(100, options(10, 0));
+    get_rows(&cache, 0, 10);
+    handler.take_queries();
+
+    futures::executor::block_on(cache.table_make_view(
+        "table",
+        "other_view",
+        &mut ViewConfigUpdate::default(),
+    ))
+    .unwrap();
+
+    assert_eq!(get_rows(&cache, 0, 10), expected_rows(0, 10));
+    assert!(handler.take_queries().is_empty());
+}
+
+#[test]
+fn test_data_fetched_across_update_is_not_cached() {
+    let (cache, handler) = cached_view(100, options(10, 0));
+    *handler.update_during_query.lock().unwrap() = true;
+    assert_eq!(get_rows(&cache, 0, 10), expected_rows(0, 10));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..10",
+        "view_get_data 0..10"
+    ]);
+
+    assert_eq!(get_rows(&cache, 0, 10), expected_rows(0, 10));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..10"
+    ]);
+}
+
+#[test]
+fn test_evicts_least_recently_used_blocks() {
+    let (cache, handler) = cached_view(100, CacheOptions {
+        max_cells: 20,
+        ..options(10, 0)
+    });
+
+    get_rows(&cache, 0, 10);
+    get_rows(&cache, 10, 20);
+    get_rows(&cache, 0, 10);
+    get_rows(&cache, 20, 30);
+    handler.take_queries();
+
+    // The second block was the least recently used.
+    assert_eq!(get_rows(&cache, 0, 10), expected_rows(0, 10));
+    assert!(handler.take_queries().is_empty());
+    assert_eq!(get_rows(&cache, 10, 20), expected_rows(10, 20));
+    assert_eq!(handler.take_queries(), vec!["view_get_data 10..20"]);
+}
+
+#[test]
+fn test_uncacheable_viewport_is_fetched_directly() {
+    let (cache, handler) = cached_view(100, CacheOptions {
+        max_cells: 5,
+        ..options(10, 0)
+    });
+
+    assert_eq!(get_rows(&cache, 2, 8), expected_rows(2, 8));
+    assert_eq!(handler.take_queries(), vec![
+        "view_size",
+        "view_get_data 0..10",
+        "view_get_data 2..8"
+    ]);
+}
@@ -0,0 +1,56 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! The column names of a virtual server view with a `split_by`, which encode
+//! the view's column paths.
+//!
+//! A column path is one element per `split_by` value followed by the source
+//! column name. Databases which support `PIVOT` (e.g. DuckDB) name the pivoted
+//! columns by joining these elements with `_`, so the elements themselves are
+//! escaped with `\` to make the name reversible: `\` is written `\\` and `_` is
+//! written `\_`.
+
+#[cfg(test)]
+mod tests;
+
+/// Escapes a single column path element, such that it can be joined with `_`
+/// and recovered by [`decode_column_path`].
+pub fn escape_column_path_element(element: &str) -> String {
+    element.replace('\\', "\\\\").replace('_', "\\_")
+}
+
+/// Encodes a column path as a single column name, as it appears in the
+/// schema of a virtual server view with a `split_by`.
+pub fn encode_column_path<S: AsRef<str>>(path: &[S]) -> String {
+    path.iter()
+        .map(|x| escape_column_path_element(x.as_ref()))
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Decodes a column name created by [`encode_column_path`] back into its
+/// column path.
+pub fn decode_column_path(name: &str) -> Vec<String> {
+    let mut path = vec![];
+    let mut element = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => element.extend(chars.next()),
+            '_' => path.push(std::mem::take(&mut element)),
+            c => element.push(c),
+        }
+    }
+
+    path.push(element);
+    path
+}
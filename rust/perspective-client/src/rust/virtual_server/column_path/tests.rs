@@ -0,0 +1,39 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use super::*;
+
+#[test]
+fn test_column_path_round_trip() {
+    for path in [
+        vec!["Sales"],
+        vec!["East", "Sales"],
+        vec!["North_East", "Sales_2024"],
+        vec!["a|b", "c\\_d", ""],
+        vec!["\\", "_", "__"],
+    ] {
+        let name = encode_column_path(&path);
+        assert_eq!(decode_column_path(&name), path, "{}", name);
+    }
+}
+
+#[test]
+fn test_column_path_encoding() {
+    assert_eq!(encode_column_path(&["East", "Sales"]), "East_Sales");
+    assert_eq!(
+        encode_column_path(&["North_East", "Unit_Price"]),
+        "North\\_East_Unit\\_Price"
+    );
+
+    assert_eq!(decode_column_path("East_Sales"), ["East", "Sales"]);
+    assert_eq!(decode_column_path("East|Sales"), ["East|Sales"]);
+}
@@ -16,13 +16,19 @@ use std::ops::{Deref, DerefMut};
 use indexmap::IndexMap;
 use serde::Serialize;
 
+use super::column_path::decode_column_path;
 use crate::config::{Scalar, ViewConfig};
 
+#[cfg(feature = "virtual-server-arrow")]
+mod arrow;
+#[cfg(test)]
+mod tests;
+
 /// A column of data returned from a virtual server query.
 ///
 /// Each variant represents a different column type, containing a vector
 /// of optional values. `None` values represent null/missing data.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(untagged)]
 pub enum VirtualDataColumn {
     Boolean(Vec<Option<bool>>),
@@ -76,6 +82,52 @@ impl VirtualDataColumn {
             VirtualDataColumn::RowPath(v) => v.len(),
         }
     }
+
+    /// The rows `start..end` of this column, clamped to its length.
+    fn slice(&self, start: usize, end: usize) -> VirtualDataColumn {
+        let range = start.min(self.len())..end.min(self.len());
+        match self {
+            VirtualDataColumn::Boolean(v) => VirtualDataColumn::Boolean(v[range].to_vec()),
+            VirtualDataColumn::String(v) => VirtualDataColumn::String(v[range].to_vec()),
+            VirtualDataColumn::Float(v) => VirtualDataColumn::Float(v[range].to_vec()),
+            VirtualDataColumn::Integer(v) => VirtualDataColumn::Integer(v[range].to_vec()),
+            VirtualDataColumn::Datetime(v) => VirtualDataColumn::Datetime(v[range].to_vec()),
+            VirtualDataColumn::IntegerIndex(v) => {
+                VirtualDataColumn::IntegerIndex(v[range].to_vec())
+            },
+            VirtualDataColumn::RowPath(v) => VirtualDataColumn::RowPath(v[range].to_vec()),
+        }
+    }
+
+    /// Pads this column to `len` rows with nulls, or empty row paths.
+    fn pad_to(&mut self, len: usize) {
+        match self {
+            VirtualDataColumn::Boolean(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::String(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::Float(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::Integer(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::Datetime(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::IntegerIndex(v) => v.resize(len.max(v.len()), None),
+            VirtualDataColumn::RowPath(v) => v.resize(len.max(v.len()), vec![]),
+        }
+    }
+
+    /// Appends the rows of `other`, which must be the same variant as this
+    /// column, returning `false` (and leaving this column unchanged) if not.
+    fn append(&mut self, other: VirtualDataColumn) -> bool {
+        match (self, other) {
+            (VirtualDataColumn::Boolean(v), VirtualDataColumn::Boolean(w)) => v.extend(w),
+            (VirtualDataColumn::String(v), VirtualDataColumn::String(w)) => v.extend(w),
+            (VirtualDataColumn::Float(v), VirtualDataColumn::Float(w)) => v.extend(w),
+            (VirtualDataColumn::Integer(v), VirtualDataColumn::Integer(w)) => v.extend(w),
+            (VirtualDataColumn::Datetime(v), VirtualDataColumn::Datetime(w)) => v.extend(w),
+            (VirtualDataColumn::IntegerIndex(v), VirtualDataColumn::IntegerIndex(w)) => v.extend(w),
+            (VirtualDataColumn::RowPath(v), VirtualDataColumn::RowPath(w)) => v.extend(w),
+            _ => return false,
+        }
+
+        true
+    }
 }
 
 /// Trait for types that can be written to a [`VirtualDataColumn`] which
@@ -160,8 +212,7 @@ impl VirtualDataSlice {
     }
 
     pub(super) fn to_rows(&self) -> Vec<IndexMap<String, VirtualDataCell>> {
-        let num_rows = self.values().next().map(|x| x.len()).unwrap_or(0);
-        (0..num_rows)
+        (0..self.num_rows())
             .map(|row_idx| {
                 self.iter()
                     .map(|(col_name, col_data)| {
@@ -187,6 +238,88 @@ impl VirtualDataSlice {
             .collect()
     }
 
+    fn num_rows(&self) -> usize {
+        self.values().next().map(|x| x.len()).unwrap_or(0)
+    }
+
+    /// The number of rows of this slice's longest column. The
+    /// `__ROW_PATH__` column omits trailing rows without a row path, e.g. a
+    /// slice of only the total row.
+    pub(super) fn row_count(&self) -> usize {
+        self.values().map(|x| x.len()).max().unwrap_or(0)
+    }
+
+    /// The number of values in this slice, across all of its columns.
+    pub(super) fn cell_count(&self) -> usize {
+        self.values().map(|x| x.len()).sum()
+    }
+
+    /// The rows `start..end` of this slice.
+    pub(super) fn slice_rows(&self, start: usize, end: usize) -> VirtualDataSlice {
+        VirtualDataSlice(
+            self.0.clone(),
+            self.iter()
+                .map(|(name, col)| (name.clone(), col.slice(start, end)))
+                .collect(),
+        )
+    }
+
+    /// Appends the rows of `other`, a slice of the same view, padding the
+    /// columns missing from either slice with nulls.
+    pub(super) fn append_rows(&mut self, other: VirtualDataSlice) {
+        let len = self.row_count();
+        let other_len = other.row_count();
+        for (name, mut col) in other.1 {
+            match self.get_mut(&name) {
+                Some(existing) => {
+                    existing.pad_to(len);
+                    if !existing.append(col) {
+                        tracing::warn!("Column `{}` changed type between slices", name);
+                    }
+                },
+                None => {
+                    let mut existing = col.slice(0, 0);
+                    existing.pad_to(len);
+                    col.pad_to(other_len);
+                    existing.append(col);
+                    self.insert(name, existing);
+                },
+            }
+        }
+
+        for col in self.values_mut() {
+            col.pad_to(len + other_len);
+        }
+    }
+
+    /// Serializes this slice as newline-delimited JSON, one row object per
+    /// line.
+    pub(super) fn to_ndjson(&self) -> Result<String, serde_json::Error> {
+        let rows = self
+            .to_rows()
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows.join("\n"))
+    }
+
+    /// Renames the columns of a view with a `split_by`, which are named by
+    /// their encoded column paths (see [`super::encode_column_path`]), to
+    /// their `|`-joined column path as the Perspective engine names them.
+    pub(super) fn decode_column_paths(&mut self) {
+        self.1 = std::mem::take(&mut self.1)
+            .into_iter()
+            .map(|(name, col)| {
+                if name.starts_with("__") {
+                    (name, col)
+                } else {
+                    (decode_column_path(&name).join("|"), col)
+                }
+            })
+            .collect();
+    }
+
     /// Sets a value in a column at the specified row index.
     ///
     /// If `group_by_index` is `Some`, the value is added to the `__ROW_PATH__`
@@ -0,0 +1,149 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! Encodes [`VirtualDataSlice`] as Arrow and CSV, for the
+//! `"virtual-server-arrow"` feature.
+
+use std::sync::Arc;
+
+use arrow_array::types::Int32Type;
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int32Array, ListArray, RecordBatch, RecordBatchOptions,
+    StringArray, TimestampMillisecondArray,
+};
+use arrow_ipc::CompressionType;
+use arrow_ipc::writer::{IpcWriteOptions, StreamWriter};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use super::{VirtualDataColumn, VirtualDataSlice};
+use crate::config::Scalar;
+
+impl VirtualDataColumn {
+    /// Converts this column to an Arrow array. [`VirtualDataColumn::RowPath`]
+    /// columns have no single Arrow representation and must be split by
+    /// group-by level via [`row_path_to_arrow`] instead.
+    fn to_arrow(&self) -> Option<ArrayRef> {
+        Some(match self {
+            VirtualDataColumn::Boolean(v) => Arc::new(BooleanArray::from_iter(v.iter().copied())),
+            VirtualDataColumn::String(v) => {
+                Arc::new(StringArray::from_iter(v.iter().map(|x| x.as_deref())))
+            },
+            VirtualDataColumn::Float(v) => Arc::new(Float64Array::from_iter(v.iter().copied())),
+            VirtualDataColumn::Integer(v) => Arc::new(Int32Array::from_iter(v.iter().copied())),
+            VirtualDataColumn::Datetime(v) => {
+                Arc::new(TimestampMillisecondArray::from_iter(v.iter().copied()))
+            },
+            VirtualDataColumn::IntegerIndex(v) => {
+                Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(
+                    v.iter()
+                        .map(|x| x.as_ref().map(|x| x.iter().copied().map(Some))),
+                ))
+            },
+            VirtualDataColumn::RowPath(_) => return None,
+        })
+    }
+}
+
+/// Converts a single group-by level of a `__ROW_PATH__` column to an Arrow
+/// array, inferring the type from the first non-null value at that level.
+/// Rows whose path is shorter than `level` (e.g. the total row), or which
+/// are missing from `paths`, are `null`, padding the array to `num_rows`.
+fn row_path_to_arrow(paths: &[Vec<Scalar>], level: usize, num_rows: usize) -> ArrayRef {
+    let cells = (0..num_rows).map(|idx| paths.get(idx).and_then(|path| path.get(level)));
+    let first = paths
+        .iter()
+        .find_map(|path| path.get(level).filter(|x| !matches!(x, Scalar::Null)));
+
+    match first {
+        Some(Scalar::Float(_)) => Arc::new(Float64Array::from_iter(cells.map(|x| match x {
+            Some(Scalar::Float(x)) => Some(*x),
+            _ => None,
+        }))),
+        Some(Scalar::Bool(_)) => Arc::new(BooleanArray::from_iter(cells.map(|x| match x {
+            Some(Scalar::Bool(x)) => Some(*x),
+            _ => None,
+        }))),
+        _ => Arc::new(StringArray::from_iter(cells.map(|x| match x {
+            Some(Scalar::String(x)) => Some(x.as_str()),
+            _ => None,
+        }))),
+    }
+}
+
+impl VirtualDataSlice {
+    /// Converts this slice to an Arrow [`RecordBatch`].
+    ///
+    /// The `__ROW_PATH__` column is split into one column per `group_by`
+    /// level, named `"{column} (Group by {n})"`, matching the output of the
+    /// Perspective engine.
+    pub(crate) fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let num_rows = self.row_count();
+        let mut fields = vec![];
+        let mut arrays = vec![];
+        if let Some(VirtualDataColumn::RowPath(paths)) = self.get("__ROW_PATH__") {
+            for (level, group_by) in self.0.group_by.iter().enumerate() {
+                let array = row_path_to_arrow(paths, level, num_rows);
+                let name = format!("{} (Group by {})", group_by, level + 1);
+                fields.push(Field::new(name, array.data_type().clone(), true));
+                arrays.push(array);
+            }
+        }
+
+        for (name, col) in self.iter() {
+            if let Some(array) = col.to_arrow() {
+                fields.push(Field::new(name, array.data_type().clone(), true));
+                arrays.push(array);
+            }
+        }
+
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options)
+    }
+
+    /// Serializes this slice as an Arrow IPC stream. Like the Perspective
+    /// engine, `"lz4"` is the only recognized `compression` and any other
+    /// value writes an uncompressed stream.
+    pub(crate) fn to_arrow_ipc(&self, compression: Option<&str>) -> Result<Vec<u8>, ArrowError> {
+        let batch = self.to_record_batch()?;
+        let compression = match compression {
+            Some("lz4") => Some(CompressionType::LZ4_FRAME),
+            _ => None,
+        };
+
+        let options = IpcWriteOptions::default().try_with_compression(compression)?;
+        let mut writer = StreamWriter::try_new_with_options(vec![], &batch.schema(), options)?;
+        writer.write(&batch)?;
+        writer.into_inner()
+    }
+
+    /// Serializes this slice as CSV with a header row. List-typed columns
+    /// (e.g. `__ID__`) have no CSV representation and are omitted.
+    pub(crate) fn to_csv(&self) -> Result<String, ArrowError> {
+        let batch = self.to_record_batch()?;
+        let indices = batch
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| !matches!(field.data_type(), DataType::List(_)))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        let batch = batch.project(&indices)?;
+        let mut writer = arrow_csv::WriterBuilder::new()
+            .with_header(true)
+            .build(vec![]);
+
+        writer.write(&batch)?;
+        String::from_utf8(writer.into_inner()).map_err(|e| ArrowError::ExternalError(Box::new(e)))
+    }
+}
@@ -0,0 +1,135 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(feature = "virtual-server-arrow")]
+use arrow_array::{Array, Float64Array, StringArray};
+#[cfg(feature = "virtual-server-arrow")]
+use arrow_ipc::reader::StreamReader;
+
+use super::*;
+
+fn grouped_slice() -> VirtualDataSlice {
+    let config = ViewConfig {
+        group_by: vec!["category".to_string()],
+        ..ViewConfig::default()
+    };
+
+    let mut slice = VirtualDataSlice::new(config);
+    let rows = [(1, None, 10.0), (0, Some("a"), 4.0), (0, Some("b"), 6.0)];
+    for (idx, (grouping_id, category, _)) in rows.iter().enumerate() {
+        let category = category.map(|x| x.to_string());
+        slice
+            .set_col("__ROW_PATH_0__", Some(*grouping_id), idx, category)
+            .unwrap();
+    }
+
+    for (idx, (grouping_id, _, value)) in rows.iter().enumerate() {
+        slice
+            .set_col("value", Some(*grouping_id), idx, Some(*value))
+            .unwrap();
+    }
+
+    slice
+}
+
+#[test]
+fn test_to_ndjson() {
+    let mut slice = VirtualDataSlice::new(ViewConfig::default());
+    slice.set_col("x", None, 0, Some(1.5)).unwrap();
+    slice.set_col("x", None, 1, None as Option<f64>).unwrap();
+    assert_eq!(slice.to_ndjson().unwrap(), "{\"x\":1.5}\n{\"x\":null}");
+}
+
+#[cfg(feature = "virtual-server-arrow")]
+#[test]
+fn test_to_csv_splits_row_path() {
+    let csv = grouped_slice().to_csv().unwrap();
+    assert_eq!(csv, "category (Group by 1),value\n,10.0\na,4.0\nb,6.0\n");
+}
+
+#[cfg(feature = "virtual-server-arrow")]
+#[test]
+fn test_to_csv_pads_short_row_path() {
+    let config = ViewConfig {
+        group_by: vec!["category".to_string()],
+        ..ViewConfig::default()
+    };
+
+    // The total row sorts last, so `__ROW_PATH__` has no entry for it.
+    let mut slice = VirtualDataSlice::new(config);
+    let rows = [(0, Some("a"), 4.0), (0, Some("b"), 6.0), (1, None, 10.0)];
+    for (idx, (grouping_id, category, value)) in rows.iter().enumerate() {
+        let category = category.map(|x| x.to_string());
+        slice
+            .set_col("__ROW_PATH_0__", Some(*grouping_id), idx, category)
+            .unwrap();
+        slice
+            .set_col("value", Some(*grouping_id), idx, Some(*value))
+            .unwrap();
+    }
+
+    let csv = slice.to_csv().unwrap();
+    assert_eq!(csv, "category (Group by 1),value\na,4.0\nb,6.0\n,10.0\n");
+}
+
+#[cfg(feature = "virtual-server-arrow")]
+#[test]
+fn test_to_arrow_ipc_round_trip() {
+    for compression in [None, Some("lz4")] {
+        let bytes = grouped_slice().to_arrow_ipc(compression).unwrap();
+        let mut reader = StreamReader::try_new(bytes.as_slice(), None).unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+
+        let row_path = batch
+            .column_by_name("category (Group by 1)")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert!(row_path.is_null(0));
+        assert_eq!(row_path.value(1), "a");
+        let values = batch
+            .column_by_name("value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+
+        assert_eq!(values.values().to_vec(), vec![10.0, 4.0, 6.0]);
+    }
+}
+
+#[test]
+fn test_slice_and_append_rows() {
+    let slice = grouped_slice();
+    let mut rows = slice.slice_rows(0, 1);
+    assert_eq!(rows.row_count(), 1);
+    rows.append_rows(slice.slice_rows(1, 3));
+    assert_eq!(
+        serde_json::to_value(&rows).unwrap(),
+        serde_json::to_value(&slice).unwrap()
+    );
+
+    // A slice of only the total row has no row path.
+    let mut total = VirtualDataSlice::new(slice.0.clone());
+    total.set_col("value", Some(1), 0, Some(10.0)).unwrap();
+    total.append_rows(slice.slice_rows(1, 3));
+    assert_eq!(
+        serde_json::to_value(&total).unwrap(),
+        serde_json::json!({
+            "value": [10.0, 4.0, 6.0],
+            "__ROW_PATH__": [[], ["a"], ["b"]],
+        })
+    );
+}
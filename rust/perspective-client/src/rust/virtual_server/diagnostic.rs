@@ -0,0 +1,71 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::proto::table_validate_expr_resp::ExprValidationError;
+
+/// Why an expression is invalid, and where in its text, as returned by
+/// [`VirtualServerHandler::table_validate_expression`](super::VirtualServerHandler::table_validate_expression).
+///
+/// `line` and `column` are 0-based and count characters, and `span` is the
+/// number of characters of the invalid token, or `0` if the error has no
+/// known location.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpressionDiagnostic {
+    pub message: String,
+    pub line: u32,
+    pub column: u32,
+    pub span: u32,
+}
+
+impl ExpressionDiagnostic {
+    /// A diagnostic with no known location.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+
+    /// A diagnostic for the text at byte range `range` of `expression`.
+    pub fn at(message: impl Into<String>, expression: &str, range: Range<usize>) -> Self {
+        let start = range.start.min(expression.len());
+        let end = range.end.clamp(start, expression.len());
+        let before = &expression[..start];
+        let line_start = before.rfind('\n').map_or(0, |x| x + 1);
+        Self {
+            message: message.into(),
+            line: before.matches('\n').count() as u32,
+            column: before[line_start..].chars().count() as u32,
+            span: expression[start..end].chars().count() as u32,
+        }
+    }
+}
+
+impl fmt::Display for ExpressionDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<ExpressionDiagnostic> for ExprValidationError {
+    fn from(value: ExpressionDiagnostic) -> Self {
+        ExprValidationError {
+            error_message: value.message,
+            line: value.line,
+            column: value.column,
+            span: value.span,
+        }
+    }
+}
@@ -13,6 +13,9 @@
 use prost::{DecodeError, EncodeError};
 use thiserror::Error;
 
+use super::diagnostic::ExpressionDiagnostic;
+use crate::proto::StatusCode;
+
 /// Error type for virtual server operations.
 ///
 /// This enum represents the various errors that can occur when processing
@@ -34,10 +37,34 @@ pub enum VirtualServerError<T: std::fmt::Debug> {
     #[error("Invalid JSON'{0}'")]
     InvalidJSON(std::sync::Arc<serde_json::Error>),
 
+    #[cfg(feature = "virtual-server-arrow")]
+    #[error("Arrow error '{0}'")]
+    ArrowError(std::sync::Arc<arrow_schema::ArrowError>),
+
+    #[error("Unsupported request: {0}")]
+    UnsupportedRequest(String),
+
+    #[error("Invalid expression '{0}': {1}")]
+    InvalidExpression(String, ExpressionDiagnostic),
+
     #[error("{0}")]
     Other(String),
 }
 
+impl<T: std::fmt::Debug> VirtualServerError<T> {
+    /// The [`StatusCode`] reported to the client for this error, where
+    /// handler errors are classified by `handler_status_code`.
+    pub fn status_code(&self, handler_status_code: impl FnOnce(&T) -> StatusCode) -> StatusCode {
+        match self {
+            Self::InternalError(err) => handler_status_code(err),
+            Self::UnknownViewId(_) => StatusCode::ViewNotFound,
+            Self::UnsupportedRequest(_) => StatusCode::UnsupportedOperation,
+            Self::InvalidExpression(..) => StatusCode::InvalidExpression,
+            _ => StatusCode::ServerError,
+        }
+    }
+}
+
 /// Extension trait for extracting internal errors from [`VirtualServerError`]
 /// results.
 ///
@@ -13,19 +13,10 @@
 //! SQL query builder for virtual server operations.
 //!
 //! This module provides a stateless SQL query generator that produces
-//! generic SQL strings for perspective virtual server operations.
+//! generic SQL strings for perspective virtual server operations, in the
+//! syntax of a [`SqlDialect`].
 
-// TODO(texodus): Missing these features
-//
-// - `min_max` API for value-coloring and value-sizing.
-//
-// - row expand/collapse in the datagrid needs datamodel support, this is likely
-//   a "collapsed" boolean column in the temp table we `UPDATE`.
-//
-// - `on_update` real-time support will be method which takes sa view name and a
-//   handler and calls the handler when the view needs to be recalculated.
-//
-// Nice to have:
+// TODO(texodus): Nice to have:
 //
 // - Optional `view_change` method can be implemented for engine optimization,
 //   defaulting to just delete & recreate (as Perspective engine does now).
@@ -33,19 +24,34 @@
 // - Would like to add a metadata API so that e.g. Viewer debug panel could show
 //   internal generated SQL.
 
+mod aggregate;
+mod dialect;
+mod expression;
 mod table_make_view;
+mod table_update;
 
 #[cfg(test)]
 mod tests;
 
+use std::borrow::Cow;
 use std::fmt;
+use std::sync::Arc;
 
+pub use dialect::{
+    AnsiDialect, ClickHouseDialect, DataFusionDialect, DuckDbDialect, PostgresDialect, SqlDialect,
+    SqlDialectName, SqliteDialect,
+};
 use indexmap::IndexMap;
 use serde::Deserialize;
 
-use crate::config::{FilterTerm, Scalar, Sort, SortDir, ViewConfig};
-use crate::proto::{ColumnType, ViewPort};
+use crate::config::{Aggregate, Scalar, Sort, SortDir, ViewConfig};
+use crate::proto::{ColumnType, MakeTableData, StatusCode, ViewPort};
+use crate::virtual_server::generic_sql_model::aggregate::{AGGREGATES, SqlAggregate};
 use crate::virtual_server::generic_sql_model::table_make_view::ViewQueryContext;
+use crate::virtual_server::generic_sql_model::table_update::{
+    SqlValues, UpdateRows, parse_index_values, without_params,
+};
+use crate::virtual_server::{AggSpec, ExpressionDiagnostic, Features};
 
 /// Error type for SQL generation operations.
 #[derive(Debug, Clone)]
@@ -56,6 +62,8 @@ pub enum GenericSQLError {
     InvalidConfig(String),
     /// An unsupported operation was requested.
     UnsupportedOperation(String),
+    /// An expression could not be parsed.
+    InvalidExpression(ExpressionDiagnostic),
 }
 
 impl fmt::Display for GenericSQLError {
@@ -64,12 +72,25 @@ impl fmt::Display for GenericSQLError {
             Self::ColumnNotFound(col) => write!(f, "Column not found: {}", col),
             Self::InvalidConfig(msg) => write!(f, "Invalid configuration: {}", msg),
             Self::UnsupportedOperation(msg) => write!(f, "Unsupported operation: {}", msg),
+            Self::InvalidExpression(diagnostic) => write!(f, "Invalid expression: {}", diagnostic),
         }
     }
 }
 
 impl std::error::Error for GenericSQLError {}
 
+impl GenericSQLError {
+    /// The [`StatusCode`] a handler should report for this error, for
+    /// [`VirtualServerHandler::error_status_code`](crate::virtual_server::VirtualServerHandler::error_status_code).
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ColumnNotFound(_) | Self::InvalidConfig(_) => StatusCode::InvalidConfig,
+            Self::UnsupportedOperation(_) => StatusCode::UnsupportedOperation,
+            Self::InvalidExpression(_) => StatusCode::InvalidExpression,
+        }
+    }
+}
+
 /// Result type alias for SQL operations.
 pub type GenericSQLResult<T> = Result<T, GenericSQLError>;
 
@@ -77,38 +98,190 @@ pub type GenericSQLResult<T> = Result<T, GenericSQLError>;
 pub struct GenericSQLVirtualServerModelArgs {
     create_entity: Option<String>,
     grouping_fn: Option<String>,
+    dialect: Option<SqlDialectName>,
+}
+
+impl GenericSQLVirtualServerModelArgs {
+    /// Sets the kind of entity each `View` is created as, e.g. `"TEMP TABLE"`
+    /// or `"VIEW"`. Defaults to `"TABLE"`.
+    pub fn with_create_entity(mut self, create_entity: impl Into<String>) -> Self {
+        self.create_entity = Some(create_entity.into());
+        self
+    }
+
+    /// Sets the built-in dialect queries are generated for. Defaults to
+    /// [`SqlDialectName::DuckDb`].
+    pub fn with_dialect(mut self, dialect: SqlDialectName) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
 }
 
 /// A stateless SQL query builder virtual server operations.
 ///
 /// This struct generates SQL query strings without executing them, allowing
 /// the caller to execute the queries against a SQL connection.
-#[derive(Debug, Default, Clone)]
-pub struct GenericSQLVirtualServerModel(GenericSQLVirtualServerModelArgs);
+#[derive(Debug, Clone)]
+pub struct GenericSQLVirtualServerModel {
+    args: GenericSQLVirtualServerModelArgs,
+    dialect: Arc<dyn SqlDialect>,
+}
+
+impl Default for GenericSQLVirtualServerModel {
+    fn default() -> Self {
+        Self::new(GenericSQLVirtualServerModelArgs::default())
+    }
+}
 
 impl GenericSQLVirtualServerModel {
-    /// Creates a new `GenericSQLVirtualServerModel` instance.
+    /// Creates a new `GenericSQLVirtualServerModel` instance, for the built-in
+    /// dialect named by `args` (DuckDB by default).
     pub fn new(args: GenericSQLVirtualServerModelArgs) -> Self {
-        Self(args)
+        let dialect = args.dialect.unwrap_or_default().dialect();
+        Self { args, dialect }
+    }
+
+    /// Creates a new `GenericSQLVirtualServerModel` instance for a custom
+    /// [`SqlDialect`], ignoring the `dialect` field of `args`.
+    pub fn with_dialect(
+        args: GenericSQLVirtualServerModelArgs,
+        dialect: Arc<dyn SqlDialect>,
+    ) -> Self {
+        Self { args, dialect }
+    }
+
+    /// The [`SqlDialect`] queries are generated for.
+    pub fn dialect(&self) -> &dyn SqlDialect {
+        self.dialect.as_ref()
+    }
+
+    /// Returns the Perspective column type for a column whose type the
+    /// database reports as `type_name`, e.g. from the query returned by
+    /// [`GenericSQLVirtualServerModel::table_schema`].
+    pub fn column_type(&self, type_name: &str) -> ColumnType {
+        self.dialect.column_type(type_name)
+    }
+
+    /// The filter operators translated to SQL for columns of type `ty`,
+    /// default first.
+    pub fn filter_ops(ty: ColumnType) -> &'static [&'static str] {
+        match ty {
+            ColumnType::String => &[
+                "==",
+                "!=",
+                "<",
+                ">",
+                "<=",
+                ">=",
+                "begins with",
+                "contains",
+                "ends with",
+                "in",
+                "not in",
+                "is null",
+                "is not null",
+            ],
+            ColumnType::Boolean => &["==", "!=", "is null", "is not null"],
+            ColumnType::Integer | ColumnType::Float | ColumnType::Date | ColumnType::Datetime => &[
+                "==",
+                "!=",
+                "<",
+                ">",
+                "<=",
+                ">=",
+                "in",
+                "not in",
+                "is null",
+                "is not null",
+            ],
+        }
+    }
+
+    /// The type of a grouped view's column of type `ty` once aggregated by
+    /// `aggregate`, e.g. `integer` for `count`, for engines whose catalog
+    /// cannot report the types of a view's columns. Columns without an
+    /// aggregate, and aggregates which are functions of the engine, keep
+    /// their type.
+    pub fn aggregate_type(aggregate: Option<&Aggregate>, ty: ColumnType) -> ColumnType {
+        let name = match aggregate {
+            Some(Aggregate::SingleAggregate(name) | Aggregate::MultiAggregate(name, _)) => name,
+            None => return ty,
+        };
+
+        SqlAggregate::find(name)
+            .and_then(|x| x.result_type)
+            .unwrap_or(ty)
+    }
+
+    /// Returns the [`Features`] supported by views created with this model,
+    /// for a [`crate::virtual_server::VirtualServerHandler::get_features`]
+    /// implementation, with `filter_ops` for every column type and the
+    /// `aggregates` the dialect can translate. `on_update` is left for the
+    /// handler to fill in. Dialects which do not
+    /// [`SqlDialect::supports_pivot`] must create `split_by` views with
+    /// [`GenericSQLVirtualServerModel::table_make_view_with_split_values`].
+    pub fn features(&self) -> Features<'static> {
+        let column_types = [
+            ColumnType::Integer,
+            ColumnType::Float,
+            ColumnType::String,
+            ColumnType::Boolean,
+            ColumnType::Date,
+            ColumnType::Datetime,
+        ];
+
+        let filter_ops = column_types
+            .into_iter()
+            .map(|ty| {
+                let ops = Self::filter_ops(ty).iter().copied().map(Cow::Borrowed);
+                (ty, ops.collect())
+            })
+            .collect();
+
+        let aggregates = column_types
+            .into_iter()
+            .map(|ty| {
+                let aggs = AGGREGATES
+                    .iter()
+                    .filter(|x| x.column_types.contains(&ty) && x.is_supported(self.dialect()))
+                    .map(|x| match x.args {
+                        [] => AggSpec::Single(Cow::Borrowed(x.name)),
+                        args => AggSpec::Multiple(Cow::Borrowed(x.name), args.to_vec()),
+                    });
+
+                (ty, aggs.collect())
+            })
+            .collect();
+
+        Features {
+            group_by: true,
+            split_by: true,
+            sort: true,
+            expressions: true,
+            filter_ops,
+            aggregates,
+            ..Features::default()
+        }
     }
 
     /// Returns the SQL query to list all hosted tables.
     ///
     /// # Returns
-    /// SQL: `SHOW ALL TABLES`
+    /// SQL: `SHOW ALL TABLES` for DuckDB
     pub fn get_hosted_tables(&self) -> GenericSQLResult<String> {
-        Ok("SHOW ALL TABLES".to_string())
+        Ok(self.dialect.list_tables())
     }
 
-    /// Returns the SQL query to describe a table's schema.
+    /// Returns the SQL query to describe a table's schema, whose first two
+    /// result columns are the column name and type name.
     ///
     /// # Arguments
     /// * `table_id` - The identifier of the table to describe.
     ///
     /// # Returns
-    /// SQL: `DESCRIBE {table_id}`
+    /// SQL: `DESCRIBE {table_id}` for DuckDB
     pub fn table_schema(&self, table_id: &str) -> GenericSQLResult<String> {
-        Ok(format!("DESCRIBE {}", table_id))
+        Ok(self.dialect.describe_table(table_id))
     }
 
     /// Returns the SQL query to get the row count of a table.
@@ -122,36 +295,312 @@ impl GenericSQLVirtualServerModel {
         Ok(format!("SELECT COUNT(*) FROM {}", table_id))
     }
 
-    /// Returns the SQL query to get the column count of a view.
+    /// Returns the SQL query to get the column count of a view, excluding
+    /// internal `__`-prefixed columns.
     ///
     /// # Arguments
     /// * `view_id` - The identifier of the view.
     ///
     /// # Returns
-    /// SQL: `SELECT COUNT(*) FROM (DESCRIBE {view_id})`
+    /// SQL: `SELECT COUNT(*) FROM (DESCRIBE {view_id}) WHERE ...` for DuckDB
     pub fn view_column_size(&self, view_id: &str) -> GenericSQLResult<String> {
-        Ok(format!("SELECT COUNT(*) FROM (DESCRIBE {})", view_id))
+        Ok(self.dialect.count_view_columns(view_id))
     }
 
-    /// Returns the SQL query to validate an expression against a table.
+    /// Translates an ExprTK expression column, e.g. `"Profit" / "Sales"`, to a
+    /// SQL expression. Literals, column references, variables, operators,
+    /// `if`/`else` and most of Perspective's expression functions are
+    /// supported; the rest (e.g. `for` loops or `vlookup()`) are a
+    /// [`GenericSQLError::UnsupportedOperation`].
+    ///
+    /// # Arguments
+    /// * `expression` - The ExprTK expression.
+    ///
+    /// # Returns
+    /// SQL: `("Profit" / "Sales")`
+    pub fn expression_sql(&self, expression: &str) -> GenericSQLResult<String> {
+        expression::expression_to_sql(self.dialect(), expression)
+    }
+
+    /// Returns the SQL query to validate an expression against a table. The
+    /// second column of its first result row is the expression's type name.
     ///
     /// # Arguments
     /// * `table_id` - The identifier of the table.
-    /// * `expression` - The SQL expression to validate.
+    /// * `expression` - The ExprTK expression to validate, which is translated
+    ///   to SQL as [`GenericSQLVirtualServerModel::expression_sql`] does.
     ///
     /// # Returns
-    /// SQL: `DESCRIBE (SELECT {expression} FROM {table_id})`
+    /// SQL: `DESCRIBE (SELECT {expression} FROM {table_id})` for DuckDB
     pub fn table_validate_expression(
         &self,
         table_id: &str,
         expression: &str,
     ) -> GenericSQLResult<String> {
-        Ok(format!(
-            "DESCRIBE (SELECT {} FROM {})",
-            expression, table_id
+        let expression = self.expression_sql(expression)?;
+        self.dialect
+            .describe_expression(table_id, &expression)
+            .ok_or_else(|| {
+                GenericSQLError::UnsupportedOperation(format!(
+                    "expressions cannot be validated in the {} dialect",
+                    self.dialect.name()
+                ))
+            })
+    }
+
+    /// Returns the SQL statements to write `data` to a table, as
+    /// `Table::update` does. Without an `index`, rows are appended. With one,
+    /// rows whose `index` value already exists in the table are updated in
+    /// place, and the rest are inserted.
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the table.
+    /// * `index` - The table's `index` column, if it has one.
+    /// * `data` - JSON row, column or NDJSON formatted data, or Arrow and CSV
+    ///   with the `"virtual-server-arrow"` feature.
+    ///
+    /// # Returns
+    /// SQL: `INSERT INTO {table_id} (...) VALUES ...`, preceded by an
+    /// `UPDATE {table_id} ... FROM (VALUES ...)` for indexed tables.
+    pub fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<String>> {
+        Ok(without_params(
+            self.update_sql(table_id, index, data, false)?,
+        ))
+    }
+
+    /// Returns the SQL statements to write `data` to a table, as
+    /// [`GenericSQLVirtualServerModel::table_update`] does, but with cell
+    /// values bound as positional parameters (see
+    /// [`SqlDialect::placeholder`]) rather than inlined as literals.
+    ///
+    /// # Returns
+    /// The statements, each with the values to bind to its placeholders, in
+    /// order.
+    pub fn table_update_parameterized(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        self.update_sql(table_id, index, data, true)
+    }
+
+    /// Returns the SQL statements to remove rows from a table by their
+    /// `index` column values, as `Table::remove` does.
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the table.
+    /// * `index` - The table's `index` column. Tables without one do not
+    ///   support `remove`.
+    /// * `data` - A JSON list of `index` values (or of rows), or a JSON column
+    ///   object containing the `index` column.
+    ///
+    /// # Returns
+    /// SQL: `DELETE FROM {table_id} WHERE "{index}" IN (...)`
+    pub fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<String>> {
+        Ok(without_params(
+            self.remove_sql(table_id, index, data, false)?,
         ))
     }
 
+    /// Returns the SQL statements to remove rows from a table, as
+    /// [`GenericSQLVirtualServerModel::table_remove`] does, but with the
+    /// `index` values bound as positional parameters.
+    ///
+    /// # Returns
+    /// The statements, each with the values to bind to its placeholders, in
+    /// order.
+    pub fn table_remove_parameterized(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        self.remove_sql(table_id, index, data, true)
+    }
+
+    /// Returns the SQL statements to replace all rows of a table with `data`,
+    /// as `Table::replace` (and `Table::clear`, with no rows) does.
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the table.
+    /// * `data` - Data in any format
+    ///   [`GenericSQLVirtualServerModel::table_update`] accepts.
+    ///
+    /// # Returns
+    /// SQL: `DELETE FROM {table_id}`, followed by an `INSERT INTO {table_id}`
+    /// when there are rows to write.
+    pub fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<String>> {
+        Ok(without_params(self.replace_sql(table_id, data, false)?))
+    }
+
+    /// Returns the SQL statements to replace all rows of a table, as
+    /// [`GenericSQLVirtualServerModel::table_replace`] does, but with cell
+    /// values bound as positional parameters.
+    ///
+    /// # Returns
+    /// The statements, each with the values to bind to its placeholders, in
+    /// order.
+    pub fn table_replace_parameterized(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        self.replace_sql(table_id, data, true)
+    }
+
+    fn update_sql(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+        parameterized: bool,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        let mut update = UpdateRows::parse(data)?;
+        if update.rows.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let Some(index) = index else {
+            let mut values = SqlValues::new(self.dialect(), parameterized);
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                table_id,
+                update.columns_sql(self.dialect()),
+                update.values_sql(&mut values)
+            );
+
+            return Ok(vec![values.finish(sql)]);
+        };
+
+        if !self.dialect.supports_update_from() {
+            return Err(GenericSQLError::UnsupportedOperation(format!(
+                "indexed updates are not supported in the {} dialect",
+                self.dialect.name()
+            )));
+        }
+
+        let index_pos = update.merge_by_index(index)?;
+        let index = self.dialect.quote_ident(index);
+        let source = |values: &mut SqlValues| {
+            self.dialect
+                .values_source(&update.values_sql(values), "__UPDATE__", &update.columns)
+        };
+
+        let mut queries = vec![];
+        let assignments = update
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != index_pos)
+            .map(|(_, col)| {
+                let col = self.dialect.quote_ident(col);
+                format!("{} = __UPDATE__.{}", col, col)
+            })
+            .collect::<Vec<_>>();
+
+        if !assignments.is_empty() {
+            let mut values = SqlValues::new(self.dialect(), parameterized);
+            let sql = format!(
+                "UPDATE {} AS __TABLE__ SET {} FROM {} WHERE __TABLE__.{} = __UPDATE__.{}",
+                table_id,
+                assignments.join(", "),
+                source(&mut values),
+                index,
+                index
+            );
+
+            queries.push(values.finish(sql));
+        }
+
+        let mut values = SqlValues::new(self.dialect(), parameterized);
+        let sql = format!(
+            "INSERT INTO {} ({}) SELECT __UPDATE__.* FROM {} WHERE NOT EXISTS (SELECT 1 FROM {} \
+             AS __TABLE__ WHERE __TABLE__.{} = __UPDATE__.{})",
+            table_id,
+            update.columns_sql(self.dialect()),
+            source(&mut values),
+            table_id,
+            index,
+            index
+        );
+
+        queries.push(values.finish(sql));
+        Ok(queries)
+    }
+
+    fn remove_sql(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+        parameterized: bool,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        let index = index.ok_or_else(|| {
+            GenericSQLError::UnsupportedOperation(format!(
+                "remove requires an indexed table, but {} has no index",
+                table_id
+            ))
+        })?;
+
+        let keys = parse_index_values(data, index)?;
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut values = SqlValues::new(self.dialect(), parameterized);
+        let keys = keys
+            .iter()
+            .map(|x| values.push(Some(x)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let sql = format!(
+            "DELETE FROM {} WHERE {} IN ({})",
+            table_id,
+            self.dialect.quote_ident(index),
+            keys
+        );
+
+        Ok(vec![values.finish(sql)])
+    }
+
+    fn replace_sql(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+        parameterized: bool,
+    ) -> GenericSQLResult<Vec<(String, Vec<Scalar>)>> {
+        let mut queries = vec![(format!("DELETE FROM {}", table_id), vec![])];
+        queries.extend(self.update_sql(table_id, None, data, parameterized)?);
+        Ok(queries)
+    }
+
+    /// Returns the SQL query to delete a table, as `Table::delete` does.
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the table to delete.
+    ///
+    /// # Returns
+    /// SQL: `DROP TABLE {table_id}`
+    pub fn table_delete(&self, table_id: &str) -> GenericSQLResult<String> {
+        Ok(format!("DROP TABLE {}", table_id))
+    }
+
     /// Returns the SQL query to delete a view.
     ///
     /// # Arguments
@@ -166,12 +615,23 @@ impl GenericSQLVirtualServerModel {
     /// Returns the SQL query to create a view from a table with the given
     /// configuration.
     ///
+    /// Views with a `group_by` also have `__COLLAPSED__` and `__HIDDEN__`
+    /// columns, which track row expansion state (see
+    /// [`GenericSQLVirtualServerModel::view_collapse`]) and are initialized
+    /// from `group_by_depth`.
+    ///
     /// # Arguments
     /// * `table_id` - The identifier of the source table.
     /// * `view_id` - The identifier for the new view.
     /// * `config` - The view configuration specifying columns, group_by,
     ///   split_by, etc.
     ///
+    /// Dialects which do not support `PIVOT` (see
+    /// [`SqlDialect::supports_pivot`]) cannot create `split_by` views with
+    /// this method, and must use
+    /// [`GenericSQLVirtualServerModel::table_make_view_with_split_values`]
+    /// instead.
+    ///
     /// # Returns
     /// SQL: `CREATE TABLE {view_id} AS (...)`
     pub fn table_make_view(
@@ -180,10 +640,168 @@ impl GenericSQLVirtualServerModel {
         view_id: &str,
         config: &ViewConfig,
     ) -> GenericSQLResult<String> {
-        let ctx = ViewQueryContext::new(self, table_id, config);
-        let query = ctx.build_query();
-        let template = self.0.create_entity.as_deref().unwrap_or("TABLE");
-        Ok(format!("CREATE {} {} AS ({})", template, view_id, query))
+        Ok(self
+            .make_view_sql(table_id, view_id, config, None, false)?
+            .0)
+    }
+
+    /// Returns the SQL query to create a view, as
+    /// [`GenericSQLVirtualServerModel::table_make_view`] does, but with
+    /// filter values bound as positional parameters (see
+    /// [`SqlDialect::placeholder`]) rather than inlined as literals.
+    ///
+    /// # Returns
+    /// SQL: `CREATE TABLE {view_id} AS (... WHERE "col" = ?)`, and the values
+    /// to bind to its placeholders, in order.
+    pub fn table_make_view_parameterized(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> GenericSQLResult<(String, Vec<Scalar>)> {
+        self.make_view_sql(table_id, view_id, config, None, true)
+    }
+
+    /// Returns the SQL query for the distinct values of a view's `split_by`
+    /// columns, as strings (or `NULL`), one row per combination. These are
+    /// the `split_values` to create the view with
+    /// [`GenericSQLVirtualServerModel::table_make_view_with_split_values`].
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the source table.
+    /// * `config` - The view configuration.
+    ///
+    /// # Returns
+    /// SQL: `SELECT DISTINCT CAST("{split_by}" AS VARCHAR), ... FROM
+    /// {table_id}`
+    pub fn view_split_values(
+        &self,
+        table_id: &str,
+        config: &ViewConfig,
+    ) -> GenericSQLResult<String> {
+        Ok(self.split_values_sql(table_id, config, false)?.0)
+    }
+
+    /// Returns the SQL query for the distinct values of a view's `split_by`
+    /// columns, as [`GenericSQLVirtualServerModel::view_split_values`] does,
+    /// but with filter values bound as positional parameters.
+    ///
+    /// # Returns
+    /// SQL: `SELECT DISTINCT ... FROM {table_id} WHERE "col" = ?`, and the
+    /// values to bind to its placeholders, in order.
+    pub fn view_split_values_parameterized(
+        &self,
+        table_id: &str,
+        config: &ViewConfig,
+    ) -> GenericSQLResult<(String, Vec<Scalar>)> {
+        self.split_values_sql(table_id, config, true)
+    }
+
+    /// Returns the SQL query to create a `split_by` view from a table without
+    /// `PIVOT`, using one conditional aggregate per column and combination of
+    /// `split_values`, as returned by the query from
+    /// [`GenericSQLVirtualServerModel::view_split_values`]. This works in
+    /// every dialect.
+    ///
+    /// # Arguments
+    /// * `table_id` - The identifier of the source table.
+    /// * `view_id` - The identifier for the new view.
+    /// * `config` - The view configuration.
+    /// * `split_values` - The values of the `split_by` columns, one `Vec` per
+    ///   combination, in `split_by` order.
+    ///
+    /// # Returns
+    /// SQL: `CREATE TABLE {view_id} AS (...)`
+    pub fn table_make_view_with_split_values(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &ViewConfig,
+        split_values: &[Vec<Option<String>>],
+    ) -> GenericSQLResult<String> {
+        Ok(self
+            .make_view_sql(table_id, view_id, config, Some(split_values), false)?
+            .0)
+    }
+
+    /// Returns the SQL query to create a `split_by` view, as
+    /// [`GenericSQLVirtualServerModel::table_make_view_with_split_values`]
+    /// does, but with filter and split values bound as positional parameters.
+    ///
+    /// # Returns
+    /// SQL: `CREATE TABLE {view_id} AS (...)`, and the values to bind to its
+    /// placeholders, in order.
+    pub fn table_make_view_with_split_values_parameterized(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &ViewConfig,
+        split_values: &[Vec<Option<String>>],
+    ) -> GenericSQLResult<(String, Vec<Scalar>)> {
+        self.make_view_sql(table_id, view_id, config, Some(split_values), true)
+    }
+
+    fn make_view_sql(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &ViewConfig,
+        split_values: Option<&[Vec<Option<String>>]>,
+        parameterized: bool,
+    ) -> GenericSQLResult<(String, Vec<Scalar>)> {
+        match split_values {
+            None if !config.split_by.is_empty() && !self.dialect.supports_pivot() => {
+                return Err(GenericSQLError::UnsupportedOperation(format!(
+                    "split_by requires table_make_view_with_split_values in the {} dialect",
+                    self.dialect.name()
+                )));
+            },
+            Some(split_values) => {
+                if let Some(values) = split_values
+                    .iter()
+                    .find(|x| x.len() != config.split_by.len())
+                {
+                    return Err(GenericSQLError::InvalidConfig(format!(
+                        "expected {} split values, found {}",
+                        config.split_by.len(),
+                        values.len()
+                    )));
+                }
+            },
+            None => {},
+        }
+
+        let mut ctx = ViewQueryContext::new(self, table_id, config, split_values)?;
+        if parameterized {
+            ctx = ctx.parameterized()?;
+        }
+
+        let entity = self.args.create_entity.as_deref().unwrap_or("TABLE");
+        let query = self
+            .dialect
+            .create_table_as(entity, view_id, &ctx.build_query());
+
+        Ok(ctx.bind_params(&query))
+    }
+
+    fn split_values_sql(
+        &self,
+        table_id: &str,
+        config: &ViewConfig,
+        parameterized: bool,
+    ) -> GenericSQLResult<(String, Vec<Scalar>)> {
+        if config.split_by.is_empty() {
+            return Err(GenericSQLError::InvalidConfig(
+                "split values require a split_by".to_string(),
+            ));
+        }
+
+        let mut ctx = ViewQueryContext::new(self, table_id, config, None)?;
+        if parameterized {
+            ctx = ctx.parameterized()?;
+        }
+
+        Ok(ctx.bind_params(&ctx.split_values_query()))
     }
 
     /// Returns the SQL query to fetch data from a view with the given viewport.
@@ -210,7 +828,7 @@ impl GenericSQLVirtualServerModel {
         let start_row = viewport.start_row.unwrap_or(0);
         let end_row = viewport.end_row;
         let limit_clause = if let Some(end) = end_row {
-            format!("LIMIT {} OFFSET {}", end - start_row, start_row)
+            self.dialect.limit_offset(end - start_row, start_row)
         } else {
             String::new()
         };
@@ -241,7 +859,9 @@ impl GenericSQLVirtualServerModel {
             .collect();
 
         let mut group_by_cols: Vec<String> = Vec::new();
+        let mut where_clause = "";
         if !group_by.is_empty() {
+            where_clause = " WHERE __HIDDEN__ = 0";
             group_by_cols.push("\"__GROUPING_ID__\"".to_string());
             for idx in 0..group_by.len() {
                 group_by_cols.push(format!("\"__ROW_PATH_{}__\"", idx));
@@ -250,13 +870,14 @@ impl GenericSQLVirtualServerModel {
 
         let all_columns: Vec<String> = group_by_cols
             .into_iter()
-            .chain(data_columns.iter().map(|col| format!("\"{}\"", col)))
+            .chain(data_columns.iter().map(|col| self.dialect.quote_ident(col)))
             .collect();
 
         Ok(format!(
-            "SELECT {} FROM {} {}",
+            "SELECT {} FROM {}{} {}",
             all_columns.join(", "),
             view_id,
+            where_clause,
             limit_clause
         )
         .trim()
@@ -269,42 +890,256 @@ impl GenericSQLVirtualServerModel {
     /// * `view_id` - The identifier of the view.
     ///
     /// # Returns
-    /// SQL: `DESCRIBE {view_id}`
+    /// SQL: `DESCRIBE {view_id}` for DuckDB
     pub fn view_schema(&self, view_id: &str) -> GenericSQLResult<String> {
-        Ok(format!("DESCRIBE {}", view_id))
+        Ok(self.dialect.describe_table(view_id))
     }
 
-    /// Returns the SQL query to get the row count of a view.
+    /// Returns the SQL query to get the row count of a view, excluding the
+    /// rows hidden by a collapsed group.
     ///
     /// # Arguments
     /// * `view_id` - The identifier of the view.
+    /// * `config` - The view configuration.
     ///
     /// # Returns
     /// SQL: `SELECT COUNT(*) FROM {view_id}`
-    pub fn view_size(&self, view_id: &str) -> GenericSQLResult<String> {
-        Ok(format!("SELECT COUNT(*) FROM {}", view_id))
-    }
-
-    fn filter_term_to_sql(term: &FilterTerm) -> Option<String> {
-        match term {
-            FilterTerm::Scalar(scalar) => Self::scalar_to_sql(scalar),
-            FilterTerm::Array(scalars) => {
-                let values: Vec<String> = scalars.iter().filter_map(Self::scalar_to_sql).collect();
-                if values.is_empty() {
-                    None
-                } else {
-                    Some(format!("({})", values.join(", ")))
-                }
-            },
+    pub fn view_size(&self, view_id: &str, config: &ViewConfig) -> GenericSQLResult<String> {
+        if config.group_by.is_empty() {
+            Ok(format!("SELECT COUNT(*) FROM {}", view_id))
+        } else {
+            Ok(format!(
+                "SELECT COUNT(*) FROM {} WHERE __HIDDEN__ = 0",
+                view_id
+            ))
         }
     }
 
-    fn scalar_to_sql(scalar: &Scalar) -> Option<String> {
+    /// Returns the SQL query to expand the collapsed group at visible row
+    /// `row_index` of a view. The query returns the number of rows updated,
+    /// which includes the expanded row itself.
+    ///
+    /// # Arguments
+    /// * `view_id` - The identifier of the view.
+    /// * `config` - The view configuration.
+    /// * `row_index` - The index of the row to expand, among visible rows.
+    ///
+    /// # Returns
+    /// SQL: `UPDATE {view_id} SET ... FROM (...) AS __TARGET__ WHERE ...`
+    pub fn view_expand(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> GenericSQLResult<String> {
+        self.expand_collapse_query(view_id, config, row_index, false)
+    }
+
+    /// Returns the SQL query to collapse the group at visible row `row_index`
+    /// of a view, hiding its descendants. The query returns the number of
+    /// rows updated, which includes the collapsed row itself.
+    ///
+    /// # Arguments
+    /// * `view_id` - The identifier of the view.
+    /// * `config` - The view configuration.
+    /// * `row_index` - The index of the row to collapse, among visible rows.
+    ///
+    /// # Returns
+    /// SQL: `UPDATE {view_id} SET ... FROM (...) AS __TARGET__ WHERE ...`
+    pub fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> GenericSQLResult<String> {
+        self.expand_collapse_query(view_id, config, row_index, true)
+    }
+
+    /// Returns the SQL query to expand every group of a view to `depth`, and
+    /// collapse the groups below it, as the Perspective engine's
+    /// `View::set_depth` does.
+    ///
+    /// # Arguments
+    /// * `view_id` - The identifier of the view.
+    /// * `config` - The view configuration.
+    /// * `depth` - The depth to expand to.
+    ///
+    /// # Returns
+    /// SQL: `UPDATE {view_id} SET __COLLAPSED__ = ..., __HIDDEN__ = ...`
+    pub fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        depth: u32,
+    ) -> GenericSQLResult<String> {
+        if config.group_by.is_empty() {
+            return Err(GenericSQLError::UnsupportedOperation(
+                "set_depth requires a group_by".to_string(),
+            ));
+        }
+
+        let [collapsed, hidden] =
+            self.expand_state_sql("__GROUPING_ID__", config.group_by.len(), Some(depth));
+
+        Ok(format!(
+            "UPDATE {} SET __COLLAPSED__ = {}, __HIDDEN__ = {}",
+            view_id, collapsed, hidden
+        ))
+    }
+
+    /// Returns the SQL query to get the minimum and maximum values of a column
+    /// in a view. For grouped views, only the leaf rows (where
+    /// `__GROUPING_ID__` is `0`) are considered, as the Perspective engine
+    /// does.
+    ///
+    /// # Arguments
+    /// * `view_id` - The identifier of the view.
+    /// * `column_name` - The column name in the view's schema, which for a
+    ///   split-by view is its encoded column path (see
+    ///   [`crate::virtual_server::encode_column_path`]), e.g. `"East_Sales"`.
+    /// * `config` - The view configuration.
+    ///
+    /// # Returns
+    /// SQL: `SELECT MIN("{col}"), MAX("{col}") FROM {view_id}`
+    pub fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> GenericSQLResult<String> {
+        let col = self.dialect.quote_ident(column_name);
+
+        let where_clause = if config.group_by.is_empty() {
+            ""
+        } else {
+            " WHERE __GROUPING_ID__ = 0"
+        };
+
+        Ok(format!(
+            "SELECT MIN({}), MAX({}) FROM {}{}",
+            col, col, view_id, where_clause
+        ))
+    }
+
+    fn expand_collapse_query(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+        collapse: bool,
+    ) -> GenericSQLResult<String> {
+        let num_groups = config.group_by.len();
+        if num_groups == 0 {
+            return Err(GenericSQLError::UnsupportedOperation(
+                "expand/collapse requires a group_by".to_string(),
+            ));
+        }
+
+        if !self.dialect.supports_update_from() {
+            return Err(GenericSQLError::UnsupportedOperation(format!(
+                "expand/collapse is not supported in the {} dialect",
+                self.dialect.name()
+            )));
+        }
+
+        let mut target_cols = vec![
+            "__GROUPING_ID__ AS gid".to_string(),
+            "__COLLAPSED__ AS collapsed".to_string(),
+        ];
+
+        // A row is the target or one of its descendants when it matches the
+        // target's row path on every column the target is grouped by.
+        let mut conditions = vec![
+            "__TARGET__.gid > 0".to_string(),
+            format!("{}__TARGET__.collapsed", if collapse { "NOT " } else { "" }),
+            "__GROUPING_ID__ <= __TARGET__.gid".to_string(),
+        ];
+
+        for idx in 0..num_groups {
+            target_cols.push(format!("__ROW_PATH_{}__ AS p{}", idx, idx));
+            conditions.push(format!(
+                "({} OR {})",
+                self.dialect
+                    .bit_is_set("__TARGET__.gid", num_groups - 1 - idx),
+                self.dialect.is_not_distinct_from(
+                    &format!("__ROW_PATH_{}__", idx),
+                    &format!("__TARGET__.p{}", idx)
+                )
+            ));
+        }
+
+        let (collapsed, hidden) = if collapse {
+            ("true", "__HIDDEN__ + 1")
+        } else {
+            ("false", "__HIDDEN__ - 1")
+        };
+
+        Ok(format!(
+            "UPDATE {} SET __COLLAPSED__ = CASE WHEN __GROUPING_ID__ = __TARGET__.gid THEN {} \
+             ELSE __COLLAPSED__ END, __HIDDEN__ = CASE WHEN __GROUPING_ID__ = __TARGET__.gid THEN \
+             __HIDDEN__ ELSE {} END FROM (SELECT {} FROM {} WHERE __HIDDEN__ = 0 {}) AS \
+             __TARGET__ WHERE {}",
+            view_id,
+            collapsed,
+            hidden,
+            target_cols.join(", "),
+            view_id,
+            self.dialect.limit_offset(1, row_index),
+            conditions.join(" AND ")
+        ))
+    }
+
+    /// Returns the `__COLLAPSED__` and `__HIDDEN__` column expressions for a
+    /// view with `num_groups` group by columns, whose rows are expanded to
+    /// `depth` (or fully expanded if `None`). `grouping_id` is the `ROLLUP`
+    /// grouping ID of the row, from which its depth is derived.
+    pub(crate) fn expand_state_sql(
+        &self,
+        grouping_id: &str,
+        num_groups: usize,
+        depth: Option<u32>,
+    ) -> [String; 2] {
+        let Some(depth) = depth else {
+            return ["false".to_string(), "0".to_string()];
+        };
+
+        // Mirrors the engine, which clamps `depth` to the deepest expandable
+        // level and shows rows one level below it.
+        let visible_depth = depth.min(num_groups as u32 - 1) + 1;
+        let row_depth = format!(
+            "(CASE {} {} END)",
+            grouping_id,
+            (0..=num_groups)
+                .map(|row_depth| format!(
+                    "WHEN {} THEN {}",
+                    (1_u64 << (num_groups - row_depth)) - 1,
+                    row_depth
+                ))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+
+        [
+            format!(
+                "({} >= {} AND {} < {})",
+                row_depth, visible_depth, row_depth, num_groups
+            ),
+            self.dialect
+                .greatest(&format!("{} - {}", row_depth, visible_depth), "0"),
+        ]
+    }
+
+    /// Renders `scalar` as an inline SQL literal, or `None` for `Null`.
+    pub(crate) fn scalar_to_sql(&self, scalar: &Scalar) -> Option<String> {
         match scalar {
             Scalar::Null => None,
             Scalar::Bool(b) => Some(if *b { "TRUE" } else { "FALSE" }.to_string()),
             Scalar::Float(f) => Some(f.to_string()),
-            Scalar::String(s) => Some(format!("'{}'", s.replace('\'', "''"))),
+            Scalar::String(s) => Some(dialect::string_literal(s)),
+            Scalar::Date(x) => Some(self.dialect.date_literal(x)),
+            Scalar::Datetime(_) => {
+                Some(self.dialect.datetime_literal(&scalar.to_datetime_string()?))
+            },
         }
     }
 }
@@ -0,0 +1,308 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! Translation of Perspective's aggregates to SQL aggregate expressions.
+//!
+//! [`AGGREGATES`] maps every aggregate Perspective's engine offers to its SQL
+//! equivalent, where the [`SqlDialect`] has one. Other aggregate names which
+//! are valid SQL identifiers are passed through as the engine's own
+//! aggregate function, e.g. DuckDB's `kahan_sum`.
+
+use super::dialect::SqlDialect;
+use super::{GenericSQLError, GenericSQLResult};
+use crate::config::Aggregate;
+use crate::proto::ColumnType;
+
+const ALL: &[ColumnType] = &[
+    ColumnType::Integer,
+    ColumnType::Float,
+    ColumnType::String,
+    ColumnType::Boolean,
+    ColumnType::Date,
+    ColumnType::Datetime,
+];
+
+const NUMBER: &[ColumnType] = &[ColumnType::Integer, ColumnType::Float];
+const ORDERED: &[ColumnType] = &[
+    ColumnType::Integer,
+    ColumnType::Float,
+    ColumnType::Date,
+    ColumnType::Datetime,
+];
+
+const STRING: &[ColumnType] = &[ColumnType::String, ColumnType::Boolean];
+
+/// The rows a `pct sum` aggregate is a percentage of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PercentOf {
+    Parent,
+    Total,
+}
+
+/// The SQL for an aggregate of `expr` with column arguments `args` in a
+/// dialect, or `None` if the dialect cannot express it.
+type Translate = fn(&dyn SqlDialect, &str, &[String]) -> Option<String>;
+
+/// An entry of [`AGGREGATES`].
+pub(crate) struct SqlAggregate {
+    /// The aggregate's name in a [`crate::config::ViewConfig`].
+    pub name: &'static str,
+
+    /// The types of the columns the aggregate applies to.
+    pub column_types: &'static [ColumnType],
+
+    /// The types of the aggregate's column arguments, e.g. the weights of a
+    /// `weighted mean`.
+    pub args: &'static [ColumnType],
+
+    /// For `pct sum` aggregates, which are the `SUM` of a column as a
+    /// percentage of another row's, computed by the caller with a window.
+    pub percent_of: Option<PercentOf>,
+
+    /// The type of the aggregate's result, if it is not the type of the
+    /// column it applies to.
+    pub result_type: Option<ColumnType>,
+    sql: Translate,
+}
+
+impl SqlAggregate {
+    const fn new(name: &'static str, column_types: &'static [ColumnType], sql: Translate) -> Self {
+        Self {
+            name,
+            column_types,
+            args: &[],
+            percent_of: None,
+            result_type: None,
+            sql,
+        }
+    }
+
+    const fn with_args(self, args: &'static [ColumnType]) -> Self {
+        Self { args, ..self }
+    }
+
+    const fn percent_of(self, percent_of: PercentOf) -> Self {
+        Self {
+            percent_of: Some(percent_of),
+            ..self
+        }
+    }
+
+    const fn returns(self, result_type: ColumnType) -> Self {
+        Self {
+            result_type: Some(result_type),
+            ..self
+        }
+    }
+
+    /// The entry of [`AGGREGATES`] for the aggregate `name` or one of its
+    /// [`ALIASES`], ignoring case.
+    pub fn find(name: &str) -> Option<&'static SqlAggregate> {
+        let lower = name.to_lowercase();
+        let lower = ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lower)
+            .map_or(lower.as_str(), |(_, name)| name);
+
+        AGGREGATES.iter().find(|x| x.name == lower)
+    }
+
+    /// The SQL for this aggregate of `expr`, or `None` if `dialect` cannot
+    /// express it.
+    pub fn sql(&self, dialect: &dyn SqlDialect, expr: &str, args: &[String]) -> Option<String> {
+        (self.sql)(dialect, expr, args)
+    }
+
+    /// Whether `dialect` can express this aggregate.
+    pub fn is_supported(&self, dialect: &dyn SqlDialect) -> bool {
+        let args = vec!["y".to_string(); self.args.len()];
+        self.sql(dialect, "x", &args).is_some()
+    }
+}
+
+fn first(dialect: &dyn SqlDialect, expr: &str) -> Option<String> {
+    dialect.arg_min(expr, dialect.row_order()?)
+}
+
+fn last(dialect: &dyn SqlDialect, expr: &str) -> Option<String> {
+    dialect.arg_max(expr, dialect.row_order()?)
+}
+
+/// The population variance of `expr`, `NULL` for fewer than 2 values.
+fn variance(dialect: &dyn SqlDialect, expr: &str) -> String {
+    format!(
+        "CASE WHEN COUNT({}) > 1 THEN {} END",
+        expr,
+        dialect.var_pop(expr)
+    )
+}
+
+/// Every aggregate Perspective's engine offers which has a SQL translation,
+/// in the order they are listed in
+/// [`super::GenericSQLVirtualServerModel::features`].
+pub(crate) const AGGREGATES: &[SqlAggregate] = &[
+    SqlAggregate::new("sum", NUMBER, |_, x, _| Some(format!("SUM({})", x))),
+    SqlAggregate::new("abs sum", NUMBER, |_, x, _| {
+        Some(format!("ABS(SUM({}))", x))
+    }),
+    SqlAggregate::new("sum abs", NUMBER, |_, x, _| {
+        Some(format!("SUM(ABS({}))", x))
+    }),
+    SqlAggregate::new("sum not null", NUMBER, |_, x, _| {
+        Some(format!("SUM({})", x))
+    }),
+    SqlAggregate::new("any", ALL, |_, x, _| Some(format!("MAX({})", x))),
+    SqlAggregate::new("avg", NUMBER, |_, x, _| Some(format!("AVG({})", x)))
+        .returns(ColumnType::Float),
+    SqlAggregate::new("mean", NUMBER, |_, x, _| Some(format!("AVG({})", x)))
+        .returns(ColumnType::Float),
+    SqlAggregate::new("count", ALL, |_, x, _| Some(format!("COUNT({})", x)))
+        .returns(ColumnType::Integer),
+    SqlAggregate::new("distinct count", ALL, |_, x, _| {
+        Some(format!("COUNT(DISTINCT {})", x))
+    })
+    .returns(ColumnType::Integer),
+    SqlAggregate::new("dominant", ALL, |d, x, _| d.mode(x)),
+    SqlAggregate::new("first", ALL, |d, x, _| first(d, x)),
+    SqlAggregate::new("last", ALL, |d, x, _| last(d, x)),
+    SqlAggregate::new("last by index", ALL, |d, x, _| last(d, x)),
+    SqlAggregate::new("last minus first", NUMBER, |d, x, _| {
+        Some(format!("({} - {})", last(d, x)?, first(d, x)?))
+    }),
+    SqlAggregate::new("high", ORDERED, |_, x, _| Some(format!("MAX({})", x))),
+    SqlAggregate::new("low", ORDERED, |_, x, _| Some(format!("MIN({})", x))),
+    SqlAggregate::new("max", ORDERED, |_, x, _| Some(format!("MAX({})", x))),
+    SqlAggregate::new("min", ORDERED, |_, x, _| Some(format!("MIN({})", x))),
+    SqlAggregate::new("high minus low", NUMBER, |_, x, _| {
+        Some(format!("(MAX({}) - MIN({}))", x, x))
+    }),
+    SqlAggregate::new("median", ALL, |d, x, _| d.quantile(x, 0.5)),
+    SqlAggregate::new("q1", ALL, |d, x, _| d.quantile(x, 0.25)),
+    SqlAggregate::new("q3", ALL, |d, x, _| d.quantile(x, 0.75)),
+    SqlAggregate::new("pct sum parent", NUMBER, |_, x, _| {
+        Some(format!("SUM({})", x))
+    })
+    .percent_of(PercentOf::Parent)
+    .returns(ColumnType::Float),
+    SqlAggregate::new("pct sum total", NUMBER, |_, x, _| {
+        Some(format!("SUM({})", x))
+    })
+    .percent_of(PercentOf::Total)
+    .returns(ColumnType::Float),
+    SqlAggregate::new("stddev", NUMBER, |d, x, _| {
+        Some(format!("SQRT({})", variance(d, x)))
+    })
+    .returns(ColumnType::Float),
+    SqlAggregate::new("var", NUMBER, |d, x, _| Some(variance(d, x))).returns(ColumnType::Float),
+    SqlAggregate::new("unique", ALL, |_, x, _| {
+        Some(format!(
+            "CASE WHEN COUNT(DISTINCT {}) = 1 THEN MIN({}) END",
+            x, x
+        ))
+    }),
+    SqlAggregate::new("join", STRING, |d, x, _| d.join_distinct(x)).returns(ColumnType::String),
+    SqlAggregate::new("weighted mean", NUMBER, |d, x, args| {
+        let [weight] = args else { return None };
+        let total = format!("SUM(CASE WHEN {} IS NOT NULL THEN {} END)", x, weight);
+        Some(d.divide(
+            &format!("SUM({} * {})", x, weight),
+            &format!("NULLIF({}, 0)", total),
+        ))
+    })
+    .with_args(&[ColumnType::Float])
+    .returns(ColumnType::Float),
+    SqlAggregate::new("min by", STRING, |d, x, args| {
+        let [key] = args else { return None };
+        d.arg_min(x, key)
+    })
+    .with_args(&[ColumnType::Float]),
+    SqlAggregate::new("max by", STRING, |d, x, args| {
+        let [key] = args else { return None };
+        d.arg_max(x, key)
+    })
+    .with_args(&[ColumnType::Float]),
+];
+
+/// Other names the engine accepts for entries of [`AGGREGATES`].
+const ALIASES: &[(&str, &str)] = &[
+    ("pct sum grand total", "pct sum total"),
+    ("first by index", "first"),
+];
+
+/// An [`Aggregate`] of a view's column, resolved to its SQL translation.
+pub(crate) enum ColumnAggregate<'a> {
+    /// An entry of [`AGGREGATES`], with the SQL of its column arguments.
+    Sql(&'static SqlAggregate, Vec<String>),
+
+    /// An aggregate function of the engine, called by name.
+    Function(&'a str),
+}
+
+impl ColumnAggregate<'_> {
+    /// Resolves `aggregate`, whose column arguments are translated by
+    /// `col_name`, failing if `dialect` cannot express it.
+    pub fn new<'a>(
+        dialect: &dyn SqlDialect,
+        aggregate: &'a Aggregate,
+        col_name: impl Fn(&str) -> String,
+    ) -> GenericSQLResult<ColumnAggregate<'a>> {
+        let (name, args) = match aggregate {
+            Aggregate::SingleAggregate(name) => (name.as_str(), &[][..]),
+            Aggregate::MultiAggregate(name, args) => (name.as_str(), args.as_slice()),
+        };
+
+        let Some(agg) = SqlAggregate::find(name) else {
+            let is_ident = name.starts_with(|x: char| x.is_ascii_alphabetic() || x == '_')
+                && name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_');
+
+            return if is_ident && args.is_empty() {
+                Ok(ColumnAggregate::Function(name))
+            } else {
+                Err(GenericSQLError::UnsupportedOperation(format!(
+                    "aggregate `{}`",
+                    aggregate
+                )))
+            };
+        };
+
+        if args.len() != agg.args.len() {
+            return Err(GenericSQLError::InvalidConfig(format!(
+                "Aggregate `{}` takes {} column argument(s)",
+                agg.name,
+                agg.args.len()
+            )));
+        }
+
+        if !agg.is_supported(dialect) {
+            return Err(GenericSQLError::UnsupportedOperation(format!(
+                "aggregate `{}` in {}",
+                agg.name,
+                dialect.name()
+            )));
+        }
+
+        let args = args.iter().map(|x| col_name(x)).collect();
+        Ok(ColumnAggregate::Sql(agg, args))
+    }
+
+    /// The SQL for this aggregate of `expr`, and the rows it is a percentage
+    /// of, if any.
+    pub fn sql(&self, dialect: &dyn SqlDialect, expr: &str) -> (String, Option<PercentOf>) {
+        match self {
+            Self::Sql(agg, args) => (
+                agg.sql(dialect, expr, args).unwrap_or_default(),
+                agg.percent_of,
+            ),
+            Self::Function(name) => (format!("{}({})", name, expr), None),
+        }
+    }
+}
@@ -0,0 +1,977 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! SQL dialects for [`super::GenericSQLVirtualServerModel`].
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::proto::ColumnType;
+
+/// The SQL syntax and catalog differences between database engines which
+/// [`super::GenericSQLVirtualServerModel`] needs to account for.
+///
+/// Every method has a default implementation which emits standard SQL, so an
+/// implementation only needs to override what its engine does differently.
+/// See [`DuckDbDialect`], [`PostgresDialect`], [`SqliteDialect`],
+/// [`ClickHouseDialect`], [`DataFusionDialect`] and [`AnsiDialect`] for the
+/// built-in dialects.
+pub trait SqlDialect: Debug + Send + Sync {
+    /// The name of this dialect, e.g. `"duckdb"`.
+    fn name(&self) -> &'static str;
+
+    /// Quotes `name` as an identifier, e.g. a column name.
+    fn quote_ident(&self, name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    /// The placeholder for the `index`th (from 1) positional parameter of a
+    /// parameterized query.
+    fn placeholder(&self, _index: usize) -> String {
+        "?".to_string()
+    }
+
+    /// A query listing the hosted tables.
+    fn list_tables(&self) -> String {
+        "SELECT table_schema, table_name FROM information_schema.tables WHERE table_schema NOT IN \
+         ('information_schema', 'pg_catalog')"
+            .to_string()
+    }
+
+    /// A query listing the columns of `table_id`, whose first two result
+    /// columns are the column name and its type name.
+    fn describe_table(&self, table_id: &str) -> String {
+        let (schema, table) = match table_id.rsplit_once('.') {
+            Some((schema, table)) => (Some(schema), table),
+            None => (None, table_id),
+        };
+
+        let schema_clause = schema
+            .map(|x| format!(" AND table_schema = {}", string_literal(x)))
+            .unwrap_or_default();
+
+        format!(
+            "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = \
+             {}{} ORDER BY ordinal_position",
+            string_literal(table),
+            schema_clause
+        )
+    }
+
+    /// A query for the number of columns of `view_id`, excluding internal
+    /// `__`-prefixed columns.
+    fn count_view_columns(&self, view_id: &str) -> String {
+        format!(
+            "SELECT COUNT(*) FROM ({}) AS __COLUMNS__ WHERE SUBSTR(column_name, 1, 2) <> '__'",
+            self.describe_table(view_id)
+        )
+    }
+
+    /// A query for the type name of `expression` evaluated against
+    /// `table_id`, as the second result column of its first row, or `None` if
+    /// the engine cannot report it.
+    fn describe_expression(&self, _table_id: &str, _expression: &str) -> Option<String> {
+        None
+    }
+
+    /// A statement creating `view_id` as a `entity` (e.g. `TABLE`) from
+    /// `query`.
+    fn create_table_as(&self, entity: &str, view_id: &str, query: &str) -> String {
+        format!("CREATE {} {} AS ({})", entity, view_id, query)
+    }
+
+    /// The pagination clause selecting `limit` rows after the first `offset`.
+    fn limit_offset(&self, limit: u32, offset: u32) -> String {
+        format!("LIMIT {} OFFSET {}", limit, offset)
+    }
+
+    /// The function returning the grouping bit mask of the `ROLLUP` columns
+    /// passed to it, or `None` if the engine does not support `ROLLUP`, in
+    /// which case it is emulated with `UNION ALL`.
+    fn grouping_fn(&self) -> Option<&'static str> {
+        Some("GROUPING")
+    }
+
+    /// Whether the engine supports DuckDB's `PIVOT ... ON ... USING`
+    /// statement. Otherwise, `split_by` is emulated with conditional
+    /// aggregation over the split values, see
+    /// [`super::GenericSQLVirtualServerModel::view_split_values`].
+    fn supports_pivot(&self) -> bool {
+        false
+    }
+
+    /// Whether the engine supports `UPDATE ... FROM`, which row
+    /// expand/collapse and indexed `Table::update` require.
+    fn supports_update_from(&self) -> bool {
+        true
+    }
+
+    /// An expression for the insertion order of a table's rows, which flat
+    /// views are sorted by when they have no `sort`.
+    fn row_order(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The aggregate applied to columns without one in a grouped view.
+    fn default_aggregate(&self) -> &'static str {
+        "MAX"
+    }
+
+    /// The window function returning the first value of its window.
+    fn first_value_fn(&self) -> &'static str {
+        "FIRST_VALUE"
+    }
+
+    /// The value of `value` in the row with the smallest `key`, or `None` if
+    /// the engine has no such aggregate.
+    fn arg_min(&self, _value: &str, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// The value of `value` in the row with the largest `key`, or `None` if
+    /// the engine has no such aggregate.
+    fn arg_max(&self, _value: &str, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// The most frequent value of `expr`, or `None` if the engine has no such
+    /// aggregate.
+    fn mode(&self, expr: &str) -> Option<String> {
+        Some(format!("MODE() WITHIN GROUP (ORDER BY {})", expr))
+    }
+
+    /// The `q` quantile of `expr`, one of its values, or `None` if the engine
+    /// has no such aggregate.
+    fn quantile(&self, expr: &str, q: f64) -> Option<String> {
+        Some(format!(
+            "PERCENTILE_DISC({}) WITHIN GROUP (ORDER BY {})",
+            q, expr
+        ))
+    }
+
+    /// The sorted distinct values of `expr` as text, joined by `, `, or
+    /// `None` if the engine has no such aggregate.
+    fn join_distinct(&self, expr: &str) -> Option<String> {
+        let text = self.cast_to_text(expr);
+        Some(format!(
+            "STRING_AGG(DISTINCT {}, ', ' ORDER BY {})",
+            text, text
+        ))
+    }
+
+    /// The population variance of `expr`.
+    fn var_pop(&self, expr: &str) -> String {
+        format!("VAR_POP({})", expr)
+    }
+
+    /// The larger of `a` and `b`.
+    fn greatest(&self, a: &str, b: &str) -> String {
+        format!("GREATEST({}, {})", a, b)
+    }
+
+    /// The integer `expr` shifted right by `bits`.
+    fn shift_right(&self, expr: &str, bits: usize) -> String {
+        format!("({} >> {})", expr, bits)
+    }
+
+    /// Whether bit `bit` of the integer `expr` is set.
+    fn bit_is_set(&self, expr: &str, bit: usize) -> String {
+        format!("(({} >> {}) & 1) = 1", expr, bit)
+    }
+
+    /// Whether `a` equals `b`, treating `NULL`s as equal.
+    fn is_not_distinct_from(&self, a: &str, b: &str) -> String {
+        format!("{} IS NOT DISTINCT FROM {}", a, b)
+    }
+
+    /// Whether the string `expr` matches the `LIKE` pattern `pattern`, in
+    /// which `\` escapes `%` and `_`.
+    fn like(&self, expr: &str, pattern: &str) -> String {
+        format!("{} LIKE {} ESCAPE '\\'", expr, pattern)
+    }
+
+    /// `expr` cast to a string, for comparison with `split_by` values.
+    fn cast_to_text(&self, expr: &str) -> String {
+        format!("CAST({} AS VARCHAR)", expr)
+    }
+
+    /// `expr` cast to the SQL type for the Perspective column type `ty`, for
+    /// the `string()`, `integer()`, `float()` and `boolean()` expression
+    /// functions.
+    fn cast(&self, expr: &str, ty: ColumnType) -> String {
+        let type_name = match ty {
+            ColumnType::String => return self.cast_to_text(expr),
+            ColumnType::Integer => "INTEGER",
+            ColumnType::Float => "DOUBLE PRECISION",
+            ColumnType::Boolean => "BOOLEAN",
+            ColumnType::Date => "DATE",
+            ColumnType::Datetime => "TIMESTAMP",
+        };
+
+        format!("CAST({} AS {})", expr, type_name)
+    }
+
+    /// A literal for the ISO 8601 `YYYY-MM-DD` date `date`.
+    fn date_literal(&self, date: &str) -> String {
+        format!("DATE {}", string_literal(date))
+    }
+
+    /// A literal for the ISO 8601 `YYYY-MM-DD HH:MM:SS.sss` UTC timestamp
+    /// `datetime`.
+    fn datetime_literal(&self, datetime: &str) -> String {
+        format!("TIMESTAMP {}", string_literal(datetime))
+    }
+
+    /// The smaller of `a` and `b`.
+    fn least(&self, a: &str, b: &str) -> String {
+        format!("LEAST({}, {})", a, b)
+    }
+
+    /// `a` divided by `b`, as a floating point number even when both are
+    /// integers.
+    fn divide(&self, a: &str, b: &str) -> String {
+        format!("({} / {})", a, b)
+    }
+
+    /// The remainder of `a` divided by `b`.
+    fn modulo(&self, a: &str, b: &str) -> String {
+        format!("({} % {})", a, b)
+    }
+
+    /// The date or timestamp `expr` truncated to the start of its `unit`,
+    /// one of `second`, `minute`, `hour`, `day`, `week`, `month` or `year`,
+    /// or `None` if the engine cannot.
+    fn date_trunc(&self, unit: &str, expr: &str) -> Option<String> {
+        Some(format!("DATE_TRUNC('{}', {})", unit, expr))
+    }
+
+    /// Whether any part of the string `expr` matches the regular expression
+    /// `pattern`, or `None` if the engine has no regular expressions.
+    fn regexp_matches(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("REGEXP_MATCHES({}, {})", expr, pattern))
+    }
+
+    /// The first capturing group of the regular expression `pattern` in the
+    /// string `expr`, or `None` if the engine has no regular expressions.
+    fn regexp_extract(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("REGEXP_EXTRACT({}, {}, 1)", expr, pattern))
+    }
+
+    /// The string `expr` with the first (or every, if `all`) match of the
+    /// regular expression `pattern` replaced by `replacement`, or `None` if
+    /// the engine has no regular expressions.
+    fn regexp_replace(
+        &self,
+        expr: &str,
+        pattern: &str,
+        replacement: &str,
+        all: bool,
+    ) -> Option<String> {
+        Some(if all {
+            format!(
+                "REGEXP_REPLACE({}, {}, {}, 'g')",
+                expr, pattern, replacement
+            )
+        } else {
+            format!("REGEXP_REPLACE({}, {}, {})", expr, pattern, replacement)
+        })
+    }
+
+    /// A `FROM` source aliased `alias`, with columns `columns`, for a
+    /// `VALUES` list.
+    fn values_source(&self, values: &str, alias: &str, columns: &[String]) -> String {
+        let columns = columns
+            .iter()
+            .map(|x| self.quote_ident(x))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("(VALUES {}) AS {}({})", values, alias, columns)
+    }
+
+    /// The Perspective column type for the engine's type name `type_name`.
+    fn column_type(&self, type_name: &str) -> ColumnType {
+        default_column_type(type_name)
+    }
+}
+
+pub(crate) fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Maps common SQL type names to Perspective column types. 64-bit and wider
+/// integers are `float`, as they do not fit Perspective's 32-bit `integer`.
+fn default_column_type(type_name: &str) -> ColumnType {
+    let name = type_name.to_lowercase();
+    let name = name
+        .trim_start_matches("nullable(")
+        .trim_start_matches("lowcardinality(")
+        .trim_start_matches("nullable(");
+
+    if name.starts_with("bool") {
+        ColumnType::Boolean
+    } else if name.starts_with("timestamp") || name.starts_with("datetime") {
+        ColumnType::Datetime
+    } else if name.starts_with("date") {
+        ColumnType::Date
+    } else if name.starts_with("interval") {
+        ColumnType::String
+    } else if [
+        "bigint",
+        "ubigint",
+        "hugeint",
+        "uhugeint",
+        "uinteger",
+        "int8",
+        "int64",
+        "int128",
+        "int256",
+        "uint",
+        "bigserial",
+    ]
+    .iter()
+    .any(|x| name.starts_with(x))
+    {
+        ColumnType::Float
+    } else if [
+        "int",
+        "smallint",
+        "usmallint",
+        "tinyint",
+        "utinyint",
+        "serial",
+        "smallserial",
+        "mediumint",
+    ]
+    .iter()
+    .any(|x| name.starts_with(x))
+    {
+        ColumnType::Integer
+    } else if ["double", "float", "real", "decimal", "numeric", "number"]
+        .iter()
+        .any(|x| name.starts_with(x))
+    {
+        ColumnType::Float
+    } else {
+        ColumnType::String
+    }
+}
+
+/// [DuckDB](https://duckdb.org), the default dialect.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DuckDbDialect;
+
+impl SqlDialect for DuckDbDialect {
+    fn name(&self) -> &'static str {
+        "duckdb"
+    }
+
+    fn list_tables(&self) -> String {
+        "SHOW ALL TABLES".to_string()
+    }
+
+    fn describe_table(&self, table_id: &str) -> String {
+        format!("DESCRIBE {}", table_id)
+    }
+
+    fn count_view_columns(&self, view_id: &str) -> String {
+        format!(
+            "SELECT COUNT(*) FROM (DESCRIBE {}) WHERE NOT starts_with(column_name, '__')",
+            view_id
+        )
+    }
+
+    fn describe_expression(&self, table_id: &str, expression: &str) -> Option<String> {
+        Some(format!(
+            "DESCRIBE (SELECT {} FROM {})",
+            expression, table_id
+        ))
+    }
+
+    fn grouping_fn(&self) -> Option<&'static str> {
+        Some("GROUPING_ID")
+    }
+
+    fn supports_pivot(&self) -> bool {
+        true
+    }
+
+    fn row_order(&self) -> Option<&'static str> {
+        Some("rowid")
+    }
+
+    fn default_aggregate(&self) -> &'static str {
+        "any_value"
+    }
+
+    fn first_value_fn(&self) -> &'static str {
+        "first"
+    }
+
+    fn arg_min(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!("arg_min({}, {})", value, key))
+    }
+
+    fn arg_max(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!("arg_max({}, {})", value, key))
+    }
+
+    fn mode(&self, expr: &str) -> Option<String> {
+        Some(format!("mode({})", expr))
+    }
+
+    fn quantile(&self, expr: &str, q: f64) -> Option<String> {
+        Some(format!("quantile_disc({}, {})", expr, q))
+    }
+}
+
+/// [PostgreSQL](https://www.postgresql.org).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PostgresDialect;
+
+impl SqlDialect for PostgresDialect {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn placeholder(&self, index: usize) -> String {
+        format!("${}", index)
+    }
+
+    fn describe_expression(&self, table_id: &str, expression: &str) -> Option<String> {
+        Some(format!(
+            "SELECT NULL, pg_typeof({})::text FROM {} LIMIT 1",
+            expression, table_id
+        ))
+    }
+
+    fn default_aggregate(&self) -> &'static str {
+        "any_value"
+    }
+
+    fn cast_to_text(&self, expr: &str) -> String {
+        format!("CAST({} AS TEXT)", expr)
+    }
+
+    fn divide(&self, a: &str, b: &str) -> String {
+        format!("({} / {})", a, self.cast(b, ColumnType::Float))
+    }
+
+    fn regexp_matches(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("({} ~ {})", expr, pattern))
+    }
+
+    fn regexp_extract(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("SUBSTRING({} FROM {})", expr, pattern))
+    }
+
+    fn arg_min(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!(
+            "(ARRAY_AGG({} ORDER BY {}) FILTER (WHERE {} IS NOT NULL))[1]",
+            value, key, key
+        ))
+    }
+
+    fn arg_max(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!(
+            "(ARRAY_AGG({} ORDER BY {} DESC) FILTER (WHERE {} IS NOT NULL))[1]",
+            value, key, key
+        ))
+    }
+}
+
+/// [SQLite](https://sqlite.org), which supports neither `ROLLUP`,
+/// `GREATEST` nor regular expressions. Table names must be unqualified.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SqliteDialect;
+
+impl SqlDialect for SqliteDialect {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn list_tables(&self) -> String {
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+            .to_string()
+    }
+
+    fn describe_table(&self, table_id: &str) -> String {
+        format!(
+            "SELECT name, type FROM pragma_table_info({})",
+            string_literal(table_id)
+        )
+    }
+
+    fn count_view_columns(&self, view_id: &str) -> String {
+        format!(
+            "SELECT COUNT(*) FROM pragma_table_info({}) WHERE SUBSTR(name, 1, 2) <> '__'",
+            string_literal(view_id)
+        )
+    }
+
+    fn describe_expression(&self, table_id: &str, expression: &str) -> Option<String> {
+        Some(format!(
+            "SELECT NULL, typeof({}) FROM {} LIMIT 1",
+            expression, table_id
+        ))
+    }
+
+    fn create_table_as(&self, entity: &str, view_id: &str, query: &str) -> String {
+        format!("CREATE {} {} AS {}", entity, view_id, query)
+    }
+
+    fn grouping_fn(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn row_order(&self) -> Option<&'static str> {
+        Some("rowid")
+    }
+
+    fn greatest(&self, a: &str, b: &str) -> String {
+        format!("MAX({}, {})", a, b)
+    }
+
+    fn least(&self, a: &str, b: &str) -> String {
+        format!("MIN({}, {})", a, b)
+    }
+
+    fn is_not_distinct_from(&self, a: &str, b: &str) -> String {
+        format!("{} IS {}", a, b)
+    }
+
+    fn cast_to_text(&self, expr: &str) -> String {
+        format!("CAST({} AS TEXT)", expr)
+    }
+
+    fn cast(&self, expr: &str, ty: ColumnType) -> String {
+        // Dates are stored as text, which `CAST(... AS DATE)` would truncate
+        // to a number.
+        match ty {
+            ColumnType::String => self.cast_to_text(expr),
+            ColumnType::Integer => format!("CAST({} AS INTEGER)", expr),
+            ColumnType::Float => format!("CAST({} AS REAL)", expr),
+            ColumnType::Boolean => format!("(CAST({} AS INTEGER) <> 0)", expr),
+            ColumnType::Date => format!("date({})", expr),
+            ColumnType::Datetime => format!("datetime({})", expr),
+        }
+    }
+
+    fn mode(&self, _expr: &str) -> Option<String> {
+        None
+    }
+
+    fn quantile(&self, _expr: &str, _q: f64) -> Option<String> {
+        None
+    }
+
+    fn join_distinct(&self, _expr: &str) -> Option<String> {
+        // `GROUP_CONCAT(DISTINCT ...)` cannot take a separator.
+        None
+    }
+
+    fn var_pop(&self, expr: &str) -> String {
+        format!("(AVG({} * {}) - AVG({}) * AVG({}))", expr, expr, expr, expr)
+    }
+
+    fn date_literal(&self, date: &str) -> String {
+        self.cast(&string_literal(date), ColumnType::Date)
+    }
+
+    fn datetime_literal(&self, datetime: &str) -> String {
+        self.cast(&string_literal(datetime), ColumnType::Datetime)
+    }
+
+    fn divide(&self, a: &str, b: &str) -> String {
+        format!("({} / {})", a, self.cast(b, ColumnType::Float))
+    }
+
+    fn date_trunc(&self, unit: &str, expr: &str) -> Option<String> {
+        Some(match unit {
+            "second" => format!("strftime('%Y-%m-%d %H:%M:%S', {})", expr),
+            "minute" => format!("strftime('%Y-%m-%d %H:%M:00', {})", expr),
+            "hour" => format!("strftime('%Y-%m-%d %H:00:00', {})", expr),
+            "day" => format!("date({})", expr),
+            "week" => format!("date({}, '-6 days', 'weekday 1')", expr),
+            "month" => format!("strftime('%Y-%m-01', {})", expr),
+            "year" => format!("strftime('%Y-01-01', {})", expr),
+            _ => return None,
+        })
+    }
+
+    fn regexp_matches(&self, _expr: &str, _pattern: &str) -> Option<String> {
+        None
+    }
+
+    fn regexp_extract(&self, _expr: &str, _pattern: &str) -> Option<String> {
+        None
+    }
+
+    fn regexp_replace(
+        &self,
+        _expr: &str,
+        _pattern: &str,
+        _replacement: &str,
+        _all: bool,
+    ) -> Option<String> {
+        None
+    }
+
+    fn values_source(&self, values: &str, alias: &str, columns: &[String]) -> String {
+        let columns = columns
+            .iter()
+            .enumerate()
+            .map(|(idx, x)| format!("column{} AS {}", idx + 1, self.quote_ident(x)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("(SELECT {} FROM (VALUES {})) AS {}", columns, values, alias)
+    }
+
+    fn column_type(&self, type_name: &str) -> ColumnType {
+        // `typeof()` reports storage classes rather than declared types.
+        match type_name.to_lowercase().as_str() {
+            "real" => ColumnType::Float,
+            "text" | "blob" | "null" => ColumnType::String,
+            _ => default_column_type(type_name),
+        }
+    }
+}
+
+/// [ClickHouse](https://clickhouse.com), which does not support
+/// `UPDATE ... FROM`, so views cannot be expanded or collapsed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClickHouseDialect;
+
+impl SqlDialect for ClickHouseDialect {
+    fn name(&self) -> &'static str {
+        "clickhouse"
+    }
+
+    fn list_tables(&self) -> String {
+        "SHOW TABLES".to_string()
+    }
+
+    fn describe_table(&self, table_id: &str) -> String {
+        format!("DESCRIBE {}", table_id)
+    }
+
+    fn count_view_columns(&self, view_id: &str) -> String {
+        format!(
+            "SELECT COUNT() FROM system.columns WHERE table = {} AND NOT startsWith(name, '__')",
+            string_literal(view_id)
+        )
+    }
+
+    fn describe_expression(&self, table_id: &str, expression: &str) -> Option<String> {
+        Some(format!(
+            "DESCRIBE (SELECT {} FROM {})",
+            expression, table_id
+        ))
+    }
+
+    fn supports_update_from(&self) -> bool {
+        false
+    }
+
+    fn default_aggregate(&self) -> &'static str {
+        "any"
+    }
+
+    fn first_value_fn(&self) -> &'static str {
+        "first_value"
+    }
+
+    fn shift_right(&self, expr: &str, bits: usize) -> String {
+        format!("bitShiftRight({}, {})", expr, bits)
+    }
+
+    fn bit_is_set(&self, expr: &str, bit: usize) -> String {
+        format!("bitTest({}, {}) = 1", expr, bit)
+    }
+
+    fn is_not_distinct_from(&self, a: &str, b: &str) -> String {
+        format!("({} = {} OR ({} IS NULL AND {} IS NULL))", a, b, a, b)
+    }
+
+    fn cast_to_text(&self, expr: &str) -> String {
+        format!("toString({})", expr)
+    }
+
+    fn like(&self, expr: &str, pattern: &str) -> String {
+        // `\` is always the escape character, and `ESCAPE` is unsupported.
+        format!("{} LIKE {}", expr, pattern)
+    }
+
+    fn arg_min(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!("argMin({}, {})", value, key))
+    }
+
+    fn arg_max(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!("argMax({}, {})", value, key))
+    }
+
+    fn mode(&self, expr: &str) -> Option<String> {
+        Some(format!("topK(1)({})[1]", expr))
+    }
+
+    fn quantile(&self, expr: &str, q: f64) -> Option<String> {
+        Some(format!("quantileExact({})({})", q, expr))
+    }
+
+    fn join_distinct(&self, expr: &str) -> Option<String> {
+        Some(format!(
+            "arrayStringConcat(arraySort(groupUniqArray({})), ', ')",
+            self.cast_to_text(expr)
+        ))
+    }
+
+    fn var_pop(&self, expr: &str) -> String {
+        format!("varPop({})", expr)
+    }
+
+    fn date_literal(&self, date: &str) -> String {
+        format!("toDate({})", string_literal(date))
+    }
+
+    fn datetime_literal(&self, datetime: &str) -> String {
+        format!("toDateTime64({}, 3, 'UTC')", string_literal(datetime))
+    }
+
+    fn regexp_matches(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("match({}, {})", expr, pattern))
+    }
+
+    fn regexp_extract(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("extract({}, {})", expr, pattern))
+    }
+
+    fn regexp_replace(
+        &self,
+        expr: &str,
+        pattern: &str,
+        replacement: &str,
+        all: bool,
+    ) -> Option<String> {
+        let func = if all {
+            "replaceRegexpAll"
+        } else {
+            "replaceRegexpOne"
+        };
+
+        Some(format!("{}({}, {}, {})", func, expr, pattern, replacement))
+    }
+
+    fn column_type(&self, type_name: &str) -> ColumnType {
+        // ClickHouse's `Int8` is 8 bits wide, not 8 bytes.
+        let name = type_name.trim_start_matches("Nullable(");
+        if ["Int8", "Int16", "Int32", "UInt8", "UInt16"]
+            .iter()
+            .any(|x| {
+                name.strip_prefix(x)
+                    .is_some_and(|x| !x.starts_with(char::is_numeric))
+            })
+        {
+            ColumnType::Integer
+        } else {
+            default_column_type(type_name)
+        }
+    }
+}
+
+/// [Apache DataFusion](https://datafusion.apache.org), which cannot `UPDATE`
+/// its tables, so views cannot be expanded or collapsed. Unquoted table names
+/// are lowercased, so tables must have lowercase names.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DataFusionDialect;
+
+impl SqlDialect for DataFusionDialect {
+    fn name(&self) -> &'static str {
+        "datafusion"
+    }
+
+    fn list_tables(&self) -> String {
+        "SELECT table_schema, table_name FROM information_schema.tables WHERE table_type = 'BASE \
+         TABLE'"
+            .to_string()
+    }
+
+    fn describe_expression(&self, table_id: &str, expression: &str) -> Option<String> {
+        Some(format!(
+            "SELECT NULL, arrow_typeof({}) FROM {} LIMIT 1",
+            expression, table_id
+        ))
+    }
+
+    fn create_table_as(&self, entity: &str, view_id: &str, query: &str) -> String {
+        format!("CREATE {} {} AS {}", entity, view_id, query)
+    }
+
+    fn supports_update_from(&self) -> bool {
+        false
+    }
+
+    fn arg_min(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!(
+            "first_value({} ORDER BY {} ASC NULLS LAST)",
+            value, key
+        ))
+    }
+
+    fn arg_max(&self, value: &str, key: &str) -> Option<String> {
+        Some(format!(
+            "first_value({} ORDER BY {} DESC NULLS LAST)",
+            value, key
+        ))
+    }
+
+    fn mode(&self, _expr: &str) -> Option<String> {
+        None
+    }
+
+    fn quantile(&self, _expr: &str, _q: f64) -> Option<String> {
+        None
+    }
+
+    fn join_distinct(&self, expr: &str) -> Option<String> {
+        Some(format!(
+            "array_to_string(array_sort(array_agg(DISTINCT {})), ', ')",
+            self.cast_to_text(expr)
+        ))
+    }
+
+    fn like(&self, expr: &str, pattern: &str) -> String {
+        // `\` is always the escape character.
+        format!("{} LIKE {}", expr, pattern)
+    }
+
+    fn divide(&self, a: &str, b: &str) -> String {
+        format!("({} / {})", a, self.cast(b, ColumnType::Float))
+    }
+
+    fn regexp_matches(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("regexp_like({}, {})", expr, pattern))
+    }
+
+    fn regexp_extract(&self, expr: &str, pattern: &str) -> Option<String> {
+        Some(format!("regexp_match({}, {})[1]", expr, pattern))
+    }
+
+    fn column_type(&self, type_name: &str) -> ColumnType {
+        // Arrow's `Int8` is 8 bits wide, not 8 bytes.
+        if ["Int8", "Int16", "Int32", "UInt8", "UInt16"].contains(&type_name) {
+            ColumnType::Integer
+        } else {
+            default_column_type(type_name)
+        }
+    }
+}
+
+/// Standard SQL, for engines without a built-in dialect. Expression types
+/// cannot be inferred, bit operations use arithmetic, and expressions cannot
+/// use `bucket()` or regular expressions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnsiDialect;
+
+impl SqlDialect for AnsiDialect {
+    fn name(&self) -> &'static str {
+        "ansi"
+    }
+
+    fn limit_offset(&self, limit: u32, offset: u32) -> String {
+        format!("OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit)
+    }
+
+    fn shift_right(&self, expr: &str, bits: usize) -> String {
+        format!("FLOOR({} / {})", expr, 1_u64 << bits)
+    }
+
+    fn bit_is_set(&self, expr: &str, bit: usize) -> String {
+        format!("MOD({}, 2) = 1", self.shift_right(expr, bit))
+    }
+
+    fn cast_to_text(&self, expr: &str) -> String {
+        format!("CAST({} AS VARCHAR(1024))", expr)
+    }
+
+    fn divide(&self, a: &str, b: &str) -> String {
+        format!("({} / {})", a, self.cast(b, ColumnType::Float))
+    }
+
+    fn modulo(&self, a: &str, b: &str) -> String {
+        format!("MOD({}, {})", a, b)
+    }
+
+    fn mode(&self, _expr: &str) -> Option<String> {
+        None
+    }
+
+    fn join_distinct(&self, expr: &str) -> Option<String> {
+        let text = self.cast_to_text(expr);
+        Some(format!(
+            "LISTAGG(DISTINCT {}, ', ') WITHIN GROUP (ORDER BY {})",
+            text, text
+        ))
+    }
+
+    fn date_trunc(&self, _unit: &str, _expr: &str) -> Option<String> {
+        None
+    }
+
+    fn regexp_matches(&self, _expr: &str, _pattern: &str) -> Option<String> {
+        None
+    }
+
+    fn regexp_extract(&self, _expr: &str, _pattern: &str) -> Option<String> {
+        None
+    }
+
+    fn regexp_replace(
+        &self,
+        _expr: &str,
+        _pattern: &str,
+        _replacement: &str,
+        _all: bool,
+    ) -> Option<String> {
+        None
+    }
+}
+
+/// The name of a built-in [`SqlDialect`], as accepted by the `dialect` field
+/// of [`super::GenericSQLVirtualServerModelArgs`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialectName {
+    #[default]
+    DuckDb,
+    Postgres,
+    Sqlite,
+    ClickHouse,
+    DataFusion,
+    Ansi,
+}
+
+impl SqlDialectName {
+    /// Creates the dialect of this name.
+    pub fn dialect(self) -> Arc<dyn SqlDialect> {
+        match self {
+            Self::DuckDb => Arc::new(DuckDbDialect),
+            Self::Postgres => Arc::new(PostgresDialect),
+            Self::Sqlite => Arc::new(SqliteDialect),
+            Self::ClickHouse => Arc::new(ClickHouseDialect),
+            Self::DataFusion => Arc::new(DataFusionDialect),
+            Self::Ansi => Arc::new(AnsiDialect),
+        }
+    }
+}
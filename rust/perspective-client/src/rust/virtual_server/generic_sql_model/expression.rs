@@ -0,0 +1,834 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! Translation of Perspective's ExprTK expression columns to SQL.
+//!
+//! Supports literals, column references, `var` declarations, arithmetic,
+//! comparison and logical operators, `if`/`else` and the ternary operator,
+//! and the functions listed in [`crate::config::COMPLETIONS`] which have a
+//! SQL equivalent. Anything else (e.g. `for` loops, `col()`, `vlookup()`)
+//! is a [`GenericSQLError::UnsupportedOperation`].
+
+use std::ops::Range;
+
+use super::dialect::{SqlDialect, string_literal};
+use super::{GenericSQLError, GenericSQLResult};
+use crate::exprtk::{Token, tokenize};
+use crate::proto::ColumnType;
+use crate::virtual_server::ExpressionDiagnostic;
+
+/// ExprTK operators, longest first so e.g. `<=` is not lexed as `<`, `=`.
+const OPERATORS: [&str; 28] = [
+    ":=", "==", "!=", "<>", "<=", ">=", "+", "-", "*", "/", "%", "^", "&", "|", "=", "<", ">", "(",
+    ")", "{", "}", "[", "]", ",", ";", ":", "?", "!",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Lexeme {
+    Number(String),
+    String(String),
+    Column(String),
+    Symbol(String),
+    Operator(&'static str),
+}
+
+/// Translates the ExprTK `expression` to a SQL expression in `dialect`.
+pub(crate) fn expression_to_sql(
+    dialect: &dyn SqlDialect,
+    expression: &str,
+) -> GenericSQLResult<String> {
+    let mut lexemes = vec![];
+    let mut spans = vec![];
+    lex(expression, expression, &mut lexemes, &mut spans)?;
+    let mut translator = Translator {
+        dialect,
+        source: expression,
+        lexemes,
+        spans,
+        pos: 0,
+        vars: vec![],
+    };
+
+    translator.program()
+}
+
+fn invalid(msg: String) -> GenericSQLError {
+    GenericSQLError::InvalidExpression(ExpressionDiagnostic::new(msg))
+}
+
+/// An [`invalid`] error for the text at byte range `range` of `source`.
+fn invalid_at(source: &str, range: Range<usize>, msg: String) -> GenericSQLError {
+    GenericSQLError::InvalidExpression(ExpressionDiagnostic::at(msg, source, range))
+}
+
+/// The byte range in `source` of `part`, which is a slice of `source`.
+fn range_of(source: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - source.as_ptr() as usize;
+    start..start + part.len()
+}
+
+/// Lexes `input`, a slice of the expression `source`, pushing each lexeme
+/// and its byte range in `source`.
+fn lex(
+    source: &str,
+    input: &str,
+    lexemes: &mut Vec<Lexeme>,
+    spans: &mut Vec<Range<usize>>,
+) -> GenericSQLResult<()> {
+    let mut tokens = tokenize(input).into_iter();
+    while let Some(token) = tokens.next() {
+        let range = range_of(source, token.content());
+        match token {
+            Token::Comment(_) | Token::Whitespace(_) | Token::Break(_) => {},
+            Token::Symbol(x) => {
+                lexemes.push(Lexeme::Symbol(x.to_lowercase()));
+                spans.push(range);
+            },
+            Token::Literal(x) if !x.starts_with('\'') => {
+                let mut number = x.replace('_', "");
+                if number.starts_with('.') {
+                    number.insert(0, '0');
+                }
+
+                if number.ends_with('.') {
+                    number.push('0');
+                }
+
+                lexemes.push(Lexeme::Number(number));
+                spans.push(range);
+            },
+            Token::Literal(x) | Token::Column(x) => {
+                // Multi-line strings are tokenized one line at a time.
+                let mut text = x.to_string();
+                let mut end = range.end;
+                while !is_terminated(&text) {
+                    match tokens.next() {
+                        Some(next) => {
+                            match next {
+                                Token::Break(_) => text.push('\n'),
+                                next => text.push_str(next.content()),
+                            }
+
+                            end = range_of(source, next.content()).end;
+                        },
+                        None => {
+                            let msg = format!("unterminated string {}", x);
+                            return Err(invalid_at(source, range.start..end, msg));
+                        },
+                    }
+                }
+
+                let value = unescape(&text[1..text.len() - 1]);
+                lexemes.push(if text.starts_with('"') {
+                    Lexeme::Column(value)
+                } else {
+                    Lexeme::String(value)
+                });
+
+                spans.push(range.start..end);
+            },
+            Token::Operator(x) => {
+                let rest = lex_operators(source, x, lexemes, spans);
+                if !rest.is_empty() {
+                    let msg = format!("unexpected `{}`", rest);
+                    return Err(invalid_at(source, range_of(source, rest), msg));
+                }
+            },
+            Token::Unknown(x) => {
+                // `!` and `?` are not tokenized as operators, so whatever
+                // follows them is tokenized with them.
+                let rest = lex_operators(source, x, lexemes, spans);
+                if rest.len() == x.len() {
+                    return Err(invalid_at(source, range, format!("unexpected `{}`", x)));
+                }
+
+                lex(source, rest, lexemes, spans)?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes the operators `input` starts with, returning the remainder.
+fn lex_operators<'a>(
+    source: &str,
+    mut input: &'a str,
+    lexemes: &mut Vec<Lexeme>,
+    spans: &mut Vec<Range<usize>>,
+) -> &'a str {
+    while let Some(op) = OPERATORS.iter().find(|op| input.starts_with(**op)) {
+        lexemes.push(Lexeme::Operator(op));
+        spans.push(range_of(source, &input[..op.len()]));
+        input = &input[op.len()..];
+    }
+
+    input
+}
+
+fn is_terminated(text: &str) -> bool {
+    let quote = &text[..1];
+    let escapes = text[..text.len() - 1]
+        .chars()
+        .rev()
+        .take_while(|x| *x == '\\')
+        .count();
+
+    text.len() >= 2 && text.ends_with(quote) && escapes % 2 == 0
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('b') => result.push('\u{08}'),
+            Some('f') => result.push('\u{0C}'),
+            Some('u') => {
+                let hex: String = chars
+                    .by_ref()
+                    .skip_while(|x| *x == '{')
+                    .take_while(|x| *x != '}')
+                    .collect();
+
+                result.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+            },
+            Some(x) if x.is_whitespace() => while chars.next_if(|x| x.is_whitespace()).is_some() {},
+            Some(x) => result.push(x),
+            None => {},
+        }
+    }
+
+    result
+}
+
+fn case_when(cond: &str, then: &str, otherwise: Option<&str>) -> String {
+    match otherwise {
+        Some(otherwise) => format!("CASE WHEN {} THEN {} ELSE {} END", cond, then, otherwise),
+        None => format!("CASE WHEN {} THEN {} END", cond, then),
+    }
+}
+
+/// A recursive descent parser which emits SQL as it parses.
+struct Translator<'a> {
+    dialect: &'a dyn SqlDialect,
+    source: &'a str,
+    lexemes: Vec<Lexeme>,
+
+    /// The byte range in `source` of each of `lexemes`.
+    spans: Vec<Range<usize>>,
+    pos: usize,
+
+    /// The SQL of each `var` in scope, which is inlined where it is used.
+    vars: Vec<(String, String)>,
+}
+
+impl Translator<'_> {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Lexeme> {
+        let lexeme = self.lexemes.get(self.pos).cloned();
+        self.pos += 1;
+        lexeme
+    }
+
+    fn peek_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Lexeme::Operator(x)) if *x == op)
+    }
+
+    fn peek_symbol(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Lexeme::Symbol(x)) if x == name)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = self.peek_op(op);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn eat_symbol(&mut self, name: &str) -> bool {
+        let found = self.peek_symbol(name);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn expect_op(&mut self, op: &str) -> GenericSQLResult<()> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", op)))
+        }
+    }
+
+    /// An [`invalid`] error for the lexeme at `pos`, or for the end of the
+    /// expression if there is none.
+    fn invalid_lexeme(&self, pos: usize, msg: String) -> GenericSQLError {
+        let end = self.source.len();
+        let range = self.spans.get(pos).cloned().unwrap_or(end..end);
+        invalid_at(self.source, range, msg)
+    }
+
+    fn unexpected(&self, expected: &str) -> GenericSQLError {
+        let msg = match self.peek() {
+            Some(lexeme) => format!("expected {}, found {:?}", expected, lexeme),
+            None => format!("expected {}, found end of expression", expected),
+        };
+
+        self.invalid_lexeme(self.pos, msg)
+    }
+
+    fn var(&self, name: &str) -> Option<&String> {
+        self.vars
+            .iter()
+            .rev()
+            .find(|(var, _)| var == name)
+            .map(|(_, sql)| sql)
+    }
+
+    /// `statement (";" statement)*`, evaluating to the last statement.
+    fn program(&mut self) -> GenericSQLResult<String> {
+        let value = self.statements(false)?;
+        match self.peek() {
+            None => Ok(value),
+            Some(_) => Err(self.unexpected("end of expression")),
+        }
+    }
+
+    fn statements(&mut self, in_block: bool) -> GenericSQLResult<String> {
+        let mut value = None;
+        loop {
+            while self.eat_op(";") {}
+            if self.peek().is_none() || (in_block && self.peek_op("}")) {
+                break;
+            }
+
+            value = Some(self.statement(in_block)?);
+            if !self.peek_op(";") {
+                break;
+            }
+        }
+
+        value.ok_or_else(|| self.invalid_lexeme(self.pos, "expected a value".to_string()))
+    }
+
+    fn statement(&mut self, in_block: bool) -> GenericSQLResult<String> {
+        if self.eat_symbol("var") {
+            let Some(Lexeme::Symbol(name)) = self.next() else {
+                let msg = "expected a variable name after `var`".to_string();
+                return Err(self.invalid_lexeme(self.pos - 1, msg));
+            };
+
+            // ExprTK variables are initialized to 0.
+            let value = if self.eat_op(":=") {
+                self.expr()?
+            } else {
+                "0".to_string()
+            };
+
+            self.vars.push((name, value.clone()));
+            Ok(value)
+        } else if let (Some(Lexeme::Symbol(name)), Some(Lexeme::Operator(":="))) =
+            (self.peek(), self.lexemes.get(self.pos + 1))
+        {
+            let name = name.clone();
+            if in_block {
+                return Err(GenericSQLError::UnsupportedOperation(format!(
+                    "assignment to `{}` within a block cannot be translated to SQL",
+                    name
+                )));
+            }
+
+            if self.var(&name).is_none() {
+                let msg = format!("unknown variable `{}`", name);
+                return Err(self.invalid_lexeme(self.pos, msg));
+            }
+
+            self.pos += 2;
+            let value = self.expr()?;
+            self.vars.push((name, value.clone()));
+            Ok(value)
+        } else {
+            self.expr()
+        }
+    }
+
+    /// `or ("?" expr ":" expr)?`
+    fn expr(&mut self) -> GenericSQLResult<String> {
+        let cond = self.or()?;
+        if self.eat_op("?") {
+            let then = self.expr()?;
+            self.expect_op(":")?;
+            let otherwise = self.expr()?;
+            Ok(case_when(&cond, &then, Some(&otherwise)))
+        } else {
+            Ok(cond)
+        }
+    }
+
+    fn or(&mut self) -> GenericSQLResult<String> {
+        let mut lhs = self.and()?;
+        loop {
+            lhs = if self.eat_symbol("or") || self.eat_op("|") {
+                format!("({} OR {})", lhs, self.and()?)
+            } else if self.eat_symbol("nor") {
+                format!("(NOT ({} OR {}))", lhs, self.and()?)
+            } else if self.eat_symbol("xor") {
+                format!("({} <> {})", lhs, self.and()?)
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn and(&mut self) -> GenericSQLResult<String> {
+        let mut lhs = self.comparison()?;
+        loop {
+            lhs = if self.eat_symbol("and") || self.eat_op("&") {
+                format!("({} AND {})", lhs, self.comparison()?)
+            } else if self.eat_symbol("nand") {
+                format!("(NOT ({} AND {}))", lhs, self.comparison()?)
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn comparison(&mut self) -> GenericSQLResult<String> {
+        let mut lhs = self.additive()?;
+        loop {
+            let op = match self.peek() {
+                Some(Lexeme::Operator("==" | "=")) => "=",
+                Some(Lexeme::Operator("!=" | "<>")) => "<>",
+                Some(Lexeme::Operator(op @ ("<" | ">" | "<=" | ">="))) => *op,
+                _ => return Ok(lhs),
+            };
+
+            self.pos += 1;
+            lhs = format!("({} {} {})", lhs, op, self.additive()?);
+        }
+    }
+
+    fn additive(&mut self) -> GenericSQLResult<String> {
+        let mut lhs = self.multiplicative()?;
+        loop {
+            lhs = if self.eat_op("+") {
+                format!("({} + {})", lhs, self.multiplicative()?)
+            } else if self.eat_op("-") {
+                format!("({} - {})", lhs, self.multiplicative()?)
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn multiplicative(&mut self) -> GenericSQLResult<String> {
+        let mut lhs = self.unary()?;
+        loop {
+            lhs = if self.eat_op("*") {
+                format!("({} * {})", lhs, self.unary()?)
+            } else if self.eat_op("/") {
+                self.dialect.divide(&lhs, &self.unary()?)
+            } else if self.eat_op("%") {
+                self.dialect.modulo(&lhs, &self.unary()?)
+            } else {
+                return Ok(lhs);
+            };
+        }
+    }
+
+    fn unary(&mut self) -> GenericSQLResult<String> {
+        if self.eat_op("-") {
+            Ok(format!("(-{})", self.unary()?))
+        } else if self.eat_op("+") {
+            self.unary()
+        } else if self.eat_symbol("not") || self.eat_op("!") {
+            Ok(format!("(NOT {})", self.unary()?))
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> GenericSQLResult<String> {
+        let base = self.primary()?;
+        if self.eat_op("^") {
+            Ok(format!("POWER({}, {})", base, self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn primary(&mut self) -> GenericSQLResult<String> {
+        match self.next() {
+            Some(Lexeme::Number(x)) => Ok(x),
+            Some(Lexeme::String(x)) => Ok(string_literal(&x)),
+            Some(Lexeme::Column(x)) => Ok(self.dialect.quote_ident(&x)),
+            Some(Lexeme::Operator("(")) => {
+                let value = self.expr()?;
+                self.expect_op(")")?;
+                Ok(value)
+            },
+            Some(Lexeme::Symbol(name)) => match name.as_str() {
+                "true" => Ok("TRUE".to_string()),
+                "false" => Ok("FALSE".to_string()),
+                "pi" => Ok("PI()".to_string()),
+                "if" => self.if_else(),
+                "for" | "while" | "repeat" | "switch" | "return" => {
+                    Err(GenericSQLError::UnsupportedOperation(format!(
+                        "`{}` cannot be translated to SQL",
+                        name
+                    )))
+                },
+                _ if self.peek_op("(") => {
+                    let at = self.pos - 1;
+                    let args = self.args()?;
+                    self.function(&name, &args).map_err(|err| match err {
+                        GenericSQLError::InvalidExpression(diagnostic) => {
+                            self.invalid_lexeme(at, diagnostic.message)
+                        },
+                        err => err,
+                    })
+                },
+                _ => self.var(&name).cloned().ok_or_else(|| {
+                    self.invalid_lexeme(self.pos - 1, format!("unknown variable `{}`", name))
+                }),
+            },
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a value"))
+            },
+        }
+    }
+
+    /// `if (cond) branch [else branch]`, `if (cond, then, else)`.
+    fn if_else(&mut self) -> GenericSQLResult<String> {
+        self.expect_op("(")?;
+        let cond = self.expr()?;
+        if self.eat_op(",") {
+            let then = self.expr()?;
+            self.expect_op(",")?;
+            let otherwise = self.expr()?;
+            self.expect_op(")")?;
+            return Ok(case_when(&cond, &then, Some(&otherwise)));
+        }
+
+        self.expect_op(")")?;
+        let then = self.branch()?;
+        if self.peek_op(";")
+            && matches!(self.lexemes.get(self.pos + 1), Some(Lexeme::Symbol(x)) if x == "else")
+        {
+            self.pos += 1;
+        }
+
+        let otherwise = if !self.eat_symbol("else") {
+            None
+        } else if self.eat_symbol("if") {
+            Some(self.if_else()?)
+        } else {
+            Some(self.branch()?)
+        };
+
+        Ok(case_when(&cond, &then, otherwise.as_deref()))
+    }
+
+    /// A `{ ... }` block or a single expression.
+    fn branch(&mut self) -> GenericSQLResult<String> {
+        if !self.eat_op("{") {
+            return self.expr();
+        }
+
+        let scope = self.vars.len();
+        let value = self.statements(true)?;
+        while self.eat_op(";") {}
+        self.expect_op("}")?;
+        self.vars.truncate(scope);
+        Ok(value)
+    }
+
+    fn args(&mut self) -> GenericSQLResult<Vec<String>> {
+        self.expect_op("(")?;
+        let mut args = vec![];
+        if self.eat_op(")") {
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expr()?);
+            if self.eat_op(")") {
+                return Ok(args);
+            }
+
+            self.expect_op(",")?;
+        }
+    }
+
+    fn function(&self, name: &str, args: &[String]) -> GenericSQLResult<String> {
+        let dialect = self.dialect;
+        let arity = |min: usize, max: usize| {
+            if args.len() < min || args.len() > max {
+                Err(invalid(format!(
+                    "`{}()` expects {} arguments, found {}",
+                    name,
+                    if min == max {
+                        min.to_string()
+                    } else if max == usize::MAX {
+                        format!("at least {}", min)
+                    } else {
+                        format!("{} to {}", min, max)
+                    },
+                    args.len()
+                )))
+            } else {
+                Ok(())
+            }
+        };
+
+        let unsupported = || {
+            GenericSQLError::UnsupportedOperation(format!(
+                "`{}()` is not supported in the {} dialect",
+                name,
+                dialect.name()
+            ))
+        };
+
+        let sql_fn = match name {
+            "abs" | "ceil" | "floor" | "exp" | "sqrt" | "log10" | "trunc" | "round" | "upper"
+            | "lower" | "length" | "acos" | "acosh" | "asin" | "asinh" | "atan" | "atanh"
+            | "cos" | "cosh" | "cot" | "sin" | "sinh" | "tan" | "tanh" => Some(name.to_uppercase()),
+            "log" => Some("LN".to_string()),
+            "sgn" => Some("SIGN".to_string()),
+            "deg2rad" => Some("RADIANS".to_string()),
+            "rad2deg" => Some("DEGREES".to_string()),
+            _ => None,
+        };
+
+        if let Some(sql_fn) = sql_fn {
+            arity(1, 1)?;
+            return Ok(format!("{}({})", sql_fn, args[0]));
+        }
+
+        match name {
+            "log2" => {
+                arity(1, 1)?;
+                Ok(dialect.divide(&format!("LN({})", args[0]), "LN(2)"))
+            },
+            "log1p" => {
+                arity(1, 1)?;
+                Ok(format!("LN((1 + {}))", args[0]))
+            },
+            "logn" => {
+                arity(2, 2)?;
+                Ok(dialect.divide(&format!("LN({})", args[0]), &format!("LN({})", args[1])))
+            },
+            "pow" => {
+                arity(2, 2)?;
+                Ok(format!("POWER({}, {})", args[0], args[1]))
+            },
+            "root" => {
+                arity(2, 2)?;
+                Ok(format!(
+                    "POWER({}, {})",
+                    args[0],
+                    dialect.divide("1", &args[1])
+                ))
+            },
+            "frac" => {
+                arity(1, 1)?;
+                Ok(format!("({} - TRUNC({}))", args[0], args[0]))
+            },
+            "sinc" => {
+                arity(1, 1)?;
+                let sinc = dialect.divide(&format!("SIN({})", args[0]), &args[0]);
+                Ok(case_when(&format!("{} = 0", args[0]), "1", Some(&sinc)))
+            },
+            "deg2grad" => {
+                arity(1, 1)?;
+                Ok(dialect.divide(&format!("({} * 10)", args[0]), "9"))
+            },
+            "grad2deg" => {
+                arity(1, 1)?;
+                Ok(dialect.divide(&format!("({} * 9)", args[0]), "10"))
+            },
+            "min" | "max" => {
+                arity(1, usize::MAX)?;
+                let mut args = args.iter();
+                let first = args.next().cloned().unwrap_or_default();
+                Ok(args.fold(first, |acc, x| {
+                    if name == "min" {
+                        dialect.least(&acc, x)
+                    } else {
+                        dialect.greatest(&acc, x)
+                    }
+                }))
+            },
+            "sum" => {
+                arity(1, usize::MAX)?;
+                Ok(format!("({})", args.join(" + ")))
+            },
+            "avg" => {
+                arity(1, usize::MAX)?;
+                Ok(dialect.divide(&format!("({})", args.join(" + ")), &args.len().to_string()))
+            },
+            "mul" => {
+                arity(1, usize::MAX)?;
+                Ok(format!("({})", args.join(" * ")))
+            },
+            "inrange" => {
+                arity(3, 3)?;
+                Ok(format!(
+                    "({} >= {} AND {} <= {})",
+                    args[1], args[0], args[1], args[2]
+                ))
+            },
+            "percent_of" => {
+                arity(2, 2)?;
+                Ok(format!("({} * 100)", dialect.divide(&args[0], &args[1])))
+            },
+            "concat" => {
+                arity(1, usize::MAX)?;
+                Ok(format!("({})", args.join(" || ")))
+            },
+            "order" => {
+                arity(1, usize::MAX)?;
+                let whens = args[1..]
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, x)| format!("WHEN {} THEN {} ", x, idx))
+                    .collect::<String>();
+
+                Ok(format!(
+                    "CASE {} {}ELSE {} END",
+                    args[0],
+                    whens,
+                    args.len() - 1
+                ))
+            },
+            "is_null" => {
+                arity(1, 1)?;
+                Ok(format!("({} IS NULL)", args[0]))
+            },
+            "is_not_null" => {
+                arity(1, 1)?;
+                Ok(format!("({} IS NOT NULL)", args[0]))
+            },
+            "not" => {
+                arity(1, 1)?;
+                Ok(format!("(NOT {})", args[0]))
+            },
+            "string" | "integer" | "float" | "boolean" => {
+                arity(1, 1)?;
+                let ty = match name {
+                    "string" => ColumnType::String,
+                    "integer" => ColumnType::Integer,
+                    "float" => ColumnType::Float,
+                    _ => ColumnType::Boolean,
+                };
+
+                Ok(dialect.cast(&args[0], ty))
+            },
+            "now" => {
+                arity(0, 0)?;
+                Ok("CURRENT_TIMESTAMP".to_string())
+            },
+            "today" => {
+                arity(0, 0)?;
+                Ok("CURRENT_DATE".to_string())
+            },
+            "bucket" => {
+                arity(2, 2)?;
+                self.bucket(&args[0], &args[1]).ok_or_else(unsupported)?
+            },
+            "substring" => {
+                arity(2, 3)?;
+                let start = format!("({} + 1)", args[1]);
+                Ok(match args.get(2) {
+                    Some(len) => format!("SUBSTR({}, {}, {})", args[0], start, len),
+                    None => format!("SUBSTR({}, {})", args[0], start),
+                })
+            },
+            "match" => {
+                arity(2, 2)?;
+                dialect
+                    .regexp_matches(&args[0], &args[1])
+                    .ok_or_else(unsupported)
+            },
+            "match_all" => {
+                arity(2, 2)?;
+                let pattern = format!("('^(?:' || {} || ')$')", args[1]);
+                dialect
+                    .regexp_matches(&args[0], &pattern)
+                    .ok_or_else(unsupported)
+            },
+            "search" => {
+                arity(2, 2)?;
+                dialect
+                    .regexp_extract(&args[0], &args[1])
+                    .ok_or_else(unsupported)
+            },
+            "replace" | "replace_all" => {
+                arity(3, 3)?;
+                dialect
+                    .regexp_replace(&args[0], &args[1], &args[2], name == "replace_all")
+                    .ok_or_else(unsupported)
+            },
+            _ => Err(GenericSQLError::UnsupportedOperation(format!(
+                "`{}()` cannot be translated to SQL",
+                name
+            ))),
+        }
+    }
+
+    /// `bucket(x, y)` for a number `y`, or a date unit such as `'M'`, or
+    /// `None` if the dialect cannot truncate dates.
+    fn bucket(&self, expr: &str, unit: &str) -> Option<GenericSQLResult<String>> {
+        let Some(unit) = unit.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) else {
+            let floor = format!("FLOOR({})", self.dialect.divide(expr, unit));
+            return Some(Ok(format!("({} * {})", floor, unit)));
+        };
+
+        let multiplier = unit.trim_end_matches(char::is_alphabetic);
+        let unit = match &unit[multiplier.len()..] {
+            "s" => "second",
+            "m" => "minute",
+            "h" => "hour",
+            "D" => "day",
+            "W" => "week",
+            "M" => "month",
+            "Y" => "year",
+            _ => return Some(Err(invalid(format!("unknown bucket unit '{}'", unit)))),
+        };
+
+        if !multiplier.is_empty() && multiplier != "1" {
+            return Some(Err(GenericSQLError::UnsupportedOperation(format!(
+                "bucketing by multiple {}s cannot be translated to SQL",
+                unit
+            ))));
+        }
+
+        self.dialect.date_trunc(unit, expr).map(Ok)
+    }
+}
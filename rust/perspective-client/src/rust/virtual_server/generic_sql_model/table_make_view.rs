@@ -10,14 +10,21 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
-use crate::config::{Aggregate, Sort, SortDir, ViewConfig};
+use std::cell::RefCell;
 
-fn aggregate_to_string(agg: &Aggregate) -> String {
-    match agg {
-        Aggregate::SingleAggregate(name) => name.clone(),
-        Aggregate::MultiAggregate(name, _args) => name.clone(),
-    }
-}
+use indexmap::IndexMap;
+
+use super::aggregate::{ColumnAggregate, PercentOf};
+use super::dialect::{SqlDialect, string_literal};
+use super::expression::expression_to_sql;
+use super::{GenericSQLError, GenericSQLResult, GenericSQLVirtualServerModel};
+use crate::config::{Filter, FilterReducer, FilterTerm, Scalar, Sort, SortDir, ViewConfig};
+use crate::proto::ColumnType;
+use crate::virtual_server::{encode_column_path, escape_column_path_element};
+
+/// Delimits the index of a bound parameter in a query under construction,
+/// before it is replaced by the dialect's placeholder.
+const PARAM_MARKER: char = '\u{0}';
 
 fn sort_dir_to_string(dir: &SortDir) -> &'static str {
     match dir {
@@ -41,55 +48,269 @@ enum QueryOrientation {
     GroupedAndPivoted,
 }
 
+/// The rows a grouped query's aggregates are computed for, which the
+/// `pct sum` aggregates' windows are partitioned by.
+#[derive(Clone, Copy)]
+enum GroupLevel {
+    /// Every level of a `GROUP BY ROLLUP`.
+    Rollup,
+
+    /// The groups of the first `n` `group_by` columns, a branch of an
+    /// emulated `ROLLUP`.
+    Depth(usize),
+}
+
 /// Precomputed context for building a SQL view query from a [`ViewConfig`].
 ///
-/// Holds the resolved column names, grouping function, and row-path aliases
-/// needed to emit the correct `SELECT`, `GROUP BY`, `PIVOT`, `ORDER BY`, and
-/// `WINDOW` clauses for every combination of `group_by` / `split_by`.
+/// Holds the resolved column names, expressions translated to SQL, grouping
+/// function, and row-path aliases needed to emit the correct `SELECT`, `GROUP
+/// BY`, `PIVOT`, `ORDER BY`, and `WINDOW` clauses for every combination of
+/// `group_by` / `split_by`.
+///
+/// When `split_values` are given, `split_by` is emulated with conditional
+/// aggregates rather than `PIVOT`, and when the dialect has no grouping
+/// function, `ROLLUP` is emulated with `UNION ALL`. When `params` is set,
+/// filter and split values are bound as parameters rather than inlined.
 pub(crate) struct ViewQueryContext<'a> {
+    model: &'a GenericSQLVirtualServerModel,
     table: &'a str,
     config: &'a ViewConfig,
+    expressions: IndexMap<&'a str, String>,
+    aggregates: IndexMap<&'a str, ColumnAggregate<'a>>,
     group_col_names: Vec<String>,
-    grouping_fn: &'a str,
+    grouping_fn: Option<&'a str>,
     row_path_aliases: Vec<String>,
+    split_values: Option<&'a [Vec<Option<String>>]>,
+    params: Option<RefCell<Vec<Scalar>>>,
 }
 
 impl<'a> ViewQueryContext<'a> {
-    /// Creates a new query context by resolving expressions, the grouping
-    /// function, and row-path aliases from the given model and config.
+    /// Creates a new query context by translating expressions and resolving
+    /// the grouping function and row-path aliases from the given model and
+    /// config.
     pub(crate) fn new(
-        model: &'a super::GenericSQLVirtualServerModel,
+        model: &'a GenericSQLVirtualServerModel,
         table: &'a str,
         config: &'a ViewConfig,
-    ) -> Self {
-        let expressions = &config.expressions.0;
-        let col_name_resolve = |col: &str| -> String {
-            expressions
-                .get(col)
-                .cloned()
-                .unwrap_or_else(|| format!("\"{}\"", col))
-        };
+        split_values: Option<&'a [Vec<Option<String>>]>,
+    ) -> GenericSQLResult<Self> {
+        if let Some(filter) = config.filter.iter().find(|x| {
+            !GenericSQLVirtualServerModel::filter_ops(ColumnType::String)
+                .contains(&x.op().to_lowercase().as_str())
+        }) {
+            return Err(GenericSQLError::UnsupportedOperation(format!(
+                "filter operator `{}`",
+                filter.op()
+            )));
+        }
 
-        let grouping_fn = model.0.grouping_fn.as_deref().unwrap_or("GROUPING_ID");
-        let group_col_names: Vec<String> = config
-            .group_by
+        let expressions = config
+            .expressions
+            .0
             .iter()
-            .map(|c| col_name_resolve(c))
-            .collect();
+            .map(|(name, expr)| Ok((name.as_str(), expression_to_sql(model.dialect(), expr)?)))
+            .collect::<GenericSQLResult<_>>()?;
+
+        let grouping_fn = model
+            .args
+            .grouping_fn
+            .as_deref()
+            .or(model.dialect.grouping_fn());
 
         let row_path_aliases: Vec<String> = (0..config.group_by.len())
             .map(|i| format!("__ROW_PATH_{}__", i))
             .collect();
 
-        Self {
+        let mut ctx = Self {
+            model,
             table,
             config,
-            group_col_names,
+            expressions,
+            aggregates: IndexMap::new(),
+            group_col_names: vec![],
             grouping_fn,
             row_path_aliases,
+            split_values,
+            params: None,
+        };
+
+        ctx.group_col_names = config.group_by.iter().map(|c| ctx.col_name(c)).collect();
+        if !config.group_by.is_empty() {
+            ctx.aggregates = config
+                .aggregates
+                .iter()
+                .map(|(col, agg)| {
+                    let agg = ColumnAggregate::new(model.dialect(), agg, |x| ctx.col_name(x))?;
+                    Ok((col.as_str(), agg))
+                })
+                .collect::<GenericSQLResult<_>>()?;
+        }
+
+        Ok(ctx)
+    }
+
+    /// Binds filter and split values as positional parameters, rather than
+    /// inlining them as SQL literals, see
+    /// [`ViewQueryContext::bind_params`].
+    pub(crate) fn parameterized(mut self) -> GenericSQLResult<Self> {
+        let config = self.config;
+        let mut names = config
+            .columns
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(config.group_by.iter().map(String::as_str))
+            .chain(config.split_by.iter().map(String::as_str))
+            .chain(config.sort.iter().map(|Sort(col, _)| col.as_str()))
+            .chain(config.filter.iter().map(|x| x.column()))
+            .chain(
+                config
+                    .expressions
+                    .0
+                    .iter()
+                    .flat_map(|(k, v)| [k.as_str(), v.as_str()]),
+            );
+
+        if names.any(|x| x.contains(PARAM_MARKER)) {
+            return Err(GenericSQLError::InvalidConfig(
+                "Column names and expressions cannot contain NUL characters".to_string(),
+            ));
+        }
+
+        self.params = Some(RefCell::default());
+        Ok(self)
+    }
+
+    /// Replaces the parameter markers in `sql`, a query built by this
+    /// context, with the dialect's placeholders, returning the values to bind
+    /// to them in order. Values are repeated when their marker is, e.g. in
+    /// each branch of a `UNION ALL`.
+    pub(crate) fn bind_params(&self, sql: &str) -> (String, Vec<Scalar>) {
+        let Some(params) = &self.params else {
+            return (sql.to_string(), vec![]);
+        };
+
+        let params = params.borrow();
+        let mut query = String::with_capacity(sql.len());
+        let mut values = vec![];
+        for (idx, part) in sql.split(PARAM_MARKER).enumerate() {
+            if idx % 2 == 0 {
+                query.push_str(part);
+            } else if let Some(value) = part.parse().ok().and_then(|x: usize| params.get(x)) {
+                values.push(value.clone());
+                query.push_str(&self.dialect().placeholder(values.len()));
+            }
+        }
+
+        (query, values)
+    }
+
+    fn dialect(&self) -> &'a dyn SqlDialect {
+        self.model.dialect.as_ref()
+    }
+
+    /// A literal for `scalar`, or a parameter marker if parameterized.
+    /// `Date` and `Datetime` parameters are bound as ISO 8601 strings and cast
+    /// to the column type, as drivers have no common date representation.
+    fn literal(&self, scalar: &Scalar) -> Option<String> {
+        match (&self.params, scalar) {
+            (_, Scalar::Null) => None,
+            (Some(params), scalar) => {
+                let (value, ty) = match scalar {
+                    Scalar::Date(x) => (Scalar::String(x.clone()), Some(ColumnType::Date)),
+                    Scalar::Datetime(_) => (
+                        Scalar::String(scalar.to_datetime_string()?),
+                        Some(ColumnType::Datetime),
+                    ),
+                    scalar => (scalar.clone(), None),
+                };
+
+                let mut params = params.borrow_mut();
+                params.push(value);
+                let marker = format!("{}{}{}", PARAM_MARKER, params.len() - 1, PARAM_MARKER);
+                Some(match ty {
+                    Some(ty) => self.dialect().cast(&marker, ty),
+                    None => marker,
+                })
+            },
+            (None, scalar) => self.model.scalar_to_sql(scalar),
         }
     }
 
+    /// The condition for `filter`, or `None` if its term is missing.
+    fn filter_sql(&self, filter: &Filter) -> Option<String> {
+        let col = self.col_name(filter.column());
+        let scalar = match filter.term() {
+            FilterTerm::Scalar(scalar) => Some(scalar),
+            FilterTerm::Array(_) => None,
+        };
+
+        match filter.op().to_lowercase().as_str() {
+            "is null" => Some(format!("{} IS NULL", col)),
+            "is not null" => Some(format!("{} IS NOT NULL", col)),
+            op @ ("in" | "not in") => {
+                let values: Vec<String> = match filter.term() {
+                    FilterTerm::Scalar(scalar) => self.literal(scalar).into_iter().collect(),
+                    FilterTerm::Array(scalars) => {
+                        scalars.iter().filter_map(|x| self.literal(x)).collect()
+                    },
+                };
+
+                (!values.is_empty())
+                    .then(|| format!("{} {} ({})", col, op.to_uppercase(), values.join(", ")))
+            },
+            op @ ("begins with" | "contains" | "ends with") => {
+                let value = scalar.filter(|x| **x != Scalar::Null)?.to_string();
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+
+                let pattern = match op {
+                    "begins with" => format!("{}%", value),
+                    "ends with" => format!("%{}", value),
+                    _ => format!("%{}%", value),
+                };
+
+                let pattern = self.literal(&Scalar::String(pattern))?;
+                Some(self.dialect().like(&col, &pattern))
+            },
+            op => {
+                let op = match op {
+                    "==" => "=",
+                    "!=" => "<>",
+                    op => op,
+                };
+
+                self.literal(scalar?)
+                    .map(|value| format!("{} {} {}", col, op, value))
+            },
+        }
+    }
+
+    /// Builds the query for the distinct combinations of `split_by` values.
+    pub(crate) fn split_values_query(&self) -> String {
+        let (columns, aliases): (Vec<_>, Vec<_>) = self
+            .config
+            .split_by
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| {
+                let alias = format!("__SPLIT_{}__", idx);
+                let cast = self.dialect().cast_to_text(&self.col_name(col));
+                (format!("{} AS {}", cast, alias), alias)
+            })
+            .unzip();
+
+        format!(
+            "SELECT DISTINCT {} FROM {}{} ORDER BY {}",
+            columns.join(", "),
+            self.table,
+            self.where_sql(),
+            aliases.join(", ")
+        )
+    }
+
     /// Builds the inner `SELECT` query (without the outer `CREATE TABLE`
     /// wrapper) for the four `group_by` x `split_by` combinations, appending
     /// `WINDOW` and `ORDER BY` clauses as needed.
@@ -99,13 +320,22 @@ impl<'a> ViewQueryContext<'a> {
         let windows = self.window_clauses();
         let mut query = match self.query_orientation() {
             QueryOrientation::Flat => {
-                let select = self.select_clauses().join(", ");
+                let select = self.select_clauses(GroupLevel::Rollup).join(", ");
                 format!("SELECT {} FROM {}{}", select, self.table, where_sql)
             },
+            QueryOrientation::Grouped if self.grouping_fn.is_none() => {
+                self.union_all_rollup_query(&where_sql)
+            },
             QueryOrientation::Grouped => {
-                let mut clauses = self.select_clauses();
+                let mut clauses = self.select_clauses(GroupLevel::Rollup);
                 clauses.extend(self.row_path_select_clauses());
                 clauses.push(self.grouping_id_clause());
+                clauses.extend(self.expand_state_clauses(&format!(
+                    "{}({})",
+                    self.grouping_fn(),
+                    self.group_col_names.join(", ")
+                )));
+
                 format!(
                     "SELECT {} FROM {}{} GROUP BY ROLLUP({})",
                     clauses.join(", "),
@@ -115,32 +345,30 @@ impl<'a> ViewQueryContext<'a> {
                 )
             },
             QueryOrientation::Pivoted => {
-                let select = self.select_clauses();
+                let select = self.select_clauses(GroupLevel::Rollup);
                 let pivot_using: Vec<String> = self
                     .config
                     .columns
                     .iter()
                     .flatten()
                     .map(|col| {
-                        let escaped = col.replace('"', "\"\"").replace('_', "-");
-                        format!("first(\"{}\") as \"{}\"", escaped, escaped)
+                        let escaped = self.dialect().quote_ident(&self.column_alias(col));
+                        format!("first({}) as {}", escaped, escaped)
                     })
                     .collect();
 
-                let split_cols: String = self
-                    .config
-                    .split_by
-                    .iter()
-                    .map(|c| format!("\"{}\"", c))
-                    .collect::<Vec<_>>()
-                    .join(", ");
+                let row_order = self
+                    .dialect()
+                    .row_order()
+                    .map(|x| format!("ORDER BY {}", x))
+                    .unwrap_or_default();
 
                 format!(
                     "SELECT * EXCLUDE (__ROW_NUM__) FROM (PIVOT (SELECT {}, {}, ROW_NUMBER() OVER \
-                     (ORDER BY rowid) as __ROW_NUM__ FROM {}{}) ON {} USING {} GROUP BY \
-                     __ROW_NUM__)",
+                     ({}) as __ROW_NUM__ FROM {}{}) ON {} USING {} GROUP BY __ROW_NUM__)",
                     select.join(", "),
-                    split_cols,
+                    self.pivot_select_clauses().join(", "),
+                    row_order,
                     self.table,
                     where_sql,
                     self.pivot_on_expr(),
@@ -150,21 +378,17 @@ impl<'a> ViewQueryContext<'a> {
             QueryOrientation::GroupedAndPivoted => {
                 let groups_joined = self.group_col_names.join(", ");
                 let split_cols_joined = self.pivot_on_expr();
-                let mut inner_clauses = self.select_clauses();
+                let mut inner_clauses = self.select_clauses(GroupLevel::Rollup);
                 inner_clauses.extend(self.row_path_select_clauses());
                 inner_clauses.push(self.grouping_id_clause());
-                for sb_col in &self.config.split_by {
-                    inner_clauses.push(self.col_name(sb_col));
-                }
+                inner_clauses.extend(self.pivot_select_clauses());
 
                 for (sidx, Sort(sort_col, sort_dir)) in self.config.sort.iter().enumerate() {
                     if *sort_dir != SortDir::None && !is_col_sort(sort_dir) {
-                        let agg = self.get_aggregate(sort_col);
                         inner_clauses.push(format!(
-                            "sum({}({})) OVER (PARTITION BY {}({}), {}) AS __SORT_{}__",
-                            agg,
-                            self.col_name(sort_col),
-                            self.grouping_fn,
+                            "sum({}) OVER (PARTITION BY {}({}), {}) AS __SORT_{}__",
+                            self.sort_aggregate_sql(sort_col),
+                            self.grouping_fn(),
                             groups_joined,
                             groups_joined,
                             sidx,
@@ -181,7 +405,18 @@ impl<'a> ViewQueryContext<'a> {
                     split_cols_joined,
                 );
 
-                let pivot_using = self.select_clauses().join(", ");
+                // Each cell of the pivot is a single row of the grouped query.
+                let pivot_using = self
+                    .config
+                    .columns
+                    .iter()
+                    .flatten()
+                    .map(|col| {
+                        let escaped = self.dialect().quote_ident(&self.column_alias(col));
+                        format!("first({}) as {}", escaped, escaped)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 let mut row_id_cols = self.row_path_aliases.clone();
                 row_id_cols.push("__GROUPING_ID__".to_string());
                 for (sidx, Sort(_, sort_dir)) in self.config.sort.iter().enumerate() {
@@ -191,7 +426,8 @@ impl<'a> ViewQueryContext<'a> {
                 }
 
                 format!(
-                    "SELECT * FROM (PIVOT ({}) ON {} USING {} GROUP BY {})",
+                    "SELECT *, {} FROM (PIVOT ({}) ON {} USING {} GROUP BY {})",
+                    self.expand_state_clauses("__GROUPING_ID__").join(", "),
                     inner_query,
                     self.pivot_on_expr(),
                     pivot_using,
@@ -207,23 +443,95 @@ impl<'a> ViewQueryContext<'a> {
         if !order_by.is_empty() {
             query = format!("{} ORDER BY {}", query, order_by.join(", "));
         } else if self.config.group_by.is_empty() {
-            let default_order = if self.config.split_by.is_empty() {
-                "rowid"
+            let default_order = if self.is_pivot() {
+                Some("__ROW_NUM__")
             } else {
-                "__ROW_NUM__"
+                self.dialect().row_order()
             };
 
-            query = format!("{} ORDER BY {}", query, default_order);
+            if let Some(default_order) = default_order {
+                query = format!("{} ORDER BY {}", query, default_order);
+            }
         }
 
         query
     }
 
+    /// Emulates `GROUP BY ROLLUP` with one `UNION ALL` branch per grouping
+    /// level, sorted by the branches' `__SORT_{n}__` columns.
+    fn union_all_rollup_query(&self, where_sql: &str) -> String {
+        let num_groups = self.group_col_names.len();
+        let branches = (0..=num_groups)
+            .rev()
+            .map(|depth| {
+                let mut clauses = self.select_clauses(GroupLevel::Depth(depth));
+                for (idx, alias) in self.row_path_aliases.iter().enumerate() {
+                    let col = if idx < depth {
+                        self.group_col_names[idx].as_str()
+                    } else {
+                        "NULL"
+                    };
+
+                    clauses.push(format!("{} as {}", col, alias));
+                }
+
+                clauses.push(format!(
+                    "{} AS __GROUPING_ID__",
+                    (1_u64 << (num_groups - depth)) - 1
+                ));
+
+                for (sidx, Sort(sort_col, sort_dir)) in self.config.sort.iter().enumerate() {
+                    if *sort_dir != SortDir::None && !is_col_sort(sort_dir) {
+                        clauses.push(format!(
+                            "{} AS __SORT_{}__",
+                            self.sort_aggregate_sql(sort_col),
+                            sidx
+                        ));
+                    }
+                }
+
+                let group_by = if depth > 0 {
+                    format!(" GROUP BY {}", self.group_col_names[..depth].join(", "))
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "SELECT {} FROM {}{}{}",
+                    clauses.join(", "),
+                    self.table,
+                    where_sql,
+                    group_by
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "SELECT *, {} FROM ({}) AS __ROLLUP__",
+            self.expand_state_clauses("__GROUPING_ID__").join(", "),
+            branches.join(" UNION ALL ")
+        )
+    }
+
+    /// Whether `split_by` is implemented with `PIVOT`.
+    fn is_pivot(&self) -> bool {
+        !self.config.split_by.is_empty() && self.split_values.is_none()
+    }
+
+    /// Whether the row order of a grouped view is computed from the
+    /// `__GROUPING_ID__` and `__SORT_{n}__` columns of a subquery, rather
+    /// than aggregates of the grouped query itself.
+    fn sorts_subquery(&self) -> bool {
+        self.is_pivot() || self.grouping_fn.is_none()
+    }
+
+    /// The grouping function for dialects which support `ROLLUP`.
+    fn grouping_fn(&self) -> &str {
+        self.grouping_fn.unwrap_or("GROUPING")
+    }
+
     fn query_orientation(&self) -> QueryOrientation {
-        match (
-            self.config.group_by.is_empty(),
-            self.config.split_by.is_empty(),
-        ) {
+        match (self.config.group_by.is_empty(), !self.is_pivot()) {
             (true, true) => QueryOrientation::Flat,
             (false, true) => QueryOrientation::Grouped,
             (true, false) => QueryOrientation::Pivoted,
@@ -232,69 +540,196 @@ impl<'a> ViewQueryContext<'a> {
     }
 
     fn col_name(&self, col: &str) -> String {
-        self.config
-            .expressions
-            .0
+        self.expressions
             .get(col)
             .cloned()
-            .unwrap_or_else(|| format!("\"{}\"", col))
+            .unwrap_or_else(|| self.dialect().quote_ident(col))
     }
 
-    fn get_aggregate(&self, col: &str) -> String {
-        self.config
-            .aggregates
-            .get(col)
-            .map(aggregate_to_string)
-            .unwrap_or_else(|| "any_value".to_string())
+    /// The aggregate of `expr`, the SQL for column `col` or a conditional
+    /// thereof, in a query grouped by `level`.
+    fn aggregate_sql(&self, col: &str, expr: &str, level: GroupLevel) -> String {
+        let Some(agg) = self.aggregates.get(col) else {
+            return format!("{}({})", self.dialect().default_aggregate(), expr);
+        };
+
+        match agg.sql(self.dialect(), expr) {
+            (sql, None) => sql,
+            (sql, Some(percent_of)) => self.dialect().divide(
+                &format!("100 * {}", sql),
+                &format!(
+                    "NULLIF(SUM({}) OVER ({}), 0)",
+                    sql,
+                    self.percent_partition(level, percent_of)
+                ),
+            ),
+        }
+    }
+
+    /// The aggregate of column `col` which its groups are sorted by. `pct
+    /// sum` aggregates sort by their sum, as siblings share a denominator.
+    fn sort_aggregate_sql(&self, col: &str) -> String {
+        let expr = self.col_name(col);
+        match self.aggregates.get(col) {
+            Some(agg) => agg.sql(self.dialect(), &expr).0,
+            None => format!("{}({})", self.dialect().default_aggregate(), expr),
+        }
+    }
+
+    /// The `PARTITION BY` clause of the window over the groups at `level`
+    /// which a `pct sum` aggregate is a percentage of.
+    fn percent_partition(&self, level: GroupLevel, percent_of: PercentOf) -> String {
+        let groups = &self.group_col_names;
+        let mut partition = vec![];
+        match level {
+            GroupLevel::Rollup => {
+                // Rows of the same depth, and for `Parent`, the same group
+                // columns but the last.
+                partition.push(format!("{}({})", self.grouping_fn(), groups.join(", ")));
+                if percent_of == PercentOf::Parent {
+                    for (col, next) in groups.iter().zip(groups.iter().skip(1)) {
+                        partition.push(format!(
+                            "CASE WHEN {}({}) = 0 THEN {} END",
+                            self.grouping_fn(),
+                            next,
+                            col
+                        ));
+                    }
+                }
+
+                if self.is_pivot() {
+                    partition.extend(self.config.split_by.iter().map(|x| self.col_name(x)));
+                }
+            },
+            GroupLevel::Depth(depth) => {
+                if percent_of == PercentOf::Parent {
+                    partition.extend(groups[..depth.saturating_sub(1)].iter().cloned());
+                }
+            },
+        }
+
+        if partition.is_empty() {
+            String::new()
+        } else {
+            format!("PARTITION BY {}", partition.join(", "))
+        }
     }
 
-    fn select_clauses(&self) -> Vec<String> {
+    fn select_clauses(&self, level: GroupLevel) -> Vec<String> {
         let mut clauses = Vec::new();
-        if !self.config.group_by.is_empty() {
+        if let Some(split_values) = self.split_values {
+            for values in split_values {
+                let condition = self.split_condition(values);
+                let mut path = values
+                    .iter()
+                    .map(|x| x.as_deref().unwrap_or("NULL"))
+                    .collect::<Vec<_>>();
+
+                for col in self.config.columns.iter().flatten() {
+                    let value = format!("CASE WHEN {} THEN {} END", condition, self.col_name(col));
+                    let value = if self.config.group_by.is_empty() {
+                        value
+                    } else {
+                        self.aggregate_sql(col, &value, level)
+                    };
+
+                    path.push(col);
+                    let alias = encode_column_path(&path);
+                    path.pop();
+                    clauses.push(format!(
+                        "{} as {}",
+                        value,
+                        self.dialect().quote_ident(&alias)
+                    ));
+                }
+            }
+        } else if !self.config.group_by.is_empty() {
             for col in self.config.columns.iter().flatten() {
-                let agg = self.get_aggregate(col);
-                let escaped = col.replace('"', "\"\"").replace("_", "-");
-                clauses.push(format!(
-                    "{}({}) as \"{}\"",
-                    agg,
-                    self.col_name(col),
-                    escaped
-                ));
+                let agg = self.aggregate_sql(col, &self.col_name(col), level);
+                let escaped = self.dialect().quote_ident(&self.column_alias(col));
+                clauses.push(format!("{} as {}", agg, escaped));
             }
         } else if !self.config.columns.is_empty() {
             for col in self.config.columns.iter().flatten() {
-                let escaped = col.replace('"', "\"\"").replace("_", "-");
-                clauses.push(format!("{} as \"{}\"", self.col_name(col), escaped));
+                let escaped = self.dialect().quote_ident(&self.column_alias(col));
+                clauses.push(format!("{} as {}", self.col_name(col), escaped));
             }
         }
 
         clauses
     }
 
+    /// The condition for a row to belong to the `split_by` column `values`.
+    fn split_condition(&self, values: &[Option<String>]) -> String {
+        self.config
+            .split_by
+            .iter()
+            .zip(values)
+            .map(|(col, value)| match value {
+                Some(value) => format!(
+                    "{} = {}",
+                    self.dialect().cast_to_text(&self.col_name(col)),
+                    self.literal(&Scalar::String(value.clone()))
+                        .unwrap_or_default()
+                ),
+                None => format!("{} IS NULL", self.col_name(col)),
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
     fn where_sql(&self) -> String {
         let clauses: Vec<String> = self
             .config
             .filter
             .iter()
-            .filter_map(|flt| {
-                super::GenericSQLVirtualServerModel::filter_term_to_sql(flt.term()).map(
-                    |term_lit| format!("{} {} {}", self.col_name(flt.column()), flt.op(), term_lit),
-                )
-            })
+            .filter_map(|flt| self.filter_sql(flt))
             .collect();
 
-        if clauses.is_empty() {
-            String::new()
+        match (clauses.len(), &self.config.filter_op) {
+            (0, _) => String::new(),
+            (1, _) | (_, FilterReducer::And) => format!(" WHERE {}", clauses.join(" AND ")),
+            (_, FilterReducer::Or) => format!(" WHERE ({})", clauses.join(" OR ")),
+        }
+    }
+
+    /// The alias of the (possibly aggregated) column `col`. `PIVOT` joins
+    /// this to the `split_by` values with `_`, so for views with a `split_by`
+    /// it is escaped as an element of the encoded column path.
+    fn column_alias(&self, col: &str) -> String {
+        if self.config.split_by.is_empty() {
+            col.to_owned()
         } else {
-            format!(" WHERE {}", clauses.join(" AND "))
+            escape_column_path_element(col)
         }
     }
 
+    /// The `split_by` columns for the `PIVOT` source query, escaped as
+    /// elements of the encoded column path such that the pivoted column
+    /// names can be decoded.
+    fn pivot_select_clauses(&self) -> Vec<String> {
+        self.config
+            .split_by
+            .iter()
+            .map(|col| {
+                format!(
+                    "REPLACE(REPLACE({}, {}, {}), {}, {}) AS {}",
+                    self.dialect().cast_to_text(&self.col_name(col)),
+                    string_literal("\\"),
+                    string_literal("\\\\"),
+                    string_literal("_"),
+                    string_literal("\\_"),
+                    self.dialect().quote_ident(col)
+                )
+            })
+            .collect()
+    }
+
     fn pivot_on_expr(&self) -> String {
         self.config
             .split_by
             .iter()
-            .map(|c| format!("\"{}\"", c))
+            .map(|c| self.dialect().quote_ident(c))
             .collect::<Vec<_>>()
             .join(", ")
     }
@@ -302,11 +737,26 @@ impl<'a> ViewQueryContext<'a> {
     fn grouping_id_clause(&self) -> String {
         format!(
             "{}({}) AS __GROUPING_ID__",
-            self.grouping_fn,
+            self.grouping_fn(),
             self.group_col_names.join(", ")
         )
     }
 
+    /// The `__COLLAPSED__` and `__HIDDEN__` row expansion state columns, for
+    /// a grouping ID expression `grouping_id`.
+    fn expand_state_clauses(&self, grouping_id: &str) -> [String; 2] {
+        let [collapsed, hidden] = self.model.expand_state_sql(
+            grouping_id,
+            self.config.group_by.len(),
+            self.config.group_by_depth,
+        );
+
+        [
+            format!("{} AS __COLLAPSED__", collapsed),
+            format!("{} AS __HIDDEN__", hidden),
+        ]
+    }
+
     fn row_path_select_clauses(&self) -> Vec<String> {
         self.config
             .group_by
@@ -320,10 +770,13 @@ impl<'a> ViewQueryContext<'a> {
         let mut clauses = Vec::new();
         if !self.config.group_by.is_empty() {
             for gidx in 0..self.config.group_by.len() {
-                if !self.config.split_by.is_empty() {
+                if self.sorts_subquery() {
                     let shift = self.config.group_by.len() - 1 - gidx;
                     if shift > 0 {
-                        clauses.push(format!("(__GROUPING_ID__ >> {}) DESC", shift));
+                        clauses.push(format!(
+                            "{} DESC",
+                            self.dialect().shift_right("__GROUPING_ID__", shift)
+                        ));
                     } else {
                         clauses.push("__GROUPING_ID__ DESC".to_string());
                     }
@@ -333,7 +786,7 @@ impl<'a> ViewQueryContext<'a> {
                         .map(|c| self.col_name(c))
                         .collect::<Vec<_>>()
                         .join(", ");
-                    clauses.push(format!("{}({}) DESC", self.grouping_fn, groups_up_to));
+                    clauses.push(format!("{}({}) DESC", self.grouping_fn(), groups_up_to));
                 }
 
                 let is_leaf = gidx >= self.config.group_by.len() - 1;
@@ -343,26 +796,24 @@ impl<'a> ViewQueryContext<'a> {
                     }
 
                     let dir = sort_dir_to_string(sort_dir);
-                    if !self.config.split_by.is_empty() {
+                    let first = self.dialect().first_value_fn();
+                    if self.sorts_subquery() {
                         if is_leaf {
                             clauses.push(format!("__SORT_{}__ {}", sidx, dir));
                         } else {
                             clauses.push(format!(
-                                "first(__SORT_{}__) OVER __WINDOW_{}__ {}",
-                                sidx, gidx, dir
+                                "{}(__SORT_{}__) OVER __WINDOW_{}__ {}",
+                                first, sidx, gidx, dir
                             ));
                         }
                     } else {
-                        let agg = self.get_aggregate(sort_col);
+                        let agg = self.sort_aggregate_sql(sort_col);
                         if is_leaf {
-                            clauses.push(format!("{}({}) {}", agg, self.col_name(sort_col), dir));
+                            clauses.push(format!("{} {}", agg, dir));
                         } else {
                             clauses.push(format!(
-                                "first({}({})) OVER __WINDOW_{}__ {}",
-                                agg,
-                                self.col_name(sort_col),
-                                gidx,
-                                dir
+                                "{}({}) OVER __WINDOW_{}__ {}",
+                                first, agg, gidx, dir
                             ));
                         }
                     }
@@ -390,10 +841,10 @@ impl<'a> ViewQueryContext<'a> {
         let mut clauses = Vec::new();
         for gidx in 0..(self.config.group_by.len() - 1) {
             let partition = self.row_path_aliases[..=gidx].join(", ");
-            if !self.config.split_by.is_empty() {
+            if self.sorts_subquery() {
                 let shift = self.config.group_by.len() - 1 - gidx;
                 let grouping_expr = if shift > 0 {
-                    format!("(__GROUPING_ID__ >> {})", shift)
+                    self.dialect().shift_right("__GROUPING_ID__", shift)
                 } else {
                     "__GROUPING_ID__".to_string()
                 };
@@ -412,7 +863,7 @@ impl<'a> ViewQueryContext<'a> {
                 clauses.push(format!(
                     "__WINDOW_{}__ AS (PARTITION BY {}({}), {} ORDER BY {})",
                     gidx,
-                    self.grouping_fn,
+                    self.grouping_fn(),
                     sub_groups,
                     partition,
                     self.group_col_names.join(", ")
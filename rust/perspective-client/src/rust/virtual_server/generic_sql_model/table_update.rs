@@ -0,0 +1,278 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(feature = "virtual-server-arrow")]
+mod arrow;
+
+use indexmap::{IndexMap, IndexSet};
+use serde_json::Value;
+
+use super::dialect::SqlDialect;
+use super::{GenericSQLError, GenericSQLResult};
+use crate::config::Scalar;
+use crate::proto::MakeTableData;
+use crate::proto::make_table_data::Data;
+
+fn invalid_data(e: serde_json::Error) -> GenericSQLError {
+    GenericSQLError::InvalidConfig(format!("Invalid update data: {}", e))
+}
+
+fn unsupported_format() -> GenericSQLError {
+    let msg = if cfg!(feature = "virtual-server-arrow") {
+        "Only JSON row, column, NDJSON, CSV and Arrow data can be written to a virtual table"
+    } else {
+        "Only JSON row, column and NDJSON data can be written to a virtual table"
+    };
+
+    GenericSQLError::UnsupportedOperation(msg.to_string())
+}
+
+fn parse_ndjson(ndjson: &str) -> GenericSQLResult<Vec<Value>> {
+    ndjson
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(invalid_data))
+        .collect()
+}
+
+/// Renders a JSON cell value as a SQL literal. Missing cells, nested arrays
+/// and objects are written as `NULL` and JSON strings respectively.
+pub(crate) fn sql_literal(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "NULL".to_string(),
+        Some(Value::Bool(x)) => if *x { "TRUE" } else { "FALSE" }.to_string(),
+        Some(Value::Number(x)) => x.to_string(),
+        Some(Value::String(x)) => format!("'{}'", x.replace('\'', "''")),
+        Some(x @ (Value::Array(_) | Value::Object(_))) => {
+            format!("'{}'", x.to_string().replace('\'', "''"))
+        },
+    }
+}
+
+/// Converts a JSON cell value to the [`Scalar`] parameter bound in place of
+/// its [`sql_literal`].
+fn json_scalar(value: Option<&Value>) -> Scalar {
+    match value {
+        None | Some(Value::Null) => Scalar::Null,
+        Some(Value::Bool(x)) => Scalar::Bool(*x),
+        Some(Value::Number(x)) => x.as_f64().map_or(Scalar::Null, Scalar::Float),
+        Some(Value::String(x)) => Scalar::String(x.clone()),
+        Some(x @ (Value::Array(_) | Value::Object(_))) => Scalar::String(x.to_string()),
+    }
+}
+
+/// Drops the (empty) parameters of statements generated without them.
+pub(crate) fn without_params(queries: Vec<(String, Vec<Scalar>)>) -> Vec<String> {
+    queries.into_iter().map(|(sql, _)| sql).collect()
+}
+
+/// Renders the cell values of a single statement, either inlined as SQL
+/// literals or as the dialect's positional placeholders, in which case the
+/// values to bind to them are collected in order.
+pub(crate) struct SqlValues<'a> {
+    dialect: &'a dyn SqlDialect,
+    params: Option<Vec<Scalar>>,
+}
+
+impl<'a> SqlValues<'a> {
+    pub(crate) fn new(dialect: &'a dyn SqlDialect, parameterized: bool) -> Self {
+        Self {
+            dialect,
+            params: parameterized.then(Vec::new),
+        }
+    }
+
+    /// The SQL for `value`, a literal or the next placeholder.
+    pub(crate) fn push(&mut self, value: Option<&Value>) -> String {
+        match &mut self.params {
+            None => sql_literal(value),
+            Some(params) => {
+                params.push(json_scalar(value));
+                self.dialect.placeholder(params.len())
+            },
+        }
+    }
+
+    /// Pairs the finished statement `sql` with its parameters.
+    pub(crate) fn finish(self, sql: String) -> (String, Vec<Scalar>) {
+        (sql, self.params.unwrap_or_default())
+    }
+}
+
+/// Row-oriented update data parsed from a [`MakeTableData`], over the union
+/// of the columns present in any row. Cells absent from a row are `None`.
+pub(crate) struct UpdateRows {
+    pub(crate) columns: Vec<String>,
+    pub(crate) rows: Vec<Vec<Option<Value>>>,
+}
+
+impl UpdateRows {
+    /// Parses JSON row, column or NDJSON formatted [`MakeTableData`], or
+    /// Arrow and CSV with the `"virtual-server-arrow"` feature.
+    pub(crate) fn parse(data: &MakeTableData) -> GenericSQLResult<Self> {
+        match &data.data {
+            Some(Data::FromRows(rows)) => {
+                Self::from_records(serde_json::from_str(rows).map_err(invalid_data)?)
+            },
+            Some(Data::FromNdjson(ndjson)) => Self::from_records(parse_ndjson(ndjson)?),
+            Some(Data::FromCols(cols)) => {
+                let cols: IndexMap<String, Vec<Value>> =
+                    serde_json::from_str(cols).map_err(invalid_data)?;
+
+                let num_rows = cols.values().map(|x| x.len()).max().unwrap_or_default();
+                let rows = (0..num_rows)
+                    .map(|ridx| cols.values().map(|col| col.get(ridx).cloned()).collect())
+                    .collect();
+
+                Ok(Self {
+                    columns: cols.into_keys().collect(),
+                    rows,
+                })
+            },
+            #[cfg(feature = "virtual-server-arrow")]
+            Some(Data::FromArrow(arrow)) => Self::from_arrow(arrow),
+            #[cfg(feature = "virtual-server-arrow")]
+            Some(Data::FromCsv(csv)) => Self::from_csv(csv),
+            _ => Err(unsupported_format()),
+        }
+    }
+
+    fn from_records(records: Vec<Value>) -> GenericSQLResult<Self> {
+        let mut columns = IndexSet::<String>::new();
+        let mut records_maps = Vec::with_capacity(records.len());
+        for record in records {
+            let Value::Object(record) = record else {
+                return Err(GenericSQLError::InvalidConfig(
+                    "Update rows must be JSON objects".to_string(),
+                ));
+            };
+
+            for key in record.keys() {
+                columns.insert(key.clone());
+            }
+
+            records_maps.push(record);
+        }
+
+        let columns: Vec<String> = columns.into_iter().collect();
+        let rows = records_maps
+            .into_iter()
+            .map(|mut record| columns.iter().map(|col| record.remove(col)).collect())
+            .collect();
+
+        Ok(Self { columns, rows })
+    }
+
+    /// Merges rows which share an `index` value, with later rows' cells
+    /// overwriting earlier ones, as Perspective's indexed tables do. Returns
+    /// the position of the `index` column.
+    pub(crate) fn merge_by_index(&mut self, index: &str) -> GenericSQLResult<usize> {
+        let idx = self
+            .columns
+            .iter()
+            .position(|col| col == index)
+            .ok_or_else(|| GenericSQLError::ColumnNotFound(index.to_string()))?;
+
+        let mut merged = IndexMap::<String, Vec<Option<Value>>>::new();
+        for row in std::mem::take(&mut self.rows) {
+            let key = sql_literal(row[idx].as_ref());
+            match merged.get_mut(&key) {
+                Some(existing) => {
+                    for (cell, new_cell) in existing.iter_mut().zip(row) {
+                        if new_cell.is_some() {
+                            *cell = new_cell;
+                        }
+                    }
+                },
+                None => {
+                    merged.insert(key, row);
+                },
+            }
+        }
+
+        self.rows = merged.into_values().collect();
+        Ok(idx)
+    }
+
+    /// The quoted, comma-separated column list, e.g. `"a", "b"`.
+    pub(crate) fn columns_sql(&self, dialect: &dyn SqlDialect) -> String {
+        self.columns
+            .iter()
+            .map(|col| dialect.quote_ident(col))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The rows as a `VALUES` list, e.g. `(1, 'x'), (2, 'y')` or
+    /// `(?, ?), (?, ?)`.
+    pub(crate) fn values_sql(&self, values: &mut SqlValues) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .iter()
+                    .map(|cell| values.push(cell.as_ref()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("({})", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Parses the `index` column values of rows to remove from a
+/// [`MakeTableData`], which may be a JSON list of values or of row objects,
+/// a JSON column object, or (with the `"virtual-server-arrow"` feature) Arrow
+/// or CSV data containing the `index` column.
+pub(crate) fn parse_index_values(
+    data: &MakeTableData,
+    index: &str,
+) -> GenericSQLResult<Vec<Value>> {
+    let values = match &data.data {
+        Some(Data::FromRows(rows)) => serde_json::from_str(rows).map_err(invalid_data)?,
+        Some(Data::FromNdjson(ndjson)) => parse_ndjson(ndjson)?,
+        Some(Data::FromCols(cols)) => {
+            let mut cols: IndexMap<String, Vec<Value>> =
+                serde_json::from_str(cols).map_err(invalid_data)?;
+
+            return cols
+                .shift_remove(index)
+                .ok_or_else(|| GenericSQLError::ColumnNotFound(index.to_string()));
+        },
+        #[cfg(feature = "virtual-server-arrow")]
+        Some(Data::FromArrow(_) | Data::FromCsv(_)) => {
+            let update = UpdateRows::parse(data)?;
+            let idx = update
+                .columns
+                .iter()
+                .position(|col| col == index)
+                .ok_or_else(|| GenericSQLError::ColumnNotFound(index.to_string()))?;
+
+            return Ok(update
+                .rows
+                .into_iter()
+                .map(|mut row| row.swap_remove(idx).unwrap_or_default())
+                .collect());
+        },
+        _ => return Err(unsupported_format()),
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(mut row) => row.remove(index).unwrap_or_default(),
+            value => value,
+        })
+        .collect())
+}
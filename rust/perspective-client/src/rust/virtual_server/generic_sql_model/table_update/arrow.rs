@@ -0,0 +1,134 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! Decodes Arrow and CSV update data, for the `"virtual-server-arrow"`
+//! feature.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt64Type};
+use arrow_array::{Array, RecordBatch};
+use arrow_cast::cast;
+use arrow_csv::ReaderBuilder;
+use arrow_csv::reader::Format;
+use arrow_ipc::reader::StreamReader;
+use arrow_schema::{ArrowError, DataType};
+use serde_json::Value;
+
+use super::UpdateRows;
+use crate::virtual_server::generic_sql_model::{GenericSQLError, GenericSQLResult};
+
+fn invalid_data(e: ArrowError) -> GenericSQLError {
+    GenericSQLError::InvalidConfig(format!("Invalid update data: {}", e))
+}
+
+/// Converts an Arrow array to JSON cell values. Numbers and booleans are
+/// kept as such, and every other type (dates, timestamps, dictionaries, ...)
+/// is written as its string representation, which SQL engines coerce to the
+/// column's type on insert.
+fn array_to_json(array: &dyn Array) -> Result<Vec<Option<Value>>, ArrowError> {
+    let dtype = array.data_type();
+    Ok(if *dtype == DataType::Null {
+        vec![Some(Value::Null); array.len()]
+    } else if *dtype == DataType::Boolean {
+        array
+            .as_boolean()
+            .iter()
+            .map(|x| Some(x.map_or(Value::Null, Value::Bool)))
+            .collect()
+    } else if dtype.is_signed_integer() {
+        cast(array, &DataType::Int64)?
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|x| Some(x.map_or(Value::Null, Value::from)))
+            .collect()
+    } else if dtype.is_unsigned_integer() {
+        cast(array, &DataType::UInt64)?
+            .as_primitive::<UInt64Type>()
+            .iter()
+            .map(|x| Some(x.map_or(Value::Null, Value::from)))
+            .collect()
+    } else if dtype.is_numeric() {
+        cast(array, &DataType::Float64)?
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|x| Some(x.map_or(Value::Null, Value::from)))
+            .collect()
+    } else {
+        cast(array, &DataType::Utf8)?
+            .as_string::<i32>()
+            .iter()
+            .map(|x| Some(x.map_or(Value::Null, |x| Value::String(x.to_string()))))
+            .collect()
+    })
+}
+
+impl UpdateRows {
+    /// Parses an Arrow IPC stream.
+    pub(super) fn from_arrow(arrow: &[u8]) -> GenericSQLResult<Self> {
+        let reader = StreamReader::try_new(Cursor::new(arrow), None).map_err(invalid_data)?;
+        let columns = reader
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid_data)?;
+
+        Self::from_batches(columns, &batches)
+    }
+
+    /// Parses CSV with a header row, inferring column types from its values.
+    pub(super) fn from_csv(csv: &str) -> GenericSQLResult<Self> {
+        let format = Format::default().with_header(true);
+        let (schema, _) = format
+            .infer_schema(Cursor::new(csv), None)
+            .map_err(invalid_data)?;
+
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+
+        let batches = ReaderBuilder::new(Arc::new(schema))
+            .with_format(format)
+            .build(Cursor::new(csv))
+            .and_then(|reader| reader.collect::<Result<Vec<_>, _>>())
+            .map_err(invalid_data)?;
+
+        Self::from_batches(columns, &batches)
+    }
+
+    fn from_batches(columns: Vec<String>, batches: &[RecordBatch]) -> GenericSQLResult<Self> {
+        let mut rows = vec![];
+        for batch in batches {
+            let mut cols = batch
+                .columns()
+                .iter()
+                .map(|col| array_to_json(col).map(Vec::into_iter))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_data)?;
+
+            for _ in 0..batch.num_rows() {
+                rows.push(cols.iter_mut().map(|col| col.next().flatten()).collect());
+            }
+        }
+
+        Ok(Self { columns, rows })
+    }
+}
@@ -13,7 +13,8 @@
 use std::collections::HashMap;
 
 use super::*;
-use crate::config::Aggregate;
+use crate::config::{Aggregate, Filter, FilterReducer, FilterTerm};
+use crate::virtual_server::{decode_column_path, encode_column_path};
 
 #[test]
 fn test_get_hosted_tables() {
@@ -21,6 +22,23 @@ fn test_get_hosted_tables() {
     assert_eq!(builder.get_hosted_tables().unwrap(), "SHOW ALL TABLES");
 }
 
+#[test]
+fn test_args_builder() {
+    let args = GenericSQLVirtualServerModelArgs::default()
+        .with_create_entity("TEMP TABLE")
+        .with_dialect(SqlDialectName::Sqlite);
+
+    let builder = GenericSQLVirtualServerModel::new(args);
+    let sql = builder
+        .table_make_view("my_table", "my_view", &ViewConfig::default())
+        .unwrap();
+
+    assert!(
+        sql.starts_with("CREATE TEMP TABLE my_view AS SELECT"),
+        "{sql}"
+    );
+}
+
 #[test]
 fn test_table_schema() {
     let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
@@ -193,7 +211,7 @@ fn test_table_make_view_with_sort_and_group_by_no_split_by() {
         .unwrap();
 
     assert!(
-        sql.contains("sum(\"value\") ASC"),
+        sql.contains("SUM(\"value\") ASC"),
         "expected raw aggregate in ORDER BY: {}",
         sql
     );
@@ -359,3 +377,1387 @@ fn test_view_get_data() {
     assert!(sql.contains("FROM my_view"));
     assert!(sql.contains("LIMIT 100 OFFSET 0"));
 }
+
+#[test]
+fn test_view_get_min_max() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig::default();
+    assert_eq!(
+        builder
+            .view_get_min_max("my_view", "Sales", &config)
+            .unwrap(),
+        "SELECT MIN(\"Sales\"), MAX(\"Sales\") FROM my_view"
+    );
+}
+
+#[test]
+fn test_view_get_min_max_group_by_split_by() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        group_by: vec!["State".to_string()],
+        split_by: vec!["Region".to_string()],
+        ..ViewConfig::default()
+    };
+
+    assert_eq!(
+        builder
+            .view_get_min_max(
+                "my_view",
+                &encode_column_path(&["East", "Order_Total"]),
+                &config
+            )
+            .unwrap(),
+        "SELECT MIN(\"East_Order\\_Total\"), MAX(\"East_Order\\_Total\") FROM my_view WHERE \
+         __GROUPING_ID__ = 0"
+    );
+}
+
+#[test]
+fn test_view_size_group_by() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    assert_eq!(
+        builder
+            .view_size("my_view", &ViewConfig::default())
+            .unwrap(),
+        "SELECT COUNT(*) FROM my_view"
+    );
+
+    let config = ViewConfig {
+        group_by: vec!["State".to_string()],
+        ..ViewConfig::default()
+    };
+
+    assert_eq!(
+        builder.view_size("my_view", &config).unwrap(),
+        "SELECT COUNT(*) FROM my_view WHERE __HIDDEN__ = 0"
+    );
+}
+
+#[test]
+fn test_table_make_view_group_by_depth() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        columns: vec![Some("value".to_string())],
+        group_by: vec!["a".to_string(), "b".to_string()],
+        group_by_depth: Some(0),
+        ..ViewConfig::default()
+    };
+
+    let sql = builder
+        .table_make_view("source_table", "dest_view", &config)
+        .unwrap();
+
+    assert!(sql.contains(
+        "((CASE GROUPING_ID(\"a\", \"b\") WHEN 3 THEN 0 WHEN 1 THEN 1 WHEN 0 THEN 2 END) >= 1 AND \
+         (CASE GROUPING_ID(\"a\", \"b\") WHEN 3 THEN 0 WHEN 1 THEN 1 WHEN 0 THEN 2 END) < 2) AS \
+         __COLLAPSED__"
+    ));
+
+    assert!(sql.contains(
+        "GREATEST((CASE GROUPING_ID(\"a\", \"b\") WHEN 3 THEN 0 WHEN 1 THEN 1 WHEN 0 THEN 2 END) \
+         - 1, 0) AS __HIDDEN__"
+    ));
+}
+
+#[test]
+fn test_view_get_data_group_by_hides_collapsed_rows() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        columns: vec![Some("value".to_string())],
+        group_by: vec!["a".to_string()],
+        ..ViewConfig::default()
+    };
+
+    let mut schema = IndexMap::new();
+    schema.insert("value".to_string(), ColumnType::Float);
+    let viewport = ViewPort {
+        start_row: Some(0),
+        end_row: Some(10),
+        start_col: Some(0),
+        end_col: Some(1),
+    };
+
+    let sql = builder
+        .view_get_data("my_view", &config, &viewport, &schema)
+        .unwrap();
+
+    assert!(sql.contains("FROM my_view WHERE __HIDDEN__ = 0"));
+}
+
+#[test]
+fn test_view_collapse_and_expand() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        group_by: vec!["a".to_string(), "b".to_string()],
+        ..ViewConfig::default()
+    };
+
+    let collapse = builder.view_collapse("my_view", &config, 3).unwrap();
+    assert!(collapse.starts_with("UPDATE my_view SET __COLLAPSED__ = CASE WHEN"));
+    assert!(collapse.contains("THEN true ELSE __COLLAPSED__ END"));
+    assert!(collapse.contains("ELSE __HIDDEN__ + 1 END"));
+    assert!(collapse.contains("WHERE __HIDDEN__ = 0 LIMIT 1 OFFSET 3) AS __TARGET__"));
+    assert!(collapse.contains("NOT __TARGET__.collapsed"));
+    assert!(collapse.contains(
+        "(((__TARGET__.gid >> 1) & 1) = 1 OR __ROW_PATH_0__ IS NOT DISTINCT FROM __TARGET__.p0)"
+    ));
+
+    let expand = builder.view_expand("my_view", &config, 3).unwrap();
+    assert!(expand.contains("THEN false ELSE __COLLAPSED__ END"));
+    assert!(expand.contains("ELSE __HIDDEN__ - 1 END"));
+    assert!(!expand.contains("NOT __TARGET__.collapsed"));
+
+    assert!(matches!(
+        builder.view_expand("my_view", &ViewConfig::default(), 0),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_view_set_depth() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        group_by: vec!["a".to_string()],
+        ..ViewConfig::default()
+    };
+
+    assert_eq!(
+        builder.view_set_depth("my_view", &config, 0).unwrap(),
+        "UPDATE my_view SET __COLLAPSED__ = ((CASE __GROUPING_ID__ WHEN 1 THEN 0 WHEN 0 THEN 1 \
+         END) >= 1 AND (CASE __GROUPING_ID__ WHEN 1 THEN 0 WHEN 0 THEN 1 END) < 1), __HIDDEN__ = \
+         GREATEST((CASE __GROUPING_ID__ WHEN 1 THEN 0 WHEN 0 THEN 1 END) - 1, 0)"
+    );
+}
+
+fn rows(json: &str) -> MakeTableData {
+    MakeTableData {
+        data: Some(crate::proto::make_table_data::Data::FromRows(
+            json.to_string(),
+        )),
+    }
+}
+
+#[test]
+fn test_table_update_appends_without_index() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let data = rows(r#"[{"a": 1, "b": "x"}, {"a": 2, "c": true}]"#);
+    assert_eq!(
+        builder.table_update("my_table", None, &data).unwrap(),
+        vec!["INSERT INTO my_table (\"a\", \"b\", \"c\") VALUES (1, 'x', NULL), (2, NULL, TRUE)"]
+    );
+}
+
+#[test]
+fn test_table_update_with_index() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let data = MakeTableData {
+        data: Some(crate::proto::make_table_data::Data::FromCols(
+            r#"{"id": [1, 2, 1], "name": ["a", "O'Brien", "c"]}"#.to_string(),
+        )),
+    };
+
+    assert_eq!(
+        builder.table_update("my_table", Some("id"), &data).unwrap(),
+        vec![
+            "UPDATE my_table AS __TABLE__ SET \"name\" = __UPDATE__.\"name\" FROM (VALUES (1, \
+             'c'), (2, 'O''Brien')) AS __UPDATE__(\"id\", \"name\") WHERE __TABLE__.\"id\" = \
+             __UPDATE__.\"id\"",
+            "INSERT INTO my_table (\"id\", \"name\") SELECT __UPDATE__.* FROM (VALUES (1, 'c'), \
+             (2, 'O''Brien')) AS __UPDATE__(\"id\", \"name\") WHERE NOT EXISTS (SELECT 1 FROM \
+             my_table AS __TABLE__ WHERE __TABLE__.\"id\" = __UPDATE__.\"id\")",
+        ]
+    );
+
+    assert!(matches!(
+        builder.table_update("my_table", Some("missing"), &data),
+        Err(GenericSQLError::ColumnNotFound(_))
+    ));
+}
+
+#[test]
+fn test_table_remove() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    assert_eq!(
+        builder
+            .table_remove("my_table", Some("id"), &rows(r#"["a", "b"]"#))
+            .unwrap(),
+        vec!["DELETE FROM my_table WHERE \"id\" IN ('a', 'b')"]
+    );
+
+    assert!(matches!(
+        builder.table_remove("my_table", None, &rows(r#"["a"]"#)),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_table_replace_and_clear() {
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    assert_eq!(
+        builder
+            .table_replace("my_table", &rows(r#"[{"a": 1.5}]"#))
+            .unwrap(),
+        vec![
+            "DELETE FROM my_table",
+            "INSERT INTO my_table (\"a\") VALUES (1.5)"
+        ]
+    );
+
+    assert_eq!(
+        builder.table_replace("my_table", &rows("[]")).unwrap(),
+        vec!["DELETE FROM my_table"]
+    );
+
+    let view = MakeTableData {
+        data: Some(crate::proto::make_table_data::Data::FromView(
+            "view".to_string(),
+        )),
+    };
+
+    assert!(matches!(
+        builder.table_replace("my_table", &view),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_table_update_parameterized() {
+    let model = dialect_model(SqlDialectName::Postgres);
+    let data = rows(r#"[{"id": 1, "name": "O'Brien"}, {"id": 2, "name": null}]"#);
+    assert_eq!(
+        model
+            .table_update_parameterized("my_table", None, &data)
+            .unwrap(),
+        vec![(
+            "INSERT INTO my_table (\"id\", \"name\") VALUES ($1, $2), ($3, $4)".to_string(),
+            vec![
+                Scalar::Float(1.0),
+                Scalar::String("O'Brien".to_string()),
+                Scalar::Float(2.0),
+                Scalar::Null,
+            ]
+        )]
+    );
+
+    // Each statement of an indexed update numbers its placeholders from 1.
+    let queries = model
+        .table_update_parameterized("my_table", Some("id"), &data)
+        .unwrap();
+
+    assert_eq!(queries.len(), 2);
+    for (sql, params) in queries {
+        assert!(sql.contains("(VALUES ($1, $2), ($3, $4))"), "{}", sql);
+        assert!(!sql.contains("O''Brien"), "{}", sql);
+        assert_eq!(params.len(), 4);
+    }
+
+    assert_eq!(
+        model
+            .table_remove_parameterized("my_table", Some("id"), &rows(r#"["a", "b'c"]"#))
+            .unwrap(),
+        vec![(
+            "DELETE FROM my_table WHERE \"id\" IN ($1, $2)".to_string(),
+            vec![
+                Scalar::String("a".to_string()),
+                Scalar::String("b'c".to_string())
+            ]
+        )]
+    );
+
+    assert_eq!(
+        model
+            .table_replace_parameterized("my_table", &rows(r#"[{"a": true}]"#))
+            .unwrap(),
+        vec![
+            ("DELETE FROM my_table".to_string(), vec![]),
+            (
+                "INSERT INTO my_table (\"a\") VALUES ($1)".to_string(),
+                vec![Scalar::Bool(true)]
+            ),
+        ]
+    );
+}
+
+#[cfg(feature = "virtual-server-arrow")]
+#[test]
+fn test_table_update_csv_and_arrow() {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+    use arrow_ipc::writer::StreamWriter;
+
+    use crate::proto::make_table_data::Data;
+
+    let builder = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let csv = MakeTableData {
+        data: Some(Data::FromCsv("id,name,price\n1,a,1.5\n2,,2".to_string())),
+    };
+
+    assert_eq!(builder.table_update("my_table", None, &csv).unwrap(), vec![
+        "INSERT INTO my_table (\"id\", \"name\", \"price\") VALUES (1, 'a', 1.5), (2, NULL, 2.0)"
+    ]);
+
+    assert_eq!(
+        builder.table_remove("my_table", Some("id"), &csv).unwrap(),
+        vec!["DELETE FROM my_table WHERE \"id\" IN (1, 2)"]
+    );
+
+    let batch = RecordBatch::try_from_iter([
+        (
+            "name",
+            Arc::new(StringArray::from(vec![Some("x"), None])) as ArrayRef,
+        ),
+        (
+            "price",
+            Arc::new(Float64Array::from(vec![0.5, 1.0])) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let mut arrow = vec![];
+    let mut writer = StreamWriter::try_new(&mut arrow, &batch.schema()).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    drop(writer);
+
+    let arrow = MakeTableData {
+        data: Some(Data::FromArrow(arrow)),
+    };
+
+    assert_eq!(
+        builder.table_update("my_table", None, &arrow).unwrap(),
+        vec!["INSERT INTO my_table (\"name\", \"price\") VALUES ('x', 0.5), (NULL, 1.0)"]
+    );
+}
+
+fn dialect_model(dialect: SqlDialectName) -> GenericSQLVirtualServerModel {
+    GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs {
+        dialect: Some(dialect),
+        ..GenericSQLVirtualServerModelArgs::default()
+    })
+}
+
+#[test]
+fn test_dialect_from_args() {
+    let args: GenericSQLVirtualServerModelArgs =
+        serde_json::from_str(r#"{"dialect": "clickhouse"}"#).unwrap();
+
+    let model = GenericSQLVirtualServerModel::new(args);
+    assert_eq!(model.dialect().name(), "clickhouse");
+    assert_eq!(
+        GenericSQLVirtualServerModel::default().dialect().name(),
+        "duckdb"
+    );
+
+    assert!(
+        serde_json::from_str::<GenericSQLVirtualServerModelArgs>(r#"{"dialect": "oracle"}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_dialect_catalog_queries() {
+    let postgres = dialect_model(SqlDialectName::Postgres);
+    assert_eq!(
+        postgres.table_schema("public.sales").unwrap(),
+        "SELECT column_name, data_type FROM information_schema.columns WHERE table_name = 'sales' \
+         AND table_schema = 'public' ORDER BY ordinal_position"
+    );
+
+    assert_eq!(
+        postgres
+            .table_validate_expression("sales", "\"a\" + 1")
+            .unwrap(),
+        "SELECT NULL, pg_typeof((\"a\" + 1))::text FROM sales LIMIT 1"
+    );
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    assert_eq!(
+        sqlite.table_schema("sales").unwrap(),
+        "SELECT name, type FROM pragma_table_info('sales')"
+    );
+
+    assert_eq!(
+        sqlite.view_column_size("my_view").unwrap(),
+        "SELECT COUNT(*) FROM pragma_table_info('my_view') WHERE SUBSTR(name, 1, 2) <> '__'"
+    );
+
+    let clickhouse = dialect_model(SqlDialectName::ClickHouse);
+    assert_eq!(clickhouse.get_hosted_tables().unwrap(), "SHOW TABLES");
+
+    let ansi = dialect_model(SqlDialectName::Ansi);
+    assert!(matches!(
+        ansi.table_validate_expression("sales", "1"),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_ansi_dialect_pagination() {
+    let model = dialect_model(SqlDialectName::Ansi);
+    let mut schema = IndexMap::new();
+    schema.insert("value".to_string(), ColumnType::Float);
+    let viewport = ViewPort {
+        start_row: Some(10),
+        end_row: Some(30),
+        start_col: None,
+        end_col: None,
+    };
+
+    let sql = model
+        .view_get_data("my_view", &ViewConfig::default(), &viewport, &schema)
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        "SELECT \"value\" FROM my_view OFFSET 10 ROWS FETCH NEXT 20 ROWS ONLY"
+    );
+}
+
+#[test]
+fn test_sqlite_dialect_emulates_rollup() {
+    let model = dialect_model(SqlDialectName::Sqlite);
+    let config = ViewConfig {
+        columns: vec![Some("value".to_string())],
+        group_by: vec!["a".to_string(), "b".to_string()],
+        sort: vec![Sort("value".to_string(), SortDir::Desc)],
+        ..ViewConfig::default()
+    };
+
+    let sql = model.table_make_view("t", "v", &config).unwrap();
+    assert!(!sql.contains("ROLLUP("), "unexpected ROLLUP: {}", sql);
+    assert!(sql.starts_with("CREATE TABLE v AS SELECT *, false AS __COLLAPSED__"));
+    assert!(sql.contains(
+        "SELECT MAX(\"value\") as \"value\", \"a\" as __ROW_PATH_0__, NULL as __ROW_PATH_1__, 1 \
+         AS __GROUPING_ID__, MAX(\"value\") AS __SORT_0__ FROM t GROUP BY \"a\" UNION ALL"
+    ));
+
+    assert!(sql.ends_with(
+        "ORDER BY (__GROUPING_ID__ >> 1) DESC, FIRST_VALUE(__SORT_0__) OVER __WINDOW_0__ DESC, \
+         __ROW_PATH_0__ ASC, __GROUPING_ID__ DESC, __SORT_0__ DESC, __ROW_PATH_1__ ASC"
+    ));
+
+    let sql = model.view_set_depth("v", &config, 0).unwrap();
+    assert!(sql.contains("__HIDDEN__ = MAX((CASE __GROUPING_ID__"));
+}
+
+#[test]
+fn test_split_by_without_pivot() {
+    let model = dialect_model(SqlDialectName::Postgres);
+    let config = ViewConfig {
+        columns: vec![Some("value".to_string())],
+        group_by: vec!["a".to_string()],
+        split_by: vec!["s".to_string()],
+        ..ViewConfig::default()
+    };
+
+    assert!(matches!(
+        model.table_make_view("t", "v", &config),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    assert_eq!(
+        model.view_split_values("t", &config).unwrap(),
+        "SELECT DISTINCT CAST(\"s\" AS TEXT) AS __SPLIT_0__ FROM t ORDER BY __SPLIT_0__"
+    );
+
+    let split_values = vec![vec![Some("x's".to_string())], vec![None]];
+    let sql = model
+        .table_make_view_with_split_values("t", "v", &config, &split_values)
+        .unwrap();
+
+    assert!(!sql.contains("PIVOT"), "unexpected PIVOT: {}", sql);
+    assert!(sql.contains("GROUP BY ROLLUP(\"a\")"));
+    assert!(sql.contains(
+        "SELECT any_value(CASE WHEN CAST(\"s\" AS TEXT) = 'x''s' THEN \"value\" END) as \
+         \"x's_value\", any_value(CASE WHEN \"s\" IS NULL THEN \"value\" END) as \"NULL_value\""
+    ));
+
+    assert!(matches!(
+        model.table_make_view_with_split_values("t", "v", &config, &[vec![]]),
+        Err(GenericSQLError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_clickhouse_dialect_rejects_update_from() {
+    let model = dialect_model(SqlDialectName::ClickHouse);
+    let config = ViewConfig {
+        group_by: vec!["a".to_string()],
+        ..ViewConfig::default()
+    };
+
+    assert!(matches!(
+        model.view_collapse("v", &config, 0),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    assert!(matches!(
+        model.table_update("t", Some("id"), &rows(r#"[{"id": 1}]"#)),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    assert_eq!(
+        model
+            .table_update("t", None, &rows(r#"[{"id": 1}]"#))
+            .unwrap(),
+        vec!["INSERT INTO t (\"id\") VALUES (1)"]
+    );
+}
+
+#[test]
+fn test_datafusion_dialect() {
+    let model = dialect_model(SqlDialectName::DataFusion);
+    assert_eq!(
+        model
+            .table_validate_expression("sales", "\"a\" / 2")
+            .unwrap(),
+        "SELECT NULL, arrow_typeof((\"a\" / CAST(2 AS DOUBLE PRECISION))) FROM sales LIMIT 1"
+    );
+
+    let config = ViewConfig {
+        group_by: vec!["a".to_string()],
+        ..aggregate_view(Aggregate::MultiAggregate("min by".to_string(), vec![
+            "w".to_string(),
+        ]))
+    };
+
+    let sql = model.table_make_view("t", "v", &config).unwrap();
+    assert!(sql.starts_with("CREATE TABLE v AS SELECT"), "{}", sql);
+    assert!(
+        sql.contains("GROUPING(\"a\") AS __GROUPING_ID__"),
+        "{}",
+        sql
+    );
+    assert!(
+        sql.contains("first_value(\"x\" ORDER BY \"w\" ASC NULLS LAST) as \"x\""),
+        "{}",
+        sql
+    );
+
+    assert!(matches!(
+        model.view_expand("v", &config, 0),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    assert!(matches!(
+        model.table_make_view("t", "v", &aggregate_view(Aggregate::from("median"))),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    assert_eq!(model.column_type("Int8"), ColumnType::Integer);
+    assert_eq!(model.column_type("Int64"), ColumnType::Float);
+    assert_eq!(model.column_type("Utf8View"), ColumnType::String);
+    assert_eq!(model.column_type("Date32"), ColumnType::Date);
+    assert_eq!(
+        model.column_type("Timestamp(Millisecond, None)"),
+        ColumnType::Datetime
+    );
+}
+
+#[test]
+fn test_dialect_column_types() {
+    let duckdb = GenericSQLVirtualServerModel::default();
+    assert_eq!(duckdb.column_type("VARCHAR"), ColumnType::String);
+    assert_eq!(duckdb.column_type("INTEGER"), ColumnType::Integer);
+    assert_eq!(duckdb.column_type("BIGINT"), ColumnType::Float);
+    assert_eq!(duckdb.column_type("DOUBLE"), ColumnType::Float);
+    assert_eq!(duckdb.column_type("DATE"), ColumnType::Date);
+    assert_eq!(duckdb.column_type("TIMESTAMP"), ColumnType::Datetime);
+    assert_eq!(duckdb.column_type("BOOLEAN"), ColumnType::Boolean);
+
+    let postgres = dialect_model(SqlDialectName::Postgres);
+    assert_eq!(
+        postgres.column_type("timestamp without time zone"),
+        ColumnType::Datetime
+    );
+
+    assert_eq!(postgres.column_type("interval"), ColumnType::String);
+    assert_eq!(postgres.column_type("double precision"), ColumnType::Float);
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    assert_eq!(sqlite.column_type("real"), ColumnType::Float);
+    assert_eq!(sqlite.column_type("integer"), ColumnType::Integer);
+
+    let clickhouse = dialect_model(SqlDialectName::ClickHouse);
+    assert_eq!(
+        clickhouse.column_type("Nullable(Int8)"),
+        ColumnType::Integer
+    );
+    assert_eq!(clickhouse.column_type("Int64"), ColumnType::Float);
+    assert_eq!(
+        clickhouse.column_type("LowCardinality(String)"),
+        ColumnType::String
+    );
+
+    assert_eq!(
+        clickhouse.column_type("DateTime64(3)"),
+        ColumnType::Datetime
+    );
+}
+
+#[test]
+fn test_table_make_view_parameterized() {
+    let model = GenericSQLVirtualServerModel::default();
+    let config = ViewConfig {
+        columns: vec![Some("a\"b".to_string())],
+        filter: vec![
+            Filter::new(
+                "name",
+                "==",
+                FilterTerm::Scalar(Scalar::String("x' OR 1=1 --".to_string())),
+            ),
+            Filter::new("state", "IN", ["NY", "TX"]),
+            Filter::new("value", ">", FilterTerm::Scalar(Scalar::Null)),
+        ],
+        ..ViewConfig::default()
+    };
+
+    let (sql, params) = model
+        .table_make_view_parameterized("t", "v", &config)
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        "CREATE TABLE v AS (SELECT \"a\"\"b\" as \"a\"\"b\" FROM t WHERE \"name\" = ? AND \
+         \"state\" IN (?, ?) ORDER BY rowid)"
+    );
+
+    assert_eq!(params, vec![
+        Scalar::String("x' OR 1=1 --".to_string()),
+        Scalar::String("NY".to_string()),
+        Scalar::String("TX".to_string()),
+    ]);
+
+    assert_eq!(
+        model.table_make_view("t", "v", &config).unwrap(),
+        "CREATE TABLE v AS (SELECT \"a\"\"b\" as \"a\"\"b\" FROM t WHERE \"name\" = 'x'' OR 1=1 \
+         --' AND \"state\" IN ('NY', 'TX') ORDER BY rowid)"
+    );
+}
+
+#[test]
+fn test_table_make_view_parameterized_placeholder_order() {
+    let model = dialect_model(SqlDialectName::Postgres);
+    let config = ViewConfig {
+        columns: vec![Some("value".to_string())],
+        split_by: vec!["s".to_string()],
+        filter: vec![Filter::new(
+            "value",
+            ">",
+            FilterTerm::Scalar(Scalar::Float(1.5)),
+        )],
+        ..ViewConfig::default()
+    };
+
+    let (sql, params) = model.view_split_values_parameterized("t", &config).unwrap();
+    assert_eq!(
+        sql,
+        "SELECT DISTINCT CAST(\"s\" AS TEXT) AS __SPLIT_0__ FROM t WHERE \"value\" > $1 ORDER BY \
+         __SPLIT_0__"
+    );
+
+    assert_eq!(params, vec![Scalar::Float(1.5)]);
+    let (sql, params) = model
+        .table_make_view_with_split_values_parameterized("t", "v", &config, &[vec![Some(
+            "x".to_string(),
+        )]])
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        "CREATE TABLE v AS (SELECT CASE WHEN CAST(\"s\" AS TEXT) = $1 THEN \"value\" END as \
+         \"x_value\" FROM t WHERE \"value\" > $2)"
+    );
+
+    assert_eq!(params, vec![
+        Scalar::String("x".to_string()),
+        Scalar::Float(1.5)
+    ]);
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    let config = ViewConfig {
+        group_by: vec!["a".to_string()],
+        ..config
+    };
+
+    let (sql, params) = sqlite
+        .table_make_view_with_split_values_parameterized("t", "v", &config, &[vec![Some(
+            "x".to_string(),
+        )]])
+        .unwrap();
+
+    assert_eq!(sql.matches('?').count(), 4, "{}", sql);
+    assert_eq!(params, vec![
+        Scalar::String("x".to_string()),
+        Scalar::Float(1.5),
+        Scalar::String("x".to_string()),
+        Scalar::Float(1.5)
+    ]);
+}
+
+#[test]
+fn test_expression_sql() {
+    let duckdb = GenericSQLVirtualServerModel::default();
+    let cases = [
+        ("\"Sales\" * 2", "(\"Sales\" * 2)"),
+        (
+            "\"Profit\" / \"Sales\" * 100",
+            "((\"Profit\" / \"Sales\") * 100)",
+        ),
+        ("-\"a\" ^ 2 + .5", "((-POWER(\"a\", 2)) + 0.5)"),
+        (
+            "// Comment\nvar x := \"a\" + 1;\nx * x",
+            "((\"a\" + 1) * (\"a\" + 1))",
+        ),
+        (
+            "if (\"Sales\" > 100) { 'big' } else if (\"Sales\" > 10) { 'medium' } else { 'small' }",
+            "CASE WHEN (\"Sales\" > 100) THEN 'big' ELSE CASE WHEN (\"Sales\" > 10) THEN 'medium' \
+             ELSE 'small' END END",
+        ),
+        (
+            "\"x\" != 1 and not is_null(\"y\") ? 2 : 3",
+            "CASE WHEN ((\"x\" <> 1) AND (NOT (\"y\" IS NULL))) THEN 2 ELSE 3 END",
+        ),
+        (
+            "concat(upper(\"Name\"), 'it\\'s')",
+            "(UPPER(\"Name\") || 'it''s')",
+        ),
+        (
+            "max(\"a\", \"b\", 0)",
+            "GREATEST(GREATEST(\"a\", \"b\"), 0)",
+        ),
+        (
+            "bucket(\"Order Date\", 'M')",
+            "DATE_TRUNC('month', \"Order Date\")",
+        ),
+        ("bucket(\"Sales\", 10)", "(FLOOR((\"Sales\" / 10)) * 10)"),
+        ("integer(\"a\")", "CAST(\"a\" AS INTEGER)"),
+        (
+            "order(\"Region\", 'East', 'West')",
+            "CASE \"Region\" WHEN 'East' THEN 0 WHEN 'West' THEN 1 ELSE 2 END",
+        ),
+        (
+            "replace_all(\"s\", 'a+', 'b')",
+            "REGEXP_REPLACE(\"s\", 'a+', 'b', 'g')",
+        ),
+    ];
+
+    for (expression, sql) in cases {
+        assert_eq!(duckdb.expression_sql(expression).unwrap(), sql);
+    }
+
+    let postgres = dialect_model(SqlDialectName::Postgres);
+    assert_eq!(
+        postgres.expression_sql("\"a\" / 2").unwrap(),
+        "(\"a\" / CAST(2 AS DOUBLE PRECISION))"
+    );
+
+    assert_eq!(
+        postgres.expression_sql("match(\"s\", 'a+')").unwrap(),
+        "(\"s\" ~ 'a+')"
+    );
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    assert_eq!(
+        sqlite.expression_sql("bucket(\"d\", 'W')").unwrap(),
+        "date(\"d\", '-6 days', 'weekday 1')"
+    );
+
+    assert!(matches!(
+        sqlite.expression_sql("match(\"s\", 'a+')"),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_expression_sql_errors() {
+    let model = GenericSQLVirtualServerModel::default();
+    for expression in [
+        "vlookup('a', 1)",
+        "col('a')",
+        "var x := 0; for (var i := 0; i < 10; i += 1) { x := x + i }; x",
+        "if (\"a\" > 1) { var y := 0; y := 1 }",
+        "bucket(\"d\", '5m')",
+    ] {
+        assert!(
+            matches!(
+                model.expression_sql(expression),
+                Err(GenericSQLError::UnsupportedOperation(_))
+            ),
+            "{}",
+            expression
+        );
+    }
+
+    for expression in ["\"a\" +", "abs(\"a\", 1)", "y + 1", "(\"a\"", "\"a\" # 1"] {
+        assert!(
+            matches!(
+                model.expression_sql(expression),
+                Err(GenericSQLError::InvalidExpression(_))
+            ),
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn test_expression_diagnostics() {
+    let model = GenericSQLVirtualServerModel::default();
+    for (expression, line, column, span) in [
+        ("\"a\" +", 0, 5, 0),
+        ("abs(\"a\", 1)", 0, 0, 3),
+        ("y + 1", 0, 0, 1),
+        ("\"a\" +\n  # 1", 1, 2, 1),
+        ("var x := 1;\nx + (\"a\" 2)", 1, 9, 1),
+        ("'a\nb", 0, 0, 2),
+    ] {
+        let Err(GenericSQLError::InvalidExpression(diagnostic)) = model.expression_sql(expression)
+        else {
+            panic!("{}", expression);
+        };
+
+        assert_eq!(
+            (diagnostic.line, diagnostic.column, diagnostic.span),
+            (line, column, span),
+            "{}: {}",
+            expression,
+            diagnostic
+        );
+    }
+}
+
+#[test]
+fn test_table_make_view_translates_expressions() {
+    let model = GenericSQLVirtualServerModel::default();
+    let mut config = ViewConfig {
+        columns: vec![Some("margin".to_string())],
+        group_by: vec!["bucketed".to_string()],
+        ..ViewConfig::default()
+    };
+
+    config
+        .expressions
+        .0
+        .insert("margin".to_string(), "\"Profit\" / \"Sales\"".to_string());
+
+    config.expressions.0.insert(
+        "bucketed".to_string(),
+        "bucket(\"Order Date\", 'Y')".to_string(),
+    );
+
+    let sql = model.table_make_view("t", "v", &config).unwrap();
+    assert!(
+        sql.contains("DATE_TRUNC('year', \"Order Date\") as __ROW_PATH_0__"),
+        "{}",
+        sql
+    );
+
+    assert!(
+        sql.contains("any_value((\"Profit\" / \"Sales\"))"),
+        "{}",
+        sql
+    );
+
+    config
+        .expressions
+        .0
+        .insert("lookup".to_string(), "vlookup('a', 1)".to_string());
+
+    assert!(matches!(
+        model.table_make_view("t", "v", &config),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+}
+
+#[test]
+fn test_table_make_view_filter_ops() {
+    let model = GenericSQLVirtualServerModel::default();
+    let string = |x: &str| FilterTerm::Scalar(x.into());
+    let float = |x: f64| FilterTerm::Scalar(Scalar::Float(x));
+    let cases = [
+        (Filter::new("a", "==", string("x")), "\"a\" = 'x'"),
+        (Filter::new("a", "!=", string("x")), "\"a\" <> 'x'"),
+        (Filter::new("a", "<", float(1.0)), "\"a\" < 1"),
+        (Filter::new("a", ">", float(1.5)), "\"a\" > 1.5"),
+        (Filter::new("a", "<=", float(1.0)), "\"a\" <= 1"),
+        (Filter::new("a", ">=", float(1.0)), "\"a\" >= 1"),
+        (
+            Filter::new("a", "begins with", string("x%")),
+            "\"a\" LIKE 'x\\%%' ESCAPE '\\'",
+        ),
+        (
+            Filter::new("a", "contains", string("it's")),
+            "\"a\" LIKE '%it''s%' ESCAPE '\\'",
+        ),
+        (
+            Filter::new("a", "ends with", string("x_")),
+            "\"a\" LIKE '%x\\_' ESCAPE '\\'",
+        ),
+        (Filter::new("a", "in", ["x", "y"]), "\"a\" IN ('x', 'y')"),
+        (Filter::new("a", "not in", ["x"]), "\"a\" NOT IN ('x')"),
+        (
+            Filter::new("a", "is null", FilterTerm::default()),
+            "\"a\" IS NULL",
+        ),
+        (
+            Filter::new("a", "is not null", FilterTerm::default()),
+            "\"a\" IS NOT NULL",
+        ),
+    ];
+
+    for (filter, condition) in cases {
+        let config = ViewConfig {
+            columns: vec![Some("a".to_string())],
+            filter: vec![filter],
+            ..ViewConfig::default()
+        };
+
+        assert_eq!(
+            model.table_make_view("t", "v", &config).unwrap(),
+            format!(
+                "CREATE TABLE v AS (SELECT \"a\" as \"a\" FROM t WHERE {} ORDER BY rowid)",
+                condition
+            )
+        );
+    }
+
+    let config = ViewConfig {
+        columns: vec![Some("a".to_string())],
+        filter: vec![Filter::new("a", "LIKE", string("x%"))],
+        ..ViewConfig::default()
+    };
+
+    assert!(matches!(
+        model.table_make_view("t", "v", &config),
+        Err(GenericSQLError::UnsupportedOperation(_))
+    ));
+
+    let clickhouse = dialect_model(SqlDialectName::ClickHouse);
+    let config = ViewConfig {
+        filter: vec![Filter::new("a", "begins with", string("x"))],
+        ..config
+    };
+
+    assert!(
+        clickhouse
+            .table_make_view("t", "v", &config)
+            .unwrap()
+            .contains("WHERE \"a\" LIKE 'x%'")
+    );
+}
+
+#[test]
+fn test_table_make_view_filter_op_or() {
+    let model = GenericSQLVirtualServerModel::default();
+    let config = ViewConfig {
+        columns: vec![Some("a".to_string())],
+        group_by: vec!["b".to_string()],
+        filter: vec![
+            Filter::new("a", ">", FilterTerm::Scalar(Scalar::Float(1.0))),
+            Filter::new("b", "is null", FilterTerm::default()),
+        ],
+        filter_op: FilterReducer::Or,
+        ..ViewConfig::default()
+    };
+
+    let sql = model.table_make_view("t", "v", &config).unwrap();
+    assert!(
+        sql.contains("FROM t WHERE (\"a\" > 1 OR \"b\" IS NULL) GROUP BY"),
+        "{}",
+        sql
+    );
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    let sql = sqlite.table_make_view("t", "v", &config).unwrap();
+    assert_eq!(
+        sql.matches("WHERE (\"a\" > 1 OR \"b\" IS NULL)").count(),
+        2,
+        "{}",
+        sql
+    );
+}
+
+#[test]
+fn test_table_make_view_date_filters() {
+    let config = ViewConfig {
+        columns: vec![Some("a".to_string())],
+        filter: vec![
+            Filter::new(
+                "d",
+                ">=",
+                FilterTerm::Scalar(Scalar::Date("2024-01-31".to_string())),
+            ),
+            Filter::new(
+                "t",
+                "<",
+                FilterTerm::Scalar(Scalar::Datetime(1706704496789)),
+            ),
+        ],
+        ..ViewConfig::default()
+    };
+
+    let cases = [
+        (
+            SqlDialectName::DuckDb,
+            "\"d\" >= DATE '2024-01-31' AND \"t\" < TIMESTAMP '2024-01-31 12:34:56.789'",
+        ),
+        (
+            SqlDialectName::Sqlite,
+            "\"d\" >= date('2024-01-31') AND \"t\" < datetime('2024-01-31 12:34:56.789')",
+        ),
+        (
+            SqlDialectName::ClickHouse,
+            "\"d\" >= toDate('2024-01-31') AND \"t\" < toDateTime64('2024-01-31 12:34:56.789', 3, \
+             'UTC')",
+        ),
+    ];
+
+    for (dialect, condition) in cases {
+        let sql = dialect_model(dialect)
+            .table_make_view("t", "v", &config)
+            .unwrap();
+
+        assert!(sql.contains(&format!("WHERE {}", condition)), "{}", sql);
+    }
+
+    let (sql, params) = dialect_model(SqlDialectName::Postgres)
+        .table_make_view_parameterized("t", "v", &config)
+        .unwrap();
+
+    assert!(
+        sql.contains("WHERE \"d\" >= CAST($1 AS DATE) AND \"t\" < CAST($2 AS TIMESTAMP)"),
+        "{}",
+        sql
+    );
+
+    assert_eq!(params, vec![
+        Scalar::String("2024-01-31".to_string()),
+        Scalar::String("2024-01-31 12:34:56.789".to_string()),
+    ]);
+}
+
+#[test]
+fn test_features_filter_ops() {
+    let features = GenericSQLVirtualServerModel::default().features();
+    assert!(features.group_by && features.split_by && features.sort && features.expressions);
+    assert_eq!(features.filter_ops.len(), 6);
+    assert_eq!(features.filter_ops[&ColumnType::String].len(), 13);
+    assert_eq!(features.filter_ops[&ColumnType::Boolean], vec![
+        "==",
+        "!=",
+        "is null",
+        "is not null"
+    ]);
+
+    let resp = crate::proto::GetFeaturesResp::from(features);
+    assert_eq!(resp.default_op(ColumnType::Float), Some("=="));
+}
+
+fn aggregate_view(agg: Aggregate) -> ViewConfig {
+    ViewConfig {
+        columns: vec![Some("x".to_string())],
+        group_by: vec!["g".to_string()],
+        aggregates: HashMap::from([("x".to_string(), agg)]),
+        ..ViewConfig::default()
+    }
+}
+
+#[test]
+fn test_table_make_view_aggregates() {
+    let cases = [
+        (SqlDialectName::DuckDb, "sum", "SUM(\"x\")"),
+        (
+            SqlDialectName::DuckDb,
+            "Distinct Count",
+            "COUNT(DISTINCT \"x\")",
+        ),
+        (SqlDialectName::DuckDb, "avg", "AVG(\"x\")"),
+        (SqlDialectName::DuckDb, "first", "arg_min(\"x\", rowid)"),
+        (
+            SqlDialectName::DuckDb,
+            "last by index",
+            "arg_max(\"x\", rowid)",
+        ),
+        (
+            SqlDialectName::DuckDb,
+            "median",
+            "quantile_disc(\"x\", 0.5)",
+        ),
+        (SqlDialectName::DuckDb, "dominant", "mode(\"x\")"),
+        (SqlDialectName::DuckDb, "kahan_sum", "kahan_sum(\"x\")"),
+        (
+            SqlDialectName::DuckDb,
+            "high minus low",
+            "(MAX(\"x\") - MIN(\"x\"))",
+        ),
+        (
+            SqlDialectName::DuckDb,
+            "unique",
+            "CASE WHEN COUNT(DISTINCT \"x\") = 1 THEN MIN(\"x\") END",
+        ),
+        (
+            SqlDialectName::DuckDb,
+            "pct sum total",
+            "(100 * SUM(\"x\") / NULLIF(SUM(SUM(\"x\")) OVER (PARTITION BY GROUPING_ID(\"g\")), \
+             0))",
+        ),
+        (
+            SqlDialectName::Postgres,
+            "q1",
+            "PERCENTILE_DISC(0.25) WITHIN GROUP (ORDER BY \"x\")",
+        ),
+        (
+            SqlDialectName::Postgres,
+            "join",
+            "STRING_AGG(DISTINCT CAST(\"x\" AS TEXT), ', ' ORDER BY CAST(\"x\" AS TEXT))",
+        ),
+        (
+            SqlDialectName::Sqlite,
+            "var",
+            "CASE WHEN COUNT(\"x\") > 1 THEN (AVG(\"x\" * \"x\") - AVG(\"x\") * AVG(\"x\")) END",
+        ),
+        (
+            SqlDialectName::ClickHouse,
+            "median",
+            "quantileExact(0.5)(\"x\")",
+        ),
+    ];
+
+    for (dialect, name, sql) in cases {
+        let config = aggregate_view(Aggregate::SingleAggregate(name.to_string()));
+        let query = dialect_model(dialect)
+            .table_make_view("t", "v", &config)
+            .unwrap();
+
+        assert!(query.contains(&format!("{} as \"x\"", sql)), "{}", query);
+    }
+}
+
+#[test]
+fn test_table_make_view_multi_aggregates() {
+    let config = aggregate_view(Aggregate::MultiAggregate(
+        "weighted mean".to_string(),
+        vec!["w".to_string()],
+    ));
+
+    let sql = dialect_model(SqlDialectName::Postgres)
+        .table_make_view("t", "v", &config)
+        .unwrap();
+
+    assert!(
+        sql.contains(
+            "(SUM(\"x\" * \"w\") / CAST(NULLIF(SUM(CASE WHEN \"x\" IS NOT NULL THEN \"w\" END), \
+             0) AS DOUBLE PRECISION)) as \"x\""
+        ),
+        "{}",
+        sql
+    );
+
+    let config = aggregate_view(Aggregate::MultiAggregate("max by".to_string(), vec![
+        "w".to_string(),
+    ]));
+
+    let sql = dialect_model(SqlDialectName::ClickHouse)
+        .table_make_view("t", "v", &config)
+        .unwrap();
+
+    assert!(sql.contains("argMax(\"x\", \"w\") as \"x\""), "{}", sql);
+}
+
+#[test]
+fn test_aggregate_type() {
+    let cases = [
+        (Some("sum"), ColumnType::Integer, ColumnType::Integer),
+        (Some("COUNT"), ColumnType::String, ColumnType::Integer),
+        (
+            Some("distinct count"),
+            ColumnType::Date,
+            ColumnType::Integer,
+        ),
+        (Some("avg"), ColumnType::Integer, ColumnType::Float),
+        (
+            Some("pct sum grand total"),
+            ColumnType::Integer,
+            ColumnType::Float,
+        ),
+        (Some("join"), ColumnType::Boolean, ColumnType::String),
+        (Some("first by index"), ColumnType::Date, ColumnType::Date),
+        (Some("kahan_sum"), ColumnType::Integer, ColumnType::Integer),
+        (None, ColumnType::Datetime, ColumnType::Datetime),
+    ];
+
+    for (agg, ty, expected) in cases {
+        let agg = agg.map(Aggregate::from);
+        assert_eq!(
+            GenericSQLVirtualServerModel::aggregate_type(agg.as_ref(), ty),
+            expected,
+            "{:?}",
+            agg
+        );
+    }
+}
+
+#[test]
+fn test_table_make_view_pct_sum_parent() {
+    let config = ViewConfig {
+        group_by: vec!["a".to_string(), "b".to_string()],
+        sort: vec![Sort("x".to_string(), SortDir::Desc)],
+        ..aggregate_view(Aggregate::SingleAggregate("pct sum parent".to_string()))
+    };
+
+    let sql = GenericSQLVirtualServerModel::default()
+        .table_make_view("t", "v", &config)
+        .unwrap();
+
+    assert!(
+        sql.contains(
+            "(100 * SUM(\"x\") / NULLIF(SUM(SUM(\"x\")) OVER (PARTITION BY GROUPING_ID(\"a\", \
+             \"b\"), CASE WHEN GROUPING_ID(\"b\") = 0 THEN \"a\" END), 0)) as \"x\""
+        ),
+        "{}",
+        sql
+    );
+
+    assert!(sql.contains(", SUM(\"x\") DESC, "), "{}", sql);
+
+    let sql = dialect_model(SqlDialectName::Sqlite)
+        .table_make_view("t", "v", &config)
+        .unwrap();
+
+    for partition in ["(PARTITION BY \"a\")", "()", "()"] {
+        assert!(
+            sql.contains(&format!(
+                "(100 * SUM(\"x\") / CAST(NULLIF(SUM(SUM(\"x\")) OVER {}, 0) AS REAL)) as \"x\"",
+                partition
+            )),
+            "{}",
+            sql
+        );
+    }
+}
+
+#[test]
+fn test_table_make_view_aggregate_errors() {
+    let cases = [
+        (SqlDialectName::Sqlite, Aggregate::from("median")),
+        (SqlDialectName::Postgres, Aggregate::from("first")),
+        (SqlDialectName::DuckDb, Aggregate::from("not an aggregate")),
+    ];
+
+    for (dialect, agg) in cases {
+        assert!(matches!(
+            dialect_model(dialect).table_make_view("t", "v", &aggregate_view(agg)),
+            Err(GenericSQLError::UnsupportedOperation(_))
+        ));
+    }
+
+    for agg in [
+        Aggregate::from("weighted mean"),
+        Aggregate::from("sum by w"),
+    ] {
+        assert!(matches!(
+            GenericSQLVirtualServerModel::default().table_make_view("t", "v", &aggregate_view(agg)),
+            Err(GenericSQLError::InvalidConfig(_))
+        ));
+    }
+
+    // Ungrouped views do not aggregate.
+    let config = ViewConfig {
+        group_by: vec![],
+        ..aggregate_view(Aggregate::from("median"))
+    };
+
+    assert!(
+        dialect_model(SqlDialectName::Sqlite)
+            .table_make_view("t", "v", &config)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_features_aggregates() {
+    let names = |model: &GenericSQLVirtualServerModel, ty: ColumnType| {
+        model.features().aggregates[&ty]
+            .iter()
+            .map(|x| match x {
+                AggSpec::Single(name) => name.to_string(),
+                AggSpec::Multiple(name, args) => format!("{} {:?}", name, args),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let duckdb = GenericSQLVirtualServerModel::default();
+    let floats = names(&duckdb, ColumnType::Float);
+    assert_eq!(floats.len(), 28);
+    assert!(floats.contains(&"weighted mean [Float]".to_string()));
+    assert!(!floats.contains(&"join".to_string()));
+    assert_eq!(names(&duckdb, ColumnType::String), vec![
+        "any",
+        "count",
+        "distinct count",
+        "dominant",
+        "first",
+        "last",
+        "last by index",
+        "median",
+        "q1",
+        "q3",
+        "unique",
+        "join",
+        "min by [Float]",
+        "max by [Float]",
+    ]);
+
+    let sqlite = dialect_model(SqlDialectName::Sqlite);
+    assert_eq!(names(&sqlite, ColumnType::Date), vec![
+        "any",
+        "count",
+        "distinct count",
+        "high",
+        "low",
+        "max",
+        "min",
+        "unique",
+    ]);
+}
+
+#[test]
+fn test_split_by_column_aliases() {
+    let model = GenericSQLVirtualServerModel::new(GenericSQLVirtualServerModelArgs::default());
+    let config = ViewConfig {
+        columns: vec![Some("unit_price".to_string())],
+        group_by: vec!["a".to_string()],
+        split_by: vec!["s_1".to_string()],
+        ..ViewConfig::default()
+    };
+
+    let sql = model.table_make_view("t", "v", &config).unwrap();
+    assert!(sql.contains(
+        "REPLACE(REPLACE(CAST(\"s_1\" AS VARCHAR), '\\', '\\\\'), '_', '\\_') AS \"s_1\""
+    ));
+
+    assert!(sql.contains("USING first(\"unit\\_price\") as \"unit\\_price\""));
+
+    let model = dialect_model(SqlDialectName::Sqlite);
+    let sql = model
+        .table_make_view_with_split_values("t", "v", &config, &[vec![Some("x_y".to_string())]])
+        .unwrap();
+
+    assert!(sql.contains("as \"x\\_y_unit\\_price\""));
+    assert_eq!(decode_column_path("x\\_y_unit\\_price"), [
+        "x_y",
+        "unit_price"
+    ]);
+
+    let config = ViewConfig {
+        columns: vec![Some("unit_price".to_string())],
+        ..ViewConfig::default()
+    };
+
+    assert_eq!(
+        model.table_make_view("t", "v", &config).unwrap(),
+        "CREATE TABLE v AS SELECT \"unit_price\" as \"unit_price\" FROM t ORDER BY rowid"
+    );
+}
@@ -16,9 +16,13 @@ use std::pin::Pin;
 use indexmap::IndexMap;
 
 use super::data::VirtualDataSlice;
+use super::diagnostic::ExpressionDiagnostic;
 use super::features::Features;
-use crate::config::{ViewConfig, ViewConfigUpdate};
-use crate::proto::{ColumnType, HostedTable, TableMakePortReq, ViewPort};
+use super::notifier::VirtualServerNotifier;
+use crate::config::{Scalar, ViewConfig, ViewConfigUpdate};
+use crate::proto::{
+    ColumnType, HostedTable, MakeTableData, StatusCode, TableMakePortReq, ViewPort,
+};
 
 #[cfg(feature = "sendable")]
 pub type VirtualServerFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
@@ -64,7 +68,7 @@ pub trait VirtualServerHandler {
     /// The handler may modify the configuration to reflect any adjustments
     /// made during view creation.
     fn table_make_view(
-        &mut self,
+        &self,
         view_id: &str,
         view_id: &str,
         config: &mut ViewConfigUpdate,
@@ -74,6 +78,9 @@ pub trait VirtualServerHandler {
     fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>>;
 
     /// Retrieves data from a view within the specified viewport.
+    ///
+    /// For views with a `split_by`, columns are named by their encoded column
+    /// path, as in [`VirtualServerHandler::view_schema`].
     fn view_get_data(
         &self,
         view_id: &str,
@@ -93,8 +100,13 @@ pub trait VirtualServerHandler {
         Box::pin(async move { Ok(fut.await?.len() as u32) })
     }
 
-    /// Returns the number of rows in a `View`.
-    fn view_size(&self, view_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+    /// Returns the number of rows in a `View`, excluding the rows hidden by
+    /// collapsed groups.
+    fn view_size(
+        &self,
+        view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
         Box::pin(self.table_size(view_id))
     }
 
@@ -109,6 +121,10 @@ pub trait VirtualServerHandler {
     }
 
     /// Returns the schema of a view after applying its configuration.
+    ///
+    /// For views with a `split_by`, each column is named by its column path
+    /// (the `split_by` values followed by the source column name) encoded
+    /// with [`super::encode_column_path`].
     fn view_schema(
         &self,
         view_id: &str,
@@ -117,15 +133,92 @@ pub trait VirtualServerHandler {
         Box::pin(self.table_schema(view_id))
     }
 
-    /// Validates an expression against a table and returns its result type.
+    /// Returns the minimum and maximum values of a column in a view, used by
+    /// e.g. gradient cell styling and bar chart scales. When the view is
+    /// grouped, only the leaf (most-grouped) rows should be considered.
+    ///
+    /// `column_name` is the column's name in
+    /// [`VirtualServerHandler::view_schema`], i.e. for a view with a
+    /// `split_by`, its column path already encoded by
+    /// [`super::encode_column_path`] (e.g. `"East_Sales"`, not
+    /// `"East|Sales"`). Look it up as given, without splitting or encoding it
+    /// again.
+    ///
+    /// Default implementation returns `(Scalar::Null, Scalar::Null)`.
+    fn view_get_min_max(
+        &self,
+        _view_id: &str,
+        _column_name: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        Box::pin(async { Ok((Scalar::Null, Scalar::Null)) })
+    }
+
+    /// Expands the collapsed group at visible row `row_index` of a grouped
+    /// view, returning the number of rows changed.
+    ///
+    /// Default implementation does nothing and returns `0`.
+    fn view_expand(
+        &self,
+        _view_id: &str,
+        _config: &ViewConfig,
+        _row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        Box::pin(async { Ok(0) })
+    }
+
+    /// Collapses the group at visible row `row_index` of a grouped view,
+    /// hiding its descendants, returning the number of rows changed.
+    ///
+    /// Default implementation does nothing and returns `0`.
+    fn view_collapse(
+        &self,
+        _view_id: &str,
+        _config: &ViewConfig,
+        _row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        Box::pin(async { Ok(0) })
+    }
+
+    /// Expands every group of a grouped view to `depth`, collapsing the groups
+    /// below it. `config` is the view's configuration before this change.
+    ///
+    /// Default implementation does nothing.
+    fn view_set_depth(
+        &self,
+        _view_id: &str,
+        _config: &ViewConfig,
+        _depth: u32,
+    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Validates an expression against a table and returns its result type,
+    /// or an [`ExpressionDiagnostic`] locating the error in an invalid
+    /// expression. An `Err` fails the request instead, and should be
+    /// reserved for errors unrelated to the expression, e.g. an unavailable
+    /// backend.
     ///
     /// Default implementation returns `Float` for all expressions.
     fn table_validate_expression(
         &self,
         _table_id: &str,
         _expression: &str,
-    ) -> VirtualServerFuture<'_, Result<ColumnType, Self::Error>> {
-        Box::pin(async { Ok(ColumnType::Float) })
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        Box::pin(async { Ok(Ok(ColumnType::Float)) })
+    }
+
+    /// Returns the [`StatusCode`] reported to the client for `error`, which
+    /// lets it distinguish e.g. an unsupported operation from an unavailable
+    /// backend.
+    ///
+    /// Default implementation returns [`StatusCode::ServerError`].
+    fn error_status_code(_error: &Self::Error) -> StatusCode
+    where
+        Self: Sized,
+    {
+        StatusCode::ServerError
     }
 
     /// Returns the features supported by this handler.
@@ -145,16 +238,93 @@ pub trait VirtualServerHandler {
         Box::pin(async { Ok(0) })
     }
 
+    /// Whether [`VirtualServerHandler::table_update`] and
+    /// [`VirtualServerHandler::table_remove`] are supported, which is checked
+    /// before the table's `index` is looked up for them. Handlers which
+    /// implement either must return `true`.
+    ///
+    /// Default implementation returns `false`.
+    fn supports_table_updates(&self) -> bool {
+        false
+    }
+
+    /// Writes `data` to a table, as `Table::update` does. `index` is the
+    /// table's `index` column, as reported by
+    /// [`VirtualServerHandler::get_hosted_tables`]. `View`s of this table
+    /// are recalculated afterwards.
+    ///
+    /// Returns `None` if the handler does not support updates, in which case
+    /// the client receives an "unsupported operation" error. Default
+    /// implementation returns `None`.
+    fn table_update(
+        &self,
+        _table_id: &str,
+        _index: Option<&str>,
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        None
+    }
+
+    /// Removes the rows of a table whose `index` column values are listed in
+    /// `data`, as `Table::remove` does.
+    ///
+    /// Default implementation returns `None`, as
+    /// [`VirtualServerHandler::table_update`].
+    fn table_remove(
+        &self,
+        _table_id: &str,
+        _index: Option<&str>,
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        None
+    }
+
+    /// Replaces all rows of a table with `data`, as `Table::replace` and
+    /// `Table::clear` do.
+    ///
+    /// Default implementation returns `None`, as
+    /// [`VirtualServerHandler::table_update`].
+    fn table_replace(
+        &self,
+        _table_id: &str,
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        None
+    }
+
+    /// Deletes a table, as `Table::delete` does.
+    ///
+    /// Default implementation returns `None`, as
+    /// [`VirtualServerHandler::table_update`].
+    fn table_delete(
+        &self,
+        _table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        None
+    }
+
+    /// Receives the [`VirtualServerNotifier`] for the
+    /// [`VirtualServer`](super::VirtualServer) which owns this handler, which
+    /// the handler may retain and call
+    /// [`VirtualServerNotifier::notify_table_update`] on when a table's data
+    /// changes, to trigger `on_update` callbacks for its `View`s.
+    ///
+    /// Default implementation ignores the notifier, so `on_update` callbacks
+    /// never fire.
+    fn set_update_notifier(&mut self, _notifier: VirtualServerNotifier) {}
+
     // Unused
 
     /// Creates a new table with the given data.
     ///
-    /// Default implementation panics with "not implemented".
+    /// Returns `None` if the handler does not support creating tables, in
+    /// which case the client receives an "unsupported operation" error.
+    /// Default implementation returns `None`.
     fn make_table(
         &mut self,
         _table_id: &str,
-        _data: &crate::proto::MakeTableData,
-    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
-        Box::pin(async { unimplemented!("make_table not implemented") })
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        None
     }
 }
@@ -15,19 +15,28 @@
 //! This module provides a virtual server that can process Perspective protocol
 //! messages and delegate operations to a custom backend handler.
 
+mod cache;
+mod column_path;
 mod data;
+mod diagnostic;
 mod error;
 mod features;
 mod generic_sql_model;
 mod handler;
+mod notifier;
 mod server;
 
+pub use cache::{CacheOptions, CachingVirtualServerHandler};
+pub use column_path::{decode_column_path, encode_column_path, escape_column_path_element};
 pub use data::{SetVirtualDataColumn, VirtualDataCell, VirtualDataColumn, VirtualDataSlice};
+pub use diagnostic::ExpressionDiagnostic;
 pub use error::{ResultExt, VirtualServerError};
 pub use features::{AggSpec, Features};
 pub use generic_sql_model::{
-    GenericSQLError, GenericSQLResult, GenericSQLVirtualServerModel,
-    GenericSQLVirtualServerModelArgs,
+    AnsiDialect, ClickHouseDialect, DataFusionDialect, DuckDbDialect, GenericSQLError,
+    GenericSQLResult, GenericSQLVirtualServerModel, GenericSQLVirtualServerModelArgs,
+    PostgresDialect, SqlDialect, SqlDialectName, SqliteDialect,
 };
 pub use handler::{VirtualServerFuture, VirtualServerHandler};
-pub use server::VirtualServer;
+pub use notifier::VirtualServerNotifier;
+pub use server::{VirtualServer, VirtualSession};
@@ -0,0 +1,147 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::task::Poll;
+
+use futures::task::AtomicWaker;
+use indexmap::IndexSet;
+
+type OnUpdate = Arc<dyn Fn(&str) + Send + Sync>;
+
+#[derive(Default)]
+struct NotifierState {
+    /// Whether the session which polls this notifier has `View`s, without
+    /// which its updates would never be taken.
+    enabled: AtomicBool,
+    updated_tables: Mutex<IndexSet<String>>,
+    waker: AtomicWaker,
+    sessions: Mutex<Vec<Weak<NotifierState>>>,
+}
+
+impl NotifierState {
+    fn updated_tables(&self) -> MutexGuard<'_, IndexSet<String>> {
+        self.updated_tables
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn sessions(&self) -> MutexGuard<'_, Vec<Weak<NotifierState>>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify_table_update(&self, table_id: &str) {
+        if self.enabled.load(Ordering::Acquire) {
+            self.updated_tables().insert(table_id.to_owned());
+            self.waker.wake();
+        }
+    }
+}
+
+/// A handle a [`VirtualServerHandler`](super::VirtualServerHandler) uses to
+/// signal that the contents of a table have changed.
+///
+/// Each [`VirtualServer`](super::VirtualServer) owns one notifier, which it
+/// passes to its handler via
+/// [`VirtualServerHandler::set_update_notifier`](super::VirtualServerHandler::set_update_notifier).
+/// Notifications are coalesced per table until the next call to
+/// [`VirtualServer::poll`](super::VirtualServer::poll), which generates a
+/// `ViewOnUpdateResp` for every `on_update` subscription on a `View` of an
+/// updated table. Each [`VirtualSession`](super::VirtualSession) has its own
+/// notifier, which receives every notification sent to its server's.
+/// Notifications are dropped by a server or session with no `View`s, as it
+/// has nothing to update.
+#[derive(Clone, Default)]
+pub struct VirtualServerNotifier {
+    state: Arc<NotifierState>,
+
+    /// Called with each notified table before it is recorded, e.g. by a
+    /// [`CachingVirtualServerHandler`](super::CachingVirtualServerHandler)
+    /// to invalidate the table's cached results.
+    on_update: Option<OnUpdate>,
+}
+
+impl VirtualServerNotifier {
+    /// Mark `table_id` as updated, waking the task waiting on
+    /// [`VirtualServerNotifier::notified`] (if any).
+    pub fn notify_table_update(&self, table_id: &str) {
+        if let Some(on_update) = &self.on_update {
+            on_update(table_id);
+        }
+
+        self.state.notify_table_update(table_id);
+        self.state.sessions().retain(|session| {
+            let session = session.upgrade();
+            if let Some(session) = &session {
+                session.notify_table_update(table_id);
+            }
+
+            session.is_some()
+        });
+    }
+
+    /// Resolves when at least one table has been updated since the last
+    /// [`VirtualServer::poll`](super::VirtualServer::poll). Only one task
+    /// should wait on a notifier at a time.
+    pub async fn notified(&self) {
+        futures::future::poll_fn(|cx| {
+            self.state.waker.register(cx.waker());
+            if self.state.updated_tables().is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    /// A new notifier which receives every notification sent to this one,
+    /// until it is dropped.
+    pub(super) fn subscribe(&self) -> VirtualServerNotifier {
+        let session = VirtualServerNotifier::default();
+        self.state.sessions().push(Arc::downgrade(&session.state));
+
+        session
+    }
+
+    /// A notifier which calls `on_update` with each table notified through
+    /// it, and then notifies this one.
+    pub(super) fn intercept(
+        &self,
+        on_update: impl Fn(&str) + Send + Sync + 'static,
+    ) -> VirtualServerNotifier {
+        let parent = self.on_update.clone();
+        VirtualServerNotifier {
+            state: self.state.clone(),
+            on_update: Some(Arc::new(move |table_id| {
+                on_update(table_id);
+                if let Some(parent) = &parent {
+                    parent(table_id);
+                }
+            })),
+        }
+    }
+
+    /// Record updates only while `enabled`, i.e. while the session polling
+    /// this notifier has `View`s, discarding any pending updates otherwise.
+    pub(super) fn set_enabled(&self, enabled: bool) {
+        self.state.enabled.store(enabled, Ordering::Release);
+        if !enabled {
+            self.state.updated_tables().clear();
+        }
+    }
+
+    pub(super) fn take_updated_tables(&self) -> IndexSet<String> {
+        std::mem::take(&mut *self.state.updated_tables())
+    }
+}
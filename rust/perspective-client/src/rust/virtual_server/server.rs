@@ -11,24 +11,36 @@
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-use indexmap::IndexMap;
+use async_lock::RwLock;
+use indexmap::{IndexMap, IndexSet};
 use prost::Message as ProstMessage;
 use prost::bytes::{Bytes, BytesMut};
 
+use super::column_path::{decode_column_path, encode_column_path};
+use super::data::VirtualDataSlice;
 use super::error::VirtualServerError;
 use super::handler::VirtualServerHandler;
-use crate::config::{ViewConfig, ViewConfigUpdate};
+use super::notifier::VirtualServerNotifier;
+use crate::config::{Scalar, ViewConfig, ViewConfigUpdate};
 use crate::proto::response::ClientResp;
 use crate::proto::table_validate_expr_resp::ExprValidationError;
 use crate::proto::{
-    ColumnType, GetFeaturesResp, GetHostedTablesResp, MakeTableResp, Request, Response,
-    ServerError, TableMakePortResp, TableMakeViewResp, TableOnDeleteResp, TableRemoveDeleteResp,
-    TableSchemaResp, TableSizeResp, TableValidateExprResp, ViewColumnPathsResp, ViewDeleteResp,
-    ViewDimensionsResp, ViewExpressionSchemaResp, ViewGetConfigResp, ViewOnDeleteResp,
-    ViewOnUpdateResp, ViewRemoveDeleteResp, ViewRemoveOnUpdateResp, ViewSchemaResp,
-    ViewToColumnsStringResp, ViewToRowsStringResp,
+    ColumnPath, ColumnType, GetFeaturesResp, GetHostedTablesResp, MakeTableResp, Request, Response,
+    ServerError, TableDeleteResp, TableMakePortResp, TableMakeViewResp, TableOnDeleteResp,
+    TableRemoveDeleteResp, TableRemoveResp, TableReplaceResp, TableSchemaResp, TableSizeResp,
+    TableUpdateResp, TableValidateExprResp, ViewCollapseResp, ViewColumnPathsResp, ViewDeleteResp,
+    ViewDimensionsResp, ViewExpandResp, ViewExpressionSchemaResp, ViewGetConfigResp,
+    ViewGetMinMaxResp, ViewOnDeleteResp, ViewOnUpdateResp, ViewPort, ViewRemoveDeleteResp,
+    ViewRemoveOnUpdateResp, ViewSchemaResp, ViewSetDepthResp, ViewToColumnsStringResp,
+    ViewToNdjsonStringResp, ViewToRowsStringResp,
 };
+#[cfg(feature = "virtual-server-arrow")]
+use crate::proto::{ViewToArrowResp, ViewToCsvResp};
+
+#[cfg(test)]
+mod tests;
 
 macro_rules! respond {
     ($msg:ident, $name:ident { $($rest:tt)* }) => {{
@@ -47,37 +59,245 @@ macro_rules! respond {
     }};
 }
 
+/// The column path of the view column `name`, which for views with a
+/// `split_by` is encoded as by [`super::encode_column_path`].
+fn column_path(config: &ViewConfig, name: &str) -> Vec<String> {
+    if config.split_by.is_empty() || name.starts_with("__") {
+        vec![name.to_owned()]
+    } else {
+        decode_column_path(name)
+    }
+}
+
+/// The name in `schema` of the column with the `|`-joined column path
+/// `path`, as the Perspective engine names the columns of a view with a
+/// `split_by`. Column names which themselves contain `|` make `path`
+/// ambiguous, so it is matched against the schema rather than split.
+fn schema_column_name(
+    config: &ViewConfig,
+    schema: &IndexMap<String, ColumnType>,
+    path: &str,
+) -> String {
+    schema
+        .keys()
+        .find(|name| column_path(config, name).join("|") == path)
+        .cloned()
+        .unwrap_or_else(|| encode_column_path(&path.split('|').collect::<Vec<_>>()))
+}
+
+/// The error for a request the handler does not support, e.g. a
+/// [`VirtualServerHandler::table_update`] which returns `None`.
+fn unsupported<E: std::fmt::Debug>(method: &str) -> VirtualServerError<E> {
+    VirtualServerError::UnsupportedRequest(format!("{} is not supported", method))
+}
+
+/// The `View`s created by all sessions of a [`VirtualServer`], and the
+/// `index` of each `Table` written to.
+#[derive(Default)]
+struct ServerState {
+    view_to_table: IndexMap<String, String>,
+    view_configs: IndexMap<String, ViewConfig>,
+    view_schemas: IndexMap<String, IndexMap<String, ColumnType>>,
+    table_indexes: IndexMap<String, Option<String>>,
+
+    /// Held for writing while a `View` is re-created after a table update,
+    /// and for reading by the requests for that `View`.
+    view_locks: IndexMap<String, Arc<RwLock<()>>>,
+}
+
+impl ServerState {
+    fn view_config(&self, view_id: &str) -> Result<ViewConfig, String> {
+        self.view_configs
+            .get(view_id)
+            .cloned()
+            .ok_or_else(|| view_id.to_owned())
+    }
+
+    /// The lock of the `View` `view_id`, or `None` if there is no such
+    /// `View`.
+    fn view_lock(&mut self, view_id: &str) -> Option<Arc<RwLock<()>>> {
+        self.view_to_table.contains_key(view_id).then(|| {
+            self.view_locks
+                .entry(view_id.to_owned())
+                .or_default()
+                .clone()
+        })
+    }
+
+    /// Forgets the `View` `view_id`, returning whether it existed.
+    fn remove_view(&mut self, view_id: &str) -> bool {
+        self.view_configs.shift_remove(view_id);
+        self.view_schemas.shift_remove(view_id);
+        self.view_locks.shift_remove(view_id);
+        self.view_to_table.shift_remove(view_id).is_some()
+    }
+
+    /// Forgets the `Table` `table_id`, returning the IDs of its `View`s.
+    fn remove_table(&mut self, table_id: &str) -> Vec<String> {
+        self.table_indexes.shift_remove(table_id);
+        let view_ids = self
+            .view_to_table
+            .iter()
+            .filter(|(_, x)| *x == table_id)
+            .map(|(view_id, _)| view_id.clone())
+            .collect::<Vec<_>>();
+
+        for view_id in &view_ids {
+            self.remove_view(view_id);
+        }
+
+        view_ids
+    }
+}
+
+/// The `View`s and `on_update` subscriptions of one [`VirtualSession`].
+#[derive(Default)]
+struct SessionState {
+    view_ids: IndexSet<String>,
+    view_on_update_subscriptions: IndexMap<String, Vec<u32>>,
+}
+
+/// The handler and `View` metadata shared by every session of a
+/// [`VirtualServer`].
+struct ServerInner<T: VirtualServerHandler> {
+    handler: RwLock<T>,
+    notifier: VirtualServerNotifier,
+    state: Mutex<ServerState>,
+}
+
 /// A virtual server that processes Perspective protocol messages.
 ///
 /// `VirtualServer` acts as a bridge between the Perspective protocol and a
 /// custom data backend. It handles protocol decoding/encoding and delegates
 /// actual data operations to the provided [`VirtualServerHandler`].
+///
+/// Requests are handled from `&self`, so several requests may be in flight at
+/// once, e.g. to answer a `ViewSchemaReq` while a slow `ViewToColumnsStringReq`
+/// query runs. Each response is tagged with its request's `msg_id`, so
+/// responses may be sent in any order. Requests which create a `Table` wait
+/// for in-flight requests to finish, as [`VirtualServerHandler::make_table`]
+/// takes `&mut self`.
+///
+/// Requests which register a callback (e.g. `ViewOnUpdateReq`) have no
+/// immediate response; instead, when the handler signals a table update via
+/// its [`VirtualServerNotifier`], [`VirtualServer::poll`] generates the
+/// callback messages for every subscribed `View`.
+///
+/// A `VirtualServer` handles requests as a single session which is never
+/// closed. To serve several clients from one handler, create a
+/// [`VirtualSession`] per client with [`VirtualServer::new_session`].
 pub struct VirtualServer<T: VirtualServerHandler> {
-    handler: T,
-    view_to_table: IndexMap<String, String>,
-    view_configs: IndexMap<String, ViewConfig>,
-    view_schemas: IndexMap<String, IndexMap<String, ColumnType>>,
+    session: VirtualSession<T>,
 }
 
 impl<T: VirtualServerHandler> VirtualServer<T> {
     /// Creates a new virtual server with the given handler.
-    pub fn new(handler: T) -> Self {
+    pub fn new(mut handler: T) -> Self {
+        let notifier = VirtualServerNotifier::default();
+        handler.set_update_notifier(notifier.clone());
+        let server = Arc::new(ServerInner {
+            handler: RwLock::new(handler),
+            notifier: notifier.clone(),
+            state: Mutex::default(),
+        });
+
         Self {
-            handler,
-            view_configs: IndexMap::default(),
-            view_to_table: IndexMap::default(),
-            view_schemas: IndexMap::default(),
+            session: VirtualSession::new(server, notifier),
         }
     }
 
-    /// Processes a Perspective protocol request and returns the response.
+    /// Creates a new [`VirtualSession`] which shares this server's handler
+    /// and `Table`s, but tracks its own `View`s and `on_update`
+    /// subscriptions.
+    pub fn new_session(&self) -> VirtualSession<T> {
+        let server = self.session.server.clone();
+        let notifier = server.notifier.subscribe();
+        VirtualSession::new(server, notifier)
+    }
+
+    /// The [`VirtualServerNotifier`] shared with this server's handler, which
+    /// can be awaited via [`VirtualServerNotifier::notified`] to learn when
+    /// [`VirtualServer::poll`] should be called.
+    pub fn notifier(&self) -> VirtualServerNotifier {
+        self.session.notifier()
+    }
+
+    /// Processes a Perspective protocol request and returns the response, if
+    /// the request has one.
     ///
     /// Decodes the incoming protobuf message, dispatches to the appropriate
     /// handler method, and encodes the response.
     pub async fn handle_request(
-        &mut self,
+        &self,
+        bytes: Bytes,
+    ) -> Result<Option<Bytes>, VirtualServerError<T::Error>> {
+        self.session.handle_request(bytes).await
+    }
+
+    /// Recalculates every `View` of the tables updated since the last call,
+    /// as signalled by the handler via [`VirtualServerNotifier`], and returns
+    /// a `ViewOnUpdateResp` for each `on_update` subscription on these
+    /// `View`s.
+    pub async fn poll(&self) -> Result<Vec<Bytes>, VirtualServerError<T::Error>> {
+        self.session.poll().await
+    }
+}
+
+/// One client's connection to a [`VirtualServer`], created by
+/// [`VirtualServer::new_session`].
+///
+/// Sessions share the server's handler and `View` metadata, but each tracks
+/// the `View`s it created and its own `on_update` subscriptions (whose
+/// `msg_id`s are only unique per client). A `Table` update from any session
+/// is delivered to the [`VirtualSession::poll`] of every session.
+///
+/// Call [`VirtualSession::close`] when the client disconnects, which deletes
+/// the `View`s this session created.
+pub struct VirtualSession<T: VirtualServerHandler> {
+    server: Arc<ServerInner<T>>,
+    notifier: VirtualServerNotifier,
+    session: Mutex<SessionState>,
+}
+
+impl<T: VirtualServerHandler> VirtualSession<T> {
+    fn new(server: Arc<ServerInner<T>>, notifier: VirtualServerNotifier) -> Self {
+        Self {
+            server,
+            notifier,
+            session: Mutex::default(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, ServerState> {
+        self.server
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn session(&self) -> MutexGuard<'_, SessionState> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn view_config(&self, view_id: &str) -> Result<ViewConfig, VirtualServerError<T::Error>> {
+        self.state()
+            .view_config(view_id)
+            .map_err(VirtualServerError::UnknownViewId)
+    }
+
+    /// The [`VirtualServerNotifier`] of this session, which can be awaited via
+    /// [`VirtualServerNotifier::notified`] to learn when
+    /// [`VirtualSession::poll`] should be called.
+    pub fn notifier(&self) -> VirtualServerNotifier {
+        self.notifier.clone()
+    }
+
+    /// Processes a Perspective protocol request and returns the response, if
+    /// the request has one, as [`VirtualServer::handle_request`].
+    pub async fn handle_request(
+        &self,
         bytes: Bytes,
-    ) -> Result<Bytes, VirtualServerError<T::Error>> {
+    ) -> Result<Option<Bytes>, VirtualServerError<T::Error>> {
         let msg = Request::decode(bytes).map_err(VirtualServerError::DecodeError)?;
         tracing::debug!(
             "Handling request: entity_id={}, req={:?}",
@@ -89,66 +309,277 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             Ok(resp) => Ok(resp),
             Err(err) => {
                 tracing::error!("{}", err);
-                Ok(respond!(msg, ServerError {
+                Ok(Some(respond!(msg, ServerError {
                     message: err.to_string(),
-                    status_code: 0
-                }))
+                    status_code: err.status_code(T::error_status_code) as i32
+                })))
             },
         }
     }
 
+    /// Recalculates this session's `View`s of the tables updated since the
+    /// last call, and returns a `ViewOnUpdateResp` for each of this
+    /// session's `on_update` subscriptions on these `View`s. A `View` which
+    /// fails to recalculate is logged and skipped, so it does not prevent
+    /// the notifications of the others.
+    pub async fn poll(&self) -> Result<Vec<Bytes>, VirtualServerError<T::Error>> {
+        let updated_tables = self.notifier.take_updated_tables();
+        let view_ids = {
+            let session = self.session();
+            self.state()
+                .view_to_table
+                .iter()
+                .filter(|(view_id, table_id)| {
+                    session.view_ids.contains(*view_id) && updated_tables.contains(*table_id)
+                })
+                .map(|(view_id, table_id)| (view_id.clone(), table_id.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut resps = vec![];
+        for (view_id, table_id) in view_ids {
+            // The `View` may have been deleted since the update. Requests for
+            // it wait until it has been re-created, rather than querying it
+            // while it does not exist.
+            let Some(view_lock) = self.state().view_lock(&view_id) else {
+                continue;
+            };
+
+            let view_guard = view_lock.write().await;
+            let Ok(config) = self.view_config(&view_id) else {
+                continue;
+            };
+
+            let mut config: ViewConfigUpdate = config.into();
+            if let Err(err) = self.recreate_view(&table_id, &view_id, &mut config).await {
+                tracing::error!("Failed to update view {}: {:?}", view_id, err);
+                continue;
+            }
+
+            {
+                let mut state = self.state();
+                state.view_configs.insert(view_id.clone(), config.into());
+                state.view_schemas.shift_remove(&view_id);
+            }
+
+            drop(view_guard);
+            let msg_ids = self
+                .session()
+                .view_on_update_subscriptions
+                .get(&view_id)
+                .cloned()
+                .unwrap_or_default();
+
+            for msg_id in msg_ids {
+                let mut resp = BytesMut::new();
+                Response {
+                    msg_id,
+                    entity_id: view_id.clone(),
+                    client_resp: Some(ClientResp::ViewOnUpdateResp(ViewOnUpdateResp {
+                        delta: None,
+                        port_id: 0,
+                    })),
+                }
+                .encode(&mut resp)
+                .map_err(VirtualServerError::EncodeError)?;
+
+                resps.push(resp.freeze());
+            }
+        }
+
+        Ok(resps)
+    }
+
+    /// Re-creates the `View` `view_id` of `table_id` after the table was
+    /// updated.
+    async fn recreate_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> Result<(), T::Error> {
+        let handler = self.server.handler.read().await;
+        handler.view_delete(view_id).await?;
+        handler.table_make_view(table_id, view_id, config).await?;
+        Ok(())
+    }
+
+    /// Closes this session, deleting every `View` it created (and has not
+    /// deleted) via [`VirtualServerHandler::view_delete`]. Every `View` is
+    /// deleted even if one fails, in which case the first error is returned.
+    pub async fn close(self) -> Result<(), VirtualServerError<T::Error>> {
+        let view_ids = std::mem::take(&mut self.session().view_ids);
+        let handler = self.server.handler.read().await;
+        let mut result = Ok(());
+        for view_id in view_ids {
+            // `View`s of a deleted `Table` were deleted with it.
+            if !self.state().remove_view(&view_id) {
+                continue;
+            }
+
+            if let Err(err) = handler.view_delete(&view_id).await {
+                tracing::error!("Failed to delete view {}: {:?}", view_id, err);
+                if result.is_ok() {
+                    result = Err(err.into());
+                }
+            }
+        }
+
+        result
+    }
+
     async fn get_cached_view_schema(
-        &mut self,
+        &self,
+        handler: &T,
         entity_id: &str,
         to_psp_format: bool,
     ) -> Result<IndexMap<String, ColumnType>, VirtualServerError<T::Error>> {
-        if !self.view_schemas.contains_key(entity_id) {
-            self.view_schemas.insert(
-                entity_id.to_string(),
-                self.handler
-                    .view_schema(entity_id, self.view_configs.get(entity_id).unwrap())
-                    .await?,
-            );
-        }
+        let config = self.view_config(entity_id)?;
+        let cached = self.state().view_schemas.get(entity_id).cloned();
+        let schema = match cached {
+            Some(schema) => schema,
+            None => {
+                let schema = handler.view_schema(entity_id, &config).await?;
+                self.state()
+                    .view_schemas
+                    .insert(entity_id.to_string(), schema.clone());
+
+                schema
+            },
+        };
 
         if to_psp_format {
-            Ok(self
-                .view_schemas
-                .get(entity_id)
-                .unwrap()
+            Ok(schema
                 .iter()
-                .map(|(k, v)| {
-                    (
-                        k.split("_").collect::<Vec<_>>().last().unwrap().to_string(),
-                        *v,
-                    )
-                })
+                .map(|(k, v)| (column_path(&config, k).pop().unwrap_or_default(), *v))
                 .collect())
         } else {
-            Ok(self.view_schemas.get(entity_id).cloned().unwrap())
+            Ok(schema)
+        }
+    }
+
+    /// The `index` of `table_id`, looked up via
+    /// [`VirtualServerHandler::get_hosted_tables`] on the first write to it.
+    async fn get_table_index(
+        &self,
+        handler: &T,
+        table_id: &str,
+    ) -> Result<Option<String>, VirtualServerError<T::Error>> {
+        if let Some(index) = self.state().table_indexes.get(table_id) {
+            return Ok(index.clone());
+        }
+
+        let index = handler
+            .get_hosted_tables()
+            .await?
+            .into_iter()
+            .find(|table| table.entity_id == table_id)
+            .and_then(|table| table.index);
+
+        self.state()
+            .table_indexes
+            .insert(table_id.to_string(), index.clone());
+
+        Ok(index)
+    }
+
+    async fn get_view_data(
+        &self,
+        handler: &T,
+        entity_id: &str,
+        viewport: &ViewPort,
+    ) -> Result<VirtualDataSlice, VirtualServerError<T::Error>> {
+        let schema = self
+            .get_cached_view_schema(handler, entity_id, false)
+            .await?;
+        let config = self.view_config(entity_id)?;
+        let mut data = handler
+            .view_get_data(entity_id, &config, &schema, viewport)
+            .await?;
+
+        if !config.split_by.is_empty() {
+            data.decode_column_paths();
         }
+
+        Ok(data)
     }
 
     async fn internal_handle_request(
-        &mut self,
+        &self,
+        mut msg: Request,
+    ) -> Result<Option<Bytes>, VirtualServerError<T::Error>> {
+        use crate::proto::request::ClientReq::*;
+        let resp = match msg.client_req.take().unwrap() {
+            TableMakeViewReq(req) => {
+                let mut config: ViewConfigUpdate = req.config.clone().unwrap_or_default().into();
+                let view_id = self
+                    .server
+                    .handler
+                    .read()
+                    .await
+                    .table_make_view(msg.entity_id.as_str(), req.view_id.as_str(), &mut config)
+                    .await?;
+
+                let mut state = self.state();
+                state
+                    .view_to_table
+                    .insert(req.view_id.clone(), msg.entity_id.clone());
+                state
+                    .view_configs
+                    .insert(req.view_id.clone(), config.into());
+                self.session().view_ids.insert(req.view_id.clone());
+                self.notifier.set_enabled(true);
+                respond!(msg, TableMakeViewResp { view_id })
+            },
+            MakeTableReq(req) => {
+                self.server
+                    .handler
+                    .write()
+                    .await
+                    .make_table(&msg.entity_id, &req.data.unwrap_or_default())
+                    .ok_or_else(|| unsupported("Client::table"))?
+                    .await?;
+
+                self.state().table_indexes.shift_remove(&msg.entity_id);
+                respond!(msg, MakeTableResp {})
+            },
+            req => {
+                let view_lock = self.state().view_lock(&msg.entity_id);
+                let _view_guard = match &view_lock {
+                    Some(view_lock) => Some(view_lock.read().await),
+                    None => None,
+                };
+
+                let handler = self.server.handler.read().await;
+                return self.handle_read_request(&handler, msg, req).await;
+            },
+        };
+
+        Ok(Some(resp))
+    }
+
+    /// Handles the requests which only need shared access to the handler.
+    async fn handle_read_request(
+        &self,
+        handler: &T,
         msg: Request,
-    ) -> Result<Bytes, VirtualServerError<T::Error>> {
+        req: crate::proto::request::ClientReq,
+    ) -> Result<Option<Bytes>, VirtualServerError<T::Error>> {
         use crate::proto::request::ClientReq::*;
-        let resp = match msg.client_req.unwrap() {
+        let resp = match req {
             GetFeaturesReq(_) => {
-                let features = self.handler.get_features().await?;
+                let features = handler.get_features().await?;
                 respond!(msg, GetFeaturesResp { ..features.into() })
             },
             GetHostedTablesReq(_) => {
                 respond!(msg, GetHostedTablesResp {
-                    table_infos: self.handler.get_hosted_tables().await?
+                    table_infos: handler.get_hosted_tables().await?
                 })
             },
             TableSchemaReq(_) => {
                 respond!(msg, TableSchemaResp {
                     schema: Some(crate::proto::Schema {
-                        schema: self
-                            .handler
+                        schema: handler
                             .table_schema(msg.entity_id.as_str())
                             .await?
                             .iter()
@@ -162,27 +593,12 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             },
             TableMakePortReq(req) => {
                 respond!(msg, TableMakePortResp {
-                    port_id: self.handler.table_make_port(&req).await?
+                    port_id: handler.table_make_port(&req).await?
                 })
             },
-            TableMakeViewReq(req) => {
-                self.view_to_table
-                    .insert(req.view_id.clone(), msg.entity_id.clone());
-
-                let mut config: ViewConfigUpdate = req.config.clone().unwrap_or_default().into();
-                let bytes = respond!(msg, TableMakeViewResp {
-                    view_id: self
-                        .handler
-                        .table_make_view(msg.entity_id.as_str(), req.view_id.as_str(), &mut config)
-                        .await?
-                });
-
-                self.view_configs.insert(req.view_id.clone(), config.into());
-                bytes
-            },
             TableSizeReq(_) => {
                 respond!(msg, TableSizeResp {
-                    size: self.handler.table_size(msg.entity_id.as_str()).await?
+                    size: handler.table_size(msg.entity_id.as_str()).await?
                 })
             },
             TableValidateExprReq(req) => {
@@ -191,20 +607,15 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
                 let mut errors = HashMap::<String, ExprValidationError>::default();
                 for (name, ex) in req.column_to_expr.iter() {
                     let _ = expression_alias.insert(name.clone(), ex.clone());
-                    match self
-                        .handler
+                    match handler
                         .table_validate_expression(&msg.entity_id, ex.as_str())
-                        .await
+                        .await?
                     {
                         Ok(dtype) => {
                             let _ = expression_schema.insert(name.clone(), dtype as i32);
                         },
-                        Err(e) => {
-                            let _ = errors.insert(name.clone(), ExprValidationError {
-                                error_message: format!("{}", e),
-                                line: 0,
-                                column: 0,
-                            });
+                        Err(diagnostic) => {
+                            let _ = errors.insert(name.clone(), diagnostic.into());
                         },
                     }
                 }
@@ -218,7 +629,7 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             ViewSchemaReq(_) => {
                 respond!(msg, ViewSchemaResp {
                     schema: self
-                        .get_cached_view_schema(&msg.entity_id, true)
+                        .get_cached_view_schema(handler, &msg.entity_id, true)
                         .await?
                         .into_iter()
                         .map(|(x, y)| (x.to_string(), y as i32))
@@ -228,15 +639,17 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             ViewDimensionsReq(_) => {
                 let view_id = &msg.entity_id;
                 let table_id = self
+                    .state()
                     .view_to_table
                     .get(view_id)
+                    .cloned()
                     .ok_or_else(|| VirtualServerError::UnknownViewId(view_id.to_string()))?;
 
-                let num_table_rows = self.handler.table_size(table_id).await?;
-                let num_table_columns = self.handler.table_column_size(table_id).await? as u32;
-                let config = self.view_configs.get(view_id).unwrap();
-                let num_view_columns = self.handler.view_column_size(view_id, config).await? as u32;
-                let num_view_rows = self.handler.view_size(view_id).await?;
+                let config = self.view_config(view_id)?;
+                let num_table_rows = handler.table_size(&table_id).await?;
+                let num_table_columns = handler.table_column_size(&table_id).await? as u32;
+                let num_view_columns = handler.view_column_size(view_id, &config).await? as u32;
+                let num_view_rows = handler.view_size(view_id, &config).await?;
                 let resp = ViewDimensionsResp {
                     num_table_columns,
                     num_table_rows,
@@ -248,34 +661,31 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             },
             ViewGetConfigReq(_) => {
                 respond!(msg, ViewGetConfigResp {
-                    config: Some(
-                        ViewConfigUpdate::from(
-                            self.view_configs.get(&msg.entity_id).unwrap().clone()
-                        )
-                        .into()
-                    )
+                    config: Some(ViewConfigUpdate::from(self.view_config(&msg.entity_id)?).into())
                 })
             },
             ViewExpressionSchemaReq(_) => {
                 let mut schema = HashMap::<String, i32>::default();
-                let table_id = self.view_to_table.get(&msg.entity_id);
-                for (name, ex) in self
-                    .view_configs
+                let table_id = self
+                    .state()
+                    .view_to_table
                     .get(&msg.entity_id)
-                    .unwrap()
-                    .expressions
-                    .iter()
-                {
-                    match self
-                        .handler
-                        .table_validate_expression(table_id.unwrap(), ex.as_str())
-                        .await
+                    .cloned()
+                    .ok_or_else(|| VirtualServerError::UnknownViewId(msg.entity_id.clone()))?;
+
+                for (name, ex) in self.view_config(&msg.entity_id)?.expressions.iter() {
+                    match handler
+                        .table_validate_expression(&table_id, ex.as_str())
+                        .await?
                     {
                         Ok(dtype) => {
                             let _ = schema.insert(name.clone(), dtype as i32);
                         },
-                        Err(_e) => {
-                            // TODO: handle error
+                        Err(diagnostic) => {
+                            return Err(VirtualServerError::InvalidExpression(
+                                name.clone(),
+                                diagnostic,
+                            ));
                         },
                     }
                 }
@@ -283,29 +693,90 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
                 let resp = ViewExpressionSchemaResp { schema };
                 respond!(msg, ViewExpressionSchemaResp { ..resp })
             },
-            ViewColumnPathsReq(_) => {
+            ViewColumnPathsReq(req) => {
+                let schema = self
+                    .get_cached_view_schema(handler, &msg.entity_id, false)
+                    .await?;
+                let config = self.view_config(&msg.entity_id)?;
+                let start_col = req.start_col.unwrap_or(0) as usize;
+                let end_col = req.end_col.map(|x| x as usize).unwrap_or(usize::MAX);
+                let structured_paths: Vec<_> = schema
+                    .keys()
+                    .filter(|x| !x.starts_with("__"))
+                    .map(|x| column_path(&config, x))
+                    .take(end_col)
+                    .skip(start_col)
+                    .collect();
+
                 respond!(msg, ViewColumnPathsResp {
-                    paths: self
-                        .handler
-                        .view_schema(
-                            msg.entity_id.as_str(),
-                            self.view_configs.get(&msg.entity_id).unwrap()
-                        )
+                    paths: structured_paths.iter().map(|x| x.join("|")).collect(),
+                    structured_paths: structured_paths
+                        .into_iter()
+                        .map(|path| ColumnPath { path })
+                        .collect(),
+                })
+            },
+            ViewGetMinMaxReq(req) => {
+                let config = self.view_config(&msg.entity_id)?;
+                let column_name = if config.split_by.is_empty() {
+                    req.column_name
+                } else {
+                    let schema = self
+                        .get_cached_view_schema(handler, &msg.entity_id, false)
+                        .await?;
+
+                    schema_column_name(&config, &schema, &req.column_name)
+                };
+
+                let (min, max) = handler
+                    .view_get_min_max(&msg.entity_id, &column_name, &config)
+                    .await?;
+
+                let to_json = |x: &Scalar| {
+                    serde_json::to_string(x)
+                        .map_err(|e| VirtualServerError::InvalidJSON(std::sync::Arc::new(e)))
+                };
+
+                respond!(msg, ViewGetMinMaxResp {
+                    min: to_json(&min)?,
+                    max: to_json(&max)?,
+                })
+            },
+            ViewExpandReq(req) => {
+                let config = self.view_config(&msg.entity_id)?;
+
+                respond!(msg, ViewExpandResp {
+                    num_changed: handler
+                        .view_expand(&msg.entity_id, &config, req.row_index)
+                        .await?
+                })
+            },
+            ViewCollapseReq(req) => {
+                let config = self.view_config(&msg.entity_id)?;
+
+                respond!(msg, ViewCollapseResp {
+                    num_changed: handler
+                        .view_collapse(&msg.entity_id, &config, req.row_index)
                         .await?
-                        .keys()
-                        .cloned()
-                        .collect()
                 })
             },
+            ViewSetDepthReq(req) => {
+                let config = self.view_config(&msg.entity_id)?;
+                handler
+                    .view_set_depth(&msg.entity_id, &config, req.depth)
+                    .await?;
+
+                if let Some(config) = self.state().view_configs.get_mut(&msg.entity_id) {
+                    config.group_by_depth = Some(req.depth);
+                }
+
+                respond!(msg, ViewSetDepthResp {})
+            },
             ViewToRowsStringReq(view_to_rows_string_req) => {
                 let viewport = view_to_rows_string_req.viewport.unwrap();
-                let schema = self.get_cached_view_schema(&msg.entity_id, false).await?;
-                let config = self.view_configs.get(&msg.entity_id).unwrap();
                 let cols = self
-                    .handler
-                    .view_get_data(msg.entity_id.as_str(), config, &schema, &viewport)
+                    .get_view_data(handler, &msg.entity_id, &viewport)
                     .await?;
-
                 let rows = cols.to_rows();
                 let json_string = serde_json::to_string(&rows)
                     .map_err(|e| VirtualServerError::InvalidJSON(std::sync::Arc::new(e)))?;
@@ -314,47 +785,157 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
             },
             ViewToColumnsStringReq(view_to_columns_string_req) => {
                 let viewport = view_to_columns_string_req.viewport.unwrap();
-                let schema = self.get_cached_view_schema(&msg.entity_id, false).await?;
-                let config = self.view_configs.get(&msg.entity_id).unwrap();
                 let cols = self
-                    .handler
-                    .view_get_data(msg.entity_id.as_str(), config, &schema, &viewport)
+                    .get_view_data(handler, &msg.entity_id, &viewport)
                     .await?;
-
                 let json_string = serde_json::to_string(&cols)
                     .map_err(|e| VirtualServerError::InvalidJSON(std::sync::Arc::new(e)))?;
 
                 respond!(msg, ViewToColumnsStringResp { json_string })
             },
+            ViewToNdjsonStringReq(view_to_ndjson_string_req) => {
+                let viewport = view_to_ndjson_string_req.viewport.unwrap();
+                let cols = self
+                    .get_view_data(handler, &msg.entity_id, &viewport)
+                    .await?;
+                let ndjson_string = cols
+                    .to_ndjson()
+                    .map_err(|e| VirtualServerError::InvalidJSON(std::sync::Arc::new(e)))?;
+
+                respond!(msg, ViewToNdjsonStringResp { ndjson_string })
+            },
+            #[cfg(feature = "virtual-server-arrow")]
+            ViewToCsvReq(view_to_csv_req) => {
+                let viewport = view_to_csv_req.viewport.unwrap();
+                let cols = self
+                    .get_view_data(handler, &msg.entity_id, &viewport)
+                    .await?;
+                let csv = cols
+                    .to_csv()
+                    .map_err(|e| VirtualServerError::ArrowError(std::sync::Arc::new(e)))?;
+
+                respond!(msg, ViewToCsvResp { csv })
+            },
+            #[cfg(feature = "virtual-server-arrow")]
+            ViewToArrowReq(view_to_arrow_req) => {
+                let viewport = view_to_arrow_req.viewport.unwrap();
+                let cols = self
+                    .get_view_data(handler, &msg.entity_id, &viewport)
+                    .await?;
+                let arrow = cols
+                    .to_arrow_ipc(view_to_arrow_req.compression.as_deref())
+                    .map_err(|e| VirtualServerError::ArrowError(std::sync::Arc::new(e)))?;
+
+                respond!(msg, ViewToArrowResp { arrow })
+            },
             ViewDeleteReq(_) => {
-                self.handler.view_delete(msg.entity_id.as_str()).await?;
-                self.view_to_table.shift_remove(&msg.entity_id);
-                self.view_configs.shift_remove(&msg.entity_id);
+                handler.view_delete(msg.entity_id.as_str()).await?;
+                self.state().remove_view(&msg.entity_id);
+                let mut session = self.session();
+                session.view_ids.shift_remove(&msg.entity_id);
+                session
+                    .view_on_update_subscriptions
+                    .shift_remove(&msg.entity_id);
+                self.notifier.set_enabled(!session.view_ids.is_empty());
                 respond!(msg, ViewDeleteResp {})
             },
-            MakeTableReq(req) => {
-                self.handler
-                    .make_table(&msg.entity_id, req.data.as_ref().unwrap())
+            TableUpdateReq(req) => {
+                if !handler.supports_table_updates() {
+                    return Err(unsupported("Table::update"));
+                }
+
+                let index = self.get_table_index(handler, &msg.entity_id).await?;
+                handler
+                    .table_update(
+                        &msg.entity_id,
+                        index.as_deref(),
+                        &req.data.unwrap_or_default(),
+                    )
+                    .ok_or_else(|| unsupported("Table::update"))?
                     .await?;
-                respond!(msg, MakeTableResp {})
+
+                self.server.notifier.notify_table_update(&msg.entity_id);
+                respond!(msg, TableUpdateResp {})
+            },
+            TableRemoveReq(req) => {
+                if !handler.supports_table_updates() {
+                    return Err(unsupported("Table::remove"));
+                }
+
+                let index = self.get_table_index(handler, &msg.entity_id).await?;
+                handler
+                    .table_remove(
+                        &msg.entity_id,
+                        index.as_deref(),
+                        &req.data.unwrap_or_default(),
+                    )
+                    .ok_or_else(|| unsupported("Table::remove"))?
+                    .await?;
+
+                self.server.notifier.notify_table_update(&msg.entity_id);
+                respond!(msg, TableRemoveResp {})
+            },
+            TableReplaceReq(req) => {
+                handler
+                    .table_replace(&msg.entity_id, &req.data.unwrap_or_default())
+                    .ok_or_else(|| unsupported("Table::replace"))?
+                    .await?;
+
+                self.server.notifier.notify_table_update(&msg.entity_id);
+                respond!(msg, TableReplaceResp {})
+            },
+            TableDeleteReq(_) => {
+                handler
+                    .table_delete(&msg.entity_id)
+                    .ok_or_else(|| unsupported("Table::delete"))?
+                    .await?;
+
+                let view_ids = self.state().remove_table(&msg.entity_id);
+                for view_id in view_ids {
+                    if let Err(err) = handler.view_delete(&view_id).await {
+                        tracing::error!("Failed to delete view {}: {:?}", view_id, err);
+                    }
+
+                    let mut session = self.session();
+                    session.view_ids.shift_remove(&view_id);
+                    session.view_on_update_subscriptions.shift_remove(&view_id);
+                }
+
+                self.notifier
+                    .set_enabled(!self.session().view_ids.is_empty());
+
+                respond!(msg, TableDeleteResp {})
+            },
+
+            // Subscriptions are answered from `VirtualSession::poll`.
+            ViewOnUpdateReq(_) => {
+                self.session()
+                    .view_on_update_subscriptions
+                    .entry(msg.entity_id.clone())
+                    .or_default()
+                    .push(msg.msg_id);
+
+                return Ok(None);
+            },
+            ViewRemoveOnUpdateReq(req) => {
+                if let Some(subs) = self
+                    .session()
+                    .view_on_update_subscriptions
+                    .get_mut(&msg.entity_id)
+                {
+                    subs.retain(|msg_id| *msg_id != req.id);
+                }
+
+                respond!(msg, ViewRemoveOnUpdateResp {})
             },
 
             // Stub implementations for callback/update requests that VirtualServer doesn't support
             TableOnDeleteReq(_) => {
                 respond!(msg, TableOnDeleteResp {})
             },
-            ViewOnUpdateReq(_) => {
-                respond!(msg, ViewOnUpdateResp {
-                    delta: None,
-                    port_id: 0
-                })
-            },
             ViewOnDeleteReq(_) => {
                 respond!(msg, ViewOnDeleteResp {})
             },
-            ViewRemoveOnUpdateReq(_) => {
-                respond!(msg, ViewRemoveOnUpdateResp {})
-            },
             TableRemoveDeleteReq(_) => {
                 respond!(msg, TableRemoveDeleteResp {})
             },
@@ -364,13 +945,10 @@ impl<T: VirtualServerHandler> VirtualServer<T> {
 
             x => {
                 // Return an error response instead of empty bytes
-                return Err(VirtualServerError::Other(format!(
-                    "Unhandled request: {:?}",
-                    x
-                )));
+                return Err(VirtualServerError::UnsupportedRequest(format!("{:?}", x)));
             },
         };
 
-        Ok(resp)
+        Ok(Some(resp))
     }
 }
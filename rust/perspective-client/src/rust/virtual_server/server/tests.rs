@@ -0,0 +1,1023 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::sync::{Arc, Mutex};
+
+use futures::channel::oneshot;
+
+use super::*;
+use crate::proto::request::ClientReq;
+use crate::proto::{
+    HostedTable, MakeTableData, MakeTableReq, ServerSystemInfoReq, StatusCode, TableDeleteReq,
+    TableMakeViewReq, TableReplaceReq, TableSizeReq, TableUpdateReq, TableValidateExprReq,
+    ViewColumnPathsReq, ViewDeleteReq, ViewExpressionSchemaReq, ViewGetMinMaxReq, ViewOnUpdateReq,
+    ViewRemoveOnUpdateReq, ViewSchemaReq, ViewToColumnsStringReq,
+};
+use crate::virtual_server::{ExpressionDiagnostic, VirtualServerFuture, encode_column_path};
+
+#[derive(Debug, thiserror::Error)]
+#[error("test error")]
+struct TestError;
+
+#[derive(Clone, Default)]
+struct TestHandler {
+    notifier: Arc<Mutex<Option<VirtualServerNotifier>>>,
+    views_created: Arc<Mutex<Vec<String>>>,
+    views_deleted: Arc<Mutex<Vec<String>>>,
+    broken_views: Arc<Mutex<Vec<String>>>,
+    min_max_columns: Arc<Mutex<Vec<String>>>,
+    update_indices: Arc<Mutex<Vec<Option<String>>>>,
+    hosted_table_lookups: Arc<Mutex<usize>>,
+    read_only: bool,
+    data_gate: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
+}
+
+impl TestHandler {
+    fn notify_table_update(&self, table_id: &str) {
+        self.notifier
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .notify_table_update(table_id);
+    }
+}
+
+impl VirtualServerHandler for TestHandler {
+    type Error = TestError;
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        *self.hosted_table_lookups.lock().unwrap() += 1;
+        Box::pin(async {
+            Ok(vec![HostedTable {
+                entity_id: "table".to_string(),
+                index: Some("id".to_string()),
+                limit: None,
+            }])
+        })
+    }
+
+    fn table_schema(
+        &self,
+        _table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        Box::pin(async { Ok(IndexMap::default()) })
+    }
+
+    fn table_size(&self, table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let offline = table_id == "offline";
+        Box::pin(async move { if offline { Err(TestError) } else { Ok(0) } })
+    }
+
+    fn table_validate_expression(
+        &self,
+        _table_id: &str,
+        expression: &str,
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        let result = match expression.find("bad") {
+            Some(start) => Err(ExpressionDiagnostic::at(
+                "bad token",
+                expression,
+                start..start + 3,
+            )),
+            None => Ok(ColumnType::Integer),
+        };
+
+        Box::pin(async { Ok(result) })
+    }
+
+    fn error_status_code(_error: &Self::Error) -> StatusCode {
+        StatusCode::BackendUnavailable
+    }
+
+    fn view_schema(
+        &self,
+        _view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let schema = if config.split_by.is_empty() {
+            IndexMap::default()
+        } else {
+            IndexMap::from_iter([
+                (encode_column_path(&["East", "Sales"]), ColumnType::Float),
+                (
+                    encode_column_path(&["North_East|1", "Unit_Price"]),
+                    ColumnType::Float,
+                ),
+            ])
+        };
+
+        Box::pin(async { Ok(schema) })
+    }
+
+    fn view_get_min_max(
+        &self,
+        _view_id: &str,
+        column_name: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        self.min_max_columns
+            .lock()
+            .unwrap()
+            .push(column_name.to_string());
+
+        Box::pin(async { Ok((Scalar::Null, Scalar::Null)) })
+    }
+
+    fn table_make_view(
+        &self,
+        _table_id: &str,
+        view_id: &str,
+        _config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        self.views_created.lock().unwrap().push(view_id.to_string());
+        let broken = self
+            .broken_views
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|x| x == view_id);
+        let view_id = view_id.to_string();
+        Box::pin(async move { if broken { Err(TestError) } else { Ok(view_id) } })
+    }
+
+    fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        self.views_deleted.lock().unwrap().push(view_id.to_string());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn view_get_data(
+        &self,
+        _view_id: &str,
+        config: &ViewConfig,
+        _schema: &IndexMap<String, ColumnType>,
+        _viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let config = config.clone();
+        let gate = self.data_gate.lock().unwrap().take();
+        Box::pin(async {
+            if let Some(gate) = gate {
+                gate.await.unwrap();
+            }
+
+            Ok(VirtualDataSlice::new(config))
+        })
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        !self.read_only
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        _data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        assert_eq!(table_id, "table");
+        self.update_indices
+            .lock()
+            .unwrap()
+            .push(index.map(|x| x.to_string()));
+
+        Some(Box::pin(async { Ok(()) }))
+    }
+
+    fn table_delete(
+        &self,
+        _table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        Some(Box::pin(async { Ok(()) }))
+    }
+
+    fn set_update_notifier(&mut self, notifier: VirtualServerNotifier) {
+        *self.notifier.lock().unwrap() = Some(notifier);
+    }
+}
+
+fn request(msg_id: u32, entity_id: &str, client_req: ClientReq) -> Bytes {
+    let mut bytes = BytesMut::new();
+    Request {
+        msg_id,
+        entity_id: entity_id.to_string(),
+        client_req: Some(client_req),
+    }
+    .encode(&mut bytes)
+    .unwrap();
+
+    bytes.freeze()
+}
+
+fn decode(bytes: &Bytes) -> Response {
+    Response::decode(bytes.clone()).unwrap()
+}
+
+fn server_with_view() -> (VirtualServer<TestHandler>, TestHandler) {
+    let handler = TestHandler::default();
+    let server = VirtualServer::new(handler.clone());
+    futures::executor::block_on(server.handle_request(request(
+        1,
+        "table",
+        ClientReq::TableMakeViewReq(TableMakeViewReq {
+            view_id: "view".to_string(),
+            config: None,
+        }),
+    )))
+    .unwrap();
+
+    (server, handler)
+}
+
+#[test]
+fn test_on_update_has_no_immediate_response() {
+    let (server, _) = server_with_view();
+    let resp = futures::executor::block_on(server.handle_request(request(
+        2,
+        "view",
+        ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+    )))
+    .unwrap();
+
+    assert!(resp.is_none());
+}
+
+#[test]
+fn test_on_update_fires_for_updated_table() {
+    let (server, handler) = server_with_view();
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+            ))
+            .await
+            .unwrap();
+
+        handler.notify_table_update("other_table");
+        assert!(server.poll().await.unwrap().is_empty());
+
+        handler.notify_table_update("table");
+        server.notifier().notified().await;
+        let resps = server.poll().await.unwrap();
+        assert_eq!(resps.len(), 1);
+        let resp = decode(&resps[0]);
+        assert_eq!(resp.msg_id, 2);
+        assert_eq!(resp.entity_id, "view");
+        assert!(matches!(
+            resp.client_resp,
+            Some(ClientResp::ViewOnUpdateResp(_))
+        ));
+
+        assert!(server.poll().await.unwrap().is_empty());
+    });
+
+    assert_eq!(*handler.views_created.lock().unwrap(), vec!["view", "view"]);
+}
+
+#[test]
+fn test_on_update_skips_failed_views() {
+    let (server, handler) = server_with_view();
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(2, "table", make_view("broken")))
+            .await
+            .unwrap();
+
+        for (msg_id, view_id) in [(3, "broken"), (4, "view")] {
+            server
+                .handle_request(request(
+                    msg_id,
+                    view_id,
+                    ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+                ))
+                .await
+                .unwrap();
+        }
+
+        handler
+            .broken_views
+            .lock()
+            .unwrap()
+            .push("broken".to_string());
+
+        handler.notify_table_update("table");
+        let resps = server.poll().await.unwrap();
+        assert_eq!(resps.len(), 1);
+        assert_eq!(decode(&resps[0]).msg_id, 4);
+    });
+}
+
+#[test]
+fn test_remove_on_update_and_view_delete_unsubscribe() {
+    let (server, handler) = server_with_view();
+    futures::executor::block_on(async {
+        for msg_id in [2, 3] {
+            server
+                .handle_request(request(
+                    msg_id,
+                    "view",
+                    ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+                ))
+                .await
+                .unwrap();
+        }
+
+        server
+            .handle_request(request(
+                4,
+                "view",
+                ClientReq::ViewRemoveOnUpdateReq(ViewRemoveOnUpdateReq { id: 2 }),
+            ))
+            .await
+            .unwrap();
+
+        handler.notify_table_update("table");
+        let resps = server.poll().await.unwrap();
+        assert_eq!(resps.len(), 1);
+        assert_eq!(decode(&resps[0]).msg_id, 3);
+
+        server
+            .handle_request(request(
+                5,
+                "view",
+                ClientReq::ViewDeleteReq(ViewDeleteReq {}),
+            ))
+            .await
+            .unwrap();
+
+        handler.notify_table_update("table");
+        assert!(server.poll().await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_table_update_passes_index_and_fires_on_update() {
+    let (server, handler) = server_with_view();
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+            ))
+            .await
+            .unwrap();
+
+        let resp = server
+            .handle_request(request(
+                3,
+                "table",
+                ClientReq::TableUpdateReq(TableUpdateReq {
+                    data: Some(MakeTableData::default()),
+                    port_id: 0,
+                }),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            decode(&resp).client_resp,
+            Some(ClientResp::TableUpdateResp(_))
+        ));
+
+        let resps = server.poll().await.unwrap();
+        assert_eq!(resps.len(), 1);
+        assert_eq!(decode(&resps[0]).msg_id, 2);
+    });
+
+    assert_eq!(*handler.update_indices.lock().unwrap(), vec![Some(
+        "id".to_string()
+    )]);
+}
+
+#[test]
+fn test_table_index_is_cached_until_table_delete() {
+    let (server, handler) = server_with_view();
+    let update = |msg_id| {
+        request(
+            msg_id,
+            "table",
+            ClientReq::TableUpdateReq(TableUpdateReq {
+                data: Some(MakeTableData::default()),
+                port_id: 0,
+            }),
+        )
+    };
+
+    futures::executor::block_on(async {
+        for msg_id in [2, 3] {
+            server.handle_request(update(msg_id)).await.unwrap();
+        }
+
+        assert_eq!(*handler.hosted_table_lookups.lock().unwrap(), 1);
+        server
+            .handle_request(request(
+                4,
+                "table",
+                ClientReq::TableDeleteReq(TableDeleteReq::default()),
+            ))
+            .await
+            .unwrap();
+
+        server.handle_request(update(5)).await.unwrap();
+        assert_eq!(*handler.hosted_table_lookups.lock().unwrap(), 2);
+    });
+}
+
+#[test]
+fn test_unsupported_update_skips_table_index() {
+    let handler = TestHandler {
+        read_only: true,
+        ..TestHandler::default()
+    };
+
+    let server = VirtualServer::new(handler.clone());
+    futures::executor::block_on(async {
+        let resp = server
+            .handle_request(request(
+                1,
+                "table",
+                ClientReq::TableUpdateReq(TableUpdateReq {
+                    data: Some(MakeTableData::default()),
+                    port_id: 0,
+                }),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ServerError(err)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(err.status_code(), StatusCode::UnsupportedOperation);
+        assert_eq!(*handler.hosted_table_lookups.lock().unwrap(), 0);
+    });
+}
+
+#[test]
+fn test_table_delete_deletes_its_views() {
+    let (server, handler) = server_with_view();
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+            ))
+            .await
+            .unwrap();
+
+        server
+            .handle_request(request(
+                3,
+                "table",
+                ClientReq::TableDeleteReq(TableDeleteReq::default()),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.views_deleted.lock().unwrap(), vec!["view"]);
+        handler.notify_table_update("table");
+        assert!(server.poll().await.unwrap().is_empty());
+
+        let resp = server
+            .handle_request(request(
+                4,
+                "view",
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ServerError(err)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(err.status_code(), StatusCode::ViewNotFound);
+    });
+}
+
+#[test]
+fn test_split_by_column_paths() {
+    let server = VirtualServer::new(TestHandler::default());
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                1,
+                "table",
+                ClientReq::TableMakeViewReq(TableMakeViewReq {
+                    view_id: "view".to_string(),
+                    config: Some(crate::proto::ViewConfig {
+                        split_by: vec!["Region".to_string()],
+                        ..crate::proto::ViewConfig::default()
+                    }),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let resp = server
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewColumnPathsReq(ViewColumnPathsReq::default()),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ViewColumnPathsResp(resp)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(resp.paths, vec![
+            "East|Sales".to_string(),
+            "North_East|1|Unit_Price".to_string()
+        ]);
+
+        assert_eq!(
+            resp.structured_paths
+                .into_iter()
+                .map(|x| x.path)
+                .collect::<Vec<_>>(),
+            vec![vec!["East", "Sales"], vec!["North_East|1", "Unit_Price"]]
+        );
+
+        let resp = server
+            .handle_request(request(
+                3,
+                "view",
+                ClientReq::ViewColumnPathsReq(ViewColumnPathsReq {
+                    start_col: Some(1),
+                    end_col: None,
+                }),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ViewColumnPathsResp(resp)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(resp.paths, vec!["North_East|1|Unit_Price".to_string()]);
+
+        let resp = server
+            .handle_request(request(
+                4,
+                "view",
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ViewSchemaResp(resp)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        let mut names = resp.schema.into_keys().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["Sales", "Unit_Price"]);
+    });
+}
+
+#[test]
+fn test_split_by_min_max_column_name() {
+    let handler = TestHandler::default();
+    let server = VirtualServer::new(handler.clone());
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                1,
+                "table",
+                ClientReq::TableMakeViewReq(TableMakeViewReq {
+                    view_id: "view".to_string(),
+                    config: Some(crate::proto::ViewConfig {
+                        split_by: vec!["Region".to_string()],
+                        ..crate::proto::ViewConfig::default()
+                    }),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        for (msg_id, column_name) in [(2, "North_East|1|Unit_Price"), (3, "East|Sales")] {
+            server
+                .handle_request(request(
+                    msg_id,
+                    "view",
+                    ClientReq::ViewGetMinMaxReq(ViewGetMinMaxReq {
+                        column_name: column_name.to_string(),
+                    }),
+                ))
+                .await
+                .unwrap();
+        }
+    });
+
+    assert_eq!(*handler.min_max_columns.lock().unwrap(), vec![
+        encode_column_path(&["North_East|1", "Unit_Price"]),
+        encode_column_path(&["East", "Sales"]),
+    ]);
+}
+
+#[test]
+fn test_requests_are_handled_concurrently() {
+    let (server, handler) = server_with_view();
+    let (sender, receiver) = oneshot::channel();
+    *handler.data_gate.lock().unwrap() = Some(receiver);
+    futures::executor::block_on(async {
+        let mut data = std::pin::pin!(server.handle_request(request(
+            2,
+            "view",
+            ClientReq::ViewToColumnsStringReq(ViewToColumnsStringReq {
+                viewport: Some(ViewPort::default()),
+                ..ViewToColumnsStringReq::default()
+            }),
+        )));
+
+        assert!(futures::poll!(data.as_mut()).is_pending());
+        let resp = server
+            .handle_request(request(
+                3,
+                "view",
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(decode(&resp).msg_id, 3);
+        sender.send(()).unwrap();
+        let resp = data.await.unwrap().unwrap();
+        let resp = decode(&resp);
+        assert_eq!(resp.msg_id, 2);
+        assert!(matches!(
+            resp.client_resp,
+            Some(ClientResp::ViewToColumnsStringResp(_))
+        ));
+    });
+}
+
+#[test]
+fn test_poll_does_not_wait_for_in_flight_requests() {
+    let (server, handler) = server_with_view();
+    let (sender, receiver) = oneshot::channel();
+    *handler.data_gate.lock().unwrap() = Some(receiver);
+    futures::executor::block_on(async {
+        server
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+            ))
+            .await
+            .unwrap();
+
+        server
+            .handle_request(request(3, "other_table", make_view("other_view")))
+            .await
+            .unwrap();
+
+        let mut data = std::pin::pin!(server.handle_request(request(
+            4,
+            "other_view",
+            ClientReq::ViewToColumnsStringReq(ViewToColumnsStringReq {
+                viewport: Some(ViewPort::default()),
+                ..ViewToColumnsStringReq::default()
+            }),
+        )));
+
+        assert!(futures::poll!(data.as_mut()).is_pending());
+        handler.notify_table_update("table");
+        let resps = server.poll().await.unwrap();
+        assert_eq!(resps.len(), 1);
+        assert_eq!(decode(&resps[0]).msg_id, 2);
+        sender.send(()).unwrap();
+        data.await.unwrap().unwrap();
+    });
+}
+
+#[test]
+fn test_poll_waits_for_requests_on_recreated_view() {
+    let (server, handler) = server_with_view();
+    let (sender, receiver) = oneshot::channel();
+    *handler.data_gate.lock().unwrap() = Some(receiver);
+    futures::executor::block_on(async {
+        let mut data = std::pin::pin!(server.handle_request(request(
+            2,
+            "view",
+            ClientReq::ViewToColumnsStringReq(ViewToColumnsStringReq {
+                viewport: Some(ViewPort::default()),
+                ..ViewToColumnsStringReq::default()
+            }),
+        )));
+
+        assert!(futures::poll!(data.as_mut()).is_pending());
+        handler.notify_table_update("table");
+        let mut poll = std::pin::pin!(server.poll());
+        assert!(futures::poll!(poll.as_mut()).is_pending());
+        assert!(handler.views_deleted.lock().unwrap().is_empty());
+
+        sender.send(()).unwrap();
+        data.await.unwrap().unwrap();
+        poll.await.unwrap();
+        assert_eq!(*handler.views_deleted.lock().unwrap(), vec!["view"]);
+    });
+}
+
+fn make_view(view_id: &str) -> ClientReq {
+    ClientReq::TableMakeViewReq(TableMakeViewReq {
+        view_id: view_id.to_string(),
+        config: None,
+    })
+}
+
+#[test]
+fn test_session_close_deletes_its_views() {
+    let handler = TestHandler::default();
+    let server = VirtualServer::new(handler.clone());
+    let session1 = server.new_session();
+    let session2 = server.new_session();
+    futures::executor::block_on(async {
+        session1
+            .handle_request(request(1, "table", make_view("view1")))
+            .await
+            .unwrap();
+
+        session1
+            .handle_request(request(2, "table", make_view("view2")))
+            .await
+            .unwrap();
+
+        session1
+            .handle_request(request(
+                3,
+                "view2",
+                ClientReq::ViewDeleteReq(ViewDeleteReq {}),
+            ))
+            .await
+            .unwrap();
+
+        session2
+            .handle_request(request(1, "table", make_view("view3")))
+            .await
+            .unwrap();
+
+        session1.close().await.unwrap();
+        assert_eq!(*handler.views_deleted.lock().unwrap(), vec![
+            "view2".to_string(),
+            "view1".to_string()
+        ]);
+
+        // `View`s are shared by every session until deleted.
+        let resp = session2
+            .handle_request(request(
+                2,
+                "view1",
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            decode(&resp).client_resp,
+            Some(ClientResp::ServerError(_))
+        ));
+
+        let resp = session2
+            .handle_request(request(
+                3,
+                "view3",
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            decode(&resp).client_resp,
+            Some(ClientResp::ViewSchemaResp(_))
+        ));
+    });
+}
+
+#[test]
+fn test_table_update_notifies_every_session() {
+    let handler = TestHandler::default();
+    let server = VirtualServer::new(handler.clone());
+    let session1 = server.new_session();
+    let session2 = server.new_session();
+    futures::executor::block_on(async {
+        session1
+            .handle_request(request(1, "table", make_view("view1")))
+            .await
+            .unwrap();
+
+        session2
+            .handle_request(request(1, "table", make_view("view2")))
+            .await
+            .unwrap();
+
+        // Both sessions subscribe with the same `msg_id`.
+        for (session, view_id) in [(&session1, "view1"), (&session2, "view2")] {
+            session
+                .handle_request(request(
+                    2,
+                    view_id,
+                    ClientReq::ViewOnUpdateReq(ViewOnUpdateReq { mode: None }),
+                ))
+                .await
+                .unwrap();
+        }
+
+        session2
+            .handle_request(request(
+                3,
+                "table",
+                ClientReq::TableUpdateReq(TableUpdateReq {
+                    data: None,
+                    port_id: 0,
+                }),
+            ))
+            .await
+            .unwrap();
+
+        handler.views_created.lock().unwrap().clear();
+        for (session, view_id) in [(&session1, "view1"), (&session2, "view2")] {
+            let resps = session.poll().await.unwrap();
+            assert_eq!(resps.len(), 1);
+            let resp = decode(&resps[0]);
+            assert_eq!(resp.msg_id, 2);
+            assert_eq!(resp.entity_id, view_id);
+            assert!(session.poll().await.unwrap().is_empty());
+        }
+
+        // Each session recalculates only its own `View`s.
+        assert_eq!(*handler.views_created.lock().unwrap(), vec![
+            "view1".to_string(),
+            "view2".to_string()
+        ]);
+    });
+}
+
+#[test]
+fn test_updates_are_dropped_without_views() {
+    let handler = TestHandler::default();
+    let server = VirtualServer::new(handler.clone());
+    let session = server.new_session();
+    futures::executor::block_on(async {
+        session
+            .handle_request(request(1, "table", make_view("view")))
+            .await
+            .unwrap();
+
+        // Only the session with a `View` records the update, as the server
+        // is never polled.
+        handler.notify_table_update("table");
+        assert!(server.notifier().take_updated_tables().is_empty());
+        assert_eq!(session.notifier().take_updated_tables().len(), 1);
+
+        session
+            .handle_request(request(
+                2,
+                "view",
+                ClientReq::ViewDeleteReq(ViewDeleteReq {}),
+            ))
+            .await
+            .unwrap();
+
+        handler.notify_table_update("table");
+        assert!(session.notifier().take_updated_tables().is_empty());
+    });
+}
+
+#[test]
+fn test_error_status_codes() {
+    let server = VirtualServer::new(TestHandler::default());
+    futures::executor::block_on(async {
+        for (req, entity_id, status_code) in [
+            (
+                ClientReq::ViewSchemaReq(ViewSchemaReq {}),
+                "missing",
+                StatusCode::ViewNotFound,
+            ),
+            (
+                ClientReq::ServerSystemInfoReq(ServerSystemInfoReq {}),
+                "",
+                StatusCode::UnsupportedOperation,
+            ),
+            (
+                ClientReq::TableSizeReq(TableSizeReq {}),
+                "offline",
+                StatusCode::BackendUnavailable,
+            ),
+            (
+                ClientReq::TableReplaceReq(TableReplaceReq::default()),
+                "table",
+                StatusCode::UnsupportedOperation,
+            ),
+            (
+                ClientReq::MakeTableReq(MakeTableReq::default()),
+                "new_table",
+                StatusCode::UnsupportedOperation,
+            ),
+        ] {
+            let resp = server
+                .handle_request(request(1, entity_id, req))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let Some(ClientResp::ServerError(err)) = decode(&resp).client_resp else {
+                panic!("unexpected response");
+            };
+
+            assert_eq!(err.status_code(), status_code);
+        }
+    });
+}
+
+#[test]
+fn test_expression_diagnostics() {
+    let server = VirtualServer::new(TestHandler::default());
+    futures::executor::block_on(async {
+        let resp = server
+            .handle_request(request(
+                1,
+                "table",
+                ClientReq::TableValidateExprReq(TableValidateExprReq {
+                    column_to_expr: HashMap::from_iter([
+                        ("x".to_string(), "1 +\n  bad".to_string()),
+                        ("y".to_string(), "1".to_string()),
+                    ]),
+                }),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::TableValidateExprResp(resp)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(resp.errors["x"], ExprValidationError {
+            error_message: "bad token".to_string(),
+            line: 1,
+            column: 2,
+            span: 3,
+        });
+
+        assert_eq!(
+            resp.expression_schema,
+            HashMap::from_iter([("y".to_string(), ColumnType::Integer as i32)])
+        );
+
+        server
+            .handle_request(request(
+                2,
+                "table",
+                ClientReq::TableMakeViewReq(TableMakeViewReq {
+                    view_id: "view".to_string(),
+                    config: Some(crate::proto::ViewConfig {
+                        expressions: HashMap::from_iter([("x".to_string(), "bad".to_string())]),
+                        ..crate::proto::ViewConfig::default()
+                    }),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let resp = server
+            .handle_request(request(
+                3,
+                "view",
+                ClientReq::ViewExpressionSchemaReq(ViewExpressionSchemaReq {}),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let Some(ClientResp::ServerError(err)) = decode(&resp).client_resp else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(err.status_code(), StatusCode::InvalidExpression);
+    });
+}
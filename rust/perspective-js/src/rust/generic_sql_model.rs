@@ -19,9 +19,11 @@ use js_sys::Object;
 use perspective_client::config::ViewConfig;
 use perspective_client::proto::{ColumnType, ViewPort};
 use perspective_client::virtual_server;
+use perspective_client::virtual_server::GenericSQLError;
 use wasm_bindgen::prelude::*;
 
 use crate::utils::*;
+use crate::virtual_server::MakeTableData;
 
 /// JavaScript-facing DuckDB SQL query builder.
 ///
@@ -51,6 +53,19 @@ impl GenericSQLVirtualServerModel {
         })
     }
 
+    /// Returns the features supported by views created with this model, with
+    /// `filter_ops` for every column type.
+    #[wasm_bindgen(js_name = "getFeatures")]
+    pub fn get_features(&self) -> Result<JsValue, JsValue> {
+        Ok(JsValue::from_serde_ext(&self.inner.features())?)
+    }
+
+    /// Returns the Perspective column type for a database type name.
+    #[wasm_bindgen(js_name = "columnType")]
+    pub fn column_type(&self, type_name: &str) -> String {
+        self.inner.column_type(type_name).to_string()
+    }
+
     /// Returns the SQL query to list all hosted tables.
     #[wasm_bindgen(js_name = "getHostedTables")]
     pub fn get_hosted_tables(&self) -> Result<String, JsValue> {
@@ -83,7 +98,19 @@ impl GenericSQLVirtualServerModel {
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
+    /// Translates an ExprTK expression column to a SQL expression.
+    #[wasm_bindgen(js_name = "expressionSql")]
+    pub fn expression_sql(&self, expression: &str) -> Result<String, JsValue> {
+        self.inner
+            .expression_sql(expression)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Returns the SQL query to validate an expression against a table.
+    ///
+    /// Throws an `Error` with `line`, `column` and `span` properties if the
+    /// expression is invalid, as read by a `tableValidateExpression` handler
+    /// method.
     #[wasm_bindgen(js_name = "tableValidateExpression")]
     pub fn table_validate_expression(
         &self,
@@ -92,7 +119,12 @@ impl GenericSQLVirtualServerModel {
     ) -> Result<String, JsValue> {
         self.inner
             .table_validate_expression(table_id, expression)
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+            .map_err(|e| match e {
+                GenericSQLError::InvalidExpression(diagnostic) => {
+                    expression_diagnostic_error(&diagnostic)
+                },
+                e => JsValue::from_str(&e.to_string()),
+            })
     }
 
     /// Returns the SQL query to delete a view.
@@ -120,6 +152,112 @@ impl GenericSQLVirtualServerModel {
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
+    /// Returns the SQL query to create a view, with filter values bound as
+    /// positional parameters, as a `[sql, params]` pair.
+    #[wasm_bindgen(js_name = "tableMakeViewParameterized")]
+    pub fn table_make_view_parameterized(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let query = self
+            .inner
+            .table_make_view_parameterized(table_id, view_id, &config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&query).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query for the distinct values of a view's `split_by`
+    /// columns, for dialects which do not support `PIVOT`.
+    #[wasm_bindgen(js_name = "viewSplitValues")]
+    pub fn view_split_values(&self, table_id: &str, config: JsValue) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .view_split_values(table_id, &config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query for the distinct values of a view's `split_by`
+    /// columns, with filter values bound as positional parameters, as a
+    /// `[sql, params]` pair.
+    #[wasm_bindgen(js_name = "viewSplitValuesParameterized")]
+    pub fn view_split_values_parameterized(
+        &self,
+        table_id: &str,
+        config: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let query = self
+            .inner
+            .view_split_values_parameterized(table_id, &config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&query).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to create a `split_by` view from a table with
+    /// the `split_values` returned by the query from `viewSplitValues`.
+    #[wasm_bindgen(js_name = "tableMakeViewWithSplitValues")]
+    pub fn table_make_view_with_split_values(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: JsValue,
+        split_values: JsValue,
+    ) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let split_values: Vec<Vec<Option<String>>> =
+            serde_wasm_bindgen::from_value(split_values)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .table_make_view_with_split_values(table_id, view_id, &config, &split_values)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to create a `split_by` view from a table with
+    /// the `split_values` returned by the query from `viewSplitValues`, with
+    /// filter and split values bound as positional parameters, as a
+    /// `[sql, params]` pair.
+    #[wasm_bindgen(js_name = "tableMakeViewWithSplitValuesParameterized")]
+    pub fn table_make_view_with_split_values_parameterized(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: JsValue,
+        split_values: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let split_values: Vec<Vec<Option<String>>> =
+            serde_wasm_bindgen::from_value(split_values)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let query = self
+            .inner
+            .table_make_view_with_split_values_parameterized(
+                table_id,
+                view_id,
+                &config,
+                &split_values,
+            )
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&query).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Returns the SQL query to fetch data from a view with the given viewport.
     #[wasm_bindgen(js_name = "viewGetData")]
     pub fn view_get_data(
@@ -152,9 +290,127 @@ impl GenericSQLVirtualServerModel {
 
     /// Returns the SQL query to get the row count of a view.
     #[wasm_bindgen(js_name = "viewSize")]
-    pub fn view_size(&self, view_id: &str) -> Result<String, JsValue> {
+    pub fn view_size(&self, view_id: &str, config: JsValue) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
         self.inner
-            .view_size(view_id)
+            .view_size(view_id, &config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to expand the row at `row_index` of a grouped
+    /// view.
+    #[wasm_bindgen(js_name = "viewExpand")]
+    pub fn view_expand(
+        &self,
+        view_id: &str,
+        config: JsValue,
+        row_index: u32,
+    ) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .view_expand(view_id, &config, row_index)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to collapse the row at `row_index` of a grouped
+    /// view.
+    #[wasm_bindgen(js_name = "viewCollapse")]
+    pub fn view_collapse(
+        &self,
+        view_id: &str,
+        config: JsValue,
+        row_index: u32,
+    ) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .view_collapse(view_id, &config, row_index)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to expand a grouped view to `depth`.
+    #[wasm_bindgen(js_name = "viewSetDepth")]
+    pub fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: JsValue,
+        depth: u32,
+    ) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .view_set_depth(view_id, &config, depth)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL statements to write `data` to a table, updating rows in
+    /// place by their `index` column value when `index` is set.
+    #[wasm_bindgen(js_name = "tableUpdate")]
+    pub fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<String>,
+        data: &MakeTableData,
+    ) -> Result<Vec<String>, JsValue> {
+        self.inner
+            .table_update(table_id, index.as_deref(), &data.0)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL statements to remove rows from a table by their `index`
+    /// column values.
+    #[wasm_bindgen(js_name = "tableRemove")]
+    pub fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<String>,
+        data: &MakeTableData,
+    ) -> Result<Vec<String>, JsValue> {
+        self.inner
+            .table_remove(table_id, index.as_deref(), &data.0)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL statements to replace all rows of a table with `data`.
+    #[wasm_bindgen(js_name = "tableReplace")]
+    pub fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Result<Vec<String>, JsValue> {
+        self.inner
+            .table_replace(table_id, &data.0)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to delete a table.
+    #[wasm_bindgen(js_name = "tableDelete")]
+    pub fn table_delete(&self, table_id: &str) -> Result<String, JsValue> {
+        self.inner
+            .table_delete(table_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns the SQL query to get the minimum and maximum values of a column
+    /// in a view.
+    #[wasm_bindgen(js_name = "viewGetMinMax")]
+    pub fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: JsValue,
+    ) -> Result<String, JsValue> {
+        let config: ViewConfig = serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        self.inner
+            .view_get_min_max(view_id, column_name, &config)
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 }
@@ -187,3 +443,17 @@ impl GenericSQLVirtualServerModel {
         Ok(result)
     }
 }
+
+/// A JavaScript `Error` carrying the location of an invalid expression.
+fn expression_diagnostic_error(diagnostic: &virtual_server::ExpressionDiagnostic) -> JsValue {
+    let error = js_sys::Error::new(&diagnostic.message);
+    for (name, value) in [
+        ("line", diagnostic.line),
+        ("column", diagnostic.column),
+        ("span", diagnostic.span),
+    ] {
+        let _ = js_sys::Reflect::set(&error, &name.into(), &value.into());
+    }
+
+    error.into()
+}
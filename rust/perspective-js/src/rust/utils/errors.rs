@@ -128,6 +128,14 @@ impl ApiError {
             ApiErrorType::TableError(_) => "[TableError]",
             ApiErrorType::ExternalError(_) => "[ExternalError]",
             ApiErrorType::UnknownError(..) => "[UnknownError]",
+            ApiErrorType::ClientError(ClientError::ViewNotFound) => "[ViewNotFound]",
+            ApiErrorType::ClientError(ClientError::UnsupportedOperation(_)) => {
+                "[UnsupportedOperation]"
+            },
+            ApiErrorType::ClientError(ClientError::InvalidExpression(_)) => "[InvalidExpression]",
+            ApiErrorType::ClientError(ClientError::InvalidConfig(_)) => "[InvalidConfig]",
+            ApiErrorType::ClientError(ClientError::BackendUnavailable(_)) => "[BackendUnavailable]",
+            ApiErrorType::ClientError(ClientError::Timeout(_)) => "[Timeout]",
             ApiErrorType::ClientError(_) => "[ClientError]",
             ApiErrorType::CancelledError(_) => "[CancelledError]",
             ApiErrorType::SerdeJsonError(_) => "[SerdeJsonError]",
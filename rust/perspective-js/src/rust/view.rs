@@ -91,6 +91,24 @@ impl View {
         Ok(JsValue::from_serde_ext(&columns)?)
     }
 
+    /// Returns the column paths of the [`View`] as in [`View::column_paths`],
+    /// but with each path as an array of its elements rather than a
+    /// `|`-joined string, which is unambiguous for column names containing
+    /// `|`.
+    #[wasm_bindgen]
+    pub async fn column_paths_structured(
+        &self,
+        window: Option<JsColumnWindow>,
+    ) -> ApiResult<JsValue> {
+        let window = window.into_serde_ext::<Option<ColumnWindow>>()?;
+        let columns = self
+            .0
+            .column_paths_structured(window.unwrap_or_default())
+            .await?;
+
+        Ok(JsValue::from_serde_ext(&columns)?)
+    }
+
     /// Delete this [`View`] and clean up all resources associated with it.
     /// [`View`] objects do not stop consuming resources or processing
     /// updates when they are garbage collected - you must call this method
@@ -10,7 +10,6 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
-use std::cell::UnsafeCell;
 use std::future::Future;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -19,9 +18,12 @@ use std::sync::{Arc, Mutex};
 
 use indexmap::IndexMap;
 use js_sys::{Array, Date, Object, Reflect};
+use perspective_client::config::Scalar;
 use perspective_client::proto::{ColumnType, HostedTable};
 use perspective_client::virtual_server;
-use perspective_client::virtual_server::{Features, ResultExt, VirtualServerHandler};
+use perspective_client::virtual_server::{
+    ExpressionDiagnostic, Features, ResultExt, VirtualServerHandler,
+};
 use serde::Serialize;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
@@ -63,6 +65,10 @@ impl From<serde_wasm_bindgen::Error> for JsError {
 pub struct JsServerHandler(Object);
 
 impl JsServerHandler {
+    fn has_method(&self, method: &str) -> bool {
+        Reflect::get(&self.0, &JsValue::from_str(method)).is_ok_and(|val| !val.is_undefined())
+    }
+
     fn call_method_js(&self, method: &str, args: &Array) -> Result<JsValue, JsError> {
         let func = Reflect::get(&self.0, &JsValue::from_str(method))?;
         let func = func
@@ -82,6 +88,60 @@ impl JsServerHandler {
             Ok(result)
         }
     }
+
+    fn call_expand_collapse(
+        &self,
+        method: &'static str,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> HandlerFuture<Result<u32, JsError>> {
+        let has_method = Reflect::get(&self.0, &JsValue::from_str(method))
+            .map(|val| !val.is_undefined())
+            .unwrap_or(false);
+
+        if !has_method {
+            return Box::pin(async { Ok(0) });
+        }
+
+        let handler = self.0.clone();
+        let view_id = view_id.to_string();
+        let config_value = serde_wasm_bindgen::to_value(config).unwrap();
+        Box::pin(async move {
+            let this = JsServerHandler(handler);
+            let args = Array::new();
+            args.push(&JsValue::from_str(&view_id));
+            args.push(&config_value);
+            args.push(&JsValue::from_f64(row_index as f64));
+            let result = this.call_method_js_async(method, &args).await?;
+            Ok(result.as_f64().map(|x| x as u32).unwrap_or_default())
+        })
+    }
+}
+
+/// Reads an error thrown by `tableValidateExpression` as an
+/// [`ExpressionDiagnostic`], from its `message` and optional `line`, `column`
+/// and `span` properties.
+fn js_expression_diagnostic(err: &JsValue) -> ExpressionDiagnostic {
+    let property = |name: &str| {
+        Reflect::get(err, &JsValue::from_str(name))
+            .ok()
+            .and_then(|x| x.as_f64())
+            .map_or(0, |x| x as u32)
+    };
+
+    let message = Reflect::get(err, &JsValue::from_str("message"))
+        .ok()
+        .and_then(|x| x.as_string())
+        .or_else(|| err.as_string())
+        .unwrap_or_else(|| format!("{:?}", err));
+
+    ExpressionDiagnostic {
+        message,
+        line: property("line"),
+        column: property("column"),
+        span: property("span"),
+    }
 }
 
 impl VirtualServerHandler for JsServerHandler {
@@ -217,7 +277,7 @@ impl VirtualServerHandler for JsServerHandler {
         &self,
         table_id: &str,
         expression: &str,
-    ) -> HandlerFuture<Result<ColumnType, Self::Error>> {
+    ) -> HandlerFuture<Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>> {
         // TODO Cache these inspection calls
         let has_method = Reflect::get(&self.0, &JsValue::from_str("tableValidateExpression"))
             .map(|val| !val.is_undefined())
@@ -228,7 +288,7 @@ impl VirtualServerHandler for JsServerHandler {
         let expression = expression.to_string();
         Box::pin(async move {
             if !has_method {
-                return Err(JsError(JsValue::from_str(
+                return Ok(Err(ExpressionDiagnostic::new(
                     "feature `table_validate_expression` not implemented",
                 )));
             }
@@ -237,20 +297,24 @@ impl VirtualServerHandler for JsServerHandler {
             let args = Array::new();
             args.push(&JsValue::from_str(&table_id));
             args.push(&JsValue::from_str(&expression));
-            let result = this
+            let result = match this
                 .call_method_js_async("tableValidateExpression", &args)
-                .await?;
+                .await
+            {
+                Ok(result) => result,
+                Err(JsError(err)) => return Ok(Err(js_expression_diagnostic(&err))),
+            };
 
             let type_str = result
                 .as_string()
                 .ok_or_else(|| JsError(JsValue::from_str("Must return a string")))?;
 
-            Ok(ColumnType::from_str(&type_str).unwrap())
+            Ok(Ok(ColumnType::from_str(&type_str).unwrap()))
         })
     }
 
     fn table_make_view(
-        &mut self,
+        &self,
         table_id: &str,
         view_id: &str,
         config: &mut perspective_client::config::ViewConfigUpdate,
@@ -319,16 +383,25 @@ impl VirtualServerHandler for JsServerHandler {
         })
     }
 
-    fn view_size(&self, view_id: &str) -> HandlerFuture<Result<u32, Self::Error>> {
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+    ) -> HandlerFuture<Result<u32, Self::Error>> {
         let handler = self.0.clone();
         let view_id = view_id.to_string();
         let has_view_size =
             Reflect::get(&self.0, &JsValue::from_str("viewSize")).is_ok_and(|v| !v.is_undefined());
 
+        let config_value = serde_wasm_bindgen::to_value(config).unwrap();
         Box::pin(async move {
             let this = JsServerHandler(handler);
             let args = Array::new();
             args.push(&JsValue::from_str(&view_id));
+            if has_view_size {
+                args.push(&config_value);
+            }
+
             let result = this
                 .call_method_js_async(
                     if has_view_size {
@@ -376,6 +449,81 @@ impl VirtualServerHandler for JsServerHandler {
         })
     }
 
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &perspective_client::config::ViewConfig,
+    ) -> HandlerFuture<Result<(Scalar, Scalar), Self::Error>> {
+        let has_method = Reflect::get(&self.0, &JsValue::from_str("viewGetMinMax"))
+            .map(|val| !val.is_undefined())
+            .unwrap_or(false);
+
+        if !has_method {
+            return Box::pin(async { Ok((Scalar::Null, Scalar::Null)) });
+        }
+
+        let handler = self.0.clone();
+        let view_id = view_id.to_string();
+        let column_name = column_name.to_string();
+        let config_value = serde_wasm_bindgen::to_value(config).unwrap();
+        Box::pin(async move {
+            let this = JsServerHandler(handler);
+            let args = Array::new();
+            args.push(&JsValue::from_str(&view_id));
+            args.push(&JsValue::from_str(&column_name));
+            args.push(&config_value);
+            let result = this.call_method_js_async("viewGetMinMax", &args).await?;
+            Ok(serde_wasm_bindgen::from_value(result)?)
+        })
+    }
+
+    fn view_expand(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> HandlerFuture<Result<u32, Self::Error>> {
+        self.call_expand_collapse("viewExpand", view_id, config, row_index)
+    }
+
+    fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> HandlerFuture<Result<u32, Self::Error>> {
+        self.call_expand_collapse("viewCollapse", view_id, config, row_index)
+    }
+
+    fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        depth: u32,
+    ) -> HandlerFuture<Result<(), Self::Error>> {
+        let has_method = Reflect::get(&self.0, &JsValue::from_str("viewSetDepth"))
+            .map(|val| !val.is_undefined())
+            .unwrap_or(false);
+
+        if !has_method {
+            return Box::pin(async { Ok(()) });
+        }
+
+        let handler = self.0.clone();
+        let view_id = view_id.to_string();
+        let config_value = serde_wasm_bindgen::to_value(config).unwrap();
+        Box::pin(async move {
+            let this = JsServerHandler(handler);
+            let args = Array::new();
+            args.push(&JsValue::from_str(&view_id));
+            args.push(&config_value);
+            args.push(&JsValue::from_f64(depth as f64));
+            this.call_method_js_async("viewSetDepth", &args).await?;
+            Ok(())
+        })
+    }
+
     fn view_delete(&self, view_id: &str) -> HandlerFuture<Result<(), Self::Error>> {
         let handler = self.0.clone();
         let view_id = view_id.to_string();
@@ -416,15 +564,9 @@ impl VirtualServerHandler for JsServerHandler {
         &mut self,
         table_id: &str,
         data: &perspective_client::proto::MakeTableData,
-    ) -> HandlerFuture<Result<(), Self::Error>> {
-        let has_method = Reflect::get(&self.0, &JsValue::from_str("makeTable"))
-            .map(|val| !val.is_undefined())
-            .unwrap_or(false);
-
-        if !has_method {
-            return Box::pin(async {
-                Err(JsError(JsValue::from_str("makeTable not implemented")))
-            });
+    ) -> Option<HandlerFuture<Result<(), Self::Error>>> {
+        if !self.has_method("makeTable") {
+            return None;
         }
 
         let handler = self.0.clone();
@@ -442,14 +584,98 @@ impl VirtualServerHandler for JsServerHandler {
             _ => JsValue::from_str(""),
         };
 
-        Box::pin(async move {
+        Some(Box::pin(async move {
             let this = JsServerHandler(handler);
             let args = Array::new();
             args.push(&JsValue::from_str(&table_id));
             args.push(&data_value);
             this.call_method_js_async("makeTable", &args).await?;
             Ok(())
-        })
+        }))
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        self.has_method("tableUpdate") || self.has_method("tableRemove")
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &perspective_client::proto::MakeTableData,
+    ) -> Option<HandlerFuture<Result<(), Self::Error>>> {
+        if !self.has_method("tableUpdate") {
+            return None;
+        }
+
+        let handler = self.0.clone();
+        let args = Array::new();
+        args.push(&JsValue::from_str(table_id));
+        args.push(&index.map(JsValue::from_str).unwrap_or(JsValue::NULL));
+        args.push(&JsValue::from(MakeTableData(data.clone())));
+        Some(Box::pin(async move {
+            let this = JsServerHandler(handler);
+            this.call_method_js_async("tableUpdate", &args).await?;
+            Ok(())
+        }))
+    }
+
+    fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &perspective_client::proto::MakeTableData,
+    ) -> Option<HandlerFuture<Result<(), Self::Error>>> {
+        if !self.has_method("tableRemove") {
+            return None;
+        }
+
+        let handler = self.0.clone();
+        let args = Array::new();
+        args.push(&JsValue::from_str(table_id));
+        args.push(&index.map(JsValue::from_str).unwrap_or(JsValue::NULL));
+        args.push(&JsValue::from(MakeTableData(data.clone())));
+        Some(Box::pin(async move {
+            let this = JsServerHandler(handler);
+            this.call_method_js_async("tableRemove", &args).await?;
+            Ok(())
+        }))
+    }
+
+    fn table_replace(
+        &self,
+        table_id: &str,
+        data: &perspective_client::proto::MakeTableData,
+    ) -> Option<HandlerFuture<Result<(), Self::Error>>> {
+        if !self.has_method("tableReplace") {
+            return None;
+        }
+
+        let handler = self.0.clone();
+        let args = Array::new();
+        args.push(&JsValue::from_str(table_id));
+        args.push(&JsValue::from(MakeTableData(data.clone())));
+        Some(Box::pin(async move {
+            let this = JsServerHandler(handler);
+            this.call_method_js_async("tableReplace", &args).await?;
+            Ok(())
+        }))
+    }
+
+    fn table_delete(&self, table_id: &str) -> Option<HandlerFuture<Result<(), Self::Error>>> {
+        if !self.has_method("tableDelete") {
+            return None;
+        }
+
+        let handler = self.0.clone();
+        let table_id = table_id.to_string();
+        Some(Box::pin(async move {
+            let this = JsServerHandler(handler);
+            let args = Array::new();
+            args.push(&JsValue::from_str(&table_id));
+            this.call_method_js_async("tableDelete", &args).await?;
+            Ok(())
+        }))
     }
 
     fn view_get_data(
@@ -515,6 +741,45 @@ impl From<perspective_client::proto::ViewPort> for JsViewPort {
     }
 }
 
+/// Data written to a virtual table by `Table.update`, `Table.remove` or
+/// `Table.replace`, which a handler can pass to
+/// `GenericSQLVirtualServerModel` or read directly.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MakeTableData(pub(crate) perspective_client::proto::MakeTableData);
+
+#[wasm_bindgen]
+impl MakeTableData {
+    /// The format of this data, one of `"rows"`, `"columns"`, `"ndjson"`,
+    /// `"csv"` or `"arrow"`.
+    #[wasm_bindgen(getter)]
+    pub fn format(&self) -> Option<String> {
+        use perspective_client::proto::make_table_data::Data;
+        match &self.0.data {
+            Some(Data::FromRows(_)) => Some("rows".to_string()),
+            Some(Data::FromCols(_)) => Some("columns".to_string()),
+            Some(Data::FromNdjson(_)) => Some("ndjson".to_string()),
+            Some(Data::FromCsv(_)) => Some("csv".to_string()),
+            Some(Data::FromArrow(_)) => Some("arrow".to_string()),
+            _ => None,
+        }
+    }
+
+    /// The data itself, as a `Uint8Array` for `"arrow"` and a `string`
+    /// otherwise.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> JsValue {
+        use perspective_client::proto::make_table_data::Data;
+        match &self.0.data {
+            Some(
+                Data::FromRows(x) | Data::FromCols(x) | Data::FromNdjson(x) | Data::FromCsv(x),
+            ) => JsValue::from_str(x),
+            Some(Data::FromArrow(x)) => js_sys::Uint8Array::from(x.as_slice()).into(),
+            _ => JsValue::UNDEFINED,
+        }
+    }
+}
+
 #[wasm_bindgen(js_name = "VirtualDataSlice")]
 #[derive(Clone)]
 pub struct VirtualDataSlice(Object, Arc<Mutex<Option<virtual_server::VirtualDataSlice>>>);
@@ -713,35 +978,53 @@ impl VirtualDataSlice {
 }
 
 #[wasm_bindgen]
-pub struct VirtualServer(Rc<UnsafeCell<virtual_server::VirtualServer<JsServerHandler>>>);
+pub struct VirtualServer(Rc<virtual_server::VirtualServer<JsServerHandler>>);
 
 #[wasm_bindgen]
 impl VirtualServer {
     #[wasm_bindgen(constructor)]
     pub fn new(handler: Object) -> Result<VirtualServer, JsValue> {
-        Ok(VirtualServer(Rc::new(UnsafeCell::new(
-            virtual_server::VirtualServer::new(JsServerHandler(handler)),
+        Ok(VirtualServer(Rc::new(virtual_server::VirtualServer::new(
+            JsServerHandler(handler),
         ))))
     }
 
     #[wasm_bindgen(js_name = "handleRequest")]
-    pub fn handle_request(&self, bytes: &[u8]) -> ApiFuture<Vec<u8>> {
+    pub fn handle_request(&self, bytes: &[u8]) -> ApiFuture<JsValue> {
         let bytes = bytes.to_vec();
         let server = self.0.clone();
 
         ApiFuture::new(async move {
-            // SAFETY:
-            // - WASM is single-threaded
-            // - JS re-entrancy is allowed by design
-            // - VirtualServer must tolerate re-entrant mutation
-            let result = unsafe {
-                (&mut *server.as_ref().get())
-                    .handle_request(bytes::Bytes::from(bytes))
-                    .await
-            };
+            let result = server.handle_request(bytes::Bytes::from(bytes)).await;
 
             match result.get_internal_error() {
-                Ok(x) => Ok(x.to_vec()),
+                Ok(Some(x)) => Ok(js_sys::Uint8Array::from(&x[..]).into()),
+                Ok(None) => Ok(JsValue::UNDEFINED),
+                Err(Ok(x)) => Err(ApiError::from(JsValue::from(x))),
+                Err(Err(x)) => Err(ApiError::from(JsValue::from_str(&x))),
+            }
+        })
+    }
+
+    /// Mark a table as updated, such that the next call to `poll()` will
+    /// recalculate its `View`s and notify their `on_update` subscribers.
+    #[wasm_bindgen(js_name = "notifyTableUpdate")]
+    pub fn notify_table_update(&self, table_id: &str) {
+        self.0.notifier().notify_table_update(table_id);
+    }
+
+    /// Returns the `on_update` callback messages for the tables updated since
+    /// the last call.
+    #[wasm_bindgen]
+    pub fn poll(&self) -> ApiFuture<Array> {
+        let server = self.0.clone();
+        ApiFuture::new(async move {
+            let result = server.poll().await;
+            match result.get_internal_error() {
+                Ok(resps) => Ok(resps
+                    .iter()
+                    .map(|x| JsValue::from(js_sys::Uint8Array::from(&x[..])))
+                    .collect()),
                 Err(Ok(x)) => Err(ApiError::from(JsValue::from(x))),
                 Err(Err(x)) => Err(ApiError::from(JsValue::from_str(&x))),
             }
@@ -33,6 +33,14 @@ export interface ServerFeatures {
     expressions?: boolean;
 }
 
+/**
+ * A table name, or a table name with its `index` column (which keys
+ * `Table.update` and `Table.remove`) and `limit`.
+ */
+export type HostedTable =
+    | string
+    | { name: string; index?: string; limit?: number };
+
 /**
  * Handler interface that you implement to provide custom data sources.
  *
@@ -41,7 +49,7 @@ export interface ServerFeatures {
  * return Promises for asynchronous operations (e.g., database queries).
  */
 export interface VirtualServerHandler {
-    getHostedTables(): string[] | Promise<string[]>;
+    getHostedTables(): HostedTable[] | Promise<HostedTable[]>;
     tableSchema(
         tableId: string,
     ): Record<string, ColumnType> | Promise<Record<string, ColumnType>>;
@@ -63,16 +71,65 @@ export interface VirtualServerHandler {
         viewId: string,
         config?: ViewConfig,
     ): Record<string, ColumnType> | Promise<Record<string, ColumnType>>;
-    viewSize?(viewId: string): number | Promise<number>;
+    viewSize?(viewId: string, config?: ViewConfig): number | Promise<number>;
+    viewExpand?(
+        viewId: string,
+        config: ViewConfig,
+        rowIndex: number,
+    ): number | Promise<number>;
+    viewCollapse?(
+        viewId: string,
+        config: ViewConfig,
+        rowIndex: number,
+    ): number | Promise<number>;
+    viewSetDepth?(
+        viewId: string,
+        config: ViewConfig,
+        depth: number,
+    ): void | Promise<void>;
+    viewGetMinMax?(
+        viewId: string,
+        columnName: string,
+        config: ViewConfig,
+    ):
+        | [number | string | null, number | string | null]
+        | Promise<[number | string | null, number | string | null]>;
+    /**
+     * Returns the type of `expression`, or throws an `Error` if it is
+     * invalid. The error's optional `line`, `column` and `span` properties
+     * (0-based, in characters) locate the invalid text.
+     */
     tableValidateExpression?(
         tableId: string,
         expression: string,
     ): ColumnType | Promise<ColumnType>;
     getFeatures?(): ServerFeatures | Promise<ServerFeatures>;
+    tableUpdate?(
+        tableId: string,
+        index: string | null,
+        data: perspective.MakeTableData,
+    ): void | Promise<void>;
+    tableRemove?(
+        tableId: string,
+        index: string | null,
+        data: perspective.MakeTableData,
+    ): void | Promise<void>;
+    tableReplace?(
+        tableId: string,
+        data: perspective.MakeTableData,
+    ): void | Promise<void>;
+    tableDelete?(tableId: string): void | Promise<void>;
     makeTable?(
         tableId: string,
         data: string | Uint8Array,
     ): void | Promise<void>;
+
+    /**
+     * Receives a `notify` function which this handler should call with a
+     * table's name when its data changes, causing the `View`s of this table
+     * to be recalculated and their `on_update` callbacks to fire.
+     */
+    setUpdateNotifier?(notify: (tableId: string) => void): void;
 }
 
 export function createMessageHandler(
@@ -80,10 +137,26 @@ export function createMessageHandler(
     handler: VirtualServerHandler,
 ) {
     let virtualServer: perspective.VirtualServer;
+    async function pollUpdates(port: MessagePort) {
+        try {
+            for (const responseBytes of await virtualServer.poll()) {
+                const buffer = responseBytes.slice().buffer;
+                port.postMessage(buffer, { transfer: [buffer] });
+            }
+        } catch (error) {
+            console.error("Error polling updates in worker:", error);
+        }
+    }
+
     async function postMessage(port: MessagePort, msg: MessageEvent) {
         if (msg.data.cmd === "init") {
             try {
                 virtualServer = new mod.VirtualServer(handler);
+                handler.setUpdateNotifier?.((tableId: string) => {
+                    virtualServer.notifyTableUpdate(tableId);
+                    pollUpdates(port);
+                });
+
                 if (msg.data.id !== undefined) {
                     port.postMessage({ id: msg.data.id });
                 } else {
@@ -98,8 +171,10 @@ export function createMessageHandler(
                 const requestBytes = new Uint8Array(msg.data);
                 const responseBytes =
                     await virtualServer.handleRequest(requestBytes);
-                const buffer = responseBytes.slice().buffer;
-                port.postMessage(buffer, { transfer: [buffer] });
+                if (responseBytes !== undefined) {
+                    const buffer = responseBytes.slice().buffer;
+                    port.postMessage(buffer, { transfer: [buffer] });
+                }
             } catch (error) {
                 console.error("Error handling request in worker:", error);
                 throw error;
@@ -27,82 +27,6 @@ import type { ViewConfig } from "@perspective-dev/client/dist/esm/ts-rs/ViewConf
 import type { ViewWindow } from "@perspective-dev/client/dist/esm/ts-rs/ViewWindow.d.ts";
 import type * as clickhouse from "@clickhouse/client-web";
 
-const NUMBER_AGGS = [
-    "sum",
-    "count",
-    "any_value",
-    "arbitrary",
-    "array_agg",
-    "avg",
-    "bit_and",
-    "bit_or",
-    "bit_xor",
-    "bitstring_agg",
-    "bool_and",
-    "bool_or",
-    "countif",
-    "favg",
-    "fsum",
-    "geomean",
-    "kahan_sum",
-    "last",
-    "max",
-    "min",
-    "product",
-    "string_agg",
-    "sumkahan",
-];
-
-const STRING_AGGS = [
-    "count",
-    "any_value",
-    "arbitrary",
-    "first",
-    "countif",
-    "last",
-    "string_agg",
-];
-
-const FILTER_OPS = [
-    "==",
-    "!=",
-    "LIKE",
-    "IS DISTINCT FROM",
-    "IS NOT DISTINCT FROM",
-    ">=",
-    "<=",
-    ">",
-    "<",
-];
-
-function duckdbTypeToPsp(name: string): ColumnType {
-    if (name.startsWith("Nullable")) {
-        name = name.match(/Nullable\((.+?)\)/)![1];
-    }
-
-    if (name.startsWith("Array")) {
-        return "string";
-    }
-
-    if (name === "Int64" || name === "UInt64" || name === "Float64") {
-        return "float";
-    }
-
-    if (name === "String") {
-        return "string";
-    }
-
-    if (name === "DateTime") {
-        return "datetime";
-    }
-
-    if (name === "Date") {
-        return "date";
-    }
-
-    throw new Error(`Unknown type '${name}'`);
-}
-
 function convertDecimalToNumber(value: any, dtypeString: string) {
     if (!(value instanceof Uint32Array || value instanceof Int32Array)) {
         return value;
@@ -212,38 +136,25 @@ export class ClickhouseHandler implements perspective.VirtualServerHandler {
 
         this.db = db;
         this.sqlBuilder = new mod!.GenericSQLVirtualServerModel({
+            dialect: "clickhouse",
             create_entity: "VIEW",
-            grouping_fn: "GROUPING",
         });
     }
 
     getFeatures() {
+        const features = this.sqlBuilder.getFeatures();
         return {
             group_by: true,
             split_by: false,
             sort: true,
             expressions: true,
-            filter_ops: {
-                integer: FILTER_OPS,
-                float: FILTER_OPS,
-                string: FILTER_OPS,
-                boolean: FILTER_OPS,
-                date: FILTER_OPS,
-                datetime: FILTER_OPS,
-            },
-            aggregates: {
-                integer: NUMBER_AGGS,
-                float: NUMBER_AGGS,
-                string: STRING_AGGS,
-                boolean: STRING_AGGS,
-                date: STRING_AGGS,
-                datetime: STRING_AGGS,
-            },
+            filter_ops: features.filter_ops,
+            aggregates: features.aggregates,
         };
     }
 
     async getHostedTables() {
-        const query = "SHOW TABLES";
+        const query = this.sqlBuilder.getHostedTables();
         const results = await runQuery(this.db, query);
         return results.map((row) => {
             return `${row.name}`;
@@ -257,7 +168,7 @@ export class ClickhouseHandler implements perspective.VirtualServerHandler {
         for (const result of results) {
             const colName = result.name;
             if (!colName.startsWith("__")) {
-                schema[colName] = duckdbTypeToPsp(result.type) as ColumnType;
+                schema[colName] = this.sqlBuilder.columnType(result.type) as ColumnType;
             }
         }
 
@@ -265,15 +176,9 @@ export class ClickhouseHandler implements perspective.VirtualServerHandler {
     }
 
     async viewColumnSize(viewId: string, config: ViewConfig) {
-        const query = `SELECT COUNT() FROM system.columns WHERE table = '${viewId}'`;
+        const query = this.sqlBuilder.viewColumnSize(viewId);
         const results = await runQuery(this.db, query);
-        const gs = config.group_by?.length || 0;
-        const count = Number(results[0]["COUNT()"]);
-        console.log(count);
-        return (
-            count -
-            (gs === 0 ? 0 : gs + (config.split_by?.length === 0 ? 1 : 0))
-        );
+        return Number(results[0]["COUNT()"]);
     }
 
     async tableSize(tableId: string) {
@@ -293,7 +198,7 @@ export class ClickhouseHandler implements perspective.VirtualServerHandler {
             expression,
         );
         const results = await runQuery(this.db, query);
-        return duckdbTypeToPsp(results[0]["type"]) as ColumnType;
+        return this.sqlBuilder.columnType(results[0]["type"]) as ColumnType;
     }
 
     async viewDelete(viewId: string) {
@@ -327,12 +232,8 @@ export class ClickhouseHandler implements perspective.VirtualServerHandler {
                 continue;
             }
 
-            let col = columns[cidx];
-            if (is_split_by && !col.startsWith("__ROW_PATH_")) {
-                col = col.replaceAll("_", "|");
-            }
-
-            const dtype = duckdbTypeToPsp(dtypes[cidx]) as ColumnType;
+            const col = columns[cidx];
+            const dtype = this.sqlBuilder.columnType(dtypes[cidx]) as ColumnType;
 
             const isDecimal = dtypes[cidx].startsWith("Decimal");
             for (let ridx = 0; ridx < rows.length; ridx++) {
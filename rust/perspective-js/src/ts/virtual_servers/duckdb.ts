@@ -27,53 +27,11 @@ import type { ViewConfig } from "@perspective-dev/client/dist/esm/ts-rs/ViewConf
 import type { ViewWindow } from "@perspective-dev/client/dist/esm/ts-rs/ViewWindow.d.ts";
 import type * as duckdb from "@duckdb/duckdb-wasm";
 
-const NUMBER_AGGS = [
-    "sum",
-    "count",
-    "any_value",
-    "arbitrary",
-    "array_agg",
-    "avg",
-    "bit_and",
-    "bit_or",
-    "bit_xor",
-    "bitstring_agg",
-    "bool_and",
-    "bool_or",
-    "countif",
-    "favg",
-    "fsum",
-    "geomean",
-    "kahan_sum",
-    "last",
-    "max",
-    "min",
-    "product",
-    "string_agg",
-    "sumkahan",
-];
-
-const STRING_AGGS = [
-    "count",
-    "any_value",
-    "arbitrary",
-    "first",
-    "countif",
-    "last",
-    "string_agg",
-];
-
-const FILTER_OPS = [
-    "==",
-    "!=",
-    "LIKE",
-    "IS DISTINCT FROM",
-    "IS NOT DISTINCT FROM",
-    ">=",
-    "<=",
-    ">",
-    "<",
-];
+const PRIMARY_KEYS_QUERY = `
+    SELECT database_name, table_name, constraint_column_names
+    FROM duckdb_constraints()
+    WHERE constraint_type = 'PRIMARY KEY'
+`;
 
 function duckdbTypeToPsp(name: string): ColumnType {
     name = name.toLowerCase();
@@ -203,36 +161,35 @@ export class DuckDBHandler implements perspective.VirtualServerHandler {
     }
 
     getFeatures() {
+        const features = this.sqlBuilder.getFeatures();
         return {
             group_by: true,
             split_by: true,
             sort: true,
             expressions: true,
-            filter_ops: {
-                integer: FILTER_OPS,
-                float: FILTER_OPS,
-                string: FILTER_OPS,
-                boolean: FILTER_OPS,
-                date: FILTER_OPS,
-                datetime: FILTER_OPS,
-            },
-            aggregates: {
-                integer: NUMBER_AGGS,
-                float: NUMBER_AGGS,
-                string: STRING_AGGS,
-                boolean: STRING_AGGS,
-                date: STRING_AGGS,
-                datetime: STRING_AGGS,
-            },
+            filter_ops: features.filter_ops,
+            aggregates: features.aggregates,
         };
     }
 
     async getHostedTables() {
         const query = this.sqlBuilder.getHostedTables();
         const results = await runQuery(this.db, query);
+        const indices = new Map<string, string>();
+        for (const row of await runQuery(this.db, PRIMARY_KEYS_QUERY)) {
+            const json = row.toJSON();
+            const columns = json.constraint_column_names.toArray();
+            if (columns.length === 1) {
+                const name = `${json.database_name}.${json.table_name}`;
+                indices.set(name, columns[0]);
+            }
+        }
+
         return results.map((row) => {
             const json = row.toJSON();
-            return `${json.database || "memory"}.${json.name}`;
+            const name = `${json.database || "memory"}.${json.name}`;
+            const index = indices.get(name);
+            return index === undefined ? name : { name, index };
         });
     }
 
@@ -256,9 +213,32 @@ export class DuckDBHandler implements perspective.VirtualServerHandler {
     async viewColumnSize(viewId: string, config: ViewConfig) {
         const query = this.sqlBuilder.viewColumnSize(viewId);
         const results = await runQuery(this.db, query);
+        return Number(Object.values(results[0].toJSON())[0]);
+    }
+
+    async viewSize(viewId: string, config: ViewConfig) {
+        const query = this.sqlBuilder.viewSize(viewId, config);
+        const results = await runQuery(this.db, query);
+        return Number(Object.values(results[0].toJSON())[0]);
+    }
+
+    async viewExpand(viewId: string, config: ViewConfig, rowIndex: number) {
+        const query = this.sqlBuilder.viewExpand(viewId, config, rowIndex);
+        const results = await runQuery(this.db, query);
+        const count = Number(Object.values(results[0].toJSON())[0]);
+        return Math.max(count - 1, 0);
+    }
+
+    async viewCollapse(viewId: string, config: ViewConfig, rowIndex: number) {
+        const query = this.sqlBuilder.viewCollapse(viewId, config, rowIndex);
+        const results = await runQuery(this.db, query);
         const count = Number(Object.values(results[0].toJSON())[0]);
-        const gs = config.group_by?.length || 0;
-        return count - (gs === 0 ? 0 : gs + 1);
+        return Math.max(count - 1, 0);
+    }
+
+    async viewSetDepth(viewId: string, config: ViewConfig, depth: number) {
+        const query = this.sqlBuilder.viewSetDepth(viewId, config, depth);
+        await runQuery(this.db, query);
     }
 
     async tableSize(tableId: string) {
@@ -283,6 +263,37 @@ export class DuckDBHandler implements perspective.VirtualServerHandler {
         ) as ColumnType;
     }
 
+    async tableUpdate(
+        tableId: string,
+        index: string | null,
+        data: perspective.MakeTableData,
+    ) {
+        for (const query of this.sqlBuilder.tableUpdate(tableId, index, data)) {
+            await runQuery(this.db, query);
+        }
+    }
+
+    async tableRemove(
+        tableId: string,
+        index: string | null,
+        data: perspective.MakeTableData,
+    ) {
+        for (const query of this.sqlBuilder.tableRemove(tableId, index, data)) {
+            await runQuery(this.db, query);
+        }
+    }
+
+    async tableReplace(tableId: string, data: perspective.MakeTableData) {
+        for (const query of this.sqlBuilder.tableReplace(tableId, data)) {
+            await runQuery(this.db, query);
+        }
+    }
+
+    async tableDelete(tableId: string) {
+        const query = this.sqlBuilder.tableDelete(tableId);
+        await runQuery(this.db, query);
+    }
+
     async viewDelete(viewId: string) {
         const query = this.sqlBuilder.viewDelete(viewId);
         await runQuery(this.db, query);
@@ -296,7 +307,6 @@ export class DuckDBHandler implements perspective.VirtualServerHandler {
         dataSlice: perspective.VirtualDataSlice,
     ) {
         const is_group_by = config.group_by?.length > 0;
-        const is_split_by = config.split_by?.length > 0;
         const query = this.sqlBuilder.viewGetData(
             viewId,
             config,
@@ -314,11 +324,7 @@ export class DuckDBHandler implements perspective.VirtualServerHandler {
                 continue;
             }
 
-            let col = columns[cidx];
-            if (is_split_by && !col.startsWith("__")) {
-                col = col.replaceAll("_", "|");
-            }
-
+            const col = columns[cidx];
             const dtype = duckdbTypeToPsp(dtypes[cidx]) as ColumnType;
             const isDecimal = dtypes[cidx].startsWith("Decimal");
             for (let ridx = 0; ridx < rows.length; ridx++) {
@@ -422,10 +422,18 @@ test.describe("DuckDB Virtual Server", function () {
 
             const columns = await view.column_paths();
             expect(columns).toEqual([
-                "Central_Sales",
-                "East_Sales",
-                "South_Sales",
-                "West_Sales",
+                "Central|Sales",
+                "East|Sales",
+                "South|Sales",
+                "West|Sales",
+            ]);
+
+            const structured = await view.column_paths_structured();
+            expect(structured).toEqual([
+                ["Central", "Sales"],
+                ["East", "Sales"],
+                ["South", "Sales"],
+                ["West", "Sales"],
             ]);
 
             const json = await view.to_json();
@@ -579,11 +587,11 @@ test.describe("DuckDB Virtual Server", function () {
             await view.delete();
         });
 
-        test("filter with LIKE", async function () {
+        test("filter with begins with", async function () {
             const table = await client.open_table("memory.superstore");
             const view = await table.view({
                 columns: ["Sales", "State"],
-                filter: [["State", "LIKE", "Cal%"]],
+                filter: [["State", "begins with", "Cal"]],
             });
             const json = await view.to_json({ start_row: 0, end_row: 5 });
             expect(json).toEqual([
@@ -927,6 +935,39 @@ test.describe("DuckDB Virtual Server", function () {
         });
     });
 
+    test.describe("export", () => {
+        test("to_csv()", async function () {
+            const table = await client.open_table("memory.superstore");
+            const view = await table.view({
+                columns: ["Sales", "Profit"],
+            });
+            const csv = await view.to_csv({ start_row: 0, end_row: 3 });
+            expect(csv).toEqual(
+                "Sales,Profit\n261.96,41.9136\n731.94,219.582\n14.62,6.8714\n",
+            );
+            await view.delete();
+        });
+
+        test("to_arrow()", async function () {
+            const table = await client.open_table("memory.superstore");
+            const view = await table.view({
+                columns: ["Sales", "Profit"],
+            });
+            const arrow = await view.to_arrow({ start_row: 0, end_row: 3 });
+            const exported = await perspective.table(arrow);
+            const exported_view = await exported.view();
+            const json = await exported_view.to_json();
+            expect(json).toEqual([
+                { Sales: 261.96, Profit: 41.9136 },
+                { Sales: 731.94, Profit: 219.582 },
+                { Sales: 14.62, Profit: 6.8714 },
+            ]);
+            await exported_view.delete();
+            await exported.delete();
+            await view.delete();
+        });
+    });
+
     test.describe("combined operations", () => {
         test("group_by + filter + sort", async function () {
             const table = await client.open_table("memory.superstore");
@@ -968,10 +1009,10 @@ test.describe("DuckDB Virtual Server", function () {
 
             const paths = await view.column_paths();
             expect(paths).toEqual([
-                "Central_Sales",
-                "East_Sales",
-                "South_Sales",
-                "West_Sales",
+                "Central|Sales",
+                "East|Sales",
+                "South|Sales",
+                "West|Sales",
             ]);
 
             const numRows = await view.num_rows();
@@ -1021,10 +1062,10 @@ test.describe("DuckDB Virtual Server", function () {
 
             const paths = await view.column_paths();
             expect(paths).toEqual([
-                "Central_Sales",
-                "East_Sales",
-                "South_Sales",
-                "West_Sales",
+                "Central|Sales",
+                "East|Sales",
+                "South|Sales",
+                "West|Sales",
             ]);
 
             const numRows = await view.num_rows();
@@ -306,10 +306,17 @@ class TestDuckDBSplitBy:
 
         column_paths = view.column_paths()
         assert column_paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
+        ]
+
+        assert view.column_paths_structured() == [
+            ["Central", "Sales"],
+            ["East", "Sales"],
+            ["South", "Sales"],
+            ["West", "Sales"],
         ]
 
         json = view.to_json()
@@ -455,11 +462,11 @@ class TestDuckDBFilter:
         ]
         view.delete()
 
-    def test_filter_with_like(self, client):
+    def test_filter_with_begins_with(self, client):
         table = client.open_table("memory.superstore")
         view = table.view(
             columns=["Sales", "State"],
-            filter=[["State", "LIKE", "Cal%"]],
+            filter=[["State", "begins with", "Cal"]],
         )
         json = view.to_json(start_row=0, end_row=5)
         assert json == [
@@ -748,10 +755,10 @@ class TestDuckDBCombinedOperations:
 
         paths = view.column_paths()
         assert paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
         ]
 
         num_rows = view.num_rows()
@@ -800,10 +807,10 @@ class TestDuckDBCombinedOperations:
 
         paths = view.column_paths()
         assert paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
         ]
 
         num_rows = view.num_rows()
@@ -838,3 +845,142 @@ class TestDuckDBCombinedOperations:
             {"__ROW_PATH__": ["Central"], "profitmargin": -10.407293926323575},
         ]
         view.delete()
+
+
+class TestDuckDBMinMax:
+    def test_min_max(self, client):
+        table = client.open_table("memory.superstore")
+        view = table.view(columns=["Quantity"])
+        min, max = view.get_min_max("Quantity")
+        assert (float(min), float(max)) == (1, 14)
+        view.delete()
+
+    def test_min_max_group_by_excludes_total(self, client):
+        table = client.open_table("memory.superstore")
+        view = table.view(
+            columns=["Sales"], group_by=["Region"], aggregates={"Sales": "sum"}
+        )
+        min, max = view.get_min_max("Sales")
+        assert 0 < float(min) < float(max) < 1_000_000
+        view.delete()
+
+
+class TestDuckDBExpandCollapse:
+    def test_collapse_and_expand(self, client):
+        table = client.open_table("memory.superstore")
+        view = table.view(
+            columns=["Sales"],
+            group_by=["Region", "Category"],
+            aggregates={"Sales": "sum"},
+        )
+        assert view.num_rows() == 17
+        assert view.collapse(1) == 3
+        assert view.num_rows() == 14
+        json = view.to_json(start_row=0, end_row=3)
+        assert [row["__ROW_PATH__"] for row in json] == [
+            [],
+            ["Central"],
+            ["East"],
+        ]
+        assert view.expand(1) == 3
+        assert view.num_rows() == 17
+        view.delete()
+
+    def test_set_depth(self, client):
+        table = client.open_table("memory.superstore")
+        view = table.view(
+            columns=["Sales"],
+            group_by=["Region", "Category"],
+            aggregates={"Sales": "sum"},
+        )
+        view.set_depth(0)
+        json = view.to_json()
+        assert [row["__ROW_PATH__"] for row in json] == [
+            [],
+            ["Central"],
+            ["East"],
+            ["South"],
+            ["West"],
+        ]
+        assert view.expand(2) == 3
+        assert view.num_rows() == 8
+        view.set_depth(1)
+        assert view.num_rows() == 17
+        view.delete()
+
+
+class TestDuckDBOnUpdate:
+    def test_on_update_fires_on_table_update(self):
+        db = duckdb.connect()
+        db.execute("CREATE TABLE points AS SELECT * FROM range(3) t(x)")
+        server = DuckDBVirtualServer(db)
+
+        def handle_request(msg):
+            session.handle_request(msg)
+
+        def handle_response(msg):
+            c.handle_response(msg)
+
+        session = server.new_session(handle_response)
+        c = Client(handle_request)
+        view = c.open_table("memory.points").view()
+        updates = []
+        sub = view.on_update(lambda *args: updates.append(args))
+        assert updates == []
+        db.execute("INSERT INTO points VALUES (3)")
+        server.notify_table_update("memory.points")
+        assert len(updates) == 1
+        assert view.num_rows() == 4
+        view.remove_update(sub)
+        server.notify_table_update("memory.points")
+        assert len(updates) == 1
+        view.delete()
+
+
+class TestDuckDBWrite:
+    def make_client(self):
+        db = duckdb.connect()
+        db.execute("CREATE TABLE points (id INTEGER PRIMARY KEY, name VARCHAR)")
+        db.execute("CREATE TABLE log (x INTEGER)")
+        server = DuckDBVirtualServer(db)
+
+        def handle_request(msg):
+            session.handle_request(msg)
+
+        def handle_response(msg):
+            c.handle_response(msg)
+
+        session = server.new_session(handle_response)
+        c = Client(handle_request)
+        return c
+
+    def test_update_appends_without_index(self):
+        client = self.make_client()
+        table = client.open_table("memory.log")
+        table.update([{"x": 1}, {"x": 2}])
+        table.update({"x": [3]})
+        view = table.view()
+        assert view.to_columns() == {"x": [1, 2, 3]}
+        view.delete()
+
+    def test_update_and_remove_with_primary_key(self):
+        client = self.make_client()
+        table = client.open_table("memory.points")
+        table.update([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}])
+        table.update([{"id": 2, "name": "c"}, {"id": 3, "name": "d"}])
+        view = table.view(sort=[["id", "asc"]])
+        assert view.to_columns() == {"id": [1, 2, 3], "name": ["a", "c", "d"]}
+        table.remove([1, 3])
+        assert view.to_columns() == {"id": [2], "name": ["c"]}
+        view.delete()
+
+    def test_replace_and_clear(self):
+        client = self.make_client()
+        table = client.open_table("memory.log")
+        table.update([{"x": 1}])
+        table.replace([{"x": 5}, {"x": 6}])
+        view = table.view()
+        assert view.to_columns() == {"x": [5, 6]}
+        table.clear()
+        assert view.num_rows() == 0
+        view.delete()
@@ -462,10 +462,10 @@ class TestPolarsSplitBy:
 
         column_paths = view.column_paths()
         assert column_paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
         ]
 
         json = view.to_json()
@@ -706,10 +706,10 @@ class TestPolarsCombinedOperations:
 
         paths = view.column_paths()
         assert paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
         ]
 
         num_rows = view.num_rows()
@@ -760,10 +760,10 @@ class TestPolarsCombinedOperations:
 
         paths = view.column_paths()
         assert paths == [
-            "Central_Sales",
-            "East_Sales",
-            "South_Sales",
-            "West_Sales",
+            "Central|Sales",
+            "East|Sales",
+            "South|Sales",
+            "West|Sales",
         ]
 
         num_rows = view.num_rows()
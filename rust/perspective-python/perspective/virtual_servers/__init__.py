@@ -93,7 +93,7 @@ class VirtualServerHandler:
     def view_schema(self, view_name, config):
         return self.table_schema(view_name)
 
-    def view_size(self, view_name):
+    def view_size(self, view_name, config=None):
         return self.table_size(view_name)
 
     def table_make_view(self, table_name, view_name, config):
@@ -114,6 +114,75 @@ class VirtualServerHandler:
 
         pass
 
+    def view_get_min_max(self, view_name, column_name, config):
+        """
+        [OPTIONAL] Get the `(min, max)` of a column `column_name` in temporary
+        table `view_name`, used by gradient cell styling and bar chart scales.
+        For grouped views, only the leaf (most-grouped) rows should be
+        considered. Dates and datetimes should be returned as milliseconds
+        since epoch.
+        """
+
+        return (None, None)
+
+    def view_expand(self, view_name, config, row_index):
+        """
+        [OPTIONAL] Expand the grouped row at `row_index` (counting only visible
+        rows) of temporary table `view_name`, returning the number of rows
+        which became visible.
+        """
+
+        return 0
+
+    def view_collapse(self, view_name, config, row_index):
+        """
+        [OPTIONAL] Collapse the grouped row at `row_index` (counting only
+        visible rows) of temporary table `view_name`, returning the number of
+        rows which were hidden.
+        """
+
+        return 0
+
+    def view_set_depth(self, view_name, config, depth):
+        """
+        [OPTIONAL] Expand all grouped rows of temporary table `view_name` to
+        `depth`, collapsing rows deeper than this.
+        """
+
+        pass
+
+    def table_update(self, table_name, index, data):
+        """
+        [OPTIONAL] Write `data` (a `perspective.MakeTableData`) to table
+        `table_name`, as `Table.update` does. `index` is the table's index
+        column as reported by `get_hosted_tables`, or `None`.
+        """
+
+        raise NotImplementedError("table_update not implemented")
+
+    def table_remove(self, table_name, index, data):
+        """
+        [OPTIONAL] Remove the rows of table `table_name` whose `index` column
+        values are listed in `data`, as `Table.remove` does.
+        """
+
+        raise NotImplementedError("table_remove not implemented")
+
+    def table_replace(self, table_name, data):
+        """
+        [OPTIONAL] Replace all rows of table `table_name` with `data`, as
+        `Table.replace` and `Table.clear` do.
+        """
+
+        raise NotImplementedError("table_replace not implemented")
+
+    def table_delete(self, table_name):
+        """
+        [OPTIONAL] Delete table `table_name`, as `Table.delete` does.
+        """
+
+        raise NotImplementedError("table_delete not implemented")
+
     def view_delete(self, view_name):
         """
         Delete a temporary table. The UI will do this automatically, and it
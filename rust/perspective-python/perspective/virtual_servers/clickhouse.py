@@ -12,61 +12,16 @@
 
 import perspective
 
-from datetime import datetime
+from datetime import date, datetime
+from decimal import Decimal
+import calendar
 import logging
+import weakref
 
 from perspective.virtual_servers import VirtualServerHandler
 
 logger = logging.getLogger(__name__)
 
-NUMBER_AGGS = [
-    "sum",
-    "count",
-    "any_value",
-    "arbitrary",
-    "array_agg",
-    "avg",
-    "bit_and",
-    "bit_or",
-    "bit_xor",
-    "bitstring_agg",
-    "bool_and",
-    "bool_or",
-    "countif",
-    "favg",
-    "fsum",
-    "geomean",
-    "kahan_sum",
-    "last",
-    "max",
-    "min",
-    "product",
-    "string_agg",
-    "sumkahan",
-]
-
-STRING_AGGS = [
-    "count",
-    "any_value",
-    "arbitrary",
-    "first",
-    "countif",
-    "last",
-    "string_agg",
-]
-
-FILTER_OPS = [
-    "==",
-    "!=",
-    "LIKE",
-    "IS DISTINCT FROM",
-    "IS NOT DISTINCT FROM",
-    ">=",
-    "<=",
-    ">",
-    "<",
-]
-
 
 class ClickhouseVirtualSession:
     def __init__(self, callback, db):
@@ -74,15 +29,34 @@ class ClickhouseVirtualSession:
         self.callback = callback
 
     def handle_request(self, msg):
-        self.callback(self.session.handle_request(msg))
+        resp = self.session.handle_request(msg)
+        if resp is not None:
+            self.callback(resp)
+
+    def notify_table_update(self, table_name):
+        self.session.notify_table_update(table_name)
+        for resp in self.session.poll():
+            self.callback(resp)
 
 
 class ClickhouseVirtualServer:
     def __init__(self, db):
         self.db = db
+        self.sessions = weakref.WeakSet()
 
     def new_session(self, callback):
-        return ClickhouseVirtualSession(callback, self.db)
+        session = ClickhouseVirtualSession(callback, self.db)
+        self.sessions.add(session)
+        return session
+
+    def notify_table_update(self, table_name):
+        """
+        Notify every session that `table_name` has changed, recalculating its
+        views and firing their `on_update` callbacks.
+        """
+
+        for session in list(self.sessions):
+            session.notify_table_update(table_name)
 
 
 class ClickhouseVirtualServerHandler(VirtualServerHandler):
@@ -93,35 +67,23 @@ class ClickhouseVirtualServerHandler(VirtualServerHandler):
     def __init__(self, db):
         self.db = db
         self.sql_builder = perspective.GenericSQLVirtualServerModel(
-            {"create_entity": "VIEW", "grouping_fn": "GROUPING"}
+            {"dialect": "clickhouse", "create_entity": "VIEW"}
         )
 
     def get_features(self):
+        features = self.sql_builder.get_features()
         return {
             "group_by": True,
             "split_by": False,
             "sort": True,
             "expressions": True,
-            "filter_ops": {
-                "integer": FILTER_OPS,
-                "float": FILTER_OPS,
-                "string": FILTER_OPS,
-                "boolean": FILTER_OPS,
-                "date": FILTER_OPS,
-                "datetime": FILTER_OPS,
-            },
-            "aggregates": {
-                "integer": NUMBER_AGGS,
-                "float": NUMBER_AGGS,
-                "string": STRING_AGGS,
-                "boolean": STRING_AGGS,
-                "date": STRING_AGGS,
-                "datetime": STRING_AGGS,
-            },
+            "on_update": True,
+            "filter_ops": features["filter_ops"],
+            "aggregates": features["aggregates"],
         }
 
     def get_hosted_tables(self):
-        query = "SHOW TABLES"
+        query = self.sql_builder.get_hosted_tables()
         results = run_query(self.db, query)
         return [result[0] for result in results]
 
@@ -132,17 +94,14 @@ class ClickhouseVirtualServerHandler(VirtualServerHandler):
         for result in results:
             col_name = result[0]
             if not col_name.startswith("__"):
-                schema[col_name] = clickhouse_type_to_psp(result[1])
+                schema[col_name] = self.sql_builder.column_type(result[1])
 
         return schema
 
     def view_column_size(self, view_name, config):
-        query = f"SELECT COUNT() FROM system.columns WHERE table = '{view_name}'"
+        query = self.sql_builder.view_column_size(view_name)
         results = run_query(self.db, query)
-        gs = len(config["group_by"])
-        return results[0][0] - (
-            0 if gs == 0 else gs + (1 if len(config["split_by"]) == 0 else 0)
-        )
+        return results[0][0]
 
     def table_size(self, table_name):
         query = self.sql_builder.table_size(table_name)
@@ -156,7 +115,12 @@ class ClickhouseVirtualServerHandler(VirtualServerHandler):
     def table_validate_expression(self, view_name, expression):
         query = self.sql_builder.table_validate_expression(view_name, expression)
         results = run_query(self.db, query)
-        return clickhouse_type_to_psp(results[0][1])
+        return self.sql_builder.column_type(results[0][1])
+
+    def view_get_min_max(self, view_name, column_name, config):
+        query = self.sql_builder.view_get_min_max(view_name, column_name, config)
+        results = run_query(self.db, query)
+        return tuple(scalar_to_psp(value) for value in results[0])
 
     def view_delete(self, view_name):
         query = self.sql_builder.view_delete(view_name)
@@ -171,14 +135,11 @@ class ClickhouseVirtualServerHandler(VirtualServerHandler):
             if cidx == 0 and len(group_by) > 0 and len(split_by) == 0:
                 continue
 
-            if len(split_by) > 0 and not col.startswith("__ROW_PATH_"):
-                col = col.replace("_", "|")
-
             # print(
             #     dtypes[cidx], type(dtypes[cidx]), dir(dtypes[cidx]), dtypes[cidx].name
             # )
 
-            dtype = clickhouse_type_to_psp(str(dtypes[cidx]))
+            dtype = self.sql_builder.column_type(str(dtypes[cidx]))
             for ridx, row in enumerate(results):
                 grouping_id = (
                     row[0] if len(group_by) > 0 and len(split_by) == 0 else None
@@ -196,28 +157,16 @@ class ClickhouseVirtualServerHandler(VirtualServerHandler):
 # ClickHouse Utils
 
 
-def clickhouse_type_to_psp(name):
-    """Convert a ClickHouse `dtype` to a Perspective `ColumnType`."""
-    if name.startswith("Nullable(") and name.endswith(")"):
-        name = name[9:-1]
-
-    if name.startswith("Array"):
-        return "string"
-
-    if name in ("Int64", "UInt64", "Float64"):
-        return "float"
-
-    if name == "String":
-        return "string"
-
-    if name == "DateTime":
-        return "datetime"
-
-    if name == "Date":
-        return "date"
+def scalar_to_psp(value):
+    """Convert a query result scalar to a JSON-safe Perspective scalar."""
+    if isinstance(value, datetime):
+        return calendar.timegm(value.utctimetuple()) * 1000 + value.microsecond // 1000
+    if isinstance(value, date):
+        return calendar.timegm(value.timetuple()) * 1000
+    if isinstance(value, Decimal):
+        return float(value)
 
-    msg = f"Unknown type '{name}'"
-    raise ValueError(msg)
+    return value
 
 
 def run_query(db, query, execute=False, columns=False):
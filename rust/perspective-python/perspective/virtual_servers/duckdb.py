@@ -13,70 +13,21 @@
 import duckdb
 import perspective
 
-from datetime import datetime
+from datetime import date, datetime
+from decimal import Decimal
+import calendar
 import logging
+import weakref
 
 from perspective.virtual_servers import VirtualServerHandler
 
 logger = logging.getLogger(__name__)
 
-NUMBER_AGGS = [
-    "sum",
-    "count",
-    "any_value",
-    "arbitrary",
-    # "arg_max",
-    # "arg_max_null",
-    # "arg_min",
-    # "arg_min_null",
-    "array_agg",
-    "avg",
-    "bit_and",
-    "bit_or",
-    "bit_xor",
-    "bitstring_agg",
-    "bool_and",
-    "bool_or",
-    "countif",
-    "favg",
-    "fsum",
-    "geomean",
-    # "histogram",
-    # "histogram_values",
-    "kahan_sum",
-    "last",
-    # "list"
-    "max",
-    # "max_by"
-    "min",
-    # "min_by"
-    "product",
-    "string_agg",
-    "sumkahan",
-    # "weighted_avg",
-]
-
-STRING_AGGS = [
-    "count",
-    "any_value",
-    "arbitrary",
-    "first",
-    "countif",
-    "last",
-    "string_agg",
-]
-
-FILTER_OPS = [
-    "==",
-    "!=",
-    "LIKE",
-    "IS DISTINCT FROM",
-    "IS NOT DISTINCT FROM",
-    ">=",
-    "<=",
-    ">",
-    "<",
-]
+PRIMARY_KEYS_QUERY = """
+    SELECT database_name, table_name, constraint_column_names
+    FROM duckdb_constraints()
+    WHERE constraint_type = 'PRIMARY KEY'
+"""
 
 
 class DuckDBVirtualSession:
@@ -85,15 +36,40 @@ class DuckDBVirtualSession:
         self.callback = callback
 
     def handle_request(self, msg):
-        self.callback(self.session.handle_request(msg))
+        resp = self.session.handle_request(msg)
+        if resp is not None:
+            self.callback(resp)
+
+        # Writes via `Table.update` etc. mark their table as updated.
+        self.poll()
+
+    def notify_table_update(self, table_name):
+        self.session.notify_table_update(table_name)
+        self.poll()
+
+    def poll(self):
+        for resp in self.session.poll():
+            self.callback(resp)
 
 
 class DuckDBVirtualServer:
     def __init__(self, db):
         self.db = db
+        self.sessions = weakref.WeakSet()
 
     def new_session(self, callback):
-        return DuckDBVirtualSession(callback, self.db)
+        session = DuckDBVirtualSession(callback, self.db)
+        self.sessions.add(session)
+        return session
+
+    def notify_table_update(self, table_name):
+        """
+        Notify every session that `table_name` has changed, recalculating its
+        views and firing their `on_update` callbacks.
+        """
+
+        for session in list(self.sessions):
+            session.notify_table_update(table_name)
 
 
 class DuckDBVirtualServerHandler(VirtualServerHandler):
@@ -106,33 +82,35 @@ class DuckDBVirtualServerHandler(VirtualServerHandler):
         self.sql_builder = perspective.GenericSQLVirtualServerModel({})
 
     def get_features(self):
+        features = self.sql_builder.get_features()
         return {
             "group_by": True,
             "split_by": True,
             "sort": True,
             "expressions": True,
-            "filter_ops": {
-                "integer": FILTER_OPS,
-                "float": FILTER_OPS,
-                "string": FILTER_OPS,
-                "boolean": FILTER_OPS,
-                "date": FILTER_OPS,
-                "datetime": FILTER_OPS,
-            },
-            "aggregates": {
-                "integer": NUMBER_AGGS,
-                "float": NUMBER_AGGS,
-                "string": STRING_AGGS,
-                "boolean": STRING_AGGS,
-                "date": STRING_AGGS,
-                "datetime": STRING_AGGS,
-            },
+            "on_update": True,
+            "filter_ops": features["filter_ops"],
+            "aggregates": features["aggregates"],
         }
 
     def get_hosted_tables(self):
         query = self.sql_builder.get_hosted_tables()
         results = run_query(self.db, query)
-        return [f"{result[0]}.{result[2]}" for result in results]
+        indices = {
+            f"{database}.{table}": columns[0]
+            for database, table, columns in run_query(self.db, PRIMARY_KEYS_QUERY)
+            if len(columns) == 1
+        }
+
+        tables = []
+        for result in results:
+            name = f"{result[0]}.{result[2]}"
+            if name in indices:
+                tables.append({"name": name, "index": indices[name]})
+            else:
+                tables.append(name)
+
+        return tables
 
     def table_schema(self, table_name, config=None):
         query = self.sql_builder.table_schema(table_name)
@@ -148,10 +126,12 @@ class DuckDBVirtualServerHandler(VirtualServerHandler):
     def view_column_size(self, table_name, config):
         query = self.sql_builder.view_column_size(table_name)
         results = run_query(self.db, query)
-        gs = len(config["group_by"])
-        return results[0][0] - (
-            0 if gs == 0 else gs + (1 if len(config["split_by"]) == 0 else 0)
-        )
+        return results[0][0]
+
+    def view_size(self, view_name, config):
+        query = self.sql_builder.view_size(view_name, config)
+        results = run_query(self.db, query)
+        return results[0][0]
 
     def table_size(self, table_name):
         query = self.sql_builder.table_size(table_name)
@@ -167,24 +147,54 @@ class DuckDBVirtualServerHandler(VirtualServerHandler):
         results = run_query(self.db, query)
         return duckdb_type_to_psp(results[0][1])
 
+    def table_update(self, table_name, index, data):
+        for query in self.sql_builder.table_update(table_name, index, data):
+            run_query(self.db, query, execute=True)
+
+    def table_remove(self, table_name, index, data):
+        for query in self.sql_builder.table_remove(table_name, index, data):
+            run_query(self.db, query, execute=True)
+
+    def table_replace(self, table_name, data):
+        for query in self.sql_builder.table_replace(table_name, data):
+            run_query(self.db, query, execute=True)
+
+    def table_delete(self, table_name):
+        query = self.sql_builder.table_delete(table_name)
+        run_query(self.db, query, execute=True)
+
+    def view_get_min_max(self, view_name, column_name, config):
+        query = self.sql_builder.view_get_min_max(view_name, column_name, config)
+        results = run_query(self.db, query)
+        return tuple(scalar_to_psp(value) for value in results[0])
+
+    def view_expand(self, view_name, config, row_index):
+        query = self.sql_builder.view_expand(view_name, config, row_index)
+        results = run_query(self.db, query, execute=True)
+        return max(results[0][0] - 1, 0)
+
+    def view_collapse(self, view_name, config, row_index):
+        query = self.sql_builder.view_collapse(view_name, config, row_index)
+        results = run_query(self.db, query, execute=True)
+        return max(results[0][0] - 1, 0)
+
+    def view_set_depth(self, view_name, config, depth):
+        query = self.sql_builder.view_set_depth(view_name, config, depth)
+        run_query(self.db, query, execute=True)
+
     def view_delete(self, view_name):
         query = self.sql_builder.view_delete(view_name)
         run_query(self.db, query, execute=True)
 
     def view_get_data(self, view_name, config, schema, viewport, data):
         group_by = config["group_by"]
-        split_by = config["split_by"]
         is_group_by = len(group_by) > 0
-        is_split_by = len(split_by) > 0
         query = self.sql_builder.view_get_data(view_name, config, viewport, schema)
         results, columns, dtypes = run_query(self.db, query, columns=True)
         for cidx, col in enumerate(columns):
             if cidx == 0 and is_group_by:
                 continue
 
-            if is_split_by and not col.startswith("__"):
-                col = col.replace("_", "|")
-
             dtype = duckdb_type_to_psp(str(dtypes[cidx]))
             for ridx, row in enumerate(results):
                 grouping_id = row[0] if is_group_by else None
@@ -215,13 +225,25 @@ def duckdb_type_to_psp(name):
     raise ValueError(msg)
 
 
+def scalar_to_psp(value):
+    """Convert a query result scalar to a JSON-safe Perspective scalar."""
+    if isinstance(value, datetime):
+        return calendar.timegm(value.utctimetuple()) * 1000 + value.microsecond // 1000
+    if isinstance(value, date):
+        return calendar.timegm(value.timetuple()) * 1000
+    if isinstance(value, Decimal):
+        return float(value)
+
+    return value
+
+
 def run_query(db, query, execute=False, columns=False):
     query = " ".join(query.split())
     start = datetime.now()
     result = None
     try:
         if execute:
-            db.execute(query)
+            result = db.execute(query).fetchall()
         else:
             req = db.sql(query)
             result = req.fetchall()
@@ -16,6 +16,7 @@ import perspective
 
 from datetime import datetime
 import re
+import weakref
 
 from perspective.virtual_servers import VirtualServerHandler
 
@@ -69,15 +70,34 @@ class PolarsVirtualSession:
         self.callback = callback
 
     def handle_request(self, msg):
-        self.callback(self.session.handle_request(msg))
+        resp = self.session.handle_request(msg)
+        if resp is not None:
+            self.callback(resp)
+
+    def notify_table_update(self, table_name):
+        self.session.notify_table_update(table_name)
+        for resp in self.session.poll():
+            self.callback(resp)
 
 
 class PolarsVirtualServer:
     def __init__(self, tables):
         self.tables = tables
+        self.sessions = weakref.WeakSet()
 
     def new_session(self, callback):
-        return PolarsVirtualSession(callback, self.tables)
+        session = PolarsVirtualSession(callback, self.tables)
+        self.sessions.add(session)
+        return session
+
+    def notify_table_update(self, table_name):
+        """
+        Notify every session that `table_name` has changed, recalculating its
+        views and firing their `on_update` callbacks.
+        """
+
+        for session in list(self.sessions):
+            session.notify_table_update(table_name)
 
 
 class PolarsVirtualServerHandler(VirtualServerHandler):
@@ -96,6 +116,7 @@ class PolarsVirtualServerHandler(VirtualServerHandler):
             "split_by": True,
             "sort": True,
             "expressions": True,
+            "on_update": True,
             "filter_ops": {
                 "integer": FILTER_OPS,
                 "float": FILTER_OPS,
@@ -133,7 +154,7 @@ class PolarsVirtualServerHandler(VirtualServerHandler):
             return self.view_schemas[view_name]
         return self.table_schema(view_name)
 
-    def view_size(self, view_name):
+    def view_size(self, view_name, config=None):
         if view_name in self.views:
             return self.views[view_name].height
         return self.table_size(view_name)
@@ -648,6 +648,20 @@ impl AsyncView {
         self.view.column_paths(window).await.into_pyerr()
     }
 
+    /// Returns the column paths of the [`View`] as in [`View::column_paths`],
+    /// but with each path as a list of its elements rather than a `|`-joined
+    /// string, which is unambiguous for column names containing `|`.
+    pub async fn column_paths_structured(
+        &self,
+        window: Option<Py<PyDict>>,
+    ) -> PyResult<Vec<Vec<String>>> {
+        let window: ColumnWindow = Python::with_gil(|py| window.map(|x| depythonize(x.bind(py))))
+            .transpose()?
+            .unwrap_or_default();
+
+        self.view.column_paths_structured(window).await.into_pyerr()
+    }
+
     /// Delete this [`View`] and clean up all resources associated with it.
     /// [`View`] objects do not stop consuming resources or processing
     /// updates when they are garbage collected - you must call this method
@@ -684,6 +698,10 @@ impl AsyncView {
         self.view.collapse(index).await.into_pyerr()
     }
 
+    pub async fn set_depth(&self, depth: u32) -> PyResult<()> {
+        self.view.set_depth(depth).await.into_pyerr()
+    }
+
     /// The expression schema of this [`View`], which contains only the
     /// expressions created on this [`View`]. See [`View::schema`] for
     /// details.
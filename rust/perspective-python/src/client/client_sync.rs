@@ -496,6 +496,18 @@ impl View {
         self.0.column_paths(window).py_block_on(py)
     }
 
+    /// Returns the column paths of the [`View`] as in [`View::column_paths`],
+    /// but with each path as a list of its elements rather than a `|`-joined
+    /// string, which is unambiguous for column names containing `|`.
+    #[pyo3(signature = (**window))]
+    pub fn column_paths_structured(
+        &self,
+        py: Python<'_>,
+        window: Option<Py<PyDict>>,
+    ) -> PyResult<Vec<Vec<String>>> {
+        self.0.column_paths_structured(window).py_block_on(py)
+    }
+
     /// Renders this [`View`] as a column-oriented JSON string. Useful if you
     /// want to save additional round trip serialize/deserialize cycles.  
     #[pyo3(signature = (**window))]
@@ -605,6 +617,11 @@ impl View {
         self.0.collapse(index).py_block_on(py)
     }
 
+    /// Set expansion `depth` of the `group_by` tree.
+    pub fn set_depth(&self, py: Python<'_>, depth: u32) -> PyResult<()> {
+        self.0.set_depth(depth).py_block_on(py)
+    }
+
     /// Returns this [`View`]'s _dimensions_, row and column count, as well as
     /// those of the [`crate::Table`] from which it was derived.
     ///
@@ -13,12 +13,14 @@
 use std::str::FromStr;
 
 use indexmap::IndexMap;
-use perspective_client::config::ViewConfig;
+use perspective_client::config::{Scalar, ViewConfig};
 use perspective_client::proto::{ColumnType, ViewPort};
-use perspective_client::virtual_server::GenericSQLVirtualServerModel;
+use perspective_client::virtual_server::{GenericSQLError, GenericSQLVirtualServerModel};
 use pyo3::exceptions::PyValueError;
 use pyo3::types::{PyAnyMethods, PyDict, PyDictMethods};
-use pyo3::{Py, PyAny, PyResult, Python, pyclass, pymethods};
+use pyo3::{Py, PyAny, PyRef, PyResult, Python, pyclass, pymethods};
+
+use super::virtual_server_sync::PyMakeTableData;
 
 #[pyclass(name = "GenericSQLVirtualServerModel")]
 pub struct PyGenericSQLVirtualServerModel {
@@ -28,14 +30,24 @@ pub struct PyGenericSQLVirtualServerModel {
 #[pymethods]
 impl PyGenericSQLVirtualServerModel {
     #[new]
-    pub fn new(py: Python<'_>, config: Option<Py<PyDict>>) -> Self {
-        Self {
+    pub fn new(py: Python<'_>, config: Option<Py<PyDict>>) -> PyResult<Self> {
+        Ok(Self {
             inner: GenericSQLVirtualServerModel::new(
                 config
-                    .map(|x| pythonize::depythonize(x.bind(py)).unwrap())
+                    .map(|x| pythonize::depythonize(x.bind(py)))
+                    .transpose()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?
                     .unwrap_or_default(),
             ),
-        }
+        })
+    }
+
+    pub fn get_features(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(pythonize::pythonize(py, &self.inner.features())?.unbind())
+    }
+
+    pub fn column_type(&self, type_name: &str) -> String {
+        self.inner.column_type(type_name).to_string()
     }
 
     pub fn get_hosted_tables(&self) -> PyResult<String> {
@@ -62,12 +74,42 @@ impl PyGenericSQLVirtualServerModel {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
-    pub fn table_validate_expression(&self, table_id: &str, expression: &str) -> PyResult<String> {
+    pub fn expression_sql(&self, expression: &str) -> PyResult<String> {
         self.inner
-            .table_validate_expression(table_id, expression)
+            .expression_sql(expression)
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    /// Raises a `ValueError` with `line`, `column` and `span` attributes if
+    /// the expression is invalid.
+    pub fn table_validate_expression(
+        &self,
+        py: Python<'_>,
+        table_id: &str,
+        expression: &str,
+    ) -> PyResult<String> {
+        self.inner
+            .table_validate_expression(table_id, expression)
+            .map_err(|e| match e {
+                GenericSQLError::InvalidExpression(diagnostic) => {
+                    let err = PyValueError::new_err(diagnostic.message.clone());
+                    let value = err.value(py);
+                    for (name, x) in [
+                        ("line", diagnostic.line),
+                        ("column", diagnostic.column),
+                        ("span", diagnostic.span),
+                    ] {
+                        if let Err(e) = value.setattr(name, x) {
+                            return e;
+                        }
+                    }
+
+                    err
+                },
+                e => PyValueError::new_err(e.to_string()),
+            })
+    }
+
     pub fn view_delete(&self, view_id: &str) -> PyResult<String> {
         self.inner
             .view_delete(view_id)
@@ -90,6 +132,93 @@ impl PyGenericSQLVirtualServerModel {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    pub fn table_make_view_parameterized(
+        &self,
+        py: Python<'_>,
+        table_id: &str,
+        view_id: &str,
+        config: Py<PyAny>,
+    ) -> PyResult<(String, Py<PyAny>)> {
+        let config: ViewConfig = pythonize::depythonize(config.bind(py))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let query = self
+            .inner
+            .table_make_view_parameterized(table_id, view_id, &config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        Self::pythonize_params(py, query)
+    }
+
+    pub fn view_split_values(&self, table_id: &str, config: Py<PyAny>) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .view_split_values(table_id, &config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn table_make_view_with_split_values(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: Py<PyAny>,
+        split_values: Vec<Vec<Option<String>>>,
+    ) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .table_make_view_with_split_values(table_id, view_id, &config, &split_values)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn view_split_values_parameterized(
+        &self,
+        py: Python<'_>,
+        table_id: &str,
+        config: Py<PyAny>,
+    ) -> PyResult<(String, Py<PyAny>)> {
+        let config: ViewConfig = pythonize::depythonize(config.bind(py))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let query = self
+            .inner
+            .view_split_values_parameterized(table_id, &config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        Self::pythonize_params(py, query)
+    }
+
+    pub fn table_make_view_with_split_values_parameterized(
+        &self,
+        py: Python<'_>,
+        table_id: &str,
+        view_id: &str,
+        config: Py<PyAny>,
+        split_values: Vec<Vec<Option<String>>>,
+    ) -> PyResult<(String, Py<PyAny>)> {
+        let config: ViewConfig = pythonize::depythonize(config.bind(py))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let query = self
+            .inner
+            .table_make_view_with_split_values_parameterized(
+                table_id,
+                view_id,
+                &config,
+                &split_values,
+            )
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        Self::pythonize_params(py, query)
+    }
+
     pub fn view_get_data(
         &self,
         view_id: &str,
@@ -121,14 +250,123 @@ impl PyGenericSQLVirtualServerModel {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
-    pub fn view_size(&self, view_id: &str) -> PyResult<String> {
+    pub fn view_size(&self, view_id: &str, config: Py<PyAny>) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
         self.inner
-            .view_size(view_id)
+            .view_size(view_id, &config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn view_expand(
+        &self,
+        view_id: &str,
+        config: Py<PyAny>,
+        row_index: u32,
+    ) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .view_expand(view_id, &config, row_index)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn view_collapse(
+        &self,
+        view_id: &str,
+        config: Py<PyAny>,
+        row_index: u32,
+    ) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .view_collapse(view_id, &config, row_index)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn view_set_depth(&self, view_id: &str, config: Py<PyAny>, depth: u32) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .view_set_depth(view_id, &config, depth)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: PyRef<'_, PyMakeTableData>,
+    ) -> PyResult<Vec<String>> {
+        self.inner
+            .table_update(table_id, index, &data.0)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: PyRef<'_, PyMakeTableData>,
+    ) -> PyResult<Vec<String>> {
+        self.inner
+            .table_remove(table_id, index, &data.0)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn table_replace(
+        &self,
+        table_id: &str,
+        data: PyRef<'_, PyMakeTableData>,
+    ) -> PyResult<Vec<String>> {
+        self.inner
+            .table_replace(table_id, &data.0)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn table_delete(&self, table_id: &str) -> PyResult<String> {
+        self.inner
+            .table_delete(table_id)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: Py<PyAny>,
+    ) -> PyResult<String> {
+        let config: ViewConfig = Python::with_gil(|py| {
+            pythonize::depythonize(config.bind(py))
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })?;
+
+        self.inner
+            .view_get_min_max(view_id, column_name, &config)
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 }
 
 impl PyGenericSQLVirtualServerModel {
+    fn pythonize_params(
+        py: Python<'_>,
+        (sql, params): (String, Vec<Scalar>),
+    ) -> PyResult<(String, Py<PyAny>)> {
+        Ok((sql, pythonize::pythonize(py, &params)?.unbind()))
+    }
+
     fn parse_schema(
         &self,
         schema: &pyo3::Bound<'_, PyDict>,
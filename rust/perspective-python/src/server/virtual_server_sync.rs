@@ -15,9 +15,12 @@ use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, TimeZone, Utc};
 use indexmap::IndexMap;
-use perspective_client::proto::{ColumnType, HostedTable};
+use perspective_client::config::Scalar;
+use perspective_client::proto::make_table_data::Data;
+use perspective_client::proto::{ColumnType, HostedTable, MakeTableData};
 use perspective_client::virtual_server::{
-    Features, ResultExt, VirtualDataSlice, VirtualServer, VirtualServerFuture, VirtualServerHandler,
+    ExpressionDiagnostic, Features, ResultExt, VirtualDataSlice, VirtualServer,
+    VirtualServerFuture, VirtualServerHandler,
 };
 use pyo3::exceptions::PyValueError;
 use pyo3::types::{
@@ -28,6 +31,59 @@ use serde::Serialize;
 
 pub struct PyServerHandler(Py<PyAny>);
 
+impl PyServerHandler {
+    fn has_method(&self, method: &str) -> bool {
+        Python::with_gil(|py| self.0.getattr(py, method).is_ok())
+    }
+
+    fn call_expand_collapse(
+        &self,
+        method: &'static str,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, PyResult<u32>> {
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let view_id = view_id.to_string();
+        let config = config.clone();
+        Box::pin(async move {
+            Python::with_gil(|py| {
+                if handler.getattr(py, method).is_ok() {
+                    handler
+                        .call_method1(
+                            py,
+                            method,
+                            (&view_id, pythonize::pythonize(py, &config)?, row_index),
+                        )?
+                        .extract::<u32>(py)
+                } else {
+                    Ok(0)
+                }
+            })
+        })
+    }
+}
+
+/// Reads an exception raised by `table_validate_expression` as an
+/// [`ExpressionDiagnostic`], from its message and optional `line`, `column`
+/// and `span` attributes.
+fn py_expression_diagnostic(py: Python<'_>, err: &PyErr) -> ExpressionDiagnostic {
+    let value = err.value(py);
+    let attr = |name: &str| {
+        value
+            .getattr(name)
+            .and_then(|x| x.extract::<u32>())
+            .unwrap_or_default()
+    };
+
+    ExpressionDiagnostic {
+        message: value.to_string(),
+        line: attr("line"),
+        column: attr("column"),
+        span: attr("span"),
+    }
+}
+
 impl VirtualServerHandler for PyServerHandler {
     type Error = PyErr;
 
@@ -135,7 +191,8 @@ impl VirtualServerHandler for PyServerHandler {
         &self,
         table_id: &str,
         expression: &str,
-    ) -> VirtualServerFuture<'_, Result<ColumnType, Self::Error>> {
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
         let handler = Python::with_gil(|py| self.0.clone_ref(py));
         let table_id = table_id.to_string();
         let expression = expression.to_string();
@@ -143,21 +200,25 @@ impl VirtualServerHandler for PyServerHandler {
             Python::with_gil(|py| {
                 let name = pyo3::intern!(py, "table_validate_expression");
                 if handler.getattr(py, name).is_ok() {
-                    Ok(handler
-                        .call_method1(py, name, (&table_id, &expression))?
+                    let result = match handler.call_method1(py, name, (&table_id, &expression)) {
+                        Ok(result) => result,
+                        Err(err) => return Ok(Err(py_expression_diagnostic(py, &err))),
+                    };
+
+                    Ok(Ok(result
                         .downcast_bound::<PyString>(py)?
-                        .extract::<String>()?)
-                    .map(|x| ColumnType::from_str(x.as_str()).unwrap())
+                        .extract::<String>()
+                        .map(|x| ColumnType::from_str(x.as_str()).unwrap())?))
                 } else {
                     // TODO this should probably be an error.
-                    Ok(ColumnType::Float)
+                    Ok(Ok(ColumnType::Float))
                 }
             })
         })
     }
 
     fn table_make_view(
-        &mut self,
+        &self,
         table_id: &str,
         view_id: &str,
         config: &mut perspective_client::config::ViewConfigUpdate,
@@ -218,13 +279,22 @@ impl VirtualServerHandler for PyServerHandler {
         })
     }
 
-    fn view_size(&self, view_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
         let handler = Python::with_gil(|py| self.0.clone_ref(py));
         let view_id = view_id.to_string();
+        let config = config.clone();
         Box::pin(async move {
             Python::with_gil(|py| {
                 handler
-                    .call_method1(py, pyo3::intern!(py, "view_size"), (&view_id,))?
+                    .call_method1(
+                        py,
+                        pyo3::intern!(py, "view_size"),
+                        (&view_id, pythonize::pythonize(py, &config)?),
+                    )?
                     .extract::<u32>(py)
             })
         })
@@ -257,6 +327,171 @@ impl VirtualServerHandler for PyServerHandler {
         })
     }
 
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &perspective_client::config::ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let view_id = view_id.to_string();
+        let column_name = column_name.to_string();
+        let config = config.clone();
+        Box::pin(async move {
+            Python::with_gil(|py| {
+                let name = pyo3::intern!(py, "view_get_min_max");
+                if handler.getattr(py, name).is_ok() {
+                    let result = handler.call_method1(
+                        py,
+                        name,
+                        (&view_id, &column_name, pythonize::pythonize(py, &config)?),
+                    )?;
+
+                    Ok(pythonize::depythonize(result.bind(py))?)
+                } else {
+                    Ok((Scalar::Null, Scalar::Null))
+                }
+            })
+        })
+    }
+
+    fn view_expand(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        self.call_expand_collapse("view_expand", view_id, config, row_index)
+    }
+
+    fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        self.call_expand_collapse("view_collapse", view_id, config, row_index)
+    }
+
+    fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &perspective_client::config::ViewConfig,
+        depth: u32,
+    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let view_id = view_id.to_string();
+        let config = config.clone();
+        Box::pin(async move {
+            Python::with_gil(|py| {
+                let name = pyo3::intern!(py, "view_set_depth");
+                if handler.getattr(py, name).is_ok() {
+                    handler.call_method1(
+                        py,
+                        name,
+                        (&view_id, pythonize::pythonize(py, &config)?, depth),
+                    )?;
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        self.has_method("table_update") || self.has_method("table_remove")
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        if !self.has_method("table_update") {
+            return None;
+        }
+
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let table_id = table_id.to_string();
+        let index = index.map(|x| x.to_string());
+        let data = PyMakeTableData(data.clone());
+        Some(Box::pin(async move {
+            Python::with_gil(|py| {
+                handler.call_method1(
+                    py,
+                    pyo3::intern!(py, "table_update"),
+                    (&table_id, index, data),
+                )?;
+                Ok(())
+            })
+        }))
+    }
+
+    fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        if !self.has_method("table_remove") {
+            return None;
+        }
+
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let table_id = table_id.to_string();
+        let index = index.map(|x| x.to_string());
+        let data = PyMakeTableData(data.clone());
+        Some(Box::pin(async move {
+            Python::with_gil(|py| {
+                handler.call_method1(
+                    py,
+                    pyo3::intern!(py, "table_remove"),
+                    (&table_id, index, data),
+                )?;
+                Ok(())
+            })
+        }))
+    }
+
+    fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        if !self.has_method("table_replace") {
+            return None;
+        }
+
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let table_id = table_id.to_string();
+        let data = PyMakeTableData(data.clone());
+        Some(Box::pin(async move {
+            Python::with_gil(|py| {
+                handler.call_method1(py, pyo3::intern!(py, "table_replace"), (&table_id, data))?;
+                Ok(())
+            })
+        }))
+    }
+
+    fn table_delete(
+        &self,
+        table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        if !self.has_method("table_delete") {
+            return None;
+        }
+
+        let handler = Python::with_gil(|py| self.0.clone_ref(py));
+        let table_id = table_id.to_string();
+        Some(Box::pin(async move {
+            Python::with_gil(|py| {
+                handler.call_method1(py, pyo3::intern!(py, "table_delete"), (&table_id,))?;
+                Ok(())
+            })
+        }))
+    }
+
     fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
         let handler = Python::with_gil(|py| self.0.clone_ref(py));
         let view_id = view_id.to_string();
@@ -328,6 +563,42 @@ impl From<perspective_client::proto::ViewPort> for PyViewPort {
     }
 }
 
+/// Data written to a virtual table by `Table.update`, `Table.remove` or
+/// `Table.replace`, which a handler can pass to
+/// `GenericSQLVirtualServerModel` or read directly.
+#[derive(Clone)]
+#[pyclass(name = "MakeTableData")]
+pub struct PyMakeTableData(pub MakeTableData);
+
+#[pymethods]
+impl PyMakeTableData {
+    /// The format of this data, one of `"rows"`, `"columns"`, `"ndjson"`,
+    /// `"csv"` or `"arrow"`.
+    #[getter]
+    pub fn format(&self) -> Option<&'static str> {
+        match &self.0.data {
+            Some(Data::FromRows(_)) => Some("rows"),
+            Some(Data::FromCols(_)) => Some("columns"),
+            Some(Data::FromNdjson(_)) => Some("ndjson"),
+            Some(Data::FromCsv(_)) => Some("csv"),
+            Some(Data::FromArrow(_)) => Some("arrow"),
+            _ => None,
+        }
+    }
+
+    /// The data itself, as `bytes` for `"arrow"` and `str` otherwise.
+    #[getter]
+    pub fn data(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(match &self.0.data {
+            Some(
+                Data::FromRows(x) | Data::FromCols(x) | Data::FromNdjson(x) | Data::FromCsv(x),
+            ) => PyString::new(py, x).into_any().unbind(),
+            Some(Data::FromArrow(x)) => PyBytes::new(py, x).into_any().unbind(),
+            _ => py.None(),
+        })
+    }
+}
+
 #[derive(Clone)]
 #[pyclass(name = "VirtualDataSlice")]
 pub struct PyVirtualDataSlice(Arc<Mutex<VirtualDataSlice>>);
@@ -534,7 +805,7 @@ impl PyVirtualServer {
         ))))
     }
 
-    pub fn handle_request(&mut self, bytes: Py<PyBytes>) -> PyResult<Py<PyBytes>> {
+    pub fn handle_request(&self, bytes: Py<PyBytes>) -> PyResult<Option<Py<PyBytes>>> {
         Python::with_gil(|py| {
             let bytes_vec = bytes.as_bytes(py).to_vec();
 
@@ -544,7 +815,26 @@ impl PyVirtualServer {
             });
 
             match result.get_internal_error() {
-                Ok(x) => Ok(PyBytes::new(py, &x).unbind()),
+                Ok(x) => Ok(x.map(|x| PyBytes::new(py, &x).unbind())),
+                Err(Ok(x)) => Err(x),
+                Err(Err(x)) => Err(PyValueError::new_err(x)),
+            }
+        })
+    }
+
+    /// Mark a table as updated, such that the next call to `poll()` will
+    /// recalculate its `View`s and notify their `on_update` subscribers.
+    pub fn notify_table_update(&self, table_id: &str) {
+        self.0.notifier().notify_table_update(table_id);
+    }
+
+    /// Returns the `on_update` callback messages for the tables updated since
+    /// the last call.
+    pub fn poll(&self) -> PyResult<Vec<Py<PyBytes>>> {
+        Python::with_gil(|py| {
+            let result = futures::executor::block_on(self.0.poll());
+            match result.get_internal_error() {
+                Ok(x) => Ok(x.iter().map(|x| PyBytes::new(py, x).unbind()).collect()),
                 Err(Ok(x)) => Err(x),
                 Err(Err(x)) => Err(PyValueError::new_err(x)),
             }
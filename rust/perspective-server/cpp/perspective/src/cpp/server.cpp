@@ -1976,6 +1976,8 @@ ProtoServer::_handle_request(std::uint32_t client_id, Request&& req) {
                             break;
                         }
                         case proto::Scalar::kBool:
+                        case proto::Scalar::kDate:
+                        case proto::Scalar::kDatetime:
                         case proto::Scalar::kFloat:
                         case proto::Scalar::kNull:
                         case proto::Scalar::SCALAR_NOT_SET:
@@ -1996,6 +1998,24 @@ ProtoServer::_handle_request(std::uint32_t client_id, Request&& req) {
                             args.push_back(a);
                             break;
                         }
+                        case proto::Scalar::kDate: {
+                            a = coerce_to(
+                                schema->get_dtype(f.column()),
+                                arg.date().c_str()
+                            );
+
+                            args.push_back(a);
+                            break;
+                        }
+                        case proto::Scalar::kDatetime: {
+                            a = coerce_to(
+                                schema->get_dtype(f.column()),
+                                static_cast<double>(arg.datetime())
+                            );
+
+                            args.push_back(a);
+                            break;
+                        }
                         case proto::Scalar::kFloat: {
                             a = coerce_to(
                                 schema->get_dtype(f.column()), arg.float_()
@@ -2424,15 +2444,9 @@ ProtoServer::_handle_request(std::uint32_t client_id, Request&& req) {
             auto* view_col_paths =
                 resp.mutable_view_column_paths_resp()->mutable_paths();
 
-            // // TODO this is a better representation of column_paths but
-            // // it is not legacy compat.
-            // for (const auto& col_paths : view->column_paths()) {
-            //     auto* col_path = view_col_paths->Add();
-            //     auto* paths = col_path->mutable_path();
-            //     for (const auto& path : col_paths) {
-            //         *paths->Add() = path;
-            //     }
-            // }
+            auto* view_structured_paths =
+                resp.mutable_view_column_paths_resp()
+                    ->mutable_structured_paths();
 
             std::string col;
             const auto column_paths = r.has_start_col() ? r.has_end_col()
@@ -2456,6 +2470,11 @@ ProtoServer::_handle_request(std::uint32_t client_id, Request&& req) {
                 }
 
                 *view_col_paths->Add() = col;
+                auto* structured_path =
+                    view_structured_paths->Add()->mutable_path();
+                for (const auto& path : col_paths) {
+                    *structured_path->Add() = path;
+                }
             }
 
             push_resp(std::move(resp));
@@ -426,9 +426,13 @@ impl FilterColumnProps {
                     None
                 }
             },
+            (ColumnType::Date, FilterTerm::Scalar(Scalar::Date(x))) => Some(x.clone()),
             (ColumnType::Datetime, FilterTerm::Scalar(Scalar::Float(x))) => {
                 posix_to_utc_str(*x).ok()
             },
+            (ColumnType::Datetime, FilterTerm::Scalar(Scalar::Datetime(x))) => {
+                posix_to_utc_str(*x as f64).ok()
+            },
             (ColumnType::Boolean, FilterTerm::Scalar(Scalar::Bool(x))) => {
                 Some((if *x { "true" } else { "false" }).to_owned())
             },
@@ -492,7 +496,7 @@ impl FilterColumnProps {
                     }
                 },
                 Some(ColumnType::Date) => match NaiveDate::parse_from_str(&val, "%Y-%m-%d") {
-                    Ok(ref posix) => Some(FilterTerm::Scalar(Scalar::String(format!(
+                    Ok(ref posix) => Some(FilterTerm::Scalar(Scalar::Date(format!(
                         "{:0>4}-{:0>2}-{:0>2}",
                         posix.year(),
                         posix.month(),
@@ -501,7 +505,7 @@ impl FilterColumnProps {
                     _ => None,
                 },
                 Some(ColumnType::Datetime) => match str_to_utc_posix(&val) {
-                    Ok(x) => Some(FilterTerm::Scalar(Scalar::Float(x))),
+                    Ok(x) => Some(FilterTerm::Scalar(Scalar::Datetime(x as i64))),
                     _ => None,
                 },
                 Some(ColumnType::Boolean) => Some(FilterTerm::Scalar(match val.as_str() {
@@ -233,6 +233,7 @@ impl DebugPanelPropsState {
                                 }),
                                 line: 0_u32,
                                 column: 0,
+                                span: 0,
                             }));
                         },
                     }
@@ -244,6 +245,7 @@ impl DebugPanelPropsState {
                         error_message: err.to_string(),
                         line: err.line() as u32 - 1,
                         column: err.column() as u32 - 1,
+                        span: 0,
                     }));
 
                     Ok(())
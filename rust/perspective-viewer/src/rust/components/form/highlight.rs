@@ -12,7 +12,7 @@
 
 use yew::prelude::*;
 
-use crate::exprtk::{Cursor, Token};
+use crate::exprtk::{Cursor, Token, TokenExt};
 
 /// Highlight a token if the cursor overlaps an error.  This is not a
 /// `Component` because of the the lifetimes associated with `Cursor<'a>` etc.
@@ -10,8 +10,9 @@
 // ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
+use perspective_client::ClientError;
 use perspective_client::config::ViewConfigUpdate;
-use perspective_js::utils::ApiError;
+use perspective_js::utils::{ApiError, ApiErrorType};
 use wasm_bindgen::JsValue;
 use web_sys::*;
 use yew::prelude::*;
@@ -37,7 +38,7 @@ pub struct StatusIndicatorProps {
 pub fn StatusIndicator(props: &StatusIndicatorProps) -> Html {
     let state = use_reducer_eq(|| {
         if let Some(err) = props.session.get_error() {
-            StatusIconState::Errored(err.message(), err.stacktrace(), err.kind())
+            StatusIconState::Errored(error_message(&err), err.stacktrace(), err.kind())
         } else {
             StatusIconState::Normal
         }
@@ -119,6 +120,28 @@ pub fn StatusIndicator(props: &StatusIndicatorProps) -> Html {
     }
 }
 
+/// The message for `err`, prefixed with an explanation for errors which the
+/// server classified with a `StatusCode`.
+fn error_message(err: &ApiError) -> String {
+    let ApiErrorType::ClientError(client_err) = err.inner() else {
+        return err.message();
+    };
+
+    let explanation = match client_err {
+        ClientError::ViewNotFound => "This view was closed by the server, click to reload it",
+        ClientError::UnsupportedOperation(_) => "This operation is not supported by the server",
+        ClientError::InvalidExpression(_) => "An expression is invalid",
+        ClientError::InvalidConfig(_) => "The view configuration is invalid",
+        ClientError::BackendUnavailable(_) | ClientError::TransportError(_) => {
+            "The server is unavailable, click to reconnect"
+        },
+        ClientError::Timeout(_) => "The server did not respond in time",
+        _ => return err.message(),
+    };
+
+    format!("{}: {}", explanation, err.message())
+}
+
 #[derive(Clone, Default, Debug, PartialEq)]
 enum StatusIconState {
     Loading,
@@ -157,7 +180,7 @@ impl Reducible for StatusIconState {
                 }
             },
             (_, StatusIconStateAction::SetError(e)) => {
-                Self::Errored(e.message(), e.stacktrace(), e.kind())
+                Self::Errored(error_message(&e), e.stacktrace(), e.kind())
             },
             (
                 StatusIconState::Loading,
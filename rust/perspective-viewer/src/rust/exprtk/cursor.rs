@@ -54,11 +54,13 @@ impl<'a> Cursor<'a> {
         }
     }
 
-    /// Is the cursor currently overlapping a token with an error?
+    /// Is the cursor currently overlapping a token with an error? An error
+    /// with no `span` covers only the token at its `column`.
     pub const fn is_error(&self) -> bool {
         if let Some(err) = &self.err {
+            let span = if err.span > 0 { err.span } else { 1 };
             err.line + 1 == self.row
-                && err.column >= self.col
+                && err.column + span > self.col
                 && err.column < (self.col + self.txt.len() as u32)
         } else {
             false
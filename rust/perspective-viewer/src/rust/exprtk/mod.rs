@@ -13,7 +13,21 @@
 //! Data processing functions for the Perspective/ExprTK language.
 
 mod cursor;
-mod tokenize;
 
 pub use cursor::*;
-pub use tokenize::*;
+pub use perspective_client::exprtk::{Token, tokenize};
+use yew::prelude::*;
+
+#[extend::ext]
+pub impl<'a> Token<'a> {
+    /// Render this token as syntax-highlighted HTML.
+    fn to_html(&self) -> Html {
+        html! {
+            if matches!(self, Token::Break(_)) {
+                <br />
+            } else {
+                <span class={self.class_name()}>{ self.content() }</span>
+            }
+        }
+    }
+}
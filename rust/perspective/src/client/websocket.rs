@@ -0,0 +1,145 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! A [`Client`] transport over a WebSocket, via [`tokio_tungstenite`].
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), perspective::client::ClientError> {
+//! let client = perspective::client::websocket::connect("ws://localhost:8080/ws").await?;
+//! let tables = client.get_hosted_table_names().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use perspective_client::utils::ClientResult;
+use perspective_client::{Client, ClientError};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+/// A local error synonym for this module only.
+type PerspectiveWSError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The sending half of the current WebSocket, shared by the [`Client`]'s
+/// send callback and the message loop, which replaces it on reconnect.
+#[derive(Clone, Default)]
+struct Connection(Arc<Mutex<Option<UnboundedSender<Vec<u8>>>>>);
+
+impl Connection {
+    fn is_open(&self) -> ClientResult<bool> {
+        Ok(self.0.lock()?.as_ref().is_some_and(|x| !x.is_closed()))
+    }
+
+    fn send(&self, msg: Vec<u8>) -> Result<(), PerspectiveWSError> {
+        let guard = self.0.lock().map_err(|_| "Lock error")?;
+        match &*guard {
+            Some(sender) if !sender.is_closed() => Ok(sender.unbounded_send(msg)?),
+            _ => Err("WebSocket transport error (closed)".into()),
+        }
+    }
+
+    /// Opens a WebSocket to `url` and spawns its message loop, unless one is
+    /// already open. Boxed, as the message loop's reconnect callback calls
+    /// this function in turn.
+    fn dial(&self, url: String, client: Client) -> BoxFuture<'static, ClientResult<()>> {
+        let connection = self.clone();
+        Box::pin(async move {
+            if connection.is_open()? {
+                tracing::warn!("Already connected");
+                return Ok(());
+            }
+
+            let (socket, _) = connect_async(url.as_str())
+                .await
+                .map_err(|e| ClientError::TransportError(e.to_string()))?;
+
+            let (sender, receiver) = unbounded();
+            *connection.0.lock()? = Some(sender);
+            tokio::spawn(async move {
+                let error = process_message_loop(socket, receiver, &client).await;
+                tracing::debug!("{error}");
+                let reconnect = {
+                    let client = client.clone();
+                    move || connection.dial(url.clone(), client.clone())
+                };
+
+                if let Err(e) = client.handle_error(error, Some(reconnect)).await {
+                    tracing::error!("Internal error {}", e);
+                }
+            });
+
+            Ok(())
+        })
+    }
+}
+
+/// The inner message loop handles the full-duplex stream of messages
+/// between the [`Client`] and the WebSocket, until the WebSocket closes or
+/// errors, which is returned as a [`ClientError::TransportError`].
+async fn process_message_loop(
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mut receiver: UnboundedReceiver<Vec<u8>>,
+    client: &Client,
+) -> ClientError {
+    let (mut sink, mut stream) = socket.split();
+    let message = loop {
+        tokio::select! {
+            Some(msg) = receiver.next() => {
+                if let Err(e) = sink.send(Message::Binary(msg.into())).await {
+                    break e.to_string();
+                }
+            },
+            msg = stream.next() => match msg {
+                Some(Ok(Message::Binary(msg))) => {
+                    if let Err(e) = client.handle_response(&msg).await {
+                        tracing::error!("Internal error {}", e);
+                    }
+                },
+                Some(Ok(Message::Close(Some(frame)))) => {
+                    break format!("WebSocket closed {}", u16::from(frame.code));
+                },
+                Some(Ok(Message::Close(None))) | None => break "WebSocket closed".to_owned(),
+                Some(Ok(_)) => tracing::debug!("Unexpected msg"),
+                Some(Err(e)) => break e.to_string(),
+            },
+        }
+    };
+
+    ClientError::TransportError(message)
+}
+
+/// Connects a new [`Client`] to the Perspective server WebSocket at `url`,
+/// e.g. one hosted by `perspective::axum::websocket_handler`. Must be called
+/// from within a [`tokio`] runtime, on which the connection's message loop
+/// is spawned.
+///
+/// When the WebSocket closes or errors, the [`Client`]'s pending requests
+/// fail and its [`Client::on_error`] callbacks are called with a
+/// [`ClientError::TransportError`] and a
+/// [`ReconnectCallback`](perspective_client::ReconnectCallback) which
+/// re-dials `url`. Requests sent while disconnected fail.
+pub async fn connect(url: &str) -> ClientResult<Client> {
+    let connection = Connection::default();
+    let client = Client::new_with_callback(None, {
+        let connection = connection.clone();
+        move |msg| ready(connection.send(msg))
+    })?;
+
+    connection.dial(url.to_owned(), client.clone()).await?;
+    Ok(client)
+}
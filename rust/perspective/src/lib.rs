@@ -54,8 +54,16 @@
 
 #[cfg(feature = "axum-ws")]
 pub mod axum;
-#[cfg(feature = "axum-ws")]
+
+pub mod client;
+
+#[cfg(any(
+    feature = "axum-ws",
+    feature = "duckdb",
+    feature = "sqlite",
+    feature = "datafusion"
+))]
 pub mod virtual_server;
 
 pub use perspective_client::proto;
-pub use {perspective_client as client, perspective_server as server};
+pub use perspective_server as server;
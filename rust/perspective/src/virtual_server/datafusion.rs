@@ -0,0 +1,619 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! A [`VirtualServerHandler`] for in-memory Arrow [`RecordBatch`]es, which
+//! executes its queries with [Apache DataFusion](https://datafusion.apache.org)
+//! and generates them with [`GenericSQLVirtualServerModel`] in the
+//! [`DataFusionDialect`].
+//!
+//! Each `View` is a DataFusion `VIEW`, a logical plan over its table which is
+//! only executed for the viewport requested, so filtering, sorting,
+//! `group_by` and `split_by` never copy the table's batches. DataFusion has no
+//! `PIVOT`, so `split_by` is emulated with one conditional aggregate per
+//! column and distinct value of the `split_by` columns, which the handler
+//! queries before creating the view.
+//!
+//! # Examples
+//!
+//! Serve a table of [`RecordBatch`]es from an [`axum`] route (requires the
+//! `axum-ws` feature as well):
+//!
+//! ```rust,ignore
+//! use perspective::virtual_server::custom_websocket_handler;
+//! use perspective::virtual_server::datafusion::DataFusionHandler;
+//!
+//! let handler = DataFusionHandler::new();
+//! handler.register_batches("superstore", batches)?;
+//! let app = axum::Router::new().route("/ws", custom_websocket_handler(handler));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::ready;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+    DataType, Float64Type, Int32Type, Int64Type, SchemaRef, TimeUnit,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::datasource::MemTable;
+use datafusion::error::DataFusionError;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use indexmap::IndexMap;
+use perspective_client::config::{Scalar, ViewConfig, ViewConfigUpdate};
+use perspective_client::proto::{ColumnType, HostedTable, StatusCode, ViewPort};
+use perspective_client::virtual_server::{
+    DataFusionDialect, ExpressionDiagnostic, Features, GenericSQLError,
+    GenericSQLVirtualServerModel, GenericSQLVirtualServerModelArgs, VirtualDataSlice,
+    VirtualServerFuture, VirtualServerHandler,
+};
+
+/// An error from a [`DataFusionHandler`].
+#[derive(Debug)]
+pub enum DataFusionHandlerError {
+    /// A query failed in DataFusion.
+    DataFusion(DataFusionError),
+
+    /// A query could not be generated for the request.
+    GenericSQL(GenericSQLError),
+
+    /// A query result could not be converted to Perspective's data model.
+    Data(String),
+}
+
+impl fmt::Display for DataFusionHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataFusionHandlerError::DataFusion(e) => write!(f, "DataFusion error: {}", e),
+            DataFusionHandlerError::GenericSQL(e) => write!(f, "{}", e),
+            DataFusionHandlerError::Data(e) => write!(f, "Data error: {}", e),
+        }
+    }
+}
+
+impl Error for DataFusionHandlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DataFusionHandlerError::DataFusion(e) => Some(e),
+            DataFusionHandlerError::GenericSQL(e) => Some(e),
+            DataFusionHandlerError::Data(_) => None,
+        }
+    }
+}
+
+impl From<DataFusionError> for DataFusionHandlerError {
+    fn from(value: DataFusionError) -> Self {
+        DataFusionHandlerError::DataFusion(value)
+    }
+}
+
+impl From<datafusion::arrow::error::ArrowError> for DataFusionHandlerError {
+    fn from(value: datafusion::arrow::error::ArrowError) -> Self {
+        DataFusionHandlerError::DataFusion(value.into())
+    }
+}
+
+impl From<GenericSQLError> for DataFusionHandlerError {
+    fn from(value: GenericSQLError) -> Self {
+        DataFusionHandlerError::GenericSQL(value)
+    }
+}
+
+type DataFusionResult<T> = Result<T, DataFusionHandlerError>;
+
+/// A [`VirtualServerHandler`] which hosts the tables of a DataFusion
+/// [`SessionContext`], e.g. [`RecordBatch`]es registered with
+/// [`DataFusionHandler::register_batches`].
+///
+/// Tables are read-only, so `Table::update` and friends fail as unsupported,
+/// and the groups of a grouped view are always expanded. Unquoted table names
+/// are lowercased by DataFusion's SQL parser, so tables must have lowercase
+/// names.
+///
+/// Clones share the same [`SessionContext`], so a single handler can be passed
+/// to [`custom_websocket_handler`](super::custom_websocket_handler).
+#[derive(Clone)]
+pub struct DataFusionHandler {
+    ctx: SessionContext,
+    model: GenericSQLVirtualServerModel,
+}
+
+impl Default for DataFusionHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataFusionHandler {
+    /// Creates a handler for a new, empty [`SessionContext`].
+    pub fn new() -> Self {
+        let config = SessionConfig::new().with_information_schema(true);
+        Self::with_context(SessionContext::new_with_config(config))
+    }
+
+    /// Creates a handler for an existing [`SessionContext`], which must have
+    /// `information_schema` enabled, to host the tables it has registered.
+    pub fn with_context(ctx: SessionContext) -> Self {
+        let args = GenericSQLVirtualServerModelArgs::default().with_create_entity("VIEW");
+        DataFusionHandler {
+            ctx,
+            model: GenericSQLVirtualServerModel::with_dialect(args, Arc::new(DataFusionDialect)),
+        }
+    }
+
+    /// The handler's [`SessionContext`], e.g. to register other data sources.
+    pub fn context(&self) -> &SessionContext {
+        &self.ctx
+    }
+
+    /// Registers `batches`, which must all have `schema`, as the table
+    /// `table_id`, replacing any table of the same name. The batches are
+    /// shared rather than copied.
+    pub fn register_batches(
+        &self,
+        table_id: &str,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> DataFusionResult<()> {
+        let table = MemTable::try_new(schema, vec![batches])?;
+        self.ctx.register_table(table_id, Arc::new(table))?;
+        Ok(())
+    }
+
+    async fn query(&self, sql: &str) -> DataFusionResult<Vec<RecordBatch>> {
+        tracing::debug!("{}", sql);
+        Ok(self.ctx.sql(sql).await?.collect().await?)
+    }
+
+    async fn query_count(&self, sql: &str) -> DataFusionResult<u32> {
+        let batches = self.query(sql).await?;
+        let count = match batches.iter().find(|x| x.num_rows() > 0) {
+            Some(batch) => cast(batch.column(0), &DataType::Int64)?,
+            None => return Err(DataFusionHandlerError::Data("Expected a count".to_owned())),
+        };
+
+        let count = count.as_primitive::<Int64Type>();
+        Ok(count.value(0) as u32)
+    }
+
+    /// The string values of the first two columns of every row of `sql`.
+    async fn query_pairs(&self, sql: &str) -> DataFusionResult<Vec<(String, String)>> {
+        let mut pairs = vec![];
+        for batch in self.query(sql).await? {
+            let first = column_to_strings(batch.column(0))?;
+            let second = column_to_strings(batch.column(1))?;
+            pairs.extend(
+                first
+                    .into_iter()
+                    .zip(second)
+                    .map(|(x, y)| (x.unwrap_or_default(), y.unwrap_or_default())),
+            );
+        }
+
+        Ok(pairs)
+    }
+
+    async fn hosted_tables(&self) -> DataFusionResult<Vec<HostedTable>> {
+        let sql = self.model.get_hosted_tables()?;
+        let mut tables = self
+            .query_pairs(&sql)
+            .await?
+            .into_iter()
+            .map(|(_, entity_id)| entity_id)
+            .collect::<Vec<_>>();
+
+        tables.sort();
+        Ok(tables
+            .into_iter()
+            .map(|entity_id| HostedTable {
+                entity_id,
+                index: None,
+                limit: None,
+            })
+            .collect())
+    }
+
+    async fn describe(&self, entity_id: &str) -> DataFusionResult<IndexMap<String, ColumnType>> {
+        let sql = self.model.table_schema(entity_id)?;
+        Ok(self
+            .query_pairs(&sql)
+            .await?
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("__"))
+            .map(|(name, dtype)| (name, self.model.column_type(&dtype)))
+            .collect())
+    }
+
+    async fn expression_type(
+        &self,
+        table_id: &str,
+        expression: &str,
+    ) -> DataFusionResult<ColumnType> {
+        let sql = self.model.table_validate_expression(table_id, expression)?;
+
+        // `arrow_typeof()` needs a row to evaluate the expression against.
+        Ok(self
+            .query_pairs(&sql)
+            .await?
+            .first()
+            .map_or(ColumnType::Float, |(_, dtype)| {
+                self.model.column_type(dtype)
+            }))
+    }
+
+    /// Separates an invalid expression, reported to the user as an
+    /// [`ExpressionDiagnostic`], from a failure of the database itself.
+    fn expression_diagnostic(
+        result: DataFusionResult<ColumnType>,
+    ) -> DataFusionResult<Result<ColumnType, ExpressionDiagnostic>> {
+        match result {
+            Ok(dtype) => Ok(Ok(dtype)),
+            Err(DataFusionHandlerError::GenericSQL(GenericSQLError::InvalidExpression(
+                diagnostic,
+            ))) => Ok(Err(diagnostic)),
+            Err(e) if Self::error_status_code(&e) == StatusCode::BackendUnavailable => Err(e),
+            Err(e) => Ok(Err(ExpressionDiagnostic::new(e.to_string()))),
+        }
+    }
+
+    /// Creates a view, selecting every column of the table (and expression)
+    /// when `config` has no `columns`, as the Perspective engine does.
+    async fn make_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        mut config: ViewConfigUpdate,
+    ) -> DataFusionResult<()> {
+        if config.columns.is_none() {
+            let mut columns = self
+                .describe(table_id)
+                .await?
+                .into_keys()
+                .map(Some)
+                .collect::<Vec<_>>();
+
+            for name in config.expressions.iter().flat_map(|x| x.keys()) {
+                columns.push(Some(name.clone()));
+            }
+
+            config.columns = Some(columns);
+        }
+
+        let config: ViewConfig = config.into();
+        let sql = if config.split_by.is_empty() {
+            self.model.table_make_view(table_id, view_id, &config)?
+        } else {
+            let sql = self.model.view_split_values(table_id, &config)?;
+            let mut split_values = vec![];
+            for batch in self.query(&sql).await? {
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(column_to_strings)
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+
+                for ridx in 0..batch.num_rows() {
+                    split_values.push(columns.iter().map(|x| x[ridx].clone()).collect());
+                }
+            }
+
+            self.model.table_make_view_with_split_values(
+                table_id,
+                view_id,
+                &config,
+                &split_values,
+            )?
+        };
+
+        self.query(&sql).await?;
+        Ok(())
+    }
+
+    async fn min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> DataFusionResult<(Scalar, Scalar)> {
+        let sql = self.model.view_get_min_max(view_id, column_name, config)?;
+        let batches = self.query(&sql).await?;
+        let Some(batch) = batches.iter().find(|x| x.num_rows() > 0) else {
+            return Ok((Scalar::Null, Scalar::Null));
+        };
+
+        Ok((
+            self.column_to_scalar(batch.column(0))?,
+            self.column_to_scalar(batch.column(1))?,
+        ))
+    }
+
+    /// The first value of `array`, dates as milliseconds since the epoch.
+    fn column_to_scalar(&self, array: &ArrayRef) -> DataFusionResult<Scalar> {
+        if array.is_null(0) {
+            return Ok(Scalar::Null);
+        }
+
+        let dtype = self.model.column_type(&format!("{:?}", array.data_type()));
+        Ok(match dtype {
+            ColumnType::String => Scalar::String(array_value_to_string(array, 0)?),
+            ColumnType::Boolean => {
+                Scalar::Bool(cast(array, &DataType::Boolean)?.as_boolean().value(0))
+            },
+            ColumnType::Date | ColumnType::Datetime => {
+                Scalar::Float(column_to_millis(array)?.value(0) as f64)
+            },
+            ColumnType::Integer | ColumnType::Float => Scalar::Float(
+                cast(array, &DataType::Float64)?
+                    .as_primitive::<Float64Type>()
+                    .value(0),
+            ),
+        })
+    }
+
+    async fn data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> DataFusionResult<VirtualDataSlice> {
+        let sql = self
+            .model
+            .view_get_data(view_id, config, viewport, schema)?;
+        let batches = self.query(&sql).await?;
+        let is_group_by = !config.group_by.is_empty();
+        let mut data = VirtualDataSlice::new(config.clone());
+        let mut offset = 0;
+        for batch in batches {
+            let grouping_ids = if is_group_by {
+                let ids = cast(batch.column(0), &DataType::Int64)?;
+                let ids = ids.as_primitive::<Int64Type>();
+                (0..batch.num_rows())
+                    .map(|ridx| ids.is_valid(ridx).then(|| ids.value(ridx) as usize))
+                    .collect()
+            } else {
+                vec![None; batch.num_rows()]
+            };
+
+            for (cidx, field) in batch.schema().fields().iter().enumerate() {
+                if cidx == 0 && is_group_by {
+                    // This is the `__GROUPING_ID__` column.
+                    continue;
+                }
+
+                let dtype = self.model.column_type(&format!("{:?}", field.data_type()));
+                set_column(
+                    &mut data,
+                    field.name(),
+                    &grouping_ids,
+                    offset,
+                    dtype,
+                    batch.column(cidx),
+                )?;
+            }
+
+            offset += batch.num_rows();
+        }
+
+        Ok(data)
+    }
+}
+
+impl VirtualServerHandler for DataFusionHandler {
+    type Error = DataFusionHandlerError;
+
+    /// Views' queries are generated from their config, so DataFusion's
+    /// parsing and planning errors are caused by invalid expressions.
+    fn error_status_code(error: &Self::Error) -> StatusCode {
+        match error {
+            DataFusionHandlerError::GenericSQL(e) => e.status_code(),
+            DataFusionHandlerError::DataFusion(e) => match e.find_root() {
+                DataFusionError::SQL(..)
+                | DataFusionError::Plan(_)
+                | DataFusionError::SchemaError(..) => StatusCode::InvalidExpression,
+                DataFusionError::NotImplemented(_) => StatusCode::UnsupportedOperation,
+                _ => StatusCode::ServerError,
+            },
+            DataFusionHandlerError::Data(_) => StatusCode::ServerError,
+        }
+    }
+
+    fn get_features(&self) -> VirtualServerFuture<'_, Result<Features<'_>, Self::Error>> {
+        Box::pin(ready(Ok(self.model.features())))
+    }
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        Box::pin(self.hosted_tables())
+    }
+
+    fn table_schema(
+        &self,
+        table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let table_id = table_id.to_owned();
+        Box::pin(async move { self.describe(&table_id).await })
+    }
+
+    fn table_size(&self, table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.table_size(table_id);
+        Box::pin(async move { self.query_count(&sql?).await })
+    }
+
+    fn view_schema(
+        &self,
+        view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let view_id = view_id.to_owned();
+        Box::pin(async move { self.describe(&view_id).await })
+    }
+
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_size(view_id, config);
+        Box::pin(async move { self.query_count(&sql?).await })
+    }
+
+    fn view_column_size(
+        &self,
+        view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_column_size(view_id);
+        Box::pin(async move { self.query_count(&sql?).await })
+    }
+
+    fn table_make_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        let (table_id, view_id, config) = (table_id.to_owned(), view_id.to_owned(), config.clone());
+        Box::pin(async move {
+            self.make_view(&table_id, &view_id, config).await?;
+            Ok(view_id)
+        })
+    }
+
+    fn table_validate_expression(
+        &self,
+        table_id: &str,
+        expression: &str,
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        let (table_id, expression) = (table_id.to_owned(), expression.to_owned());
+        Box::pin(async move {
+            Self::expression_diagnostic(self.expression_type(&table_id, &expression).await)
+        })
+    }
+
+    fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let sql = self.model.view_delete(view_id);
+        Box::pin(async move {
+            self.query(&sql?).await?;
+            Ok(())
+        })
+    }
+
+    fn view_get_data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let (view_id, config) = (view_id.to_owned(), config.clone());
+        let (schema, viewport) = (schema.clone(), viewport.clone());
+        Box::pin(async move { self.data(&view_id, &config, &schema, &viewport).await })
+    }
+
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        let (view_id, column_name) = (view_id.to_owned(), column_name.to_owned());
+        let config = config.clone();
+        Box::pin(async move { self.min_max(&view_id, &column_name, &config).await })
+    }
+}
+
+/// Writes the values of `array` to column `name` of `data` from row `offset`,
+/// as values of the column's type `dtype`.
+fn set_column(
+    data: &mut VirtualDataSlice,
+    name: &str,
+    grouping_ids: &[Option<usize>],
+    offset: usize,
+    dtype: ColumnType,
+    array: &ArrayRef,
+) -> DataFusionResult<()> {
+    let data_err = |e: Box<dyn Error>| DataFusionHandlerError::Data(e.to_string());
+    match dtype {
+        ColumnType::String => {
+            for (ridx, value) in column_to_strings(array)?.into_iter().enumerate() {
+                data.set_col(name, grouping_ids[ridx], offset + ridx, value)
+                    .map_err(data_err)?;
+            }
+        },
+        ColumnType::Integer => {
+            let array = cast(array, &DataType::Int32)?;
+            for (ridx, value) in array.as_primitive::<Int32Type>().iter().enumerate() {
+                data.set_col(name, grouping_ids[ridx], offset + ridx, value)
+                    .map_err(data_err)?;
+            }
+        },
+        ColumnType::Float => {
+            let array = cast(array, &DataType::Float64)?;
+            for (ridx, value) in array.as_primitive::<Float64Type>().iter().enumerate() {
+                data.set_col(name, grouping_ids[ridx], offset + ridx, value)
+                    .map_err(data_err)?;
+            }
+        },
+        ColumnType::Boolean => {
+            let array = cast(array, &DataType::Boolean)?;
+            for (ridx, value) in array.as_boolean().iter().enumerate() {
+                data.set_col(name, grouping_ids[ridx], offset + ridx, value)
+                    .map_err(data_err)?;
+            }
+        },
+        ColumnType::Date | ColumnType::Datetime => {
+            for (ridx, value) in column_to_millis(array)?.iter().enumerate() {
+                data.set_col(name, grouping_ids[ridx], offset + ridx, value)
+                    .map_err(data_err)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Converts a date or timestamp `array` to milliseconds since the epoch.
+fn column_to_millis(array: &ArrayRef) -> DataFusionResult<datafusion::arrow::array::Int64Array> {
+    let tz = match array.data_type() {
+        DataType::Timestamp(_, tz) => tz.clone(),
+        _ => None,
+    };
+
+    let array = cast(array, &DataType::Timestamp(TimeUnit::Millisecond, tz))?;
+    let array = cast(&array, &DataType::Int64)?;
+    Ok(array.as_primitive::<Int64Type>().clone())
+}
+
+/// Converts `array` to strings, formatting values which Arrow cannot cast to
+/// `Utf8` (e.g. lists) with their display representation.
+fn column_to_strings(array: &ArrayRef) -> DataFusionResult<Vec<Option<String>>> {
+    if let Ok(strings) = cast(array, &DataType::Utf8) {
+        return Ok(strings
+            .as_string::<i32>()
+            .iter()
+            .map(|x| x.map(ToOwned::to_owned))
+            .collect());
+    }
+
+    (0..array.len())
+        .map(|idx| {
+            Ok(array
+                .is_valid(idx)
+                .then(|| array_value_to_string(array, idx))
+                .transpose()?)
+        })
+        .collect()
+}
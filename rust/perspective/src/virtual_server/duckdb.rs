@@ -0,0 +1,641 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! A [`VirtualServerHandler`] for an in-process [DuckDB](https://duckdb.org)
+//! database, which generates its queries with
+//! [`GenericSQLVirtualServerModel`].
+//!
+//! # Examples
+//!
+//! Serve the tables of a DuckDB file from an [`axum`] route (requires the
+//! `axum-ws` feature as well):
+//!
+//! ```rust,ignore
+//! use perspective::virtual_server::custom_websocket_handler;
+//! use perspective::virtual_server::duckdb::DuckDBHandler;
+//!
+//! let handler = DuckDBHandler::open("superstore.duckdb")?;
+//! let app = axum::Router::new().route("/ws", custom_websocket_handler(handler));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::ready;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use ::duckdb::Connection;
+use ::duckdb::types::{TimeUnit, Value};
+use indexmap::IndexMap;
+use perspective_client::config::{Scalar, ViewConfig, ViewConfigUpdate};
+use perspective_client::proto::{ColumnType, HostedTable, MakeTableData, StatusCode, ViewPort};
+use perspective_client::virtual_server::{
+    ExpressionDiagnostic, Features, GenericSQLError, GenericSQLVirtualServerModel,
+    GenericSQLVirtualServerModelArgs, VirtualDataSlice, VirtualServerFuture, VirtualServerHandler,
+};
+
+const PRIMARY_KEYS_QUERY: &str = "SELECT database_name, schema_name, table_name, \
+                                  constraint_column_names FROM duckdb_constraints() WHERE \
+                                  constraint_type = 'PRIMARY KEY'";
+
+/// An error from a [`DuckDBHandler`].
+#[derive(Debug)]
+pub enum DuckDBHandlerError {
+    /// A query failed in DuckDB.
+    DuckDB(::duckdb::Error),
+
+    /// A query could not be generated for the request.
+    GenericSQL(GenericSQLError),
+
+    /// A query result could not be converted to Perspective's data model.
+    Data(String),
+}
+
+impl fmt::Display for DuckDBHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuckDBHandlerError::DuckDB(e) => write!(f, "DuckDB error: {}", e),
+            DuckDBHandlerError::GenericSQL(e) => write!(f, "{}", e),
+            DuckDBHandlerError::Data(e) => write!(f, "Data error: {}", e),
+        }
+    }
+}
+
+impl Error for DuckDBHandlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DuckDBHandlerError::DuckDB(e) => Some(e),
+            DuckDBHandlerError::GenericSQL(e) => Some(e),
+            DuckDBHandlerError::Data(_) => None,
+        }
+    }
+}
+
+impl From<::duckdb::Error> for DuckDBHandlerError {
+    fn from(value: ::duckdb::Error) -> Self {
+        DuckDBHandlerError::DuckDB(value)
+    }
+}
+
+impl From<GenericSQLError> for DuckDBHandlerError {
+    fn from(value: GenericSQLError) -> Self {
+        DuckDBHandlerError::GenericSQL(value)
+    }
+}
+
+type DuckDBResult<T> = Result<T, DuckDBHandlerError>;
+
+/// A [`VirtualServerHandler`] which hosts every table of a DuckDB database.
+///
+/// Tables are named `{database}.{table}`, e.g. `memory.superstore` for an
+/// in-memory database, or `{database}.{schema}.{table}` outside of the `main`
+/// schema, and a table with a single-column `PRIMARY KEY` uses it as its
+/// `index`. Each `View` is materialized as a temporary table, which is not
+/// written to the database file nor listed as a hosted table.
+///
+/// Clones share the same [`Connection`]. Queries run on tokio's blocking
+/// thread pool, one at a time as they hold the connection's lock, so the
+/// handler must be used from within a tokio runtime.
+#[derive(Clone)]
+pub struct DuckDBHandler {
+    conn: Arc<Mutex<Connection>>,
+    model: GenericSQLVirtualServerModel,
+}
+
+impl DuckDBHandler {
+    /// Creates a handler for an open DuckDB [`Connection`].
+    pub fn new(conn: Connection) -> Self {
+        DuckDBHandler {
+            conn: Arc::new(Mutex::new(conn)),
+            model: GenericSQLVirtualServerModel::new(
+                GenericSQLVirtualServerModelArgs::default().with_create_entity("TEMP TABLE"),
+            ),
+        }
+    }
+
+    /// Creates a handler for the DuckDB database file at `path`, which is
+    /// created if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ::duckdb::Error> {
+        Ok(Self::new(Connection::open(path)?))
+    }
+
+    /// Creates a handler for a new, empty in-memory DuckDB database.
+    pub fn open_in_memory() -> Result<Self, ::duckdb::Error> {
+        Ok(Self::new(Connection::open_in_memory()?))
+    }
+
+    /// The handler's [`Connection`], e.g. to load data. Writes made this way
+    /// do not trigger `on_update` callbacks.
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `f` with a clone of this handler (sharing its connection) on
+    /// tokio's blocking thread pool, so a long query does not stall the
+    /// runtime's worker threads.
+    fn spawn_blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Self) -> DuckDBResult<T> + Send + 'static,
+    ) -> VirtualServerFuture<'static, DuckDBResult<T>> {
+        let handler = self.clone();
+        Box::pin(async move {
+            match tokio::task::spawn_blocking(move || f(&handler)).await {
+                Ok(result) => result,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        })
+    }
+
+    fn query(&self, sql: &str) -> DuckDBResult<(Vec<String>, Vec<Vec<Value>>)> {
+        tracing::debug!("{}", sql);
+        let conn = self.connection();
+        let mut stmt = conn.prepare(sql)?;
+        let mut results = vec![];
+        {
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let num_columns = row.as_ref().column_count();
+                results.push(
+                    (0..num_columns)
+                        .map(|idx| row.get::<_, Value>(idx))
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+            }
+        }
+
+        Ok((stmt.column_names(), results))
+    }
+
+    fn execute(&self, sql: &str) -> DuckDBResult<usize> {
+        tracing::debug!("{}", sql);
+        Ok(self.connection().execute(sql, [])?)
+    }
+
+    fn query_scalar(&self, sql: &str) -> DuckDBResult<Value> {
+        let (_, rows) = self.query(sql)?;
+        rows.into_iter()
+            .next()
+            .and_then(|row| row.into_iter().next())
+            .ok_or_else(|| DuckDBHandlerError::Data(format!("No result for `{}`", sql)))
+    }
+
+    fn query_count(&self, sql: &str) -> DuckDBResult<u32> {
+        let value = self.query_scalar(sql)?;
+        value_to_f64(&value)
+            .map(|x| x as u32)
+            .ok_or_else(|| DuckDBHandlerError::Data(format!("Expected a count, got {:?}", value)))
+    }
+
+    fn hosted_tables(&self) -> DuckDBResult<Vec<HostedTable>> {
+        let mut indices = IndexMap::new();
+        for row in self.query(PRIMARY_KEYS_QUERY)?.1 {
+            if let [database, schema, table, Value::List(columns)] = row.as_slice()
+                && let [column] = columns.as_slice()
+            {
+                indices.insert(
+                    table_entity_id(database, schema, table),
+                    value_to_string(column),
+                );
+            }
+        }
+
+        // `SHOW ALL TABLES` has the columns `database`, `schema`, `name`,
+        // `column_names`, `column_types` and `temporary`.
+        let (_, rows) = self.query(&self.model.get_hosted_tables()?)?;
+        Ok(rows
+            .iter()
+            .filter(|row| !matches!(row.get(5), Some(Value::Boolean(true))))
+            .map(|row| {
+                let entity_id = table_entity_id(&row[0], &row[1], &row[2]);
+                HostedTable {
+                    index: indices.get(&entity_id).cloned(),
+                    entity_id,
+                    limit: None,
+                }
+            })
+            .collect())
+    }
+
+    fn describe(&self, table_id: &str) -> DuckDBResult<IndexMap<String, ColumnType>> {
+        let (_, rows) = self.query(&self.model.table_schema(table_id)?)?;
+        Ok(rows
+            .iter()
+            .map(|row| (value_to_string(&row[0]), value_to_string(&row[1])))
+            .filter(|(name, _)| !name.starts_with("__"))
+            .map(|(name, dtype)| (name, self.model.column_type(&dtype)))
+            .collect())
+    }
+
+    fn expression_type(&self, table_id: &str, expression: &str) -> DuckDBResult<ColumnType> {
+        let sql = self.model.table_validate_expression(table_id, expression)?;
+        let (_, rows) = self.query(&sql)?;
+        let dtype = rows
+            .first()
+            .and_then(|row| row.get(1))
+            .map(value_to_string)
+            .ok_or_else(|| DuckDBHandlerError::Data(format!("No type for `{}`", expression)))?;
+
+        Ok(self.model.column_type(&dtype))
+    }
+
+    /// Reports an expression which could not be typed to the user as an
+    /// [`ExpressionDiagnostic`]. An in-process database is never unavailable,
+    /// so any failure is attributed to the expression.
+    fn expression_diagnostic(
+        result: DuckDBResult<ColumnType>,
+    ) -> Result<ColumnType, ExpressionDiagnostic> {
+        result.map_err(|e| match e {
+            DuckDBHandlerError::GenericSQL(GenericSQLError::InvalidExpression(diagnostic)) => {
+                diagnostic
+            },
+            e => ExpressionDiagnostic::new(e.to_string()),
+        })
+    }
+
+    /// Executes `queries` in order, binding each one's parameters.
+    fn execute_all(&self, queries: Vec<(String, Vec<Scalar>)>) -> DuckDBResult<()> {
+        let conn = self.connection();
+        for (sql, params) in queries {
+            tracing::debug!("{}", sql);
+            conn.execute(
+                &sql,
+                ::duckdb::params_from_iter(params.iter().map(scalar_to_value)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn min_max(&self, sql: &str) -> DuckDBResult<(Scalar, Scalar)> {
+        let (_, rows) = self.query(sql)?;
+        match rows.first().map(|row| row.as_slice()) {
+            Some([min, max]) => Ok((value_to_scalar(min), value_to_scalar(max))),
+            _ => Ok((Scalar::Null, Scalar::Null)),
+        }
+    }
+
+    fn data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> DuckDBResult<VirtualDataSlice> {
+        let sql = self
+            .model
+            .view_get_data(view_id, config, viewport, schema)?;
+        let (columns, rows) = self.query(&sql)?;
+        let is_group_by = !config.group_by.is_empty();
+        let mut data = VirtualDataSlice::new(config.clone());
+        for (cidx, name) in columns.iter().enumerate() {
+            if cidx == 0 && is_group_by {
+                // This is the `__GROUPING_ID__` column.
+                continue;
+            }
+
+            let dtype = schema.get(name).copied();
+            for (ridx, row) in rows.iter().enumerate() {
+                let grouping_id = if is_group_by {
+                    value_to_f64(&row[0]).map(|x| x as usize)
+                } else {
+                    None
+                };
+
+                set_value(&mut data, name, grouping_id, ridx, dtype, &row[cidx])?;
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+impl VirtualServerHandler for DuckDBHandler {
+    type Error = DuckDBHandlerError;
+
+    fn error_status_code(error: &Self::Error) -> StatusCode {
+        match error {
+            DuckDBHandlerError::GenericSQL(e) => e.status_code(),
+            DuckDBHandlerError::DuckDB(_) | DuckDBHandlerError::Data(_) => StatusCode::ServerError,
+        }
+    }
+
+    fn get_features(&self) -> VirtualServerFuture<'_, Result<Features<'_>, Self::Error>> {
+        Box::pin(ready(Ok(Features {
+            on_update: true,
+            ..self.model.features()
+        })))
+    }
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        self.spawn_blocking(Self::hosted_tables)
+    }
+
+    fn table_schema(
+        &self,
+        table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let table_id = table_id.to_owned();
+        self.spawn_blocking(move |this| this.describe(&table_id))
+    }
+
+    fn table_size(&self, table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.table_size(table_id);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_size(view_id, config);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn view_column_size(
+        &self,
+        view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_column_size(view_id);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn table_make_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        let query =
+            self.model
+                .table_make_view_parameterized(table_id, view_id, &config.clone().into());
+
+        let view_id = view_id.to_owned();
+        self.spawn_blocking(move |this| {
+            this.execute_all(vec![query?])?;
+            Ok(view_id)
+        })
+    }
+
+    fn table_validate_expression(
+        &self,
+        table_id: &str,
+        expression: &str,
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        let table_id = table_id.to_owned();
+        let expression = expression.to_owned();
+        self.spawn_blocking(move |this| {
+            Ok(Self::expression_diagnostic(
+                this.expression_type(&table_id, &expression),
+            ))
+        })
+    }
+
+    fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let sql = self.model.view_delete(view_id);
+        self.spawn_blocking(move |this| this.execute(&sql?).map(|_| ()))
+    }
+
+    fn view_get_data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let view_id = view_id.to_owned();
+        let config = config.clone();
+        let schema = schema.clone();
+        let viewport = viewport.clone();
+        self.spawn_blocking(move |this| this.data(&view_id, &config, &schema, &viewport))
+    }
+
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        let sql = self.model.view_get_min_max(view_id, column_name, config);
+        self.spawn_blocking(move |this| this.min_max(&sql?))
+    }
+
+    fn view_expand(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_expand(view_id, config, row_index);
+        self.spawn_blocking(move |this| Ok(this.execute(&sql?)?.saturating_sub(1) as u32))
+    }
+
+    fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_collapse(view_id, config, row_index);
+        self.spawn_blocking(move |this| Ok(this.execute(&sql?)?.saturating_sub(1) as u32))
+    }
+
+    fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        depth: u32,
+    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let sql = self.model.view_set_depth(view_id, config, depth);
+        self.spawn_blocking(move |this| this.execute(&sql?).map(|_| ()))
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        true
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_update_parameterized(table_id, index, data);
+
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_remove_parameterized(table_id, index, data);
+
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_replace_parameterized(table_id, data);
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_delete(
+        &self,
+        table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let sql = self.model.table_delete(table_id);
+        Some(self.spawn_blocking(move |this| this.execute(&sql?).map(|_| ())))
+    }
+}
+
+/// The `entity_id` of a table, omitting the default `main` schema.
+fn table_entity_id(database: &Value, schema: &Value, table: &Value) -> String {
+    match value_to_string(schema).as_str() {
+        "main" => format!("{}.{}", value_to_string(database), value_to_string(table)),
+        schema => format!(
+            "{}.{}.{}",
+            value_to_string(database),
+            schema,
+            value_to_string(table)
+        ),
+    }
+}
+
+/// Writes a query result `value` to column `name` of `data`, as a value of
+/// the column's type `dtype` or, for the `__ROW_PATH_N__` columns which are
+/// not in the view's schema, of the value's own type.
+fn set_value(
+    data: &mut VirtualDataSlice,
+    name: &str,
+    grouping_id: Option<usize>,
+    index: usize,
+    dtype: Option<ColumnType>,
+    value: &Value,
+) -> DuckDBResult<()> {
+    let dtype = dtype.unwrap_or(match value {
+        Value::Boolean(_) => ColumnType::Boolean,
+        Value::Date32(_) | Value::Timestamp(..) => ColumnType::Datetime,
+        Value::Null | Value::Text(_) | Value::Enum(_) => ColumnType::String,
+        _ if value_to_f64(value).is_some() => ColumnType::Float,
+        _ => ColumnType::String,
+    });
+
+    let is_null = matches!(value, Value::Null);
+    match dtype {
+        ColumnType::String => {
+            let value = (!is_null).then(|| value_to_string(value));
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Integer => {
+            let value = value_to_f64(value).map(|x| x as i32);
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Float => data.set_col(name, grouping_id, index, value_to_f64(value)),
+        ColumnType::Boolean => {
+            let value = match value {
+                Value::Boolean(x) => Some(*x),
+                _ => None,
+            };
+
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Date | ColumnType::Datetime => {
+            data.set_col(name, grouping_id, index, value_to_millis(value))
+        },
+    }
+    .map_err(|e| DuckDBHandlerError::Data(e.to_string()))
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    Some(match value {
+        Value::TinyInt(x) => *x as f64,
+        Value::SmallInt(x) => *x as f64,
+        Value::Int(x) => *x as f64,
+        Value::BigInt(x) => *x as f64,
+        Value::HugeInt(x) => *x as f64,
+        Value::UTinyInt(x) => *x as f64,
+        Value::USmallInt(x) => *x as f64,
+        Value::UInt(x) => *x as f64,
+        Value::UBigInt(x) => *x as f64,
+        Value::Float(x) => *x as f64,
+        Value::Double(x) => *x,
+        Value::Decimal(x) => x.to_string().parse().ok()?,
+        _ => return None,
+    })
+}
+
+/// Converts a `DATE` or `TIMESTAMP` to milliseconds since the epoch.
+fn value_to_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Date32(days) => Some(*days as i64 * 86_400_000),
+        Value::Timestamp(unit, x) => Some(match unit {
+            TimeUnit::Second => x * 1_000,
+            TimeUnit::Millisecond => *x,
+            TimeUnit::Microsecond => x / 1_000,
+            TimeUnit::Nanosecond => x / 1_000_000,
+        }),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_owned(),
+        Value::Boolean(x) => x.to_string(),
+        Value::Text(x) | Value::Enum(x) => x.clone(),
+        Value::List(xs) | Value::Array(xs) => format!(
+            "[{}]",
+            xs.iter()
+                .map(value_to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        value => match value_to_f64(value) {
+            Some(x) => x.to_string(),
+            None => format!("{:?}", value),
+        },
+    }
+}
+
+fn value_to_scalar(value: &Value) -> Scalar {
+    match value {
+        Value::Null => Scalar::Null,
+        Value::Boolean(x) => Scalar::Bool(*x),
+        Value::Date32(_) | Value::Timestamp(..) => {
+            value_to_millis(value).map_or(Scalar::Null, |x| Scalar::Float(x as f64))
+        },
+        value => match value_to_f64(value) {
+            Some(x) => Scalar::Float(x),
+            None => Scalar::String(value_to_string(value)),
+        },
+    }
+}
+
+/// Converts a query parameter to a DuckDB value, which DuckDB casts to the
+/// type of the column it is written to.
+fn scalar_to_value(scalar: &Scalar) -> Value {
+    match scalar {
+        Scalar::Null => Value::Null,
+        Scalar::Bool(x) => Value::Boolean(*x),
+        Scalar::Float(x) => Value::Double(*x),
+        Scalar::String(x) | Scalar::Date(x) => Value::Text(x.clone()),
+        Scalar::Datetime(x) => Value::Timestamp(TimeUnit::Millisecond, *x),
+    }
+}
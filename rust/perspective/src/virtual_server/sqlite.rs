@@ -0,0 +1,795 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+//! A [`VirtualServerHandler`] for a [SQLite](https://sqlite.org) database,
+//! which generates its queries with [`GenericSQLVirtualServerModel`] in the
+//! [`SqliteDialect`].
+//!
+//! SQLite has neither `ROLLUP` nor `PIVOT`, so `group_by` is emulated with a
+//! `UNION ALL` of one `GROUP BY` per level, and `split_by` with one
+//! conditional aggregate per column and distinct value of the `split_by`
+//! columns, which the handler queries before creating the view.
+//!
+//! # Examples
+//!
+//! Serve the tables of a SQLite file from an [`axum`] route (requires the
+//! `axum-ws` feature as well):
+//!
+//! ```rust,ignore
+//! use perspective::virtual_server::custom_websocket_handler;
+//! use perspective::virtual_server::sqlite::SqliteHandler;
+//!
+//! let handler = SqliteHandler::open("superstore.db")?;
+//! let app = axum::Router::new().route("/ws", custom_websocket_handler(handler));
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::ready;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use indexmap::IndexMap;
+use perspective_client::config::{Scalar, ViewConfig, ViewConfigUpdate};
+use perspective_client::proto::{ColumnType, HostedTable, MakeTableData, StatusCode, ViewPort};
+use perspective_client::virtual_server::{
+    ExpressionDiagnostic, Features, GenericSQLError, GenericSQLVirtualServerModel,
+    GenericSQLVirtualServerModelArgs, SqliteDialect, VirtualDataSlice, VirtualServerFuture,
+    VirtualServerHandler, decode_column_path,
+};
+use rusqlite::types::Value;
+use rusqlite::{Connection, ErrorCode};
+
+const PRIMARY_KEYS_QUERY: &str = "SELECT m.name, p.name FROM sqlite_master AS m JOIN \
+                                  pragma_table_info(m.name) AS p WHERE m.type = 'table' AND p.pk \
+                                  > 0";
+
+/// An error from a [`SqliteHandler`].
+#[derive(Debug)]
+pub enum SqliteHandlerError {
+    /// A query failed in SQLite.
+    Sqlite(rusqlite::Error),
+
+    /// A query could not be generated for the request.
+    GenericSQL(GenericSQLError),
+
+    /// A query result could not be converted to Perspective's data model.
+    Data(String),
+}
+
+impl fmt::Display for SqliteHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteHandlerError::Sqlite(e) => write!(f, "SQLite error: {}", e),
+            SqliteHandlerError::GenericSQL(e) => write!(f, "{}", e),
+            SqliteHandlerError::Data(e) => write!(f, "Data error: {}", e),
+        }
+    }
+}
+
+impl Error for SqliteHandlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SqliteHandlerError::Sqlite(e) => Some(e),
+            SqliteHandlerError::GenericSQL(e) => Some(e),
+            SqliteHandlerError::Data(_) => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for SqliteHandlerError {
+    fn from(value: rusqlite::Error) -> Self {
+        SqliteHandlerError::Sqlite(value)
+    }
+}
+
+impl From<GenericSQLError> for SqliteHandlerError {
+    fn from(value: GenericSQLError) -> Self {
+        SqliteHandlerError::GenericSQL(value)
+    }
+}
+
+type SqliteResult<T> = Result<T, SqliteHandlerError>;
+
+/// A [`VirtualServerHandler`] which hosts every table of a SQLite database.
+///
+/// A table with a single-column `PRIMARY KEY` uses it as its `index`. Each
+/// `View` is materialized as a `TEMP` table, so views are neither written to
+/// the database file nor listed as hosted tables.
+///
+/// SQLite's catalog does not report the types of computed columns, so a
+/// view's schema is derived from its table's declared column types (and its
+/// expressions' types) and aggregates. `DATE` and `DATETIME` columns must
+/// hold ISO 8601 text, e.g. `2024-01-31` or `2024-01-31 12:00:00`.
+///
+/// Clones share the same [`Connection`]. Queries run on tokio's blocking
+/// thread pool, one at a time as they hold the connection's lock, so the
+/// handler must be used from within a tokio runtime.
+#[derive(Clone)]
+pub struct SqliteHandler {
+    conn: Arc<Mutex<Connection>>,
+    model: GenericSQLVirtualServerModel,
+    view_sources: Arc<Mutex<HashMap<String, IndexMap<String, ColumnType>>>>,
+}
+
+impl SqliteHandler {
+    /// Creates a handler for an open SQLite [`Connection`].
+    pub fn new(conn: Connection) -> Self {
+        let args = GenericSQLVirtualServerModelArgs::default().with_create_entity("TEMP TABLE");
+        SqliteHandler {
+            conn: Arc::new(Mutex::new(conn)),
+            model: GenericSQLVirtualServerModel::with_dialect(args, Arc::new(SqliteDialect)),
+            view_sources: Arc::default(),
+        }
+    }
+
+    /// Creates a handler for the SQLite database file at `path`, which is
+    /// created if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
+        Ok(Self::new(Connection::open(path)?))
+    }
+
+    /// Creates a handler for a new, empty in-memory SQLite database.
+    pub fn open_in_memory() -> Result<Self, rusqlite::Error> {
+        Ok(Self::new(Connection::open_in_memory()?))
+    }
+
+    /// The handler's [`Connection`], e.g. to load data. Writes made this way
+    /// do not trigger `on_update` callbacks.
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The schema of the table (and expressions) each view was created from,
+    /// by view ID.
+    fn view_sources(&self) -> MutexGuard<'_, HashMap<String, IndexMap<String, ColumnType>>> {
+        self.view_sources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `f` with a clone of this handler (sharing its connection) on
+    /// tokio's blocking thread pool, so a long query does not stall the
+    /// runtime's worker threads.
+    fn spawn_blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Self) -> SqliteResult<T> + Send + 'static,
+    ) -> VirtualServerFuture<'static, SqliteResult<T>> {
+        let handler = self.clone();
+        Box::pin(async move {
+            match tokio::task::spawn_blocking(move || f(&handler)).await {
+                Ok(result) => result,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        })
+    }
+
+    fn query(&self, sql: &str) -> SqliteResult<(Vec<String>, Vec<Vec<Value>>)> {
+        tracing::debug!("{}", sql);
+        let conn = self.connection();
+        let mut stmt = conn.prepare(sql)?;
+        let columns = stmt
+            .column_names()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        let mut results = vec![];
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            results.push(
+                (0..columns.len())
+                    .map(|idx| row.get::<_, Value>(idx))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        Ok((columns, results))
+    }
+
+    fn execute(&self, sql: &str) -> SqliteResult<usize> {
+        tracing::debug!("{}", sql);
+        Ok(self.connection().execute(sql, [])?)
+    }
+
+    fn query_count(&self, sql: &str) -> SqliteResult<u32> {
+        let (_, rows) = self.query(sql)?;
+        match rows.first().and_then(|row| row.first()) {
+            Some(Value::Integer(x)) => Ok(*x as u32),
+            value => Err(SqliteHandlerError::Data(format!(
+                "Expected a count, got {:?}",
+                value
+            ))),
+        }
+    }
+
+    fn hosted_tables(&self) -> SqliteResult<Vec<HostedTable>> {
+        let mut primary_keys = IndexMap::<String, Vec<String>>::new();
+        for row in self.query(PRIMARY_KEYS_QUERY)?.1 {
+            primary_keys
+                .entry(value_to_string(&row[0]))
+                .or_default()
+                .push(value_to_string(&row[1]));
+        }
+
+        let (_, rows) = self.query(&self.model.get_hosted_tables()?)?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let entity_id = value_to_string(&row[0]);
+                let index = match primary_keys.get(&entity_id).map(Vec::as_slice) {
+                    Some([column]) => Some(column.clone()),
+                    _ => None,
+                };
+
+                HostedTable {
+                    entity_id,
+                    index,
+                    limit: None,
+                }
+            })
+            .collect())
+    }
+
+    fn describe(&self, table_id: &str) -> SqliteResult<IndexMap<String, ColumnType>> {
+        let (_, rows) = self.query(&self.model.table_schema(table_id)?)?;
+        Ok(rows
+            .iter()
+            .map(|row| (value_to_string(&row[0]), value_to_string(&row[1])))
+            .filter(|(name, _)| !name.starts_with("__"))
+            .map(|(name, dtype)| (name, self.model.column_type(&dtype)))
+            .collect())
+    }
+
+    /// The schema of the table (and expressions) a view is created from.
+    /// Selects every one of these columns when `config` has no `columns`, as
+    /// the Perspective engine does.
+    fn view_source(
+        &self,
+        table_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> SqliteResult<IndexMap<String, ColumnType>> {
+        let mut source = self.describe(table_id)?;
+        for (name, expression) in config.expressions.iter().flat_map(|x| x.iter()) {
+            source.insert(name.clone(), self.expression_type(table_id, expression)?);
+        }
+
+        if config.columns.is_none() {
+            config.columns = Some(source.keys().cloned().map(Some).collect());
+        }
+
+        Ok(source)
+    }
+
+    /// Creates a view of `table_id`, whose table's schema is `source`.
+    fn create_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &ViewConfig,
+        source: IndexMap<String, ColumnType>,
+    ) -> SqliteResult<()> {
+        let sql = if config.split_by.is_empty() {
+            self.model.table_make_view(table_id, view_id, config)?
+        } else {
+            let (_, rows) = self.query(&self.model.view_split_values(table_id, config)?)?;
+            let split_values = rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|x| (!matches!(x, Value::Null)).then(|| value_to_string(x)))
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+
+            self.model.table_make_view_with_split_values(
+                table_id,
+                view_id,
+                config,
+                &split_values,
+            )?
+        };
+
+        self.execute(&sql)?;
+        self.view_sources().insert(view_id.to_owned(), source);
+        Ok(())
+    }
+
+    fn expression_type(&self, table_id: &str, expression: &str) -> SqliteResult<ColumnType> {
+        let sql = self.model.table_validate_expression(table_id, expression)?;
+        let (_, rows) = self.query(&sql)?;
+
+        // `typeof()` needs a row to evaluate the expression against.
+        Ok(rows
+            .first()
+            .and_then(|row| row.get(1))
+            .map_or(ColumnType::Float, |dtype| {
+                self.model.column_type(&value_to_string(dtype))
+            }))
+    }
+
+    /// Separates an invalid expression, reported to the user as an
+    /// [`ExpressionDiagnostic`], from a failure of the database itself.
+    fn expression_diagnostic(
+        result: SqliteResult<ColumnType>,
+    ) -> SqliteResult<Result<ColumnType, ExpressionDiagnostic>> {
+        match result {
+            Ok(dtype) => Ok(Ok(dtype)),
+            Err(SqliteHandlerError::GenericSQL(GenericSQLError::InvalidExpression(diagnostic))) => {
+                Ok(Err(diagnostic))
+            },
+            Err(e) if Self::error_status_code(&e) == StatusCode::BackendUnavailable => Err(e),
+            Err(e) => Ok(Err(ExpressionDiagnostic::new(e.to_string()))),
+        }
+    }
+
+    /// The schema of a view, whose columns are those of the view's table,
+    /// aggregated if the view is grouped.
+    fn view_columns(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> SqliteResult<IndexMap<String, ColumnType>> {
+        let source = self
+            .view_sources()
+            .get(view_id)
+            .cloned()
+            .ok_or_else(|| SqliteHandlerError::Data(format!("Unknown view `{}`", view_id)))?;
+
+        let (_, rows) = self.query(&self.model.view_schema(view_id)?)?;
+        let mut schema = IndexMap::new();
+        for name in rows.iter().map(|row| value_to_string(&row[0])) {
+            if name.starts_with("__") {
+                continue;
+            }
+
+            let column = if config.split_by.is_empty() {
+                name.clone()
+            } else {
+                decode_column_path(&name).pop().unwrap_or_default()
+            };
+
+            let dtype = source
+                .get(&column)
+                .copied()
+                .ok_or_else(|| SqliteHandlerError::Data(format!("Unknown column `{}`", column)))?;
+
+            let dtype = if config.group_by.is_empty() {
+                dtype
+            } else {
+                GenericSQLVirtualServerModel::aggregate_type(config.aggregates.get(&column), dtype)
+            };
+
+            schema.insert(name, dtype);
+        }
+
+        Ok(schema)
+    }
+
+    /// Executes `queries` in order, binding each one's parameters.
+    fn execute_all(&self, queries: Vec<(String, Vec<Scalar>)>) -> SqliteResult<()> {
+        let conn = self.connection();
+        for (sql, params) in queries {
+            tracing::debug!("{}", sql);
+            conn.execute(
+                &sql,
+                rusqlite::params_from_iter(params.iter().map(scalar_to_value)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> SqliteResult<(Scalar, Scalar)> {
+        let dtype = self
+            .view_columns(view_id, config)?
+            .get(column_name)
+            .copied();
+        let sql = self.model.view_get_min_max(view_id, column_name, config)?;
+        let (_, rows) = self.query(&sql)?;
+        let to_scalar = |value: &Value| match dtype {
+            Some(ColumnType::Date | ColumnType::Datetime) => {
+                value_to_millis(value).map_or(Scalar::Null, |x| Scalar::Float(x as f64))
+            },
+            _ => value_to_scalar(value),
+        };
+
+        match rows.first().map(|row| row.as_slice()) {
+            Some([min, max]) => Ok((to_scalar(min), to_scalar(max))),
+            _ => Ok((Scalar::Null, Scalar::Null)),
+        }
+    }
+
+    fn data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> SqliteResult<VirtualDataSlice> {
+        let sql = self
+            .model
+            .view_get_data(view_id, config, viewport, schema)?;
+        let (columns, rows) = self.query(&sql)?;
+        let source = self
+            .view_sources()
+            .get(view_id)
+            .cloned()
+            .unwrap_or_default();
+        let is_group_by = !config.group_by.is_empty();
+        let mut data = VirtualDataSlice::new(config.clone());
+        for (cidx, name) in columns.iter().enumerate() {
+            if cidx == 0 && is_group_by {
+                // This is the `__GROUPING_ID__` column.
+                continue;
+            }
+
+            // The `__ROW_PATH_N__` columns are not in the view's schema, and
+            // have the type of the `N`th `group_by` column.
+            let dtype = schema.get(name).copied().or_else(|| {
+                let idx: usize = name
+                    .strip_prefix("__ROW_PATH_")?
+                    .strip_suffix("__")?
+                    .parse()
+                    .ok()?;
+                source.get(config.group_by.get(idx)?).copied()
+            });
+
+            for (ridx, row) in rows.iter().enumerate() {
+                let grouping_id = match (is_group_by, &row[0]) {
+                    (true, Value::Integer(x)) => Some(*x as usize),
+                    _ => None,
+                };
+
+                set_value(
+                    &mut data,
+                    name,
+                    grouping_id,
+                    ridx,
+                    dtype.unwrap_or(ColumnType::String),
+                    &row[cidx],
+                )?;
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+impl VirtualServerHandler for SqliteHandler {
+    type Error = SqliteHandlerError;
+
+    fn error_status_code(error: &Self::Error) -> StatusCode {
+        match error {
+            SqliteHandlerError::GenericSQL(e) => e.status_code(),
+            SqliteHandlerError::Sqlite(e) => match e.sqlite_error_code() {
+                Some(
+                    ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked | ErrorCode::CannotOpen,
+                ) => StatusCode::BackendUnavailable,
+                _ => StatusCode::ServerError,
+            },
+            SqliteHandlerError::Data(_) => StatusCode::ServerError,
+        }
+    }
+
+    fn get_features(&self) -> VirtualServerFuture<'_, Result<Features<'_>, Self::Error>> {
+        Box::pin(ready(Ok(Features {
+            on_update: true,
+            ..self.model.features()
+        })))
+    }
+
+    fn get_hosted_tables(&self) -> VirtualServerFuture<'_, Result<Vec<HostedTable>, Self::Error>> {
+        self.spawn_blocking(Self::hosted_tables)
+    }
+
+    fn table_schema(
+        &self,
+        table_id: &str,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let table_id = table_id.to_owned();
+        self.spawn_blocking(move |this| this.describe(&table_id))
+    }
+
+    fn table_size(&self, table_id: &str) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.table_size(table_id);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn view_schema(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<IndexMap<String, ColumnType>, Self::Error>> {
+        let view_id = view_id.to_owned();
+        let config = config.clone();
+        self.spawn_blocking(move |this| this.view_columns(&view_id, &config))
+    }
+
+    fn view_size(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_size(view_id, config);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn view_column_size(
+        &self,
+        view_id: &str,
+        _config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_column_size(view_id);
+        self.spawn_blocking(move |this| this.query_count(&sql?))
+    }
+
+    fn table_make_view(
+        &self,
+        table_id: &str,
+        view_id: &str,
+        config: &mut ViewConfigUpdate,
+    ) -> VirtualServerFuture<'_, Result<String, Self::Error>> {
+        // `config` must be completed before returning, so only the catalog
+        // lookups for the view's source schema run on the calling task.
+        let source = self.view_source(table_id, config);
+        let config: ViewConfig = config.clone().into();
+        let table_id = table_id.to_owned();
+        let view_id = view_id.to_owned();
+        self.spawn_blocking(move |this| {
+            this.create_view(&table_id, &view_id, &config, source?)?;
+            Ok(view_id)
+        })
+    }
+
+    fn table_validate_expression(
+        &self,
+        table_id: &str,
+        expression: &str,
+    ) -> VirtualServerFuture<'_, Result<Result<ColumnType, ExpressionDiagnostic>, Self::Error>>
+    {
+        let table_id = table_id.to_owned();
+        let expression = expression.to_owned();
+        self.spawn_blocking(move |this| {
+            Self::expression_diagnostic(this.expression_type(&table_id, &expression))
+        })
+    }
+
+    fn view_delete(&self, view_id: &str) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let sql = self.model.view_delete(view_id);
+        let view_id = view_id.to_owned();
+        self.spawn_blocking(move |this| {
+            this.execute(&sql?)?;
+            this.view_sources().remove(&view_id);
+            Ok(())
+        })
+    }
+
+    fn view_get_data(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        schema: &IndexMap<String, ColumnType>,
+        viewport: &ViewPort,
+    ) -> VirtualServerFuture<'_, Result<VirtualDataSlice, Self::Error>> {
+        let view_id = view_id.to_owned();
+        let config = config.clone();
+        let schema = schema.clone();
+        let viewport = viewport.clone();
+        self.spawn_blocking(move |this| this.data(&view_id, &config, &schema, &viewport))
+    }
+
+    fn view_get_min_max(
+        &self,
+        view_id: &str,
+        column_name: &str,
+        config: &ViewConfig,
+    ) -> VirtualServerFuture<'_, Result<(Scalar, Scalar), Self::Error>> {
+        let view_id = view_id.to_owned();
+        let column_name = column_name.to_owned();
+        let config = config.clone();
+        self.spawn_blocking(move |this| this.min_max(&view_id, &column_name, &config))
+    }
+
+    fn view_expand(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_expand(view_id, config, row_index);
+        self.spawn_blocking(move |this| Ok(this.execute(&sql?)?.saturating_sub(1) as u32))
+    }
+
+    fn view_collapse(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        row_index: u32,
+    ) -> VirtualServerFuture<'_, Result<u32, Self::Error>> {
+        let sql = self.model.view_collapse(view_id, config, row_index);
+        self.spawn_blocking(move |this| Ok(this.execute(&sql?)?.saturating_sub(1) as u32))
+    }
+
+    fn view_set_depth(
+        &self,
+        view_id: &str,
+        config: &ViewConfig,
+        depth: u32,
+    ) -> VirtualServerFuture<'_, Result<(), Self::Error>> {
+        let sql = self.model.view_set_depth(view_id, config, depth);
+        self.spawn_blocking(move |this| this.execute(&sql?).map(|_| ()))
+    }
+
+    fn supports_table_updates(&self) -> bool {
+        true
+    }
+
+    fn table_update(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_update_parameterized(table_id, index, data);
+
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_remove(
+        &self,
+        table_id: &str,
+        index: Option<&str>,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_remove_parameterized(table_id, index, data);
+
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_replace(
+        &self,
+        table_id: &str,
+        data: &MakeTableData,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let queries = self.model.table_replace_parameterized(table_id, data);
+        Some(self.spawn_blocking(move |this| this.execute_all(queries?)))
+    }
+
+    fn table_delete(
+        &self,
+        table_id: &str,
+    ) -> Option<VirtualServerFuture<'_, Result<(), Self::Error>>> {
+        let sql = self.model.table_delete(table_id);
+        Some(self.spawn_blocking(move |this| this.execute(&sql?).map(|_| ())))
+    }
+}
+
+/// Writes a query result `value` to column `name` of `data`, as a value of
+/// the column's type `dtype`. SQLite values are dynamically typed, so
+/// e.g. an `INTEGER` column may hold `REAL` or `TEXT` values.
+fn set_value(
+    data: &mut VirtualDataSlice,
+    name: &str,
+    grouping_id: Option<usize>,
+    index: usize,
+    dtype: ColumnType,
+    value: &Value,
+) -> SqliteResult<()> {
+    match dtype {
+        ColumnType::String => {
+            let value = (!matches!(value, Value::Null)).then(|| value_to_string(value));
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Integer => {
+            let value = value_to_f64(value).map(|x| x as i32);
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Float => data.set_col(name, grouping_id, index, value_to_f64(value)),
+        ColumnType::Boolean => {
+            let value = value_to_f64(value).map(|x| x != 0.0);
+            data.set_col(name, grouping_id, index, value)
+        },
+        ColumnType::Date | ColumnType::Datetime => {
+            data.set_col(name, grouping_id, index, value_to_millis(value))
+        },
+    }
+    .map_err(|e| SqliteHandlerError::Data(e.to_string()))
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(x) => Some(*x as f64),
+        Value::Real(x) => Some(*x),
+        Value::Text(x) => x.trim().parse().ok(),
+        Value::Null | Value::Blob(_) => None,
+    }
+}
+
+/// Converts an ISO 8601 `YYYY-MM-DD` date or `YYYY-MM-DD HH:MM[:SS[.sss]]`
+/// datetime (with a ` ` or `T` separator and an optional `Z` suffix) to
+/// milliseconds since the epoch, as UTC.
+fn value_to_millis(value: &Value) -> Option<i64> {
+    let Value::Text(text) = value else {
+        return None;
+    };
+
+    let text = text.trim().trim_end_matches('Z');
+    let (date, time) = match text.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let mut millis = 0.0;
+    if let Some(time) = time {
+        for (part, scale) in time.split(':').zip([3_600_000.0, 60_000.0, 1000.0]) {
+            millis += part.parse::<f64>().ok()? * scale;
+        }
+    }
+
+    Some(days * 86_400_000 + millis.round() as i64)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_owned(),
+        Value::Integer(x) => x.to_string(),
+        Value::Real(x) => x.to_string(),
+        Value::Text(x) => x.clone(),
+        Value::Blob(x) => String::from_utf8_lossy(x).into_owned(),
+    }
+}
+
+fn value_to_scalar(value: &Value) -> Scalar {
+    match value {
+        Value::Null => Scalar::Null,
+        Value::Integer(x) => Scalar::Float(*x as f64),
+        Value::Real(x) => Scalar::Float(*x),
+        Value::Text(_) | Value::Blob(_) => Scalar::String(value_to_string(value)),
+    }
+}
+
+/// Converts a query parameter to a SQLite value. Whole numbers are bound as
+/// integers, so they keep their type in columns without a numeric affinity.
+fn scalar_to_value(scalar: &Scalar) -> Value {
+    match scalar {
+        Scalar::Null => Value::Null,
+        Scalar::Bool(x) => Value::Integer(*x as i64),
+        Scalar::Float(x) if x.fract() == 0.0 && x.abs() < 2f64.powi(53) => {
+            Value::Integer(*x as i64)
+        },
+        Scalar::Float(x) => Value::Real(*x),
+        Scalar::String(x) | Scalar::Date(x) => Value::Text(x.clone()),
+        Scalar::Datetime(x) => Value::Integer(*x),
+    }
+}
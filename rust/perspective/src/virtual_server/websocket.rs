@@ -0,0 +1,174 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::connect_info::ConnectInfo;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::routing::{MethodRouter, get};
+use futures::future::Either;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use perspective_client::proto::response::ClientResp;
+use perspective_client::proto::{Response, ServerError};
+use perspective_client::virtual_server::{
+    VirtualServer, VirtualServerError, VirtualServerHandler, VirtualSession,
+};
+use prost::Message as _;
+
+/// A local error synonym for this module only.
+type PerspectiveWSError = Box<dyn std::error::Error + Send + Sync>;
+
+pub type PSPError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The number of requests from one connection which
+/// [`custom_websocket_handler`] processes concurrently.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// The inner message loop handles the full-duplex stream of messages
+/// between the [`perspective::Client`] and [`Session`]. When this
+/// funciton returns, messages are no longer processed.
+///
+/// Up to `max_concurrent_requests` requests are processed at once, and their
+/// responses are sent as each completes, tagged with their request's
+/// `msg_id`. Further messages are not read from the socket until a request
+/// completes. Table updates are polled alongside the in-flight requests, so
+/// neither waits for the other, with at most one poll in flight.
+///
+/// A request which fails, e.g. as it cannot be decoded, is answered with a
+/// `ServerError` and does not close the connection.
+async fn process_message_loop<T: VirtualServerHandler>(
+    socket: &mut WebSocket,
+    processor: &VirtualSession<T>,
+    max_concurrent_requests: usize,
+) -> Result<(), PerspectiveWSError> {
+    use Message::*;
+    let notifier = processor.notifier();
+    let mut in_flight = FuturesUnordered::new();
+    let mut polling = false;
+    loop {
+        tokio::select! {
+            Some((is_poll, resps)) = in_flight.next(), if !in_flight.is_empty() => {
+                if is_poll {
+                    polling = false;
+                }
+
+                match resps {
+                    Ok(resps) => {
+                        for resp in resps {
+                            socket.send(Binary(resp)).await?
+                        }
+                    },
+                    Err(err) if is_poll => tracing::error!("Failed to poll updates: {}", err),
+                    Err(err) => {
+                        tracing::error!("Failed to handle request: {}", err);
+                        socket.send(Binary(error_response::<T>(&err).into())).await?
+                    },
+                }
+            },
+            msg = socket.recv(), if in_flight.len() < max_concurrent_requests.max(1) => {
+                match msg {
+                    Some(Ok(Binary(msg))) => in_flight.push(Either::Left(
+                        processor
+                            .handle_request(msg)
+                            .map(|resp| (false, resp.map(Vec::from_iter))),
+                    )),
+                    Some(_) | None => {
+                        tracing::debug!("Unexpected msg");
+                        break;
+                    },
+                }
+            },
+            () = notifier.notified(), if !polling => {
+                polling = true;
+                in_flight.push(Either::Right(processor.poll().map(|resps| (true, resps))));
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// A `ServerError` response for a request which failed before it could be
+/// answered, whose `msg_id` may not be known.
+fn error_response<T: VirtualServerHandler>(err: &VirtualServerError<T::Error>) -> Vec<u8> {
+    Response {
+        msg_id: 0,
+        entity_id: String::new(),
+        client_resp: Some(ClientResp::ServerError(ServerError {
+            message: err.to_string(),
+            status_code: err.status_code(T::error_status_code) as i32,
+        })),
+    }
+    .encode_to_vec()
+}
+
+/// This handler is responsible for the beginning-to-end lifecycle of a
+/// single WebSocket connection to an [`axum`] server.
+///
+/// Messages will come in from the [`axum::extract::ws::WebSocket`] in binary
+/// form via [`Message::Binary`], where they'll be routed to
+/// [`perspective::Session::handle_request`]. The server may generate
+/// one or more responses, which it will then send back to
+/// the [`axum::extract::ws::WebSocket::send`] method via its
+/// [`SessionHandler`] impl.
+///
+/// All connections share one [`VirtualServer`] (and so one `handler`), each
+/// as its own [`VirtualSession`], whose `View`s are deleted when the
+/// connection closes.
+///
+/// Up to [`DEFAULT_MAX_CONCURRENT_REQUESTS`] requests per connection are
+/// processed concurrently, see [`concurrent_websocket_handler`].
+pub fn custom_websocket_handler<S, T>(handler: T) -> MethodRouter<S>
+where
+    T: VirtualServerHandler + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    concurrent_websocket_handler(handler, DEFAULT_MAX_CONCURRENT_REQUESTS)
+}
+
+/// Like [`custom_websocket_handler`], but processes up to
+/// `max_concurrent_requests` requests per connection concurrently, so that
+/// e.g. a slow query for one `View` does not delay the responses for
+/// another. `1` processes requests one at a time, in order.
+pub fn concurrent_websocket_handler<S, T>(
+    handler: T,
+    max_concurrent_requests: usize,
+) -> MethodRouter<S>
+where
+    T: VirtualServerHandler + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    let server = Arc::new(VirtualServer::new(handler));
+    let websocket_handler_internal = async move |ws: WebSocketUpgrade,
+                                                 ConnectInfo(addr): ConnectInfo<SocketAddr>|
+                -> axum::response::Response {
+        tracing::info!("{addr} Connected.");
+        let session = server.new_session();
+        ws.on_upgrade(move |mut socket| async move {
+            if let Err(msg) =
+                process_message_loop(&mut socket, &session, max_concurrent_requests).await
+            {
+                tracing::error!("Internal error {}", msg);
+            }
+
+            if let Err(msg) = session.close().await {
+                tracing::error!("Internal error {}", msg);
+            }
+
+            tracing::info!("{addr} Disconnected.");
+        })
+    };
+
+    get(websocket_handler_internal)
+}
@@ -0,0 +1,70 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+use std::error::Error;
+
+use perspective_client::virtual_server::{VirtualServer, VirtualServerHandler};
+use perspective_client::{Client, ClientError};
+use prost::bytes::Bytes;
+
+/// A [`Client`] connected to a [`VirtualServer`] for `handler`, which
+/// processes its requests on a background task.
+///
+/// A request which fails on the server fails the [`Client`]'s pending
+/// requests with a [`ClientError::TransportError`], rather than panicking the
+/// background task and leaving the test waiting for a response.
+pub fn client<T>(handler: T) -> Result<Client, ClientError>
+where
+    T: VirtualServerHandler + Send + Sync + 'static,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+    let client = Client::new_with_callback(None, move |bytes| {
+        let tx = tx.clone();
+        async move { Ok(tx.send(bytes)?) }
+    })?;
+
+    let server = VirtualServer::new(handler);
+    tokio::spawn({
+        let client = client.clone();
+        async move {
+            while let Some(bytes) = rx.recv().await {
+                if let Err(e) = respond(&server, &client, bytes).await {
+                    let message = ClientError::TransportError(e.to_string());
+                    let reconnect = None::<fn() -> std::future::Ready<Result<(), ClientError>>>;
+                    if client.handle_error(message, reconnect).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(client)
+}
+
+/// Handles the request `bytes` on `server`, and delivers its responses and
+/// any pending update notifications to `client`.
+async fn respond<T>(
+    server: &VirtualServer<T>,
+    client: &Client,
+    bytes: Vec<u8>,
+) -> Result<(), Box<dyn Error + Send + Sync>>
+where
+    T: VirtualServerHandler,
+{
+    let resp = server.handle_request(Bytes::from(bytes)).await?;
+    for resp in resp.into_iter().chain(server.poll().await?) {
+        client.handle_response(&resp).await?;
+    }
+
+    Ok(())
+}
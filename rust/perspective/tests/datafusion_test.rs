@@ -0,0 +1,267 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(feature = "datafusion")]
+mod common;
+
+#[cfg(feature = "datafusion")]
+mod internal {
+    use std::error::Error;
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Date32Array, Float64Array, Int32Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use perspective::virtual_server::datafusion::DataFusionHandler;
+    use perspective_client::config::ViewConfigUpdate;
+    use perspective_client::{
+        Client, ClientError, ColumnWindow, Table, UpdateData, UpdateOptions, ViewWindow,
+    };
+    use serde_json::{Value, json};
+
+    use crate::common::client;
+
+    /// The `superstore` table, split across two [`RecordBatch`]es.
+    fn superstore_batches() -> Result<(Arc<Schema>, Vec<RecordBatch>), Box<dyn Error>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("region", DataType::Utf8, true),
+            Field::new("category", DataType::Utf8, true),
+            Field::new("unit_price", DataType::Float64, true),
+            Field::new("quantity", DataType::Int32, true),
+            Field::new("order_date", DataType::Date32, true),
+        ]));
+
+        let batch = |region: Vec<&str>,
+                     category: Vec<&str>,
+                     unit_price: Vec<f64>,
+                     quantity: Vec<i32>,
+                     order_date: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![
+                Arc::new(StringArray::from(region)) as ArrayRef,
+                Arc::new(StringArray::from(category)),
+                Arc::new(Float64Array::from(unit_price)),
+                Arc::new(Int32Array::from(quantity)),
+                Arc::new(Date32Array::from(order_date)),
+            ])
+        };
+
+        let batches = vec![
+            batch(
+                vec!["East", "East"],
+                vec!["Furniture", "Technology"],
+                vec![10.5, 20.0],
+                vec![2, 1],
+                vec![19723, 19724],
+            )?,
+            batch(
+                vec!["West", "North_East"],
+                vec!["Furniture", "Technology"],
+                vec![5.25, 1.0],
+                vec![4, 3],
+                vec![19725, 19726],
+            )?,
+        ];
+
+        Ok((schema, batches))
+    }
+
+    async fn superstore() -> Result<(Client, Table), Box<dyn Error>> {
+        let handler = DataFusionHandler::new();
+        let (schema, batches) = superstore_batches()?;
+        handler.register_batches("superstore", schema, batches)?;
+        let client = client(handler)?;
+        let table = client.open_table("superstore".to_string()).await?;
+        Ok((client, table))
+    }
+
+    fn view_config(config: Value) -> ViewConfigUpdate {
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hosted_tables_and_schema() -> Result<(), Box<dyn Error>> {
+        let (client, table) = superstore().await?;
+        assert_eq!(table.get_index(), None);
+        assert_eq!(table.size().await?, 4);
+        assert_eq!(
+            serde_json::to_value(table.schema().await?)?,
+            json!({
+                "region": "string",
+                "category": "string",
+                "unit_price": "float",
+                "quantity": "integer",
+                "order_date": "date",
+            })
+        );
+
+        // Views are DataFusion `VIEW`s, which are not hosted.
+        let view = table.view(None).await?;
+        assert_eq!(client.get_hosted_table_names().await?, vec![
+            "superstore".to_string()
+        ]);
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flat_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["region", "unit_price", "order_date"],
+                "filter": [["quantity", ">", 1]],
+                "sort": [["unit_price", "asc"]],
+            }))))
+            .await?;
+
+        assert_eq!(view.num_rows().await?, 3);
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"region": "string", "unit_price": "float", "order_date": "date"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "region": ["North_East", "West", "East"],
+                "unit_price": [1.0, 5.25, 10.5],
+                "order_date": [1704326400000_i64, 1704240000000_i64, 1704067200000_i64],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_by_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["quantity", "unit_price"],
+                "group_by": ["category", "region"],
+                "aggregates": {"quantity": "sum", "unit_price": "avg"},
+            }))))
+            .await?;
+
+        // `SUM` of an `Int32` column is an `Int64`, which is a `float`.
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"quantity": "float", "unit_price": "float"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "__ROW_PATH__": [
+                    [],
+                    ["Furniture"],
+                    ["Furniture", "East"],
+                    ["Furniture", "West"],
+                    ["Technology"],
+                    ["Technology", "East"],
+                    ["Technology", "North_East"],
+                ],
+                "quantity": [10.0, 6.0, 2.0, 4.0, 4.0, 1.0, 3.0],
+                "unit_price": [9.1875, 7.875, 10.5, 5.25, 10.5, 20.0, 1.0],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_by_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["unit_price"],
+                "group_by": ["category"],
+                "split_by": ["region"],
+                "aggregates": {"unit_price": "sum"},
+            }))))
+            .await?;
+
+        assert_eq!(
+            view.column_paths_structured(ColumnWindow::default())
+                .await?,
+            vec![
+                vec!["East".to_string(), "unit_price".to_string()],
+                vec!["North_East".to_string(), "unit_price".to_string()],
+                vec!["West".to_string(), "unit_price".to_string()],
+            ]
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "__ROW_PATH__": [[], ["Furniture"], ["Technology"]],
+                "East|unit_price": [30.5, 10.5, 20.0],
+                "North_East|unit_price": [1.0, null, 1.0],
+                "West|unit_price": [5.25, 5.25, null],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expressions() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["total"],
+                "expressions": {"total": "\"unit_price\" * \"quantity\""},
+                "sort": [["total", "desc"]],
+            }))))
+            .await?;
+
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"total": "float"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(columns, json!({"total": [21.0, 21.0, 20.0, 3.0]}));
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tables_are_read_only() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let result = table
+            .update(
+                UpdateData::JsonRows(json!([{"region": "South"}]).to_string()),
+                UpdateOptions::default(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(ClientError::UnsupportedOperation(_))));
+        assert_eq!(table.size().await?, 4);
+        Ok(())
+    }
+}
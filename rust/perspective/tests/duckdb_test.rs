@@ -0,0 +1,229 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(feature = "duckdb")]
+mod common;
+
+#[cfg(feature = "duckdb")]
+mod internal {
+    use std::error::Error;
+
+    use perspective::virtual_server::duckdb::DuckDBHandler;
+    use perspective_client::config::ViewConfigUpdate;
+    use perspective_client::{Client, ColumnWindow, UpdateData, UpdateOptions, ViewWindow};
+    use serde_json::{Value, json};
+
+    use crate::common::client;
+
+    const SETUP: &str = "
+        CREATE TABLE superstore (
+            id INTEGER PRIMARY KEY,
+            region VARCHAR,
+            category VARCHAR,
+            unit_price DOUBLE,
+            quantity INTEGER,
+            order_date DATE
+        );
+
+        INSERT INTO superstore VALUES
+            (1, 'East', 'Furniture', 10.5, 2, '2024-01-01'),
+            (2, 'East', 'Technology', 20.0, 1, '2024-01-02'),
+            (3, 'West', 'Furniture', 5.25, 4, '2024-01-03'),
+            (4, 'North_East', 'Technology', 1.0, 3, '2024-01-04');
+    ";
+
+    fn superstore() -> Result<Client, Box<dyn Error>> {
+        let handler = DuckDBHandler::open_in_memory()?;
+        handler.connection().execute_batch(SETUP)?;
+        Ok(client(handler)?)
+    }
+
+    fn view_config(config: Value) -> ViewConfigUpdate {
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hosted_tables_and_schema() -> Result<(), Box<dyn Error>> {
+        let client = superstore()?;
+        assert_eq!(client.get_hosted_table_names().await?, vec![
+            "memory.superstore".to_string()
+        ]);
+
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        assert_eq!(table.get_index(), Some("id".to_string()));
+        assert_eq!(table.size().await?, 4);
+        assert_eq!(
+            serde_json::to_value(table.schema().await?)?,
+            json!({
+                "id": "integer",
+                "region": "string",
+                "category": "string",
+                "unit_price": "float",
+                "quantity": "integer",
+                "order_date": "date",
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hosted_tables_exclude_views_and_keep_schema() -> Result<(), Box<dyn Error>> {
+        let handler = DuckDBHandler::open_in_memory()?;
+        handler.connection().execute_batch(SETUP)?;
+        handler.connection().execute_batch(
+            "CREATE SCHEMA sales; CREATE TABLE sales.superstore (id INTEGER PRIMARY KEY);",
+        )?;
+
+        let client = client(handler)?;
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        let _view = table.view(None).await?;
+        assert_eq!(client.get_hosted_table_names().await?, vec![
+            "memory.superstore".to_string(),
+            "memory.sales.superstore".to_string(),
+        ]);
+
+        let table = client
+            .open_table("memory.sales.superstore".to_string())
+            .await?;
+        assert_eq!(table.get_index(), Some("id".to_string()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flat_view() -> Result<(), Box<dyn Error>> {
+        let client = superstore()?;
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["region", "unit_price", "order_date"],
+                "filter": [["quantity", ">", 1]],
+                "sort": [["unit_price", "asc"]],
+            }))))
+            .await?;
+
+        assert_eq!(view.num_rows().await?, 3);
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "region": ["North_East", "West", "East"],
+                "unit_price": [1.0, 5.25, 10.5],
+                "order_date": [1704326400000_i64, 1704240000000_i64, 1704067200000_i64],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_by_view() -> Result<(), Box<dyn Error>> {
+        let client = superstore()?;
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["quantity", "unit_price"],
+                "group_by": ["category"],
+                "aggregates": {"quantity": "sum", "unit_price": "max"},
+            }))))
+            .await?;
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns["__ROW_PATH__"],
+            json!([[], ["Furniture"], ["Technology"]])
+        );
+
+        let floats = |name: &str| -> Vec<f64> {
+            columns[name]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|x| x.as_f64().unwrap())
+                .collect()
+        };
+
+        assert_eq!(floats("quantity"), vec![10.0, 6.0, 4.0]);
+        assert_eq!(floats("unit_price"), vec![20.0, 10.5, 20.0]);
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_by_column_paths() -> Result<(), Box<dyn Error>> {
+        let client = superstore()?;
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["unit_price"],
+                "group_by": ["category"],
+                "split_by": ["region"],
+                "aggregates": {"unit_price": "sum"},
+            }))))
+            .await?;
+
+        assert_eq!(
+            view.column_paths_structured(ColumnWindow::default())
+                .await?,
+            vec![
+                vec!["East".to_string(), "unit_price".to_string()],
+                vec!["North_East".to_string(), "unit_price".to_string()],
+                vec!["West".to_string(), "unit_price".to_string()],
+            ]
+        );
+
+        let rows: Value = serde_json::from_str(&view.to_json_string(ViewWindow::default()).await?)?;
+        assert_eq!(rows[0]["North_East|unit_price"].as_f64(), Some(1.0));
+        assert_eq!(rows[1]["East|unit_price"].as_f64(), Some(10.5));
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_indexed_table() -> Result<(), Box<dyn Error>> {
+        let client = superstore()?;
+        let table = client.open_table("memory.superstore".to_string()).await?;
+        table
+            .update(
+                UpdateData::JsonRows(
+                    json!([
+                        {"id": 1, "quantity": 10},
+                        {"id": 5, "region": "South", "quantity": 1},
+                    ])
+                    .to_string(),
+                ),
+                UpdateOptions::default(),
+            )
+            .await?;
+
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["id", "region", "quantity"],
+                "sort": [["id", "asc"]],
+            }))))
+            .await?;
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(columns["id"], json!([1, 2, 3, 4, 5]));
+        assert_eq!(columns["quantity"], json!([10, 1, 4, 3, 1]));
+        assert_eq!(columns["region"][4], json!("South"));
+        view.delete().await?;
+        Ok(())
+    }
+}
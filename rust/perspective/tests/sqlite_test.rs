@@ -0,0 +1,362 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(feature = "sqlite")]
+mod common;
+
+#[cfg(feature = "sqlite")]
+mod internal {
+    use std::error::Error;
+
+    use perspective::virtual_server::sqlite::SqliteHandler;
+    use perspective_client::config::ViewConfigUpdate;
+    use perspective_client::{Client, ColumnWindow, Table, UpdateData, UpdateOptions, ViewWindow};
+    use serde_json::{Value, json};
+
+    use crate::common::client;
+
+    const SETUP: &str = "
+        CREATE TABLE superstore (
+            id INTEGER PRIMARY KEY,
+            region TEXT,
+            category TEXT,
+            unit_price REAL,
+            quantity INTEGER,
+            order_date DATE
+        );
+
+        INSERT INTO superstore VALUES
+            (1, 'East', 'Furniture', 10.5, 2, '2024-01-01'),
+            (2, 'East', 'Technology', 20.0, 1, '2024-01-02'),
+            (3, 'West', 'Furniture', 5.25, 4, '2024-01-03'),
+            (4, 'North_East', 'Technology', 1.0, 3, '2024-01-04');
+    ";
+
+    async fn superstore() -> Result<(Client, Table), Box<dyn Error>> {
+        let handler = SqliteHandler::open_in_memory()?;
+        handler.connection().execute_batch(SETUP)?;
+        let client = client(handler)?;
+        let table = client.open_table("superstore".to_string()).await?;
+        Ok((client, table))
+    }
+
+    fn view_config(config: Value) -> ViewConfigUpdate {
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hosted_tables_and_schema() -> Result<(), Box<dyn Error>> {
+        let (client, table) = superstore().await?;
+        assert_eq!(table.get_index(), Some("id".to_string()));
+        assert_eq!(table.size().await?, 4);
+        assert_eq!(
+            serde_json::to_value(table.schema().await?)?,
+            json!({
+                "id": "integer",
+                "region": "string",
+                "category": "string",
+                "unit_price": "float",
+                "quantity": "integer",
+                "order_date": "date",
+            })
+        );
+
+        // Views are `TEMP` tables, which are not hosted.
+        let view = table.view(None).await?;
+        assert_eq!(client.get_hosted_table_names().await?, vec![
+            "superstore".to_string()
+        ]);
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flat_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["region", "unit_price", "order_date"],
+                "filter": [["quantity", ">", 1]],
+                "sort": [["unit_price", "asc"]],
+            }))))
+            .await?;
+
+        assert_eq!(view.num_rows().await?, 3);
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"region": "string", "unit_price": "float", "order_date": "date"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "region": ["North_East", "West", "East"],
+                "unit_price": [1.0, 5.25, 10.5],
+                "order_date": [1704326400000_i64, 1704240000000_i64, 1704067200000_i64],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_by_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["quantity", "unit_price", "region"],
+                "group_by": ["category", "region"],
+                "aggregates": {"quantity": "sum", "unit_price": "avg", "region": "count"},
+            }))))
+            .await?;
+
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"quantity": "integer", "unit_price": "float", "region": "integer"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "__ROW_PATH__": [
+                    [],
+                    ["Furniture"],
+                    ["Furniture", "East"],
+                    ["Furniture", "West"],
+                    ["Technology"],
+                    ["Technology", "East"],
+                    ["Technology", "North_East"],
+                ],
+                "quantity": [10, 6, 2, 4, 4, 1, 3],
+                "unit_price": [9.1875, 7.875, 10.5, 5.25, 10.5, 20.0, 1.0],
+                "region": [4, 2, 1, 1, 2, 1, 1],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collapse_and_expand() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["quantity"],
+                "group_by": ["order_date", "region"],
+                "aggregates": {"quantity": "sum"},
+            }))))
+            .await?;
+
+        assert_eq!(view.num_rows().await?, 9);
+        assert_eq!(view.collapse(1).await?, 1);
+        assert_eq!(view.num_rows().await?, 8);
+
+        let columns: Value = serde_json::from_str(
+            &view
+                .to_columns_string(ViewWindow {
+                    end_row: Some(3.0),
+                    ..ViewWindow::default()
+                })
+                .await?,
+        )?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "__ROW_PATH__": [[], [1704067200000.0], [1704153600000.0]],
+                "quantity": [10, 2, 1],
+            })
+        );
+
+        assert_eq!(view.expand(1).await?, 1);
+        assert_eq!(view.num_rows().await?, 9);
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_by_view() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["unit_price"],
+                "group_by": ["category"],
+                "split_by": ["region"],
+                "aggregates": {"unit_price": "sum"},
+            }))))
+            .await?;
+
+        assert_eq!(
+            view.column_paths_structured(ColumnWindow::default())
+                .await?,
+            vec![
+                vec!["East".to_string(), "unit_price".to_string()],
+                vec!["North_East".to_string(), "unit_price".to_string()],
+                vec!["West".to_string(), "unit_price".to_string()],
+            ]
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "__ROW_PATH__": [[], ["Furniture"], ["Technology"]],
+                "East|unit_price": [30.5, 10.5, 20.0],
+                "North_East|unit_price": [1.0, null, 1.0],
+                "West|unit_price": [5.25, 5.25, null],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_by_date_min_max() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["order_date"],
+                "split_by": ["region"],
+            }))))
+            .await?;
+
+        let millis = |(min, max): (String, String)| -> Result<(f64, f64), Box<dyn Error>> {
+            Ok((min.parse()?, max.parse()?))
+        };
+
+        assert_eq!(
+            millis(view.get_min_max("East|order_date".to_string()).await?)?,
+            (1704067200000.0, 1704153600000.0)
+        );
+
+        assert_eq!(
+            millis(
+                view.get_min_max("North_East|order_date".to_string())
+                    .await?
+            )?,
+            (1704326400000.0, 1704326400000.0)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expressions() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["total"],
+                "expressions": {"total": "\"unit_price\" * \"quantity\""},
+                "sort": [["total", "desc"]],
+            }))))
+            .await?;
+
+        assert_eq!(
+            serde_json::to_value(view.schema().await?)?,
+            json!({"total": "float"})
+        );
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(columns, json!({"total": [21.0, 21.0, 20.0, 3.0]}));
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_indexed_table() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["id", "region", "quantity"],
+                "sort": [["id", "asc"]],
+            }))))
+            .await?;
+
+        table
+            .update(
+                UpdateData::JsonRows(
+                    json!([
+                        {"id": 1, "region": "South", "quantity": 10},
+                        {"id": 5, "region": "South", "quantity": 1},
+                    ])
+                    .to_string(),
+                ),
+                UpdateOptions::default(),
+            )
+            .await?;
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "id": [1, 2, 3, 4, 5],
+                "region": ["South", "East", "West", "North_East", "South"],
+                "quantity": [10, 1, 4, 3, 1],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_and_remove_csv() -> Result<(), Box<dyn Error>> {
+        let (_client, table) = superstore().await?;
+        let view = table
+            .view(Some(view_config(json!({
+                "columns": ["id", "region"],
+                "sort": [["id", "asc"]],
+            }))))
+            .await?;
+
+        table
+            .update(
+                UpdateData::Csv("id,region\n2,O'Neil\n6,West".to_string()),
+                UpdateOptions::default(),
+            )
+            .await?;
+
+        table
+            .remove(UpdateData::Csv("id\n1\n3".to_string()))
+            .await?;
+
+        let columns: Value =
+            serde_json::from_str(&view.to_columns_string(ViewWindow::default()).await?)?;
+
+        assert_eq!(
+            columns,
+            json!({
+                "id": [2, 4, 6],
+                "region": ["O'Neil", "North_East", "West"],
+            })
+        );
+
+        view.delete().await?;
+        Ok(())
+    }
+}
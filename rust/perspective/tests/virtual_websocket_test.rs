@@ -0,0 +1,96 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(all(feature = "axum-ws", feature = "websocket-client", feature = "sqlite"))]
+mod internal {
+    use std::error::Error;
+    use std::net::SocketAddr;
+
+    use axum::Router;
+    use futures::{SinkExt, StreamExt};
+    use perspective::proto::request::ClientReq;
+    use perspective::proto::response::ClientResp;
+    use perspective::proto::{GetHostedTablesReq, Request, Response, StatusCode};
+    use perspective::virtual_server::custom_websocket_handler;
+    use perspective::virtual_server::sqlite::SqliteHandler;
+    use prost::Message as _;
+    use tokio_tungstenite::connect_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Serves a [`SqliteHandler`] hosting the table `superstore` on a local
+    /// port, returning its address.
+    async fn serve() -> Result<SocketAddr, Box<dyn Error>> {
+        let handler = SqliteHandler::open_in_memory()?;
+        handler
+            .connection()
+            .execute_batch("CREATE TABLE superstore (id INTEGER PRIMARY KEY);")?;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let app: Router = Router::new().route("/ws", custom_websocket_handler(handler));
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+
+        Ok(addr)
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_keeps_connection_open() -> Result<(), Box<dyn Error>> {
+        let addr = serve().await?;
+        let (mut socket, _) = connect_async(format!("ws://{}/ws", addr)).await?;
+
+        socket
+            .send(Message::Binary(vec![0xff, 0xff, 0xff].into()))
+            .await?;
+
+        let Some(Message::Binary(resp)) = socket.next().await.transpose()? else {
+            return Err("expected a binary response".into());
+        };
+
+        match Response::decode(resp)?.client_resp {
+            Some(ClientResp::ServerError(err)) => {
+                assert_eq!(err.status_code, StatusCode::ServerError as i32);
+            },
+            resp => return Err(format!("expected a ServerError, got {:?}", resp).into()),
+        }
+
+        let req = Request {
+            msg_id: 1,
+            entity_id: String::new(),
+            client_req: Some(ClientReq::GetHostedTablesReq(GetHostedTablesReq::default())),
+        };
+
+        socket
+            .send(Message::Binary(req.encode_to_vec().into()))
+            .await?;
+        let Some(Message::Binary(resp)) = socket.next().await.transpose()? else {
+            return Err("expected a binary response".into());
+        };
+
+        let resp = Response::decode(resp)?;
+        assert_eq!(resp.msg_id, 1);
+        match resp.client_resp {
+            Some(ClientResp::GetHostedTablesResp(resp)) => {
+                assert_eq!(resp.table_infos.len(), 1);
+                assert_eq!(resp.table_infos[0].entity_id, "superstore");
+            },
+            resp => return Err(format!("expected hosted tables, got {:?}", resp).into()),
+        }
+
+        Ok(())
+    }
+}
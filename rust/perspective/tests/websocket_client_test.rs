@@ -0,0 +1,195 @@
+// ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+// ┃ ██████ ██████ ██████       █      █      █      █      █ █▄  ▀███ █       ┃
+// ┃ ▄▄▄▄▄█ █▄▄▄▄▄ ▄▄▄▄▄█  ▀▀▀▀▀█▀▀▀▀▀ █ ▀▀▀▀▀█ ████████▌▐███ ███▄  ▀█ █ ▀▀▀▀▀ ┃
+// ┃ █▀▀▀▀▀ █▀▀▀▀▀ █▀██▀▀ ▄▄▄▄▄ █ ▄▄▄▄▄█ ▄▄▄▄▄█ ████████▌▐███ █████▄   █ ▄▄▄▄▄ ┃
+// ┃ █      ██████ █  ▀█▄       █ ██████      █      ███▌▐███ ███████▄ █       ┃
+// ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+// ┃ Copyright (c) 2017, the Perspective Authors.                              ┃
+// ┃ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌ ┃
+// ┃ This file is part of the Perspective library, distributed under the terms ┃
+// ┃ of the [Apache License 2.0](https://www.apache.org/licenses/LICENSE-2.0). ┃
+// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+
+#[cfg(all(feature = "axum-ws", feature = "websocket-client"))]
+mod internal {
+    use std::error::Error;
+    use std::net::{SocketAddr, TcpListener};
+    use std::time::Duration;
+
+    use axum::Router;
+    use perspective::axum::websocket_handler;
+    use perspective::client::websocket::connect;
+    use perspective::client::{
+        ClientError, OnUpdateOptions, TableInitOptions, UpdateData, UpdateOptions,
+    };
+    use perspective::server::{LocalClient, Server};
+    use tokio::runtime::Runtime;
+
+    /// Serves `server` on `listener` from its own runtime, so that shutting
+    /// the runtime down closes every connection.
+    fn serve(listener: TcpListener, server: Server) -> Runtime {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.spawn(async move {
+            listener.set_nonblocking(true).unwrap();
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let app = Router::new()
+                .route("/ws", websocket_handler())
+                .with_state(server);
+
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        runtime
+    }
+
+    /// Binds `addr` again, once the previous server's listener has closed.
+    async fn rebind(addr: SocketAddr) -> std::io::Result<TcpListener> {
+        let mut attempts = 0;
+        loop {
+            match TcpListener::bind(addr) {
+                Err(_) if attempts < 50 => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// A [`Server`] hosting the table `Table1`, and a [`LocalClient`] to it.
+    async fn superstore() -> Result<(Server, LocalClient), Box<dyn Error>> {
+        let server = Server::new(None);
+        let client = LocalClient::new(&server);
+        client
+            .table(
+                UpdateData::Csv("x,y\n1,2\n3,4".to_owned()).into(),
+                TableInitOptions {
+                    name: Some("Table1".to_owned()),
+                    ..TableInitOptions::default()
+                },
+            )
+            .await?;
+
+        Ok((server, client))
+    }
+
+    #[tokio::test]
+    async fn test_websocket_clients_share_tables() -> Result<(), Box<dyn Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("ws://{}/ws", listener.local_addr()?);
+        let runtime = serve(listener, Server::new(None));
+        let client1 = connect(&url).await?;
+        let client2 = connect(&url).await?;
+        client1
+            .table(
+                UpdateData::Csv("x,y\n1,2\n3,4".to_owned()).into(),
+                TableInitOptions {
+                    name: Some("Table1".to_owned()),
+                    ..TableInitOptions::default()
+                },
+            )
+            .await?;
+
+        let table = client2.open_table("Table1".to_owned()).await?;
+        assert_eq!(table.size().await?, 2);
+        runtime.shutdown_background();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_websocket_client_reconnects() -> Result<(), Box<dyn Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let runtime = serve(listener, Server::new(None));
+        let client = connect(&format!("ws://{addr}/ws")).await?;
+        assert!(client.get_hosted_table_names().await?.is_empty());
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        client
+            .on_error(move |err, reconnect| {
+                sender.send((err, reconnect)).unwrap();
+                async { Ok::<(), ClientError>(()) }
+            })
+            .await?;
+
+        runtime.shutdown_background();
+        let (err, reconnect) = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await?
+            .unwrap();
+
+        assert!(matches!(err, ClientError::TransportError(_)), "{err:?}");
+        assert!(client.get_hosted_table_names().await.is_err());
+
+        let runtime = serve(rebind(addr).await?, Server::new(None));
+        reconnect.unwrap()().await.map_err(|e| e.to_string())?;
+        assert!(client.get_hosted_table_names().await?.is_empty());
+        runtime.shutdown_background();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_websocket_client_restores_views_after_reconnect() -> Result<(), Box<dyn Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let (server, _local) = superstore().await?;
+        let runtime = serve(listener, server);
+        let client = connect(&format!("ws://{addr}/ws")).await?;
+        let view = client
+            .open_table("Table1".to_owned())
+            .await?
+            .view(None)
+            .await?;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        client
+            .on_error(move |_, reconnect| {
+                sender.send(reconnect).unwrap();
+                async { Ok::<(), ClientError>(()) }
+            })
+            .await?;
+
+        let (updates, mut updated) = tokio::sync::mpsc::unbounded_channel();
+        view.on_update(
+            move |_| {
+                updates.send(()).unwrap();
+                async {}
+            },
+            OnUpdateOptions::default(),
+        )
+        .await?;
+
+        runtime.shutdown_background();
+        let reconnect = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await?
+            .unwrap();
+
+        let (server, local) = superstore().await?;
+        let runtime = serve(rebind(addr).await?, server);
+        reconnect.unwrap()().await.map_err(|e| e.to_string())?;
+
+        // The `View` and its `on_update` callback are restored on the new
+        // `Server`.
+        assert_eq!(view.num_rows().await?, 2);
+        local
+            .open_table("Table1".to_owned())
+            .await?
+            .update(
+                UpdateData::Csv("x,y\n5,6".to_owned()),
+                UpdateOptions::default(),
+            )
+            .await?;
+
+        tokio::time::timeout(Duration::from_secs(5), updated.recv()).await?;
+        assert_eq!(view.num_rows().await?, 3);
+        runtime.shutdown_background();
+        Ok(())
+    }
+}